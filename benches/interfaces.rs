@@ -56,6 +56,112 @@ fn bench_getifs_interface_addrs(c: &mut Criterion) {
   });
 }
 
+fn bench_getifs_interface_ipv4_addrs(c: &mut Criterion) {
+  c.bench_function("getifs::interface_ipv4_addrs", |b| {
+    b.iter(|| {
+      getifs::interface_ipv4_addrs().unwrap();
+    })
+  });
+}
+
+fn bench_getifs_interface_ipv6_addrs(c: &mut Criterion) {
+  c.bench_function("getifs::interface_ipv6_addrs", |b| {
+    b.iter(|| {
+      getifs::interface_ipv6_addrs().unwrap();
+    })
+  });
+}
+
+fn bench_getifs_try_interfaces(c: &mut Criterion) {
+  c.bench_function("getifs::try_interfaces", |b| {
+    b.iter(|| {
+      getifs::try_interfaces();
+    })
+  });
+}
+
+fn bench_getifs_routable_interfaces(c: &mut Criterion) {
+  c.bench_function("getifs::routable_interfaces", |b| {
+    b.iter(|| {
+      getifs::routable_interfaces().unwrap();
+    })
+  });
+}
+
+/// Looking up one interface (or its addresses) by index against the
+/// full dump filtered in user space. The two sides only diverge once a
+/// backend issues a single-interface request instead of dumping the
+/// whole table, which this group is here to measure.
+fn bench_getifs_single_vs_dump(c: &mut Criterion) {
+  let Some(ifi) = loopback_interface() else {
+    println!("Skipping getifs single_vs_dump - no loopback interface");
+    return;
+  };
+  let idx = ifi.index();
+
+  let mut group = c.benchmark_group("getifs::single_vs_dump");
+  group.bench_with_input(
+    BenchmarkId::new("interface_by_index", idx),
+    &idx,
+    |b, idx| {
+      b.iter(|| {
+        getifs::interface_by_index(*idx).unwrap();
+      })
+    },
+  );
+  group.bench_with_input(BenchmarkId::new("interfaces_find", idx), &idx, |b, idx| {
+    b.iter(|| {
+      getifs::interfaces()
+        .unwrap()
+        .into_iter()
+        .find(|ifi| ifi.index() == *idx);
+    })
+  });
+  group.bench_with_input(
+    BenchmarkId::new("interface_addrs_single", idx),
+    &ifi,
+    |b, ifi| {
+      b.iter(|| {
+        ifi.addrs().unwrap();
+      })
+    },
+  );
+  group.bench_with_input(
+    BenchmarkId::new("interface_addrs_find", idx),
+    &idx,
+    |b, idx| {
+      b.iter(|| {
+        getifs::interface_addrs_by_filter(|_| true)
+          .unwrap()
+          .into_iter()
+          .filter(|addr| addr.index() == *idx)
+          .count();
+      })
+    },
+  );
+  group.finish();
+}
+
+fn bench_getifs_addrs_within(c: &mut Criterion) {
+  // Route-guided, kernel-filtered lookup vs. dumping everything and
+  // filtering locally. The gap widens with the number of addresses on
+  // unrelated interfaces, so run this on a host carrying many of them
+  // (e.g. a few thousand secondaries on a dummy link) to see it.
+  let net: getifs::ipnet::IpNet = "127.0.0.0/8".parse().unwrap();
+  let mut group = c.benchmark_group("getifs::addrs_within");
+  group.bench_with_input(BenchmarkId::new("addrs_within", net), &net, |b, net| {
+    b.iter(|| {
+      getifs::addrs_within(*net).unwrap();
+    })
+  });
+  group.bench_with_input(BenchmarkId::new("filter_all", net), &net, |b, net| {
+    b.iter(|| {
+      getifs::interface_addrs_by_filter(|addr| net.contains(addr)).unwrap();
+    })
+  });
+  group.finish();
+}
+
 fn bench_getifs_interfaces_and_multicast_addrs(c: &mut Criterion) {
   let ifi = loopback_interface().unwrap();
   c.bench_with_input(
@@ -69,6 +175,34 @@ fn bench_getifs_interfaces_and_multicast_addrs(c: &mut Criterion) {
   );
 }
 
+// One socket carrying the link, address and route dumps against a
+// socket per dump.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn bench_getifs_capture_vs_separate(c: &mut Criterion) {
+  if getifs::capture_linux().is_err() {
+    println!("Skipping getifs::capture_vs_separate - netlink dumps not available");
+    return;
+  }
+
+  let mut group = c.benchmark_group("getifs::capture_vs_separate");
+  group.bench_function("capture_linux", |b| {
+    b.iter(|| {
+      getifs::capture_linux().unwrap();
+    })
+  });
+  group.bench_function("separate", |b| {
+    b.iter(|| {
+      getifs::interfaces().unwrap();
+      getifs::interface_addrs().unwrap();
+      getifs::route_table().unwrap();
+    })
+  });
+  group.finish();
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn bench_getifs_capture_vs_separate(_: &mut Criterion) {}
+
 fn bench_network_interface_interfaces(c: &mut Criterion) {
   c.bench_function("network_interface::interfaces", |b| {
     b.iter(|| {
@@ -115,7 +249,14 @@ criterion_group!(
   bench_getifs_interface_by_index,
   bench_getifs_interface_by_name,
   bench_getifs_interface_addrs,
+  bench_getifs_interface_ipv4_addrs,
+  bench_getifs_interface_ipv6_addrs,
+  bench_getifs_try_interfaces,
+  bench_getifs_routable_interfaces,
+  bench_getifs_single_vs_dump,
+  bench_getifs_addrs_within,
   bench_getifs_interfaces_and_multicast_addrs,
+  bench_getifs_capture_vs_separate,
 );
 
 criterion_group!(
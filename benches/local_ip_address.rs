@@ -16,6 +16,40 @@ fn bench_getifs_local_ipv6(c: &mut Criterion) {
   });
 }
 
+fn bench_getifs_local_addrs(c: &mut Criterion) {
+  c.bench_function("getifs::local_addrs", |b| {
+    b.iter(|| {
+      getifs::local_addrs().unwrap();
+    })
+  });
+}
+
+fn bench_getifs_best_local_ipv4(c: &mut Criterion) {
+  // Route-based: needs a default IPv4 route, which sandboxed CI runners
+  // may not have.
+  if getifs::best_local_ipv4_addrs().is_ok() {
+    c.bench_function("getifs::best_local_ipv4_addrs", |b| {
+      b.iter(|| {
+        let _ = getifs::best_local_ipv4_addrs();
+      })
+    });
+  } else {
+    println!("Skipping getifs::best_local_ipv4_addrs - not available on this system");
+  }
+}
+
+fn bench_getifs_best_local_ipv6(c: &mut Criterion) {
+  if getifs::best_local_ipv6_addrs().is_ok() {
+    c.bench_function("getifs::best_local_ipv6_addrs", |b| {
+      b.iter(|| {
+        let _ = getifs::best_local_ipv6_addrs();
+      })
+    });
+  } else {
+    println!("Skipping getifs::best_local_ipv6_addrs - not available on this system");
+  }
+}
+
 fn bench_local_ip_address_local_ipv4(c: &mut Criterion) {
   c.bench_function("local_ip_address::local_ip", |b| {
     b.iter(|| {
@@ -36,6 +70,9 @@ criterion_group!(
   getifs_benches,
   bench_getifs_local_ipv4,
   bench_getifs_local_ipv6,
+  bench_getifs_local_addrs,
+  bench_getifs_best_local_ipv4,
+  bench_getifs_best_local_ipv6,
 );
 
 criterion_group!(
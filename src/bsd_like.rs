@@ -18,11 +18,15 @@ use smol_str::SmolStr;
 use std::{
   io, mem,
   net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  ops::ControlFlow,
   ptr::null_mut,
+  time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use super::InterfaceStats;
 use super::{
-  IfNet, Ifv4Net, Ifv6Net, Interface, IpRoute, Ipv4Route, Ipv6Route, MacAddr, Net, MAC_ADDRESS_SIZE,
+  EnumerationError, Error, IfNet, Ifv4Net, Ifv6Net, Interface, IpRoute, Ipv4Route, Ipv6Route,
+  MacAddr, Net, OperStatus, RouteMetrics, MAC_ADDRESS_SIZE,
 };
 
 // `Address` / `IfAddr` / `Ifv4Addr` / `Ifv6Addr` are only referenced
@@ -103,7 +107,15 @@ macro_rules! rt_generic_mod {
 
 rt_generic_mod!(gateway(RTF_GATEWAY, RTA_GATEWAY),);
 
+#[cfg(feature = "configure")]
+pub(super) use configure::*;
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "dragonfly"))]
+pub(super) use groups::interface_groups;
+#[cfg(target_os = "macos")]
+pub(super) use in6_flags::best_local_ipv6_addr_for_outbound;
 pub(super) use local_addr::*;
+pub(super) use neighbor::neighbor_table;
+pub(super) use watch::AddrWatcher;
 
 #[inline]
 fn build_routev4(
@@ -112,6 +124,8 @@ fn build_routev4(
   dst: IpAddr,
   gateway: Option<IpAddr>,
   netmask: Option<IpAddr>,
+  metric: u32,
+  metrics: RouteMetrics,
 ) -> Option<Ipv4Route> {
   let dst_v4 = match dst {
     IpAddr::V4(ip) => ip,
@@ -153,7 +167,11 @@ fn build_routev4(
     Some(IpAddr::V4(g)) if g != Ipv4Addr::UNSPECIFIED => Some(g),
     _ => None,
   };
-  Some(Ipv4Route::new(index, net, gw))
+  Some(
+    Ipv4Route::new(index, net, gw)
+      .with_metric(metric)
+      .with_route_metrics(metrics),
+  )
 }
 
 #[inline]
@@ -163,6 +181,8 @@ fn build_routev6(
   dst: IpAddr,
   gateway: Option<IpAddr>,
   netmask: Option<IpAddr>,
+  metric: u32,
+  metrics: RouteMetrics,
 ) -> Option<Ipv6Route> {
   let dst_v6 = match dst {
     IpAddr::V6(ip) => ip,
@@ -191,7 +211,11 @@ fn build_routev6(
     Some(IpAddr::V6(g)) if g != Ipv6Addr::UNSPECIFIED => Some(g),
     _ => None,
   };
-  Some(Ipv6Route::new(index, net, gw))
+  Some(
+    Ipv6Route::new(index, net, gw)
+      .with_metric(metric)
+      .with_route_metrics(metrics),
+  )
 }
 
 /// `Ok(())` if the result is "this address-family stack isn't
@@ -221,6 +245,19 @@ pub(super) fn family_unavailable_to_empty(result: io::Result<()>) -> io::Result<
   }
 }
 
+pub(super) fn route_probe() -> io::Result<()> {
+  // Size-only `NET_RT_DUMP` query: the kernel performs the same
+  // access checks as a full dump without copying the table out.
+  unsafe {
+    let mut mib = [CTL_NET, AF_ROUTE, 0, AF_INET, libc::NET_RT_DUMP, 0];
+    let mut len: size_t = 0;
+    if sysctl(mib.as_mut_ptr(), 6, null_mut(), &mut len, null_mut(), 0) < 0 {
+      return Err(io::Error::last_os_error());
+    }
+  }
+  Ok(())
+}
+
 pub(super) fn route_table_by_filter<F>(mut f: F) -> io::Result<SmallVec<IpRoute>>
 where
   F: FnMut(&IpRoute) -> bool,
@@ -241,9 +278,9 @@ where
   // error — see `family_unavailable_to_empty` for why.
   family_unavailable_to_empty(route::walk_route_table(
     AF_INET,
-    |index, flags, dst, gw, mask| {
+    |index, flags, dst, gw, mask, metric, metrics| {
       let dst = dst.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
-      if let Some(r) = build_routev4(index, flags, dst, gw, mask) {
+      if let Some(r) = build_routev4(index, flags, dst, gw, mask, metric, metrics) {
         let r = IpRoute::V4(r);
         if f(&r) {
           out.push(r);
@@ -253,9 +290,9 @@ where
   ))?;
   family_unavailable_to_empty(route::walk_route_table(
     AF_INET6,
-    |index, flags, dst, gw, mask| {
+    |index, flags, dst, gw, mask, metric, metrics| {
       let dst = dst.unwrap_or(IpAddr::V6(Ipv6Addr::UNSPECIFIED));
-      if let Some(r) = build_routev6(index, flags, dst, gw, mask) {
+      if let Some(r) = build_routev6(index, flags, dst, gw, mask, metric, metrics) {
         let r = IpRoute::V6(r);
         if f(&r) {
           out.push(r);
@@ -271,12 +308,12 @@ where
   F: FnMut(&Ipv4Route) -> bool,
 {
   let mut out: SmallVec<Ipv4Route> = SmallVec::new();
-  route::walk_route_table(AF_INET, |index, flags, dst, gw, mask| {
+  route::walk_route_table(AF_INET, |index, flags, dst, gw, mask, metric, metrics| {
     // BSD sysctl can omit `RTAX_DST` for the default route — fold that
     // case to `0.0.0.0` here so `build_routev4` can pair it with the
     // implicit `/0` mask.
     let dst = dst.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
-    if let Some(r) = build_routev4(index, flags, dst, gw, mask) {
+    if let Some(r) = build_routev4(index, flags, dst, gw, mask, metric, metrics) {
       if f(&r) {
         out.push(r);
       }
@@ -290,11 +327,11 @@ where
   F: FnMut(&Ipv6Route) -> bool,
 {
   let mut out: SmallVec<Ipv6Route> = SmallVec::new();
-  route::walk_route_table(AF_INET6, |index, flags, dst, gw, mask| {
+  route::walk_route_table(AF_INET6, |index, flags, dst, gw, mask, metric, metrics| {
     // Same as the v4 path — missing `RTAX_DST` on AF_INET6 is BSD's
     // way of describing the `::/0` default route.
     let dst = dst.unwrap_or(IpAddr::V6(Ipv6Addr::UNSPECIFIED));
-    if let Some(r) = build_routev6(index, flags, dst, gw, mask) {
+    if let Some(r) = build_routev6(index, flags, dst, gw, mask, metric, metrics) {
       if f(&r) {
         out.push(r);
       }
@@ -305,12 +342,26 @@ where
 
 #[path = "bsd_like/compat.rs"]
 mod compat;
+#[cfg(feature = "configure")]
+#[path = "bsd_like/configure.rs"]
+mod configure;
+#[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "dragonfly"))]
+#[path = "bsd_like/groups.rs"]
+mod groups;
+#[cfg(target_os = "macos")]
+#[path = "bsd_like/in6_flags.rs"]
+mod in6_flags;
+
 #[path = "bsd_like/local_addr.rs"]
 mod local_addr;
+#[path = "bsd_like/neighbor.rs"]
+mod neighbor;
 #[path = "bsd_like/route.rs"]
 mod route;
 #[path = "bsd_like/rt_generic.rs"]
 mod rt_generic;
+#[path = "bsd_like/watch.rs"]
+mod watch;
 
 #[cfg(target_vendor = "apple")]
 const KERNAL_ALIGN: usize = 4;
@@ -322,15 +373,15 @@ const KERNAL_ALIGN: usize = core::mem::size_of::<usize>();
 const KERNAL_ALIGN: usize = 8;
 
 fn invalid_address() -> io::Error {
-  io::Error::new(io::ErrorKind::InvalidData, "invalid address")
+  Error::parse("routing socket address").into()
 }
 
 fn invalid_message() -> io::Error {
-  io::Error::new(io::ErrorKind::InvalidData, "invalid message")
+  Error::parse("routing socket message").into()
 }
 
 fn message_too_short() -> io::Error {
-  io::Error::new(io::ErrorKind::InvalidData, "message too short")
+  Error::parse("truncated routing socket message").into()
 }
 
 bitflags::bitflags! {
@@ -415,8 +466,10 @@ fn parse(mut b: &[u8]) -> io::Result<(SmolStr, Option<MacAddr>)> {
 
   let mut data = &b[4..];
   let name = if nlen > 0 {
-    let name = core::str::from_utf8(&data[..nlen])
-      .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    // Interface names are arbitrary bytes to the kernel. Decode lossily,
+    // as Linux and Windows do, so one oddly named interface surfaces
+    // with replacement characters instead of failing the enumeration.
+    let name = String::from_utf8_lossy(&data[..nlen]);
     data = &data[nlen..];
     SmolStr::from(name)
   } else {
@@ -581,31 +634,31 @@ fn parse_inet_addr(af: i32, b: &[u8]) -> io::Result<(usize, IpAddr)> {
       let sockaddr: libc::sockaddr_in6 =
         unsafe { core::ptr::read_unaligned(b.as_ptr() as *const libc::sockaddr_in6) };
 
-      let mut ip = sockaddr.sin6_addr.s6_addr;
-      // TODO: create own Ipv6Addr
-      let _zone_id = sockaddr.sin6_scope_id;
-      let mut addr: Ipv6Addr = ip.into();
-      if ip[0] == 0xfe && ip[1] & 0xc0 == 0x80
-        || ip[0] == 0xff && (ip[1] & 0x0f == 0x01 || ip[1] & 0x0f == 0x02)
-      {
-        // KAME based IPv6 protocol stack usually
-        // embeds the interface index in the
-        // interface-local or link-local address as
-        // the kernel-internal form.
-        let id = u16::from_be_bytes([ip[2], ip[3]]);
-        if id != 0 {
-          ip[2] = 0;
-          ip[3] = 0;
-          addr = ip.into();
-        }
-      }
-
-      Ok((SOCK6, addr.into()))
+      Ok((
+        SOCK6,
+        strip_embedded_scope(sockaddr.sin6_addr.s6_addr).into(),
+      ))
     }
     _ => Err(invalid_address()),
   }
 }
 
+/// KAME-based IPv6 stacks embed the zone of a scoped address (link-local
+/// unicast, interface- and link-local multicast) in bytes 2..4 of the
+/// kernel-internal form. Clears it to recover the bare address. The zone
+/// is the index of the interface the message describes, which the walkers
+/// already carry and [`Ifv6Addr::scope_id`](crate::Ifv6Addr::scope_id)
+/// reports, so nothing is lost.
+fn strip_embedded_scope(mut ip: [u8; 16]) -> Ipv6Addr {
+  if ip[0] == 0xfe && ip[1] & 0xc0 == 0x80
+    || ip[0] == 0xff && (ip[1] & 0x0f == 0x01 || ip[1] & 0x0f == 0x02)
+  {
+    ip[2] = 0;
+    ip[3] = 0;
+  }
+  ip.into()
+}
+
 pub(super) fn parse_addrs(
   addrs: u32,
   mut b: &[u8],
@@ -626,10 +679,7 @@ pub(super) fn parse_addrs(
     // folded into a synthetic `0.0.0.0/0` / `::/0` default route.
     // Surface as a malformed message instead of fabricating data.
     if b.len() < KERNAL_ALIGN {
-      return Err(io::Error::new(
-        io::ErrorKind::InvalidData,
-        "message too short",
-      ));
+      return Err(message_too_short());
     }
 
     if i <= RTAX_BRD as usize {
@@ -637,10 +687,7 @@ pub(super) fn parse_addrs(
         AF_LINK => {
           let l = roundup(b[0] as usize);
           if b.len() < l {
-            return Err(io::Error::new(
-              io::ErrorKind::InvalidData,
-              "message too short",
-            ));
+            return Err(message_too_short());
           }
           b = &b[l..];
         }
@@ -650,10 +697,7 @@ pub(super) fn parse_addrs(
           let needed = if af == AF_INET { SOCK4 } else { SOCK6 };
           let l = roundup(sa_len);
           if b.len() < l || b.len() < sa_len {
-            return Err(io::Error::new(
-              io::ErrorKind::InvalidData,
-              "message too short",
-            ));
+            return Err(message_too_short());
           }
           // BSD's NET_RT_DUMP encodes netmasks as truncated sockaddrs:
           // `sa_family = AF_INET[6]` but `sa_len` is short and only the
@@ -674,10 +718,7 @@ pub(super) fn parse_addrs(
           } else if i == RTAX_NETMASK as usize {
             parse_short_inet_addr(af, &b[..sa_len])?
           } else {
-            return Err(io::Error::new(
-              io::ErrorKind::InvalidData,
-              "short sockaddr outside RTAX_NETMASK",
-            ));
+            return Err(invalid_address());
           };
           as_[i] = Some(addr);
           b = &b[l..];
@@ -696,10 +737,7 @@ pub(super) fn parse_addrs(
     } else {
       let l = roundup(b[0] as usize);
       if b.len() < l {
-        return Err(io::Error::new(
-          io::ErrorKind::InvalidData,
-          "message too short",
-        ));
+        return Err(message_too_short());
       }
       b = &b[l..];
     }
@@ -708,6 +746,21 @@ pub(super) fn parse_addrs(
   Ok(as_)
 }
 
+/// Widens a routing-socket interface index to the crate's `u32`.
+///
+/// Every BSD routing-socket header this module decodes (`if_msghdr`,
+/// `ifa_msghdr`, `ifma_msghdr[2]`, `rt_msghdr`) carries the index as a
+/// `u_short`. Funnelling each read through a `u16` parameter, instead
+/// of an `as u32` at every call site, turns a header definition that
+/// drifts to a signed or wider field into a compile error rather than a
+/// silent sign-extension / truncation — which would otherwise make the
+/// interface table and the address tables disagree about which
+/// interface an address belongs to.
+#[inline]
+const fn kernel_index(raw: u16) -> u32 {
+  raw as u32
+}
+
 fn fetch(family: i32, rt: i32, flag: i32) -> io::Result<Vec<u8>> {
   unsafe {
     let mut mib = [CTL_NET, AF_ROUTE, 0, family, rt, flag];
@@ -739,7 +792,7 @@ fn fetch(family: i32, rt: i32, flag: i32) -> io::Result<Vec<u8>> {
     // tail of `buf` is the zero-init padding from `vec![0u8; len]`,
     // and the walker reads the leading 2 bytes of that as a
     // zero-length message header — surfacing as
-    // `Err(InvalidData "invalid message")` on platforms where the
+    // `Err(invalid_message())` on platforms where the
     // kernel routinely writes less than the size estimate
     // (especially NetBSD/OpenBSD `NET_RT_IFLIST`).
     buf.truncate(len);
@@ -748,43 +801,289 @@ fn fetch(family: i32, rt: i32, flag: i32) -> io::Result<Vec<u8>> {
   }
 }
 
+/// Maps the kernel's `ifi_link_state` to [`Interface::has_carrier`].
+///
+/// FreeBSD, DragonFly and NetBSD share `LINK_STATE_UNKNOWN = 0`,
+/// `LINK_STATE_DOWN = 1`, `LINK_STATE_UP = 2`. The field width differs
+/// (`u8` / `c_ulong` / `c_int`), so it is widened before matching.
+#[cfg(any(target_os = "freebsd", target_os = "dragonfly", target_os = "netbsd"))]
+#[inline]
+fn carrier_from_data(data: &libc::if_data) -> Option<bool> {
+  match data.ifi_link_state as u64 {
+    1 => Some(false),
+    2 => Some(true),
+    _ => None,
+  }
+}
+
+/// OpenBSD numbers its link states differently: `LINK_STATE_DOWN = 2`
+/// and `LINK_STATE_KALIVE_DOWN = 3` are down, `LINK_STATE_UP = 4` and
+/// the half/full-duplex variants above it are up.
+#[cfg(target_os = "openbsd")]
+#[inline]
+fn carrier_from_data(data: &libc::if_data) -> Option<bool> {
+  match data.ifi_link_state as i32 {
+    2 | 3 => Some(false),
+    s if s >= 4 => Some(true),
+    _ => None,
+  }
+}
+
+/// Apple's `if_data` has no link-state field; the media status is only
+/// reachable through a per-interface `SIOCGIFMEDIA` ioctl.
+#[cfg(apple)]
+#[inline]
+fn carrier_from_data(_data: &libc::if_data) -> Option<bool> {
+  None
+}
+
+/// Derives [`Interface::oper_status`] from the administrative state and
+/// the link state. The BSDs have no RFC 2863 operational status, so an
+/// administratively-up interface is up or down by its carrier alone.
+#[inline]
+fn oper_status_from(flags: Flags, carrier: Option<bool>) -> OperStatus {
+  if !flags.contains(Flags::UP) {
+    return OperStatus::Down;
+  }
+  match carrier {
+    Some(true) => OperStatus::Up,
+    Some(false) => OperStatus::Down,
+    None => OperStatus::Unknown,
+  }
+}
+
+/// Maps `ifi_type` to [`Interface::kind`]. XNU registers `utun` as
+/// `IFT_OTHER`, so on Apple its name is what marks it as a tunnel.
+#[inline]
+fn kind_from(if_type: u8, name: &str) -> crate::InterfaceKind {
+  #[cfg(apple)]
+  if crate::AppleInterfaceHint::from_name(name) == Some(crate::AppleInterfaceHint::Tunnel) {
+    return crate::InterfaceKind::Tunnel;
+  }
+  #[cfg(not(apple))]
+  let _ = name;
+  crate::utils::iana_kind(u32::from(if_type))
+}
+
+/// Converts the kernel's `ifi_lastchange` stamp, counted from
+/// `anchor`, into a [`SystemTime`]. A zero stamp means the kernel never
+/// recorded one.
+#[inline]
+fn last_change_at(anchor: SystemTime, secs: i64, nanos: u32) -> Option<SystemTime> {
+  if secs <= 0 && nanos == 0 {
+    return None;
+  }
+  anchor.checked_add(Duration::new(secs.max(0) as u64, nanos.min(999_999_999)))
+}
+
+/// FreeBSD keeps `ifi_lastchange` inside a union with a 32-bit compat
+/// layout; the native `timeval` arm is the one the kernel fills here.
+#[cfg(target_os = "freebsd")]
+#[inline]
+fn last_change_from_data(data: &libc::if_data) -> Option<SystemTime> {
+  // SAFETY: every bit pattern is a valid `timeval`.
+  let tv = unsafe { data.__ifi_lastchange.tv };
+  last_change_at(
+    UNIX_EPOCH,
+    tv.tv_sec as _,
+    (tv.tv_usec as u32).saturating_mul(1000),
+  )
+}
+
+/// NetBSD stores a `timespec`; DragonFly and OpenBSD a `timeval`.
+/// Both count from the epoch.
+#[cfg(target_os = "netbsd")]
+#[inline]
+fn last_change_from_data(data: &libc::if_data) -> Option<SystemTime> {
+  let ts = data.ifi_lastchange;
+  last_change_at(UNIX_EPOCH, ts.tv_sec as _, ts.tv_nsec as u32)
+}
+
+#[cfg(any(target_os = "dragonfly", target_os = "openbsd"))]
+#[inline]
+fn last_change_from_data(data: &libc::if_data) -> Option<SystemTime> {
+  let tv = data.ifi_lastchange;
+  last_change_at(
+    UNIX_EPOCH,
+    tv.tv_sec as _,
+    (tv.tv_usec as u32).saturating_mul(1000),
+  )
+}
+
+/// XNU stamps `ifi_lastchange` with `net_uptime()`, seconds since boot,
+/// so it is counted from `kern.boottime` rather than the epoch.
+#[cfg(apple)]
+#[inline]
+fn last_change_from_data(data: &libc::if_data) -> Option<SystemTime> {
+  let tv = data.ifi_lastchange;
+  last_change_at(
+    boot_time()?,
+    tv.tv_sec as _,
+    (tv.tv_usec as u32).saturating_mul(1000),
+  )
+}
+
+/// The wall-clock time the system booted, from `kern.boottime`.
+#[cfg(apple)]
+fn boot_time() -> Option<SystemTime> {
+  let mut mib = [libc::CTL_KERN, libc::KERN_BOOTTIME];
+  // SAFETY: `timeval` is plain-old-data; all-zero is a valid value.
+  let mut tv: libc::timeval = unsafe { mem::zeroed() };
+  let mut len = mem::size_of::<libc::timeval>();
+  // SAFETY: `tv` is writable for `len` bytes and the kernel writes at
+  // most that much.
+  let rc = unsafe {
+    libc::sysctl(
+      mib.as_mut_ptr(),
+      mib.len() as _,
+      (&mut tv as *mut libc::timeval).cast(),
+      &mut len,
+      null_mut(),
+      0,
+    )
+  };
+  if rc < 0 || len != mem::size_of::<libc::timeval>() {
+    return None;
+  }
+  last_change_at(
+    UNIX_EPOCH,
+    tv.tv_sec as _,
+    (tv.tv_usec as u32).saturating_mul(1000),
+  )
+}
+
 pub(super) fn interface_table(idx: u32) -> io::Result<TinyVec<Interface>> {
-  unsafe {
-    let buf = fetch(AF_UNSPEC, NET_RT_IFLIST, idx as i32)?;
-    let mut results = TinyVec::new();
+  interface_table_into(idx, None)
+}
 
-    let mut src = buf.as_slice();
-    while src.len() > 4 {
-      let l = u16::from_ne_bytes(src[..2].try_into().unwrap()) as usize;
-      if l == 0 {
-        return Err(invalid_message());
+/// Like [`interface_table`], but an `RTM_IFINFO` record whose
+/// link-layer `sockaddr_dl` fails to parse is recorded in `errors` and
+/// skipped instead of failing the whole table. A bad message length
+/// is still fatal: the walk cannot find the next record past it.
+pub(super) fn interface_table_lossy(
+  errors: &mut Vec<EnumerationError>,
+) -> io::Result<TinyVec<Interface>> {
+  interface_table_into(0, Some(errors))
+}
+
+fn interface_table_into(
+  idx: u32,
+  mut errors: Option<&mut Vec<EnumerationError>>,
+) -> io::Result<TinyVec<Interface>> {
+  let mut results: TinyVec<Interface> = TinyVec::new();
+  for interface in interface_iter(idx)? {
+    match (interface, errors.as_deref_mut()) {
+      (Ok(interface), _) => results.push(interface),
+      (Err(e), Some(errors)) => errors.push(e),
+      (Err(e), None) => return Err(e.into_error().into()),
+    }
+  }
+  Ok(results)
+}
+
+/// Fetches the `NET_RT_IFLIST` dump and returns an iterator that
+/// decodes its `RTM_IFINFO` records one at a time.
+///
+/// The message lengths are checked up front, so the iterator itself
+/// only fails per interface, when a record's `sockaddr_dl` does not
+/// parse.
+pub(super) fn interface_iter(idx: u32) -> io::Result<InterfaceIter> {
+  let buf = fetch(AF_UNSPEC, NET_RT_IFLIST, idx as i32)?;
+  check_iflist(&buf)?;
+  Ok(InterfaceIter { buf, pos: 0 })
+}
+
+/// Validates a `NET_RT_IFLIST` dump before [`InterfaceIter`] walks it:
+/// every message length, the headers the iterator reads, and that each
+/// `RTM_NEWADDR` names the interface of the `RTM_IFINFO` before it.
+fn check_iflist(buf: &[u8]) -> io::Result<()> {
+  // Index of the last `RTM_IFINFO` seen, for the `RTM_NEWADDR` records
+  // that follow it.
+  let mut last_index = None;
+  let mut src = buf;
+  while src.len() > 4 {
+    let l = u16::from_ne_bytes(src[..2].try_into().unwrap()) as usize;
+    if l == 0 {
+      return Err(invalid_message());
+    }
+    if src.len() < l {
+      return Err(message_too_short());
+    }
+    // The `src.len() < l` guard only proves the message fits in the
+    // sysctl buffer. An `RTM_IFINFO` *also* needs `l >= HEADER_SIZE`
+    // so the iterator's `read_unaligned` doesn't read past the message
+    // and its slice can't underflow.
+    if src[2] as i32 == RTM_VERSION && src[3] as i32 == RTM_IFINFO {
+      if l < InterfaceIter::HEADER_SIZE {
+        return Err(message_too_short());
       }
-      if src.len() < l {
+      // SAFETY: the message holds a whole `if_msghdr`; see the
+      // iterator for why the read is unaligned.
+      let ifm: if_msghdr = unsafe { core::ptr::read_unaligned(src.as_ptr() as *const if_msghdr) };
+      last_index = Some(kernel_index(ifm.ifm_index));
+    } else if src[2] as i32 == RTM_VERSION && src[3] as i32 == RTM_NEWADDR {
+      // `NET_RT_IFLIST` emits each interface's `RTM_NEWADDR` messages
+      // directly after its `RTM_IFINFO`. The address walker keys
+      // addresses by `ifam_index` while this walker keys interfaces by
+      // `ifm_index`; a dump where the two disagree would attribute
+      // addresses to the wrong interface, so it is rejected.
+      if l < mem::size_of::<ifa_msghdr>() {
         return Err(message_too_short());
       }
+      // SAFETY: as above, for a whole `ifa_msghdr`.
+      let ifam: ifa_msghdr =
+        unsafe { core::ptr::read_unaligned(src.as_ptr() as *const ifa_msghdr) };
+      if last_index.is_some_and(|last| last != kernel_index(ifam.ifam_index)) {
+        return Err(invalid_message());
+      }
+    }
+    src = &src[l..];
+  }
+  Ok(())
+}
+
+/// Iterator over the interfaces of a `NET_RT_IFLIST` dump. It owns the
+/// sysctl buffer and decodes each `RTM_IFINFO` only when asked for the
+/// next interface, so stopping early skips the rest.
+pub(crate) struct InterfaceIter {
+  /// Messages whose lengths [`interface_iter`] already checked.
+  buf: Vec<u8>,
+  pos: usize,
+}
+
+impl InterfaceIter {
+  const HEADER_SIZE: usize = size_of::<if_msghdr>();
+}
+
+impl Iterator for InterfaceIter {
+  type Item = Result<Interface, EnumerationError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.buf.len() - self.pos > 4 {
+      let src = &self.buf[self.pos..];
+      let l = u16::from_ne_bytes(src[..2].try_into().unwrap()) as usize;
+      self.pos += l;
 
       if src[2] as i32 != libc::RTM_VERSION {
-        src = &src[l..];
         continue;
       }
 
       if src[3] as i32 == libc::RTM_IFINFO {
-        const HEADER_SIZE: usize = size_of::<if_msghdr>();
-        // The outer `src.len() < l` guard only proves the message fits
-        // in the sysctl buffer. We *also* need `l >= HEADER_SIZE` so
-        // the upcoming `read_unaligned` doesn't read past the message
-        // and the slice below can't underflow.
-        if l < HEADER_SIZE {
-          return Err(message_too_short());
-        }
         // SAFETY: `src` is a `Vec<u8>` from sysctl which only
         // formally guarantees u8 alignment; `read_unaligned` copies
         // into an aligned local without that requirement.
-        let ifm: if_msghdr = core::ptr::read_unaligned(src.as_ptr() as *const if_msghdr);
+        let ifm: if_msghdr = unsafe { core::ptr::read_unaligned(src.as_ptr() as *const if_msghdr) };
         if ifm.ifm_type as i32 == RTM_IFINFO {
-          let (name, mac) = parse(&src[HEADER_SIZE..l])?;
-          let interface = Interface {
-            index: ifm.ifm_index as u32,
+          let index = kernel_index(ifm.ifm_index);
+          let (name, mac) = match parse(&src[Self::HEADER_SIZE..l]) {
+            Ok(parsed) => parsed,
+            Err(e) => return Some(Err(EnumerationError::new(Some(index), e))),
+          };
+          let flags = Flags::from_bits_truncate(ifm.ifm_flags as u32);
+          let carrier = carrier_from_data(&ifm.ifm_data);
+          let kind = kind_from(ifm.ifm_data.ifi_type, &name);
+          return Some(Ok(Interface {
+            index,
             // `ifi_mtu` is `u_int32_t` on Apple, `u_long` on FreeBSD/
             // DragonFly, `uint64_t` on NetBSD, `u_int` on OpenBSD. Cast
             // narrows to `u32` to match `Interface.mtu`'s type —
@@ -793,17 +1092,113 @@ pub(super) fn interface_table(idx: u32) -> io::Result<TinyVec<Interface>> {
             mtu: ifm.ifm_data.ifi_mtu as u32,
             name,
             mac_addr: mac,
-            flags: Flags::from_bits_truncate(ifm.ifm_flags as u32),
-          };
-          results.push(interface);
+            flags,
+            carrier,
+            oper_status: oper_status_from(flags, carrier),
+            media_type: crate::utils::iana_media_type(u32::from(ifm.ifm_data.ifi_type)),
+            kind,
+            link_speed: route::nonzero(ifm.ifm_data.ifi_baudrate),
+            last_change: last_change_from_data(&ifm.ifm_data),
+          }));
         }
       }
+    }
+    None
+  }
+}
 
-      src = &src[l..];
+/// Reads the 64-bit counters of interface `idx` from the
+/// `RTM_IFINFO2` record `NET_RT_IFLIST2` emits for it. The legacy
+/// `if_data` in `RTM_IFINFO` only has 32-bit counters, which wrap after
+/// 4 GiB of traffic.
+#[cfg(apple)]
+pub(super) fn interface_stats(idx: u32) -> io::Result<InterfaceStats> {
+  const HEADER_SIZE: usize = mem::size_of::<libc::if_msghdr2>();
+
+  let buf = fetch(AF_UNSPEC, NET_RT_IFLIST2, idx as i32)?;
+  let mut src = buf.as_slice();
+  while src.len() > 4 {
+    let l = u16::from_ne_bytes(src[..2].try_into().unwrap()) as usize;
+    if l == 0 {
+      return Err(invalid_message());
+    }
+    if src.len() < l {
+      return Err(message_too_short());
     }
 
-    Ok(results)
+    if src[2] as i32 == RTM_VERSION && src[3] as i32 == libc::RTM_IFINFO2 {
+      if l < HEADER_SIZE {
+        return Err(message_too_short());
+      }
+      // SAFETY: u8-aligned sysctl buffer; copy the header out before
+      // reading fields.
+      let ifm: libc::if_msghdr2 =
+        unsafe { core::ptr::read_unaligned(src.as_ptr() as *const libc::if_msghdr2) };
+      if kernel_index(ifm.ifm_index) == idx {
+        let data = &ifm.ifm_data;
+        return Ok(InterfaceStats {
+          rx_bytes: data.ifi_ibytes,
+          tx_bytes: data.ifi_obytes,
+          rx_packets: data.ifi_ipackets,
+          tx_packets: data.ifi_opackets,
+          rx_errors: data.ifi_ierrors,
+          tx_errors: data.ifi_oerrors,
+        });
+      }
+    }
+
+    src = &src[l..];
   }
+
+  Err(Error::InterfaceNotFound.into())
+}
+
+/// Reads the counters of interface `idx` from the `if_data` of the
+/// `RTM_IFINFO` record `NET_RT_IFLIST` emits for it.
+#[cfg(not(apple))]
+pub(super) fn interface_stats(idx: u32) -> io::Result<InterfaceStats> {
+  const HEADER_SIZE: usize = mem::size_of::<if_msghdr>();
+
+  // The counters are `u_long` on DragonFly and `uint64_t` elsewhere.
+  fn counter<T: TryInto<u64>>(v: T) -> u64 {
+    v.try_into().unwrap_or(0)
+  }
+
+  let buf = fetch(AF_UNSPEC, NET_RT_IFLIST, idx as i32)?;
+  let mut src = buf.as_slice();
+  while src.len() > 4 {
+    let l = u16::from_ne_bytes(src[..2].try_into().unwrap()) as usize;
+    if l == 0 {
+      return Err(invalid_message());
+    }
+    if src.len() < l {
+      return Err(message_too_short());
+    }
+
+    if src[2] as i32 == RTM_VERSION && src[3] as i32 == RTM_IFINFO {
+      if l < HEADER_SIZE {
+        return Err(message_too_short());
+      }
+      // SAFETY: u8-aligned sysctl buffer; copy the header out before
+      // reading fields.
+      let ifm: if_msghdr = unsafe { core::ptr::read_unaligned(src.as_ptr() as *const if_msghdr) };
+      if kernel_index(ifm.ifm_index) == idx {
+        let data = &ifm.ifm_data;
+        return Ok(InterfaceStats {
+          rx_bytes: counter(data.ifi_ibytes),
+          tx_bytes: counter(data.ifi_obytes),
+          rx_packets: counter(data.ifi_ipackets),
+          tx_packets: counter(data.ifi_opackets),
+          rx_errors: counter(data.ifi_ierrors),
+          tx_errors: counter(data.ifi_oerrors),
+        });
+      }
+    }
+
+    src = &src[l..];
+  }
+
+  Err(Error::InterfaceNotFound.into())
 }
 
 pub(super) fn interface_ipv4_addresses<F>(idx: u32, f: F) -> io::Result<SmallVec<Ifv4Net>>
@@ -843,12 +1238,33 @@ where
 pub(super) fn interface_addr_table_into<T, F>(
   family: i32,
   idx: u32,
-  mut f: F,
+  f: F,
   results: &mut SmallVec<T>,
 ) -> io::Result<()>
 where
   T: Net,
   F: FnMut(&IpAddr) -> bool,
+{
+  interface_addr_walk(family, idx, f, |addr| {
+    results.push(addr);
+    ControlFlow::Continue(())
+  })
+}
+
+pub(super) fn for_each_interface_addr<F>(mut f: F) -> io::Result<()>
+where
+  F: FnMut(&IfNet) -> ControlFlow<()>,
+{
+  interface_addr_walk(AF_UNSPEC, 0, |_| true, |net: IfNet| f(&net))
+}
+
+/// Walks the `NET_RT_IFLIST` dump, handing each address to `emit` as
+/// it is decoded until `emit` breaks.
+fn interface_addr_walk<T, F, E>(family: i32, idx: u32, mut f: F, mut emit: E) -> io::Result<()>
+where
+  T: Net,
+  F: FnMut(&IpAddr) -> bool,
+  E: FnMut(T) -> ControlFlow<()>,
 {
   const HEADER_SIZE: usize = mem::size_of::<ifa_msghdr>();
 
@@ -870,7 +1286,9 @@ where
         return Err(message_too_short());
       }
 
-      if (ifam.ifam_version as i32 != RTM_VERSION) || (ifam.ifam_index as u32 != idx && idx != 0) {
+      if (ifam.ifam_version as i32 != RTM_VERSION)
+        || (kernel_index(ifam.ifam_index) != idx && idx != 0)
+      {
         b = &b[len..];
         continue;
       }
@@ -896,9 +1314,12 @@ where
         // single interface they can detect the gap themselves.
         if let (Some(ip), Some(Ok(prefix))) = (ip, mask) {
           if let Some(ifa) =
-            T::try_from_with_filter(ifam.ifam_index as u32, ip, prefix, |addr| f(addr))
+            T::try_from_with_filter(kernel_index(ifam.ifam_index), ip, prefix, |addr| f(addr))
           {
-            results.push(ifa);
+            let ifa = ifa.with_broadcast(broadcast_of(ip, prefix, addrs[RTAX_BRD as usize]));
+            if emit(ifa).is_break() {
+              break;
+            }
           }
         }
       }
@@ -910,6 +1331,19 @@ where
   }
 }
 
+/// `RTAX_BRD` doubles as the peer address on point-to-point links, so
+/// it is taken as the broadcast address only when it lies inside the
+/// address's own subnet and is not the address itself.
+fn broadcast_of(ip: IpAddr, prefix: u8, brd: Option<IpAddr>) -> Option<Ipv4Addr> {
+  match (ip, brd) {
+    (IpAddr::V4(ip), Some(IpAddr::V4(brd))) if prefix < 31 && brd != ip => Ipv4Net::new(ip, prefix)
+      .ok()
+      .filter(|net| net.contains(&brd))
+      .map(|_| brd),
+    _ => None,
+  }
+}
+
 cfg_bsd_multicast!(
   pub(super) fn interface_multicast_ipv4_addresses<F>(
     idx: u32,
@@ -949,51 +1383,29 @@ cfg_apple!(
   pub(super) fn interface_multiaddr_table<T, F>(
     family: i32,
     idx: u32,
-    mut f: F,
+    f: F,
   ) -> io::Result<SmallVec<T>>
   where
     T: Address,
     F: FnMut(&IpAddr) -> bool,
   {
-    const HEADER_SIZE: usize = mem::size_of::<libc::ifma_msghdr2>();
-
-    unsafe {
-      let buf = fetch(family, NET_RT_IFLIST2, idx as i32)?;
-
-      let mut results = SmallVec::new();
-      let mut b = buf.as_slice();
-
-      while b.len() > HEADER_SIZE {
-        // SAFETY: u8-aligned sysctl buffer; copy header out before reading fields.
-        let ifam: libc::ifma_msghdr2 =
-          core::ptr::read_unaligned(b.as_ptr() as *const libc::ifma_msghdr2);
-        let len = ifam.ifmam_msglen as usize;
-
-        // Same per-message length checks as `interface_addr_table`.
-        if len < HEADER_SIZE || len > b.len() {
-          return Err(message_too_short());
-        }
-
-        if ifam.ifmam_version as i32 != RTM_VERSION {
-          b = &b[len..];
-          continue;
-        }
-
-        if ifam.ifmam_type as i32 == libc::RTM_NEWMADDR2 {
-          let addrs = parse_addrs(ifam.ifmam_addrs as u32, &b[HEADER_SIZE..len])?;
-
-          if let Some(ip) = addrs[RTAX_IFA as usize].as_ref() {
-            if let Some(ip) = T::try_from_with_filter(ifam.ifmam_index as u32, *ip, |addr| f(addr))
-            {
-              results.push(ip);
-            }
-          }
-        }
-
-        b = &b[len..];
-      }
-
-      Ok(results)
+    match fetch(family, NET_RT_IFLIST2, idx as i32) {
+      Ok(buf) => multiaddr_records(
+        &buf,
+        mem::size_of::<libc::ifma_msghdr2>(),
+        libc::RTM_NEWMADDR2,
+        f,
+      ),
+      // Very old releases and some sandboxes refuse the `2` selector.
+      // Fall back to the legacy dump and keep whatever `ifma_msghdr`
+      // records it carries; current kernels put none there, so this
+      // degrades to an empty list instead of failing.
+      Err(_) => multiaddr_records(
+        &fetch(family, NET_RT_IFLIST, idx as i32)?,
+        mem::size_of::<compat::IfmaMsghdr>(),
+        libc::RTM_NEWMADDR,
+        f,
+      ),
     }
   }
 );
@@ -1006,7 +1418,7 @@ cfg_apple!(
 pub(super) fn interface_multiaddr_table<T, F>(
   family: i32,
   idx: u32,
-  mut f: F,
+  f: F,
 ) -> io::Result<SmallVec<T>>
 where
   T: Address,
@@ -1014,43 +1426,59 @@ where
 {
   use compat::{IfmaMsghdr, NET_RT_IFMALIST};
 
-  const HEADER_SIZE: usize = mem::size_of::<IfmaMsghdr>();
+  let buf = fetch(family, NET_RT_IFMALIST, idx as i32)?;
+  multiaddr_records(&buf, mem::size_of::<IfmaMsghdr>(), libc::RTM_NEWMADDR, f)
+}
 
-  unsafe {
-    let buf = fetch(family, NET_RT_IFMALIST, idx as i32)?;
-    let mut results = SmallVec::new();
-    let mut b = buf.as_slice();
+/// Collects the group addresses of the `msg_type` records in a
+/// multicast dump whose headers are `header_size` bytes long.
+///
+/// Apple's `ifma_msghdr2` only appends fields to `ifma_msghdr`, so the
+/// shared prefix is read as `ifma_msghdr` for either shape.
+#[cfg(any(apple, target_os = "freebsd"))]
+fn multiaddr_records<T, F>(
+  buf: &[u8],
+  header_size: usize,
+  msg_type: i32,
+  mut f: F,
+) -> io::Result<SmallVec<T>>
+where
+  T: Address,
+  F: FnMut(&IpAddr) -> bool,
+{
+  use compat::IfmaMsghdr;
 
-    while b.len() > HEADER_SIZE {
-      // SAFETY: u8-aligned sysctl buffer; copy header out before reading fields.
-      let ifam: IfmaMsghdr = core::ptr::read_unaligned(b.as_ptr() as *const IfmaMsghdr);
-      let len = ifam.ifmam_msglen as usize;
+  let mut results = SmallVec::new();
+  let mut b = buf;
 
-      // Same per-message length checks as `interface_addr_table`.
-      if len < HEADER_SIZE || len > b.len() {
-        return Err(message_too_short());
-      }
+  while b.len() > header_size {
+    // SAFETY: u8-aligned sysctl buffer holding at least `header_size`
+    // bytes, which covers the prefix; copy it out before reading
+    // fields.
+    let ifam: IfmaMsghdr = unsafe { core::ptr::read_unaligned(b.as_ptr() as *const IfmaMsghdr) };
+    let len = ifam.ifmam_msglen as usize;
 
-      if ifam.ifmam_version as i32 != RTM_VERSION {
-        b = &b[len..];
-        continue;
-      }
+    // Same per-message length checks as `interface_addr_table`.
+    if len < header_size || len > b.len() {
+      return Err(message_too_short());
+    }
 
-      if ifam.ifmam_type as i32 == libc::RTM_NEWMADDR {
-        let addrs = parse_addrs(ifam.ifmam_addrs as u32, &b[HEADER_SIZE..len])?;
+    if ifam.ifmam_version as i32 == RTM_VERSION && ifam.ifmam_type as i32 == msg_type {
+      let addrs = parse_addrs(ifam.ifmam_addrs as u32, &b[header_size..len])?;
 
-        if let Some(ip) = addrs[RTAX_IFA as usize].as_ref() {
-          if let Some(ip) = T::try_from_with_filter(ifam.ifmam_index as u32, *ip, |addr| f(addr)) {
-            results.push(ip);
-          }
+      if let Some(ip) = addrs[RTAX_IFA as usize].as_ref() {
+        if let Some(ip) =
+          T::try_from_with_filter(kernel_index(ifam.ifmam_index), *ip, |addr| f(addr))
+        {
+          results.push(ip);
         }
       }
-
-      b = &b[len..];
     }
 
-    Ok(results)
+    b = &b[len..];
   }
+
+  Ok(results)
 }
 
 // DragonFly stub: the kernel does not expose multicast group
@@ -1060,11 +1488,11 @@ where
 //
 // The public API still surfaces on DragonFly so cross-platform
 // callers compile and link without target-specific cfgs, but a real
-// call returns `ErrorKind::Unsupported` rather than a misleading
+// call returns `Error::UnsupportedPlatform` rather than a misleading
 // empty `Ok` — `Ok(SmallVec::new())` would be indistinguishable from
 // a host with multicast enumeration available but no current
 // memberships, which is wrong-by-default semantics for everyone
-// reading the result. Callers can match on `ErrorKind::Unsupported`
+// reading the result. Callers can match on `Error::UnsupportedPlatform`
 // when they want to treat DragonFly the same way as platforms with
 // the kernel API absent.
 #[cfg(target_os = "dragonfly")]
@@ -1077,11 +1505,8 @@ where
   T: Address,
   F: FnMut(&IpAddr) -> bool,
 {
-  Err(io::Error::new(
-    io::ErrorKind::Unsupported,
-    "multicast group enumeration is not supported on DragonFly \
-     (no NET_RT_IFMALIST sysctl selector)",
-  ))
+  // DragonFly has no NET_RT_IFMALIST sysctl selector.
+  Err(Error::UnsupportedPlatform.into())
 }
 
 #[cfg(test)]
@@ -1094,6 +1519,31 @@ mod tests {
   // that are otherwise reachable only via specific kernel-message
   // shapes which a live test environment doesn't necessarily emit.
 
+  // `ifi_lastchange` counts from boot on Apple, so every stamp lands
+  // between boot and now instead of in early 1970.
+  #[cfg(apple)]
+  #[test]
+  fn last_change_is_after_boot() {
+    let boot = boot_time().unwrap();
+    let now = SystemTime::now();
+    assert!(boot < now);
+    for ifi in interface_table(0).unwrap() {
+      if let Some(at) = ifi.last_change {
+        assert!(at >= boot && at <= now, "{ifi:?}");
+      }
+    }
+  }
+
+  // `utun` comes with `IFT_OTHER`; the name still makes it a tunnel,
+  // while other `IFT_OTHER` interfaces keep the raw code.
+  #[cfg(apple)]
+  #[test]
+  fn utun_kind_is_tunnel() {
+    assert_eq!(kind_from(1, "utun4"), crate::InterfaceKind::Tunnel);
+    assert_eq!(kind_from(1, "ap1"), crate::InterfaceKind::Other(1));
+    assert_eq!(kind_from(6, "en0"), crate::InterfaceKind::Ethernet);
+  }
+
   #[test]
   fn family_unavailable_collapses_known_errnos() {
     for c in [libc::EAFNOSUPPORT, libc::EPROTONOSUPPORT, libc::EOPNOTSUPP] {
@@ -1131,6 +1581,19 @@ mod tests {
     assert_eq!(roundup(KERNAL_ALIGN + 1), 2 * KERNAL_ALIGN);
   }
 
+  #[test]
+  fn parse_non_utf8_name_is_lossy() {
+    // `sockaddr_dl` with a 4-byte name holding an invalid UTF-8 byte
+    // and a 6-byte MAC: sdl_len, sdl_family, sdl_index (2), then type,
+    // nlen, alen, slen and the data.
+    let mut b = vec![20u8, libc::AF_LINK as u8, 1, 0, 6, 4, 6, 0];
+    b.extend_from_slice(b"em\xff0");
+    b.extend_from_slice(&[0x02, 0, 0, 0, 0, 1]);
+    let (name, mac) = parse(&b).unwrap();
+    assert_eq!(name.as_str(), "em\u{fffd}0");
+    assert!(mac.is_some());
+  }
+
   #[test]
   fn parse_short_inet_addr_zero_extends_v4() {
     // BSD compact `RTAX_NETMASK` for `255.255.255.0` (a `/24`):
@@ -1173,9 +1636,193 @@ mod tests {
     assert!(parse_inet_addr(libc::AF_INET6, &buf).is_err());
   }
 
+  // The embedded zone is cleared from scoped addresses only; a global
+  // address with the same bytes in that position is left alone.
+  #[test]
+  fn strip_embedded_scope_clears_scoped_only() {
+    let kernel = |s: &str| s.parse::<Ipv6Addr>().unwrap().octets();
+    assert_eq!(
+      strip_embedded_scope(kernel("fe80:4::1")),
+      "fe80::1".parse::<Ipv6Addr>().unwrap()
+    );
+    assert_eq!(
+      strip_embedded_scope(kernel("ff02:4::fb")),
+      "ff02::fb".parse::<Ipv6Addr>().unwrap()
+    );
+    assert_eq!(
+      strip_embedded_scope(kernel("2001:db8::1")),
+      "2001:db8::1".parse::<Ipv6Addr>().unwrap()
+    );
+  }
+
   #[test]
   fn parse_inet_addr_unknown_family_errors() {
     let buf = [0u8; 32];
     assert!(parse_inet_addr(0xff, &buf).is_err());
   }
+
+  #[test]
+  fn kernel_index_is_lossless() {
+    assert_eq!(kernel_index(0), 0);
+    assert_eq!(kernel_index(0x8000), 0x8000);
+    assert_eq!(kernel_index(u16::MAX), u16::MAX as u32);
+  }
+
+  // An `RTM_NEWADDR` must follow the `RTM_IFINFO` of its own interface,
+  // and both must hold their whole header; a dump that breaks either is
+  // rejected rather than attributing addresses to the wrong interface.
+  #[test]
+  fn check_iflist_rejects_inconsistent_dumps() {
+    fn message<T>(mut hdr: T, ty: i32, set: impl FnOnce(&mut T)) -> Vec<u8> {
+      set(&mut hdr);
+      let mut bytes = vec![0u8; mem::size_of::<T>()];
+      // SAFETY: `bytes` is exactly as long as `T`.
+      unsafe { core::ptr::write_unaligned(bytes.as_mut_ptr() as *mut T, hdr) };
+      let len = bytes.len() as u16;
+      bytes[..2].copy_from_slice(&len.to_ne_bytes());
+      bytes[2] = RTM_VERSION as u8;
+      bytes[3] = ty as u8;
+      bytes
+    }
+    // SAFETY: both headers are plain C structs.
+    let ifinfo = |index| {
+      message(unsafe { mem::zeroed::<if_msghdr>() }, RTM_IFINFO, |h| {
+        h.ifm_index = index
+      })
+    };
+    let newaddr = |index| {
+      message(unsafe { mem::zeroed::<ifa_msghdr>() }, RTM_NEWADDR, |h| {
+        h.ifam_index = index
+      })
+    };
+
+    let good = [ifinfo(1), newaddr(1), ifinfo(2), newaddr(2)].concat();
+    check_iflist(&good).unwrap();
+
+    let mismatched = [ifinfo(1), newaddr(2)].concat();
+    assert_eq!(
+      check_iflist(&mismatched).unwrap_err().kind(),
+      io::ErrorKind::InvalidData
+    );
+
+    let mut short = newaddr(1);
+    short.truncate(8);
+    short[..2].copy_from_slice(&8u16.to_ne_bytes());
+    let short = [ifinfo(1), short].concat();
+    assert_eq!(
+      check_iflist(&short).unwrap_err().kind(),
+      io::ErrorKind::InvalidData
+    );
+  }
+
+  // The interface table (`ifm_index`) and the address table
+  // (`ifam_index`) must agree on every interface's index. Both the
+  // unfiltered dump and the per-interface dump are cross-checked:
+  // every address must belong to a known interface, and a
+  // per-interface query must only return addresses carrying that
+  // interface's index. Skipped on NetBSD (the `parse_addrs` gap
+  // documented on `tests/interfaces.rs::if_addrs`) and DragonFly
+  // (vmactions interface churn).
+  #[cfg(not(any(target_os = "netbsd", target_os = "dragonfly")))]
+  #[test]
+  fn interface_and_address_tables_agree_on_index() {
+    let ift = interface_table(0).unwrap();
+    let addrs: SmallVec<IfNet> = interface_addr_table(AF_UNSPEC, 0, |_| true).unwrap();
+    for addr in &addrs {
+      assert!(
+        ift.iter().any(|ifi| ifi.index == addr.index()),
+        "address {addr} has no matching interface"
+      );
+    }
+
+    for ifi in &ift {
+      let own: SmallVec<IfNet> = interface_addr_table(AF_UNSPEC, ifi.index, |_| true).unwrap();
+      for addr in &own {
+        assert_eq!(addr.index(), ifi.index, "{addr} on {}", ifi.name);
+      }
+
+      #[cfg(any(target_vendor = "apple", target_os = "freebsd"))]
+      {
+        let multi: SmallVec<IfAddr> =
+          interface_multiaddr_table(AF_UNSPEC, ifi.index, |_| true).unwrap();
+        for addr in &multi {
+          assert_eq!(addr.index(), ifi.index, "{addr} on {}", ifi.name);
+        }
+      }
+    }
+  }
+
+  // `RTAX_BRD` is a broadcast address only inside the subnet; a
+  // point-to-point peer in the same slot is not one.
+  #[test]
+  fn broadcast_of_rejects_point_to_point_peer() {
+    let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    let brd = |a, b, c, d| Some(IpAddr::V4(Ipv4Addr::new(a, b, c, d)));
+    assert_eq!(
+      broadcast_of(ip, 24, brd(192, 0, 2, 255)),
+      Some(Ipv4Addr::new(192, 0, 2, 255))
+    );
+    assert_eq!(broadcast_of(ip, 32, brd(198, 51, 100, 1)), None);
+    assert_eq!(broadcast_of(ip, 24, brd(198, 51, 100, 1)), None);
+    assert_eq!(broadcast_of(ip, 24, Some(ip)), None);
+    assert_eq!(broadcast_of(ip, 24, None), None);
+  }
+
+  // A legacy `ifma_msghdr` record, the shape FreeBSD and Apple's
+  // fallback dump use, yields its group address and index.
+  #[cfg(any(target_vendor = "apple", target_os = "freebsd"))]
+  #[test]
+  fn multiaddr_records_read_legacy_header() {
+    use compat::IfmaMsghdr;
+
+    let header_size = mem::size_of::<IfmaMsghdr>();
+    let sin = [
+      16u8,
+      AF_INET as u8,
+      0,
+      0,
+      224,
+      0,
+      0,
+      251,
+      0,
+      0,
+      0,
+      0,
+      0,
+      0,
+      0,
+      0,
+    ];
+    let mut hdr: IfmaMsghdr = unsafe { mem::zeroed() };
+    hdr.ifmam_msglen = (header_size + sin.len()) as u16;
+    hdr.ifmam_version = RTM_VERSION as u8;
+    hdr.ifmam_type = libc::RTM_NEWMADDR as u8;
+    hdr.ifmam_addrs = libc::RTA_IFA;
+    hdr.ifmam_index = 3;
+    let mut buf =
+      unsafe { std::slice::from_raw_parts(&hdr as *const IfmaMsghdr as *const u8, header_size) }
+        .to_vec();
+    buf.extend_from_slice(&sin);
+
+    let addrs: SmallVec<IfAddr> =
+      multiaddr_records(&buf, header_size, libc::RTM_NEWMADDR, |_| true).unwrap();
+    assert_eq!(addrs.len(), 1);
+    assert_eq!(addrs[0].index(), 3);
+    assert_eq!(addrs[0].addr(), IpAddr::V4(Ipv4Addr::new(224, 0, 0, 251)));
+
+    let none: SmallVec<IfAddr> =
+      multiaddr_records(&buf, header_size, libc::RTM_NEWMADDR, |_| false).unwrap();
+    assert!(none.is_empty());
+  }
+
+  // Multicast enumeration succeeds on Apple whichever dump the kernel
+  // serves; an empty list is fine, an error is not.
+  #[cfg(target_vendor = "apple")]
+  #[test]
+  fn multicast_enumeration_does_not_error() {
+    interface_multicast_addresses(0, |_| true).unwrap();
+    interface_multicast_ipv4_addresses(0, |_| true).unwrap();
+    interface_multicast_ipv6_addresses(0, |_| true).unwrap();
+  }
 }
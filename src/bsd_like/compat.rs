@@ -308,18 +308,20 @@ const _: () = {
 // ifma_msghdr (multicast group membership)
 // =====================================================================
 //
-// Apple / FreeBSD: `libc` exports the struct + `NET_RT_IFMALIST`
-// directly.
+// Apple / FreeBSD: `libc` exports the struct. FreeBSD also has
+// `NET_RT_IFMALIST`; Apple reports memberships as `ifma_msghdr2`
+// through `NET_RT_IFLIST2` and reads the legacy struct only as a
+// fallback.
 //
 // DragonFly: the kernel does not expose multicast group enumeration
 // via sysctl at all — `<sys/socket.h>` only defines four selectors
 // (`NET_RT_DUMP`, `NET_RT_FLAGS`, `NET_RT_IFLIST`, `NET_RT_MAXID`),
 // no `NET_RT_IFMALIST`. The DragonFly impl of
 // `interface_multiaddr_table` therefore returns
-// `Err(ErrorKind::Unsupported)` (see `bsd_like.rs`). It does not need
+// `Err(Error::UnsupportedPlatform)` (see `bsd_like.rs`). It does not need
 // an `IfmaMsghdr` or a sysctl selector, so we don't define them here.
 
-#[cfg(target_os = "freebsd")]
+#[cfg(any(apple, target_os = "freebsd"))]
 pub(super) use libc::ifma_msghdr as IfmaMsghdr;
 
 #[cfg(target_os = "freebsd")]
@@ -0,0 +1,435 @@
+use std::{
+  io, mem,
+  net::IpAddr,
+  os::fd::{AsRawFd, OwnedFd},
+};
+
+use ipnet::{IpNet, Ipv4Net};
+#[cfg(any(target_os = "macos", freebsdlike, netbsdlike))]
+use libc::sockaddr_in6;
+use libc::{
+  c_int, c_short, c_ulong, sockaddr_in, AF_INET, AF_ROUTE, AF_UNSPEC, IFF_UP, IFNAMSIZ, RTA_DST,
+  RTA_GATEWAY, RTA_IFP, RTA_NETMASK, RTF_GATEWAY, RTF_HOST, RTF_STATIC, RTF_UP, RTM_ADD,
+  RTM_DELETE, SOCK_DGRAM, SOCK_RAW,
+};
+
+#[cfg(any(target_os = "macos", freebsdlike, netbsdlike))]
+use super::route::sockaddr_in6;
+use super::{
+  compat::RtMsghdr,
+  route::{
+    open_socket, push_ip_sockaddr, push_sockaddr, route_message_bytes, sockaddr_dl, sockaddr_in,
+  },
+};
+
+/// Size of the `ifr_ifru` union in `struct ifreq`. NetBSD's carries a
+/// `sockaddr_storage`; everywhere else the largest member is a 16-byte
+/// `sockaddr` (or two pointers).
+#[cfg(target_os = "netbsd")]
+const IFRU_SIZE: usize = 128;
+#[cfg(not(target_os = "netbsd"))]
+const IFRU_SIZE: usize = 16;
+
+/// `struct ifreq` from <net/if.h> with the `ifr_ifru` union kept as raw
+/// bytes. Only its size has to match the kernel's, since that is
+/// encoded in the ioctl number.
+#[repr(C)]
+struct Ifreq {
+  ifr_name: [u8; IFNAMSIZ],
+  ifr_ifru: [u8; IFRU_SIZE],
+}
+
+/// `struct ifaliasreq` from <net/if.h>, the `SIOCAIFADDR` argument.
+/// FreeBSD appends the CARP `vhid`, which changes the ioctl number.
+#[repr(C)]
+struct Ifaliasreq {
+  ifra_name: [u8; IFNAMSIZ],
+  ifra_addr: sockaddr_in,
+  ifra_broadaddr: sockaddr_in,
+  ifra_mask: sockaddr_in,
+  #[cfg(target_os = "freebsd")]
+  ifra_vhid: c_int,
+}
+
+/// `_IOW('i', num, T)` / `_IOWR('i', num, T)`; the libc crate does not
+/// export the `SIOCSIF*` / `SIOC[AD]IFADDR` numbers on the BSDs.
+const fn iow<T>(num: c_ulong) -> c_ulong {
+  0x8000_0000 | (((mem::size_of::<T>() & 0x1fff) as c_ulong) << 16) | ((b'i' as c_ulong) << 8) | num
+}
+
+const fn iowr<T>(num: c_ulong) -> c_ulong {
+  0x4000_0000 | iow::<T>(num)
+}
+
+const SIOCSIFFLAGS: c_ulong = iow::<Ifreq>(16);
+const SIOCGIFFLAGS: c_ulong = iowr::<Ifreq>(17);
+const SIOCDIFADDR: c_ulong = iow::<Ifreq>(25);
+#[cfg(not(target_os = "freebsd"))]
+const SIOCAIFADDR: c_ulong = iow::<Ifaliasreq>(26);
+#[cfg(target_os = "freebsd")]
+const SIOCAIFADDR: c_ulong = iow::<Ifaliasreq>(43);
+#[cfg(any(apple, freebsdlike))]
+const SIOCSIFMTU: c_ulong = iow::<Ifreq>(52);
+#[cfg(netbsdlike)]
+const SIOCSIFMTU: c_ulong = iow::<Ifreq>(127);
+
+impl Ifreq {
+  /// An `ifreq` naming the interface with `index`.
+  fn for_index(index: u32) -> io::Result<Self> {
+    let mut ifr = Ifreq {
+      ifr_name: [0; IFNAMSIZ],
+      ifr_ifru: [0; IFRU_SIZE],
+    };
+    // SAFETY: `ifr_name` has room for the `IFNAMSIZ` bytes
+    // `if_indextoname` writes at most.
+    if unsafe { libc::if_indextoname(index, ifr.ifr_name.as_mut_ptr().cast()) }.is_null() {
+      return Err(io::Error::last_os_error());
+    }
+    Ok(ifr)
+  }
+}
+
+/// Opens the `AF_INET` datagram socket the `SIOCSIF*` ioctls are
+/// issued on.
+fn ioctl_socket(family: c_int) -> io::Result<OwnedFd> {
+  open_socket(family, SOCK_DGRAM, 0)
+}
+
+/// Issues `request` with `arg` on `sock`.
+///
+/// # Safety
+///
+/// `T` must be the structure `request` encodes.
+unsafe fn ioctl<T>(sock: &OwnedFd, request: c_ulong, arg: &mut T) -> io::Result<()> {
+  if libc::ioctl(sock.as_raw_fd(), request, arg as *mut T) < 0 {
+    return Err(io::Error::last_os_error());
+  }
+  Ok(())
+}
+
+pub(crate) fn set_mtu(index: u32, mtu: u32) -> io::Result<()> {
+  let mut ifr = Ifreq::for_index(index)?;
+  // `ifr_mtu` is the `int` at the front of the union.
+  ifr.ifr_ifru[..4].copy_from_slice(&(mtu as c_int).to_ne_bytes());
+
+  let sock = ioctl_socket(AF_INET)?;
+  // SAFETY: SIOCSIFMTU takes a `struct ifreq`.
+  unsafe { ioctl(&sock, SIOCSIFMTU, &mut ifr) }
+}
+
+pub(crate) fn set_interface_up(index: u32, up: bool) -> io::Result<()> {
+  let mut ifr = Ifreq::for_index(index)?;
+  let sock = ioctl_socket(AF_INET)?;
+  // SAFETY: SIOCGIFFLAGS takes a `struct ifreq` and fills in `ifr_flags`.
+  unsafe { ioctl(&sock, SIOCGIFFLAGS, &mut ifr) }?;
+
+  // `ifr_flags` is the `short` at the front of the union. FreeBSD
+  // keeps the upper flag bits in the next `short`, which is written
+  // back as read.
+  let mut flags = c_short::from_ne_bytes([ifr.ifr_ifru[0], ifr.ifr_ifru[1]]);
+  if up {
+    flags |= IFF_UP as c_short;
+  } else {
+    flags &= !(IFF_UP as c_short);
+  }
+  ifr.ifr_ifru[..2].copy_from_slice(&flags.to_ne_bytes());
+
+  // SAFETY: SIOCSIFFLAGS takes a `struct ifreq`.
+  unsafe { ioctl(&sock, SIOCSIFFLAGS, &mut ifr) }
+}
+
+pub(crate) fn add_addr(index: u32, net: IpNet) -> io::Result<()> {
+  match net {
+    IpNet::V4(net) => add_ipv4_addr(index, net),
+    IpNet::V6(net) => in6::add_ipv6_addr(index, net),
+  }
+}
+
+pub(crate) fn del_addr(index: u32, net: IpNet) -> io::Result<()> {
+  match net {
+    IpNet::V4(net) => {
+      let mut ifr = Ifreq::for_index(index)?;
+      // `ifr_addr` is the `sockaddr` at the front of the union.
+      // SAFETY: the union is at least `sizeof(struct sockaddr_in)`
+      // bytes; the write is unaligned because the bytes are.
+      unsafe {
+        ifr
+          .ifr_ifru
+          .as_mut_ptr()
+          .cast::<sockaddr_in>()
+          .write_unaligned(sockaddr_in(net.addr()))
+      };
+      let sock = ioctl_socket(AF_INET)?;
+      // SAFETY: SIOCDIFADDR takes a `struct ifreq`.
+      unsafe { ioctl(&sock, SIOCDIFADDR, &mut ifr) }
+    }
+    IpNet::V6(net) => in6::del_ipv6_addr(index, net),
+  }
+}
+
+fn add_ipv4_addr(index: u32, net: Ipv4Net) -> io::Result<()> {
+  let name = Ifreq::for_index(index)?.ifr_name;
+  // SAFETY: `Ifaliasreq` is a plain C struct for which all-zero is a
+  // valid value; a zeroed broadcast address lets the kernel derive it.
+  let mut req: Ifaliasreq = unsafe { mem::zeroed() };
+  req.ifra_name = name;
+  req.ifra_addr = sockaddr_in(net.addr());
+  req.ifra_mask = sockaddr_in(net.netmask());
+
+  let sock = ioctl_socket(AF_INET)?;
+  // SAFETY: SIOCAIFADDR takes a `struct ifaliasreq`.
+  unsafe { ioctl(&sock, SIOCAIFADDR, &mut req) }
+}
+
+/// Builds an `RTM_ADD` / `RTM_DELETE` routing message for `dest`: an
+/// `rt_msghdr` followed by the sockaddrs its `rtm_addrs` bits announce,
+/// in bit order. Without a gateway the interface itself is the
+/// gateway; with both, the interface goes in `RTA_IFP`. A host route
+/// carries `RTF_HOST` instead of a netmask.
+fn route_message(kind: c_int, dest: IpNet, gateway: Option<IpAddr>, ifindex: u32) -> Vec<u8> {
+  let host = dest.prefix_len() == dest.max_prefix_len();
+  let mut flags = RTF_UP | RTF_STATIC;
+  let mut addrs = RTA_DST;
+  let mut tail = Vec::with_capacity(128);
+  push_ip_sockaddr(&mut tail, dest.addr());
+  if kind == RTM_ADD {
+    match gateway {
+      Some(gateway) => {
+        flags |= RTF_GATEWAY;
+        addrs |= RTA_GATEWAY;
+        push_ip_sockaddr(&mut tail, gateway);
+      }
+      None => {
+        addrs |= RTA_GATEWAY;
+        push_sockaddr(&mut tail, &sockaddr_dl(ifindex));
+      }
+    }
+  }
+  if host {
+    flags |= RTF_HOST;
+  } else {
+    addrs |= RTA_NETMASK;
+    push_ip_sockaddr(&mut tail, dest.netmask());
+  }
+  if kind == RTM_ADD && gateway.is_some() && ifindex != 0 {
+    addrs |= RTA_IFP;
+    push_sockaddr(&mut tail, &sockaddr_dl(ifindex));
+  }
+
+  // SAFETY: `RtMsghdr` is a plain C struct for which all-zero is a
+  // valid value.
+  let mut rtm: RtMsghdr = unsafe { mem::zeroed() };
+  rtm.rtm_type = kind as u8;
+  rtm.rtm_index = ifindex as u16;
+  rtm.rtm_flags = flags;
+  rtm.rtm_addrs = addrs;
+  rtm.rtm_seq = 1;
+  route_message_bytes(rtm, &tail)
+}
+
+/// Writes `msg` to a fresh routing socket. The kernel applies it
+/// synchronously and reports failure as the `write(2)` errno.
+fn route_write(msg: &[u8]) -> io::Result<()> {
+  let sock = open_socket(AF_ROUTE, SOCK_RAW, AF_UNSPEC)?;
+  // SAFETY: `msg` is a valid buffer of `msg.len()` bytes.
+  if unsafe { libc::write(sock.as_raw_fd(), msg.as_ptr().cast(), msg.len()) } < 0 {
+    return Err(io::Error::last_os_error());
+  }
+  Ok(())
+}
+
+pub(crate) fn add_route(
+  dest: IpNet,
+  gateway: Option<IpAddr>,
+  ifindex: u32,
+  _metric: u32,
+) -> io::Result<()> {
+  route_write(&route_message(RTM_ADD, dest, gateway, ifindex))
+}
+
+pub(crate) fn del_route(dest: IpNet) -> io::Result<()> {
+  route_write(&route_message(RTM_DELETE, dest, None, 0))
+}
+
+/// IPv6 addresses go through `SIOCAIFADDR_IN6` / `SIOCDIFADDR_IN6` on
+/// an `AF_INET6` socket. On macOS the libc crate defines `in6_ifreq`,
+/// which pins down the size (and with it the ioctl number).
+#[cfg(target_os = "macos")]
+mod in6 {
+  use std::{io, mem};
+
+  use ipnet::Ipv6Net;
+  use libc::{c_int, c_ulong, in6_addrlifetime, in6_ifreq, AF_INET6, IFNAMSIZ};
+
+  use super::{ioctl, ioctl_socket, iow, sockaddr_in6, Ifreq};
+
+  /// `struct in6_aliasreq` from <netinet6/in6_var.h>.
+  #[repr(C)]
+  struct In6Aliasreq {
+    ifra_name: [u8; IFNAMSIZ],
+    ifra_addr: sockaddr_in6,
+    ifra_dstaddr: sockaddr_in6,
+    ifra_prefixmask: sockaddr_in6,
+    ifra_flags: c_int,
+    ifra_lifetime: in6_addrlifetime,
+  }
+
+  const SIOCDIFADDR_IN6: c_ulong = iow::<in6_ifreq>(25);
+  const SIOCAIFADDR_IN6: c_ulong = iow::<In6Aliasreq>(26);
+
+  /// `ND6_INFINITE_LIFETIME`: the address never expires.
+  const ND6_INFINITE_LIFETIME: u32 = u32::MAX;
+
+  pub(super) fn add_ipv6_addr(index: u32, net: Ipv6Net) -> io::Result<()> {
+    let name = Ifreq::for_index(index)?.ifr_name;
+    // SAFETY: `In6Aliasreq` is a plain C struct for which all-zero is a
+    // valid value.
+    let mut req: In6Aliasreq = unsafe { mem::zeroed() };
+    req.ifra_name = name;
+    req.ifra_addr = sockaddr_in6(net.addr());
+    req.ifra_prefixmask = sockaddr_in6(net.netmask());
+    req.ifra_lifetime.ia6t_vltime = ND6_INFINITE_LIFETIME;
+    req.ifra_lifetime.ia6t_pltime = ND6_INFINITE_LIFETIME;
+
+    let sock = ioctl_socket(AF_INET6)?;
+    // SAFETY: SIOCAIFADDR_IN6 takes a `struct in6_aliasreq`.
+    unsafe { ioctl(&sock, SIOCAIFADDR_IN6, &mut req) }
+  }
+
+  pub(super) fn del_ipv6_addr(index: u32, net: Ipv6Net) -> io::Result<()> {
+    let name = Ifreq::for_index(index)?.ifr_name;
+    // SAFETY: `in6_ifreq` is a plain C struct for which all-zero is a
+    // valid value.
+    let mut req: in6_ifreq = unsafe { mem::zeroed() };
+    for (dst, src) in req.ifr_name.iter_mut().zip(name) {
+      *dst = src as libc::c_char;
+    }
+    req.ifr_ifru.ifru_addr = sockaddr_in6(net.addr());
+
+    let sock = ioctl_socket(AF_INET6)?;
+    // SAFETY: SIOCDIFADDR_IN6 takes a `struct in6_ifreq`.
+    unsafe { ioctl(&sock, SIOCDIFADDR_IN6, &mut req) }
+  }
+}
+
+/// The same ioctls on the other BSDs, whose argument structs the libc
+/// crate does not define. Their layouts follow <netinet6/in6_var.h>.
+#[cfg(any(freebsdlike, netbsdlike))]
+mod in6 {
+  use std::{io, mem};
+
+  use ipnet::Ipv6Net;
+  use libc::{c_int, c_ulong, time_t, AF_INET6, IFNAMSIZ};
+
+  use super::{ioctl, ioctl_socket, iow, sockaddr_in6, Ifreq};
+
+  /// `struct in6_addrlifetime`.
+  #[repr(C)]
+  struct In6Addrlifetime {
+    ia6t_expire: time_t,
+    ia6t_preferred: time_t,
+    ia6t_vltime: u32,
+    ia6t_pltime: u32,
+  }
+
+  /// `struct in6_aliasreq`. FreeBSD appends the CARP `vhid`, as it
+  /// does to `ifaliasreq`; OpenBSD wraps `ifra_addr` in a union of the
+  /// same size.
+  #[repr(C)]
+  struct In6Aliasreq {
+    ifra_name: [u8; IFNAMSIZ],
+    ifra_addr: sockaddr_in6,
+    ifra_dstaddr: sockaddr_in6,
+    ifra_prefixmask: sockaddr_in6,
+    ifra_flags: c_int,
+    ifra_lifetime: In6Addrlifetime,
+    #[cfg(target_os = "freebsd")]
+    ifra_vhid: c_int,
+  }
+
+  /// Size of the `ifr_ifru` union of `struct in6_ifreq`, in `u_quad_t`s.
+  /// Its largest member is the `icmp6_ifstat` counter block (34 of
+  /// them); OpenBSD dropped the per-interface statistics, which leaves
+  /// a `sockaddr_in6` padded to pointer alignment.
+  #[cfg(not(target_os = "openbsd"))]
+  const IFRU_QUADS: usize = 34;
+  #[cfg(target_os = "openbsd")]
+  const IFRU_QUADS: usize = 4;
+
+  /// `struct in6_ifreq` with the `ifr_ifru` union kept as raw words.
+  /// Only its size has to match the kernel's, since that is encoded in
+  /// the ioctl number.
+  #[repr(C)]
+  struct In6Ifreq {
+    ifr_name: [u8; IFNAMSIZ],
+    ifr_ifru: [u64; IFRU_QUADS],
+  }
+
+  const SIOCDIFADDR_IN6: c_ulong = iow::<In6Ifreq>(25);
+  #[cfg(any(target_os = "dragonfly", target_os = "openbsd"))]
+  const SIOCAIFADDR_IN6: c_ulong = iow::<In6Aliasreq>(26);
+  #[cfg(target_os = "freebsd")]
+  const SIOCAIFADDR_IN6: c_ulong = iow::<In6Aliasreq>(27);
+  #[cfg(target_os = "netbsd")]
+  const SIOCAIFADDR_IN6: c_ulong = iow::<In6Aliasreq>(107);
+
+  /// `ND6_INFINITE_LIFETIME`: the address never expires.
+  const ND6_INFINITE_LIFETIME: u32 = u32::MAX;
+
+  pub(super) fn add_ipv6_addr(index: u32, net: Ipv6Net) -> io::Result<()> {
+    let name = Ifreq::for_index(index)?.ifr_name;
+    // SAFETY: `In6Aliasreq` is a plain C struct for which all-zero is a
+    // valid value.
+    let mut req: In6Aliasreq = unsafe { mem::zeroed() };
+    req.ifra_name = name;
+    req.ifra_addr = sockaddr_in6(net.addr());
+    req.ifra_prefixmask = sockaddr_in6(net.netmask());
+    req.ifra_lifetime.ia6t_vltime = ND6_INFINITE_LIFETIME;
+    req.ifra_lifetime.ia6t_pltime = ND6_INFINITE_LIFETIME;
+
+    let sock = ioctl_socket(AF_INET6)?;
+    // SAFETY: SIOCAIFADDR_IN6 takes a `struct in6_aliasreq`.
+    unsafe { ioctl(&sock, SIOCAIFADDR_IN6, &mut req) }
+  }
+
+  pub(super) fn del_ipv6_addr(index: u32, net: Ipv6Net) -> io::Result<()> {
+    let mut req = In6Ifreq {
+      ifr_name: Ifreq::for_index(index)?.ifr_name,
+      ifr_ifru: [0; IFRU_QUADS],
+    };
+    // `ifru_addr` is the `sockaddr_in6` at the front of the union.
+    // SAFETY: the union is larger than a `sockaddr_in6`.
+    unsafe {
+      req
+        .ifr_ifru
+        .as_mut_ptr()
+        .cast::<sockaddr_in6>()
+        .write_unaligned(sockaddr_in6(net.addr()))
+    };
+
+    let sock = ioctl_socket(AF_INET6)?;
+    // SAFETY: SIOCDIFADDR_IN6 takes a `struct in6_ifreq`.
+    unsafe { ioctl(&sock, SIOCDIFADDR_IN6, &mut req) }
+  }
+}
+
+#[cfg(not(any(target_os = "macos", freebsdlike, netbsdlike)))]
+mod in6 {
+  use std::io;
+
+  use ipnet::Ipv6Net;
+
+  /// Configuring IPv6 addresses is not supported on Apple platforms
+  /// other than macOS.
+  fn unsupported() -> io::Error {
+    crate::Error::UnsupportedPlatform.into()
+  }
+
+  pub(super) fn add_ipv6_addr(_index: u32, _net: Ipv6Net) -> io::Result<()> {
+    Err(unsupported())
+  }
+
+  pub(super) fn del_ipv6_addr(_index: u32, _net: Ipv6Net) -> io::Result<()> {
+    Err(unsupported())
+  }
+}
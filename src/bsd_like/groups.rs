@@ -0,0 +1,112 @@
+use std::{
+  ffi::CStr,
+  io, mem,
+  os::fd::{AsRawFd, FromRawFd, OwnedFd},
+};
+
+use libc::{c_char, c_uint, c_ulong, AF_INET, IFNAMSIZ, SOCK_DGRAM};
+use smallvec_wrapper::SmallVec;
+use smol_str::SmolStr;
+
+/// `struct ifg_req` from <net/if.h>: one group name.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ifg_req {
+  ifgrq_group: [c_char; IFNAMSIZ],
+}
+
+#[repr(C)]
+union ifgr_ifgru {
+  ifgru_group: [c_char; IFNAMSIZ],
+  ifgru_groups: *mut ifg_req,
+}
+
+/// `struct ifgroupreq` from <net/if.h>; the libc crate does not export it.
+#[repr(C)]
+struct ifgroupreq {
+  ifgr_name: [c_char; IFNAMSIZ],
+  ifgr_len: c_uint,
+  ifgr_ifgru: ifgr_ifgru,
+}
+
+/// `_IOWR('i', 136, struct ifgroupreq)`.
+const SIOCGIFGROUP: c_ulong = 0xC000_0000
+  | (((mem::size_of::<ifgroupreq>() & 0x1fff) as c_ulong) << 16)
+  | ((b'i' as c_ulong) << 8)
+  | 136;
+
+/// Returns the names of the groups `name` belongs to. The first call
+/// with `ifgr_len == 0` asks the kernel for the buffer size, the second
+/// fills it.
+pub(crate) fn interface_groups(name: &str) -> io::Result<SmallVec<SmolStr>> {
+  if name.len() >= IFNAMSIZ {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      "interface name too long",
+    ));
+  }
+
+  // SAFETY: plain socket(2) call; the result is checked before use.
+  let fd = unsafe { libc::socket(AF_INET, SOCK_DGRAM, 0) };
+  if fd < 0 {
+    return Err(io::Error::last_os_error());
+  }
+  // SAFETY: `fd` is a freshly opened descriptor we exclusively own.
+  let sock = unsafe { OwnedFd::from_raw_fd(fd) };
+
+  // SAFETY: `ifgroupreq` is a plain C struct for which all-zero is a
+  // valid value.
+  let mut req: ifgroupreq = unsafe { mem::zeroed() };
+  for (dst, src) in req.ifgr_name.iter_mut().zip(name.as_bytes()) {
+    *dst = *src as c_char;
+  }
+
+  // SAFETY: `req` is a properly initialised `ifgroupreq` with a zero
+  // length, so the kernel only writes back the required length.
+  if unsafe { libc::ioctl(sock.as_raw_fd(), SIOCGIFGROUP, &mut req) } < 0 {
+    return Err(io::Error::last_os_error());
+  }
+
+  let count = req.ifgr_len as usize / mem::size_of::<ifg_req>();
+  let mut groups = vec![
+    ifg_req {
+      ifgrq_group: [0; IFNAMSIZ],
+    };
+    count
+  ];
+  req.ifgr_ifgru.ifgru_groups = groups.as_mut_ptr();
+  req.ifgr_len = (count * mem::size_of::<ifg_req>()) as c_uint;
+
+  // SAFETY: `ifgru_groups` points at `ifgr_len` writable bytes that
+  // outlive the call.
+  if unsafe { libc::ioctl(sock.as_raw_fd(), SIOCGIFGROUP, &mut req) } < 0 {
+    return Err(io::Error::last_os_error());
+  }
+
+  // The set can shrink between the two calls; the kernel reports the
+  // length it actually wrote.
+  let filled = (req.ifgr_len as usize / mem::size_of::<ifg_req>()).min(count);
+  Ok(
+    groups[..filled]
+      .iter()
+      .map(|g| {
+        // SAFETY: group names are NUL-terminated within IFNAMSIZ.
+        let name = unsafe { CStr::from_ptr(g.ifgrq_group.as_ptr()) };
+        SmolStr::new(name.to_string_lossy())
+      })
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Loopback always sits in the `lo` interface-family group.
+  #[test]
+  fn loopback_in_lo_group() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    let groups = interface_groups(lo.name()).unwrap();
+    assert!(groups.iter().any(|g| g == "lo"), "{groups:?}");
+  }
+}
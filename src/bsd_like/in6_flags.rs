@@ -0,0 +1,213 @@
+use std::{
+  io, mem,
+  net::{IpAddr, Ipv6Addr},
+  os::fd::{AsRawFd, FromRawFd, OwnedFd},
+};
+
+use libc::{c_char, c_int, c_ulong, in6_ifreq, sockaddr_in6, AF_INET6, IFNAMSIZ, SOCK_DGRAM};
+
+use smallvec_wrapper::SmallVec;
+
+use crate::scope::SourceFlags;
+
+use super::{best_local_ipv6_addrs, IfNet, Ifv6Net};
+
+// `ia6_flags` bits from <netinet6/in6_var.h>.
+const IN6_IFF_ANYCAST: c_int = 0x01;
+const IN6_IFF_TENTATIVE: c_int = 0x02;
+const IN6_IFF_DUPLICATED: c_int = 0x04;
+const IN6_IFF_DETACHED: c_int = 0x08;
+const IN6_IFF_DEPRECATED: c_int = 0x10;
+const IN6_IFF_TEMPORARY: c_int = 0x80;
+
+/// Flags that make an address unusable as a source at all.
+const IN6_IFF_NOTREADY: c_int = IN6_IFF_TENTATIVE | IN6_IFF_DUPLICATED | IN6_IFF_DETACHED;
+
+/// `_IOWR('i', 73, struct in6_ifreq)`; the libc crate does not export it.
+const SIOCGIFAFLAG_IN6: c_ulong = 0xC000_0000
+  | (((mem::size_of::<in6_ifreq>() & 0x1fff) as c_ulong) << 16)
+  | ((b'i' as c_ulong) << 8)
+  | 73;
+
+/// Picks the outbound source among the best-route interface's global
+/// IPv6 addresses the way macOS does with `prefer_tempaddr` on (the
+/// default): a non-deprecated address beats a deprecated one (RFC 6724
+/// rule 3), and among those a temporary address beats a stable one
+/// (rule 7). Addresses still in DAD, or that failed it, are skipped.
+pub(crate) fn best_local_ipv6_addr_for_outbound() -> io::Result<Option<Ifv6Net>> {
+  let candidates = best_local_ipv6_addrs()?;
+  if candidates.is_empty() {
+    return Ok(None);
+  }
+
+  let sock = inet6_socket()?;
+
+  let mut best: Option<(u8, Ifv6Net)> = None;
+  for net in candidates {
+    let name = crate::ifindex_to_name(net.index())?;
+    let flags = match address_flags(&sock, &name, net.addr()) {
+      Ok(flags) => flags,
+      // The address or its interface went away since the dump.
+      Err(e) if matches!(e.raw_os_error(), Some(libc::EADDRNOTAVAIL | libc::ENXIO)) => continue,
+      Err(e) => return Err(e),
+    };
+    if flags & IN6_IFF_NOTREADY != 0 {
+      continue;
+    }
+    let rank = outbound_rank(flags);
+    if best.is_none_or(|(best_rank, _)| rank < best_rank) {
+      best = Some((rank, net));
+    }
+  }
+  Ok(best.map(|(_, net)| net))
+}
+
+/// Drops the IPv6 addresses `bind()` refuses or new sockets should
+/// avoid: anycast, tentative, duplicated, detached and deprecated ones.
+/// IPv4 addresses are kept as they are.
+pub(super) fn bindable_only(addrs: SmallVec<IfNet>) -> io::Result<SmallVec<IfNet>> {
+  if !addrs.iter().any(|net| net.addr().is_ipv6()) {
+    return Ok(addrs);
+  }
+
+  let sock = inet6_socket()?;
+  let mut kept = SmallVec::new();
+  for net in addrs {
+    let IpAddr::V6(addr) = net.addr() else {
+      kept.push(net);
+      continue;
+    };
+    let name = crate::ifindex_to_name(net.index())?;
+    let flags = match address_flags(&sock, &name, addr) {
+      Ok(flags) => flags,
+      // The address or its interface went away since the dump.
+      Err(e) if matches!(e.raw_os_error(), Some(libc::EADDRNOTAVAIL | libc::ENXIO)) => continue,
+      Err(e) => return Err(e),
+    };
+    if flags & (IN6_IFF_ANYCAST | IN6_IFF_NOTREADY | IN6_IFF_DEPRECATED) == 0 {
+      kept.push(net);
+    }
+  }
+  Ok(kept)
+}
+
+/// Pairs each address with the deprecated and temporary bits of its
+/// `ia6_flags`; IPv4 addresses carry neither.
+pub(super) fn with_source_flags(
+  addrs: SmallVec<IfNet>,
+) -> io::Result<SmallVec<(IfNet, SourceFlags)>> {
+  let sock = if addrs.iter().any(|net| net.addr().is_ipv6()) {
+    Some(inet6_socket()?)
+  } else {
+    None
+  };
+  let mut out = SmallVec::new();
+  for net in addrs {
+    let (IpAddr::V6(addr), Some(sock)) = (net.addr(), &sock) else {
+      out.push((net, SourceFlags::default()));
+      continue;
+    };
+    let name = crate::ifindex_to_name(net.index())?;
+    let flags = match address_flags(sock, &name, addr) {
+      Ok(flags) => flags,
+      // The address or its interface went away since the dump.
+      Err(e) if matches!(e.raw_os_error(), Some(libc::EADDRNOTAVAIL | libc::ENXIO)) => continue,
+      Err(e) => return Err(e),
+    };
+    out.push((
+      net,
+      SourceFlags {
+        deprecated: flags & IN6_IFF_DEPRECATED != 0,
+        temporary: flags & IN6_IFF_TEMPORARY != 0,
+      },
+    ));
+  }
+  Ok(out)
+}
+
+/// Lower is better: non-deprecated before deprecated, then temporary
+/// before stable.
+fn outbound_rank(flags: c_int) -> u8 {
+  let deprecated = flags & IN6_IFF_DEPRECATED != 0;
+  let temporary = flags & IN6_IFF_TEMPORARY != 0;
+  (deprecated as u8) << 1 | !temporary as u8
+}
+
+/// A datagram socket to issue the `SIOCGIFAFLAG_IN6` ioctl on.
+fn inet6_socket() -> io::Result<OwnedFd> {
+  // SAFETY: plain socket(2) call; the result is checked before use.
+  let fd = unsafe { libc::socket(AF_INET6, SOCK_DGRAM, 0) };
+  if fd < 0 {
+    return Err(io::Error::last_os_error());
+  }
+  // SAFETY: `fd` is a freshly opened descriptor we exclusively own.
+  Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+fn address_flags(sock: &OwnedFd, name: &str, addr: Ipv6Addr) -> io::Result<c_int> {
+  if name.len() >= IFNAMSIZ {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      "interface name too long",
+    ));
+  }
+
+  // SAFETY: `in6_ifreq` and `sockaddr_in6` are plain C structs for
+  // which all-zero is a valid value.
+  let mut req: in6_ifreq = unsafe { mem::zeroed() };
+  for (dst, src) in req.ifr_name.iter_mut().zip(name.as_bytes()) {
+    *dst = *src as c_char;
+  }
+  let mut sin6: sockaddr_in6 = unsafe { mem::zeroed() };
+  sin6.sin6_len = mem::size_of::<sockaddr_in6>() as u8;
+  sin6.sin6_family = AF_INET6 as libc::sa_family_t;
+  sin6.sin6_addr.s6_addr = addr.octets();
+  req.ifr_ifru.ifru_addr = sin6;
+
+  // SAFETY: `req` is a properly initialised `in6_ifreq` that outlives
+  // the call, which is the argument SIOCGIFAFLAG_IN6 expects.
+  if unsafe { libc::ioctl(sock.as_raw_fd(), SIOCGIFAFLAG_IN6, &mut req) } < 0 {
+    return Err(io::Error::last_os_error());
+  }
+  // SAFETY: the kernel filled in `ifru_flags6`.
+  Ok(unsafe { req.ifr_ifru.ifru_flags6 })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rank_order() {
+    let temp = IN6_IFF_TEMPORARY;
+    let dep = IN6_IFF_DEPRECATED;
+    assert!(outbound_rank(temp) < outbound_rank(0));
+    assert!(outbound_rank(0) < outbound_rank(temp | dep));
+    assert!(outbound_rank(temp | dep) < outbound_rank(dep));
+  }
+
+  // When the best interface holds a usable temporary address, the
+  // stable (EUI-64 or opaque) address must not be chosen over it.
+  #[test]
+  fn temporary_preferred_when_present() {
+    let candidates = best_local_ipv6_addrs().unwrap();
+    let fd = unsafe { libc::socket(AF_INET6, SOCK_DGRAM, 0) };
+    assert!(fd >= 0);
+    let sock = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let usable_temporary = candidates.iter().any(|net| {
+      let name = crate::ifindex_to_name(net.index()).unwrap();
+      address_flags(&sock, &name, net.addr()).is_ok_and(|flags| {
+        flags & IN6_IFF_TEMPORARY != 0 && flags & (IN6_IFF_NOTREADY | IN6_IFF_DEPRECATED) == 0
+      })
+    });
+    if !usable_temporary {
+      return;
+    }
+
+    let chosen = best_local_ipv6_addr_for_outbound().unwrap().unwrap();
+    let name = crate::ifindex_to_name(chosen.index()).unwrap();
+    let flags = address_flags(&sock, &name, chosen.addr()).unwrap();
+    assert_ne!(flags & IN6_IFF_TEMPORARY, 0, "{chosen}");
+  }
+}
@@ -21,10 +21,12 @@ const RTF_MULTICAST: libc::c_int = 0;
 use smallvec_wrapper::SmallVec;
 
 use super::{
-  super::{ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter, local_ip_filter},
+  super::{ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter, local_ip_filter, Family},
   compat::RtMsghdr,
-  fetch, interface_addr_table_into, interface_addresses, interface_ipv4_addresses,
-  interface_ipv6_addresses, message_too_short, parse_addrs, IfNet, Ifv4Net, Ifv6Net, Net,
+  fetch, interface_addr_table, interface_addr_table_into, interface_addresses,
+  interface_ipv4_addresses, interface_ipv6_addresses, message_too_short, parse_addrs,
+  route::{route_get, route_priority},
+  IfNet, Ifv4Net, Ifv6Net, Net,
 };
 
 pub(crate) fn best_local_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
@@ -39,6 +41,16 @@ pub(crate) fn best_local_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
   Ok(out)
 }
 
+pub(crate) fn best_local_ipv4_addrs_opt() -> io::Result<Option<SmallVec<Ifv4Net>>> {
+  let mut out = SmallVec::new();
+  Ok(best_local_addrs_in(AF_INET, &mut out)?.then_some(out))
+}
+
+pub(crate) fn best_local_ipv6_addrs_opt() -> io::Result<Option<SmallVec<Ifv6Net>>> {
+  let mut out = SmallVec::new();
+  Ok(best_local_addrs_in(AF_INET6, &mut out)?.then_some(out))
+}
+
 pub(crate) fn best_local_addrs() -> io::Result<SmallVec<IfNet>> {
   // Walk AF_INET and AF_INET6 separately rather than one AF_UNSPEC
   // dump. The kernel encodes "default route" by omitting `RTAX_DST`
@@ -58,34 +70,45 @@ pub(crate) fn best_local_addrs() -> io::Result<SmallVec<IfNet>> {
   // / no v4 stack) returns the populated family rather than `Err`.
   // Same rationale and predicate as `route_table_by_filter`; see
   // `family_unavailable_to_empty` for the errno set.
-  super::family_unavailable_to_empty(best_local_addrs_in(AF_INET, &mut out))?;
-  super::family_unavailable_to_empty(best_local_addrs_in(AF_INET6, &mut out))?;
+  super::family_unavailable_to_empty(best_local_addrs_in(AF_INET, &mut out).map(drop))?;
+  super::family_unavailable_to_empty(best_local_addrs_in(AF_INET6, &mut out).map(drop))?;
   Ok(out)
 }
 
-/// Per-route priority used to rank competing default routes. OpenBSD
-/// is the only BSD that exposes a documented routing priority on the
-/// route header (`rtm_priority`, lower wins — added when its
-/// kernel grew per-priority routing). FreeBSD / NetBSD / DragonFly /
-/// macOS have no equivalent field on `rt_msghdr`; the fields actually
-/// present (`rmx_recvpipe`, `rmx_pksent`, etc.) are TCP-pipe metrics,
-/// not routing priority. Returning `0` for those targets makes every
-/// candidate compare equal, and the caller-side selector then
-/// collects every default-route ifindex (instead of arbitrarily
-/// picking by an irrelevant TCP metric, which the previous code did).
-#[cfg(target_os = "openbsd")]
-#[inline]
-fn route_priority(rtm: &RtMsghdr) -> u8 {
-  rtm.rtm_priority
+/// Returns whether `family` has a default route at all.
+fn best_local_addrs_in<T: Net>(family: i32, out: &mut SmallVec<T>) -> io::Result<bool> {
+  // Fetch addresses for every selected interface, appending into the
+  // caller-provided buffer. Returns immediately on the first syscall
+  // failure; partial results stay in `out` (consistent with Linux's
+  // `netlink_best_local_addrs_into`).
+  let oifs = best_default_oifs(family)?;
+  for &idx in oifs.iter() {
+    interface_addr_table_into(family, idx as u32, local_ip_filter, out)?;
+  }
+  Ok(!oifs.is_empty())
 }
 
-#[cfg(not(target_os = "openbsd"))]
-#[inline]
-fn route_priority(_rtm: &RtMsghdr) -> u8 {
-  0
+/// The first interface behind the best IPv4 default route, else the
+/// best IPv6 one. A family without a stack counts as having no route.
+pub(crate) fn default_route_index() -> io::Result<Option<u32>> {
+  for family in [AF_INET, AF_INET6] {
+    let indices = match best_default_oifs(family) {
+      Ok(indices) => indices,
+      Err(e) => {
+        super::family_unavailable_to_empty(Err(e))?;
+        SmallVec::new()
+      }
+    };
+    if let Some(&index) = indices.first() {
+      return Ok(Some(index as u32));
+    }
+  }
+  Ok(None)
 }
 
-fn best_local_addrs_in<T: Net>(family: i32, out: &mut SmallVec<T>) -> io::Result<()> {
+/// Returns the sorted, deduplicated interface indices behind the best
+/// default route(s) for `family`.
+fn best_default_oifs(family: i32) -> io::Result<SmallVec<u16>> {
   let routes = fetch(family, NET_RT_DUMP, 0)?;
   // Selection key: route priority (lower wins on OpenBSD, all-zero
   // elsewhere). `best_oifs` holds every interface that ties at the
@@ -216,15 +239,7 @@ fn best_local_addrs_in<T: Net>(family: i32, out: &mut SmallVec<T>) -> io::Result
   // make us walk the address dump twice for the same ifindex.
   best_oifs.sort_unstable();
   best_oifs.dedup();
-
-  // Fetch addresses for every selected interface, appending into the
-  // caller-provided buffer. Returns immediately on the first syscall
-  // failure; partial results stay in `out` (consistent with Linux's
-  // `netlink_best_local_addrs_into`).
-  for idx in best_oifs {
-    interface_addr_table_into(family, idx as u32, local_ip_filter, out)?;
-  }
-  Ok(())
+  Ok(best_oifs)
 }
 
 pub(crate) fn local_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
@@ -261,3 +276,41 @@ where
 {
   interface_addresses(0, |addr| f(addr) && local_ip_filter(addr))
 }
+
+/// The outgoing interface and source address of the route the kernel
+/// would pick for `dest`.
+pub(crate) fn route_source(dest: IpAddr) -> io::Result<Option<(u32, Option<IpAddr>)>> {
+  route_get(dest)
+}
+
+/// The address of `family` on interface `index` to use as source when
+/// no route names one; see `crate::scope::pick_source`. Only macOS reads
+/// the deprecated and temporary bits from `ia6_flags`; elsewhere the
+/// choice rests on scope alone.
+pub(crate) fn interface_source_addr(index: u32, family: Family) -> io::Result<Option<IfNet>> {
+  let family = match family {
+    Family::V4 => AF_INET,
+    Family::V6 => AF_INET6,
+  };
+  let addrs = interface_addr_table(family, index, |_| true)?;
+  #[cfg(target_os = "macos")]
+  let candidates = super::in6_flags::with_source_flags(addrs)?;
+  #[cfg(not(target_os = "macos"))]
+  let candidates = addrs.into_iter().map(|net| (net, Default::default()));
+  Ok(crate::scope::pick_source(candidates))
+}
+
+/// Every address of `family`. On macOS IPv6 addresses whose
+/// `ia6_flags` mark them anycast, not ready or deprecated are dropped;
+/// the other BSDs have no `in6_ifreq` binding to read the flags with,
+/// so their list is unfiltered.
+pub(crate) fn bindable_addrs(family: Family) -> io::Result<SmallVec<IfNet>> {
+  let family = match family {
+    Family::V4 => AF_INET,
+    Family::V6 => AF_INET6,
+  };
+  let addrs = interface_addr_table(family, 0, |_| true)?;
+  #[cfg(target_os = "macos")]
+  let addrs = super::in6_flags::bindable_only(addrs)?;
+  Ok(addrs)
+}
@@ -0,0 +1,110 @@
+use std::{io, net::IpAddr};
+
+use libc::{AF_INET, AF_INET6, AF_LINK, NET_RT_FLAGS, RTAX_DST, RTAX_GATEWAY, RTM_GET};
+
+use smallvec_wrapper::SmallVec;
+
+use crate::{Family, Neighbor, NeighborState};
+
+use super::{
+  compat::RtMsghdr, family_unavailable_to_empty, fetch, kernel_index, message_too_short, parse,
+  parse_addrs, roundup, MacAddr,
+};
+
+// The link-layer-info route flag the ARP / NDP sysctl dump selects on.
+// It is `RTF_LLINFO` on Apple, OpenBSD and DragonFly and `RTF_LLDATA`
+// on FreeBSD and NetBSD, with the same value everywhere; libc only
+// names it on some targets, so spell it out.
+const RTF_LLINFO: libc::c_int = 0x400;
+
+pub(crate) fn neighbor_table<F>(family: Option<Family>, mut f: F) -> io::Result<SmallVec<Neighbor>>
+where
+  F: FnMut(&IpAddr) -> bool,
+{
+  let mut out = SmallVec::new();
+  let mut on_neigh = |index, ip: IpAddr, mac, state| {
+    if f(&ip) {
+      out.push(Neighbor::new(index, ip, mac, state));
+    }
+  };
+  // Unlike the route dump, the link-layer dump is per family: there
+  // is no `AF_UNSPEC` lltable.
+  if family != Some(Family::V6) {
+    family_unavailable_to_empty(walk_neighbors(AF_INET, &mut on_neigh))?;
+  }
+  if family != Some(Family::V4) {
+    family_unavailable_to_empty(walk_neighbors(AF_INET6, &mut on_neigh))?;
+  }
+  Ok(out)
+}
+
+/// Walks the ARP (`AF_INET`) or NDP (`AF_INET6`) table through the
+/// `NET_RT_FLAGS` / `RTF_LLINFO` sysctl, calling `on_neigh(index, ip,
+/// lladdr, state)` for each entry. `lladdr` is `None` for entries that
+/// are still resolving. The dump carries no NUD state, so static
+/// entries are reported as permanent and the rest by whether they
+/// have resolved.
+pub(super) fn walk_neighbors<F>(family: i32, mut on_neigh: F) -> io::Result<()>
+where
+  F: FnMut(u32, IpAddr, Option<MacAddr>, NeighborState),
+{
+  let buf = fetch(family, NET_RT_FLAGS, RTF_LLINFO)?;
+  let header_size = std::mem::size_of::<RtMsghdr>();
+
+  let mut src = buf.as_slice();
+  while src.len() > 4 {
+    let l = u16::from_ne_bytes(src[..2].try_into().unwrap()) as usize;
+    // Same end-of-stream / truncation rules as `walk_route_table`.
+    if l == 0 {
+      break;
+    }
+    if src.len() < l {
+      return Err(message_too_short());
+    }
+    if src[2] as i32 != libc::RTM_VERSION || src[3] as i32 != RTM_GET {
+      src = &src[l..];
+      continue;
+    }
+    if l < header_size {
+      return Err(message_too_short());
+    }
+
+    // SAFETY: `l >= header_size` bytes are in bounds; see
+    // `walk_route_table` for why the read is unaligned.
+    let rtm: RtMsghdr = unsafe { std::ptr::read_unaligned(src.as_ptr() as *const RtMsghdr) };
+    let body = &src[header_size..l];
+    let addrs = parse_addrs(rtm.rtm_addrs as u32, body)?;
+
+    // The destination is the neighbour's IP; the gateway slot that
+    // follows it is a `sockaddr_dl` carrying its link-layer address.
+    // `parse_addrs` skips `AF_LINK` sockaddrs, so decode that one here.
+    if let Some(ip) = addrs[RTAX_DST as usize] {
+      let wants = (1 << RTAX_DST) | (1 << RTAX_GATEWAY);
+      let mut mac = None;
+      if rtm.rtm_addrs as u32 & wants == wants && !body.is_empty() {
+        let gw = &body[roundup(body[0] as usize).min(body.len())..];
+        if gw.len() >= 8 && gw[1] as i32 == AF_LINK && (gw[0] as usize) <= gw.len() {
+          mac = parse(&gw[..gw[0] as usize])?.1;
+        }
+      }
+      let matches_family = match ip {
+        IpAddr::V4(_) => family != AF_INET6,
+        IpAddr::V6(_) => family != AF_INET,
+      };
+      let state = if rtm.rtm_flags & libc::RTF_STATIC != 0 {
+        NeighborState::Permanent
+      } else if mac.is_some() {
+        NeighborState::Reachable
+      } else {
+        NeighborState::Incomplete
+      };
+      if matches_family {
+        on_neigh(kernel_index(rtm.rtm_index), ip, mac, state);
+      }
+    }
+
+    src = &src[l..];
+  }
+
+  Ok(())
+}
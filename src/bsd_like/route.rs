@@ -1,8 +1,14 @@
-use std::{io, net::IpAddr};
+use std::{
+  io, mem,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  os::fd::{AsRawFd, FromRawFd, OwnedFd},
+  time::{Duration, SystemTime},
+};
 
 use libc::{
-  NET_RT_DUMP, RTAX_DST, RTAX_GATEWAY, RTAX_NETMASK, RTF_BLACKHOLE, RTF_BROADCAST, RTF_REJECT,
-  RTF_UP, RTM_GET,
+  c_int, sockaddr_dl, sockaddr_in, sockaddr_in6, AF_INET, AF_INET6, AF_LINK, AF_ROUTE, AF_UNSPEC,
+  NET_RT_DUMP, RTAX_DST, RTAX_GATEWAY, RTAX_IFA, RTAX_NETMASK, RTA_DST, RTA_IFP, RTF_BLACKHOLE,
+  RTF_BROADCAST, RTF_HOST, RTF_REJECT, RTF_UP, RTM_GET, RTM_VERSION, SOCK_RAW,
 };
 
 // `RTF_MULTICAST` exists on Apple / FreeBSD / DragonFly / OpenBSD but
@@ -18,11 +24,54 @@ use libc::RTF_MULTICAST;
 #[cfg(target_os = "netbsd")]
 const RTF_MULTICAST: libc::c_int = 0;
 
-use super::{compat::RtMsghdr, fetch, message_too_short, parse_addrs};
+use super::{compat::RtMsghdr, fetch, kernel_index, message_too_short, parse_addrs, roundup};
+use crate::RouteMetrics;
+
+/// Per-route priority used to rank competing routes. OpenBSD is the
+/// only BSD that exposes a documented routing priority on the route
+/// header (`rtm_priority`, lower wins — added when its kernel grew
+/// per-priority routing). FreeBSD / NetBSD / DragonFly / macOS have no
+/// equivalent field on `rt_msghdr`; the fields actually present
+/// (`rmx_recvpipe`, `rmx_pksent`, etc.) are TCP-pipe metrics, not
+/// routing priority. Returning `0` for those targets makes every
+/// candidate compare equal.
+#[cfg(target_os = "openbsd")]
+#[inline]
+pub(super) fn route_priority(rtm: &RtMsghdr) -> u8 {
+  rtm.rtm_priority
+}
+
+#[cfg(not(target_os = "openbsd"))]
+#[inline]
+pub(super) fn route_priority(_rtm: &RtMsghdr) -> u8 {
+  0
+}
+
+/// The path metrics in `rtm_rmx`. `rmx_rtt` is in `RTM_RTTUNIT`
+/// (microseconds) and `rmx_expire` in wall-clock seconds, which every
+/// BSD converts its internal uptime-based expiry to before copying the
+/// route out. Zero means unset for all four.
+pub(super) fn route_metrics(rtm: &RtMsghdr) -> RouteMetrics {
+  let rmx = &rtm.rtm_rmx;
+  RouteMetrics::new(
+    nonzero(rmx.rmx_mtu).map(|v| v as u32),
+    nonzero(rmx.rmx_rtt).map(Duration::from_micros),
+    nonzero(rmx.rmx_hopcount).map(|v| v as u32),
+    nonzero(rmx.rmx_expire).map(|v| SystemTime::UNIX_EPOCH + Duration::from_secs(v)),
+  )
+}
+
+/// The `rt_metrics` (and `if_data`) field types differ across the BSDs
+/// (`u_int`, `u_long`, `uint64_t`, `time_t`); widen whichever it is,
+/// treating zero and negative values as unset.
+#[inline]
+pub(super) fn nonzero<T: TryInto<u64>>(v: T) -> Option<u64> {
+  v.try_into().ok().filter(|&v| v != 0)
+}
 
 /// Walk every entry in the kernel routing-table sysctl dump (`NET_RT_DUMP`).
-/// Calls `on_route(index, rtm_flags, destination, gateway, netmask)` for
-/// each `RTM_GET` message — all five come straight from the kernel
+/// Calls `on_route(index, rtm_flags, destination, gateway, netmask,
+/// metric, metrics)` for each `RTM_GET` message — all seven come straight from the kernel
 /// header / `parse_addrs` so the caller decides how to merge them into a
 /// CIDR. `rtm_flags` is needed because BSD's "missing RTAX_NETMASK"
 /// means different things for host routes (`RTF_HOST` set, implicit
@@ -47,7 +96,7 @@ use super::{compat::RtMsghdr, fetch, message_too_short, parse_addrs};
 /// sentinel and terminates the loop cleanly.
 pub(super) fn walk_route_table<F>(family: i32, mut on_route: F) -> io::Result<()>
 where
-  F: FnMut(u32, libc::c_int, Option<IpAddr>, Option<IpAddr>, Option<IpAddr>),
+  F: FnMut(u32, libc::c_int, Option<IpAddr>, Option<IpAddr>, Option<IpAddr>, u32, RouteMetrics),
 {
   let buf = fetch(family, NET_RT_DUMP, 0)?;
 
@@ -158,7 +207,15 @@ where
       let gateway = addrs[RTAX_GATEWAY as usize];
       let netmask = addrs[RTAX_NETMASK as usize];
 
-      on_route(rtm.rtm_index as u32, rtm.rtm_flags, dst, gateway, netmask);
+      on_route(
+        kernel_index(rtm.rtm_index),
+        rtm.rtm_flags,
+        dst,
+        gateway,
+        netmask,
+        route_priority(&rtm) as u32,
+        route_metrics(&rtm),
+      );
 
       src = &src[l..];
     }
@@ -166,3 +223,162 @@ where
 
   Ok(())
 }
+
+pub(super) fn open_socket(family: c_int, ty: c_int, protocol: c_int) -> io::Result<OwnedFd> {
+  // SAFETY: plain socket(2) call; the result is checked before use.
+  let fd = unsafe { libc::socket(family, ty, protocol) };
+  if fd < 0 {
+    return Err(io::Error::last_os_error());
+  }
+  // SAFETY: `fd` is a freshly opened descriptor we exclusively own.
+  Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// A `sockaddr_in` with `sin_len` set, as the BSD kernels require.
+pub(super) fn sockaddr_in(addr: Ipv4Addr) -> sockaddr_in {
+  // SAFETY: `sockaddr_in` is a plain C struct for which all-zero is a
+  // valid value.
+  let mut sin: sockaddr_in = unsafe { mem::zeroed() };
+  sin.sin_len = mem::size_of::<sockaddr_in>() as u8;
+  sin.sin_family = AF_INET as libc::sa_family_t;
+  sin.sin_addr.s_addr = u32::from_ne_bytes(addr.octets());
+  sin
+}
+
+/// A `sockaddr_in6` with `sin6_len` set.
+pub(super) fn sockaddr_in6(addr: Ipv6Addr) -> sockaddr_in6 {
+  // SAFETY: `sockaddr_in6` is a plain C struct for which all-zero is
+  // a valid value.
+  let mut sin6: sockaddr_in6 = unsafe { mem::zeroed() };
+  sin6.sin6_len = mem::size_of::<sockaddr_in6>() as u8;
+  sin6.sin6_family = AF_INET6 as libc::sa_family_t;
+  sin6.sin6_addr.s6_addr = addr.octets();
+  sin6
+}
+
+/// Appends `sa` to a routing message, padded to the kernel's sockaddr
+/// alignment.
+pub(super) fn push_sockaddr<T>(msg: &mut Vec<u8>, sa: &T) {
+  let len = mem::size_of::<T>();
+  // SAFETY: `T` is one of the `sockaddr_*` C structs, which have no
+  // padding, so all `len` bytes are initialised.
+  let bytes = unsafe { std::slice::from_raw_parts((sa as *const T).cast::<u8>(), len) };
+  msg.extend_from_slice(bytes);
+  msg.resize(msg.len() - len + roundup(len), 0);
+}
+
+pub(super) fn push_ip_sockaddr(msg: &mut Vec<u8>, addr: IpAddr) {
+  match addr {
+    IpAddr::V4(addr) => push_sockaddr(msg, &sockaddr_in(addr)),
+    IpAddr::V6(addr) => push_sockaddr(msg, &sockaddr_in6(addr)),
+  }
+}
+
+/// A link-level `sockaddr_dl` naming the interface with `index`, the
+/// way `route add -interface` passes it.
+pub(super) fn sockaddr_dl(index: u32) -> sockaddr_dl {
+  // SAFETY: `sockaddr_dl` is a plain C struct for which all-zero is a
+  // valid value.
+  let mut sdl: sockaddr_dl = unsafe { mem::zeroed() };
+  sdl.sdl_len = mem::size_of::<sockaddr_dl>() as u8;
+  sdl.sdl_family = AF_LINK as libc::sa_family_t;
+  sdl.sdl_index = index as u16;
+  sdl
+}
+
+/// Serializes a routing message: `rtm`, with its length, version and
+/// (on OpenBSD) header length filled in here, followed by `tail`, the
+/// sockaddrs its `rtm_addrs` bits announce.
+pub(super) fn route_message_bytes(mut rtm: RtMsghdr, tail: &[u8]) -> Vec<u8> {
+  let hdrlen = mem::size_of::<RtMsghdr>();
+  rtm.rtm_msglen = (hdrlen + tail.len()) as u16;
+  rtm.rtm_version = RTM_VERSION as u8;
+  #[cfg(target_os = "openbsd")]
+  {
+    rtm.rtm_hdrlen = hdrlen as u16;
+  }
+
+  let mut msg = Vec::with_capacity(hdrlen + tail.len());
+  // SAFETY: `rtm` is a fully initialised `rt_msghdr` read as bytes.
+  msg.extend_from_slice(unsafe {
+    std::slice::from_raw_parts((&rtm as *const RtMsghdr).cast::<u8>(), hdrlen)
+  });
+  msg.extend_from_slice(tail);
+  msg
+}
+
+/// Asks the kernel which route it would use for `dest` with an
+/// `RTM_GET` on a routing socket, as `route -n get` does. Returns the
+/// outgoing interface and the source address (`RTAX_IFA`) the kernel
+/// attached to the route, or `None` when no route covers `dest`.
+pub(super) fn route_get(dest: IpAddr) -> io::Result<Option<(u32, Option<IpAddr>)>> {
+  const SEQ: c_int = 1;
+
+  // An empty `RTA_IFP` asks the kernel to fill in the interface, and
+  // with it `RTA_IFA`.
+  let mut tail = Vec::with_capacity(64);
+  push_ip_sockaddr(&mut tail, dest);
+  push_sockaddr(&mut tail, &sockaddr_dl(0));
+  // SAFETY: `RtMsghdr` is a plain C struct for which all-zero is a
+  // valid value.
+  let mut rtm: RtMsghdr = unsafe { mem::zeroed() };
+  rtm.rtm_type = RTM_GET as u8;
+  rtm.rtm_flags = RTF_UP | RTF_HOST;
+  rtm.rtm_addrs = RTA_DST | RTA_IFP;
+  rtm.rtm_seq = SEQ;
+  let msg = route_message_bytes(rtm, &tail);
+
+  let sock = open_socket(AF_ROUTE, SOCK_RAW, AF_UNSPEC)?;
+  // SAFETY: `msg` is a valid buffer of `msg.len()` bytes.
+  if unsafe { libc::write(sock.as_raw_fd(), msg.as_ptr().cast(), msg.len()) } < 0 {
+    let err = io::Error::last_os_error();
+    return match err.raw_os_error() {
+      Some(libc::ESRCH | libc::ENETUNREACH | libc::EHOSTUNREACH) => Ok(None),
+      _ => Err(err),
+    };
+  }
+
+  // SAFETY: no preconditions.
+  let pid = unsafe { libc::getpid() };
+  let header_size = mem::size_of::<RtMsghdr>();
+  let mut buf = [0u8; 2048];
+  loop {
+    // SAFETY: `buf` is a writable local buffer of the given length.
+    let n = unsafe { libc::read(sock.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+    if n < 0 {
+      let err = io::Error::last_os_error();
+      if err.kind() == io::ErrorKind::Interrupted {
+        continue;
+      }
+      return Err(err);
+    }
+
+    // The socket sees every routing message on the host; only the
+    // reply to our request ends the wait.
+    let b = &buf[..n as usize];
+    if b.len() < 4 || b[2] as i32 != RTM_VERSION || b[3] as i32 != RTM_GET {
+      continue;
+    }
+    if b.len() < header_size {
+      return Err(message_too_short());
+    }
+    // SAFETY: u8-aligned buffer; copy the header out before reading
+    // fields.
+    let rtm: RtMsghdr = unsafe { std::ptr::read_unaligned(b.as_ptr() as *const RtMsghdr) };
+    if rtm.rtm_pid != pid || rtm.rtm_seq != SEQ {
+      continue;
+    }
+    if rtm.rtm_errno != 0 {
+      return Err(io::Error::from_raw_os_error(rtm.rtm_errno));
+    }
+    let len = (rtm.rtm_msglen as usize).min(b.len());
+    if len < header_size {
+      return Err(message_too_short());
+    }
+    let addrs = parse_addrs(rtm.rtm_addrs as u32, &b[header_size..len])?;
+    return Ok(Some((
+      kernel_index(rtm.rtm_index),
+      addrs[RTAX_IFA as usize],
+    )));
+  }
+}
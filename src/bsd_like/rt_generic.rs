@@ -1,7 +1,7 @@
 use std::{
   collections::HashSet,
   io,
-  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  net::{IpAddr, Ipv4Addr},
 };
 
 use libc::{AF_INET, AF_INET6, AF_UNSPEC, NET_RT_FLAGS, RTF_UP};
@@ -9,7 +9,10 @@ use smallvec_wrapper::SmallVec;
 
 use crate::is_ipv6_unspecified;
 
-use super::{super::Address, compat::RtMsghdr, fetch, message_too_short, roundup};
+use super::{
+  super::Address, compat::RtMsghdr, fetch, kernel_index, message_too_short, roundup,
+  strip_embedded_scope,
+};
 
 pub(super) fn rt_generic_addrs_in<A, F>(
   family: i32,
@@ -140,7 +143,7 @@ where
                   let bytes = sa_in.sin_addr.s_addr.to_ne_bytes();
                   let ip = IpAddr::V4(Ipv4Addr::from(bytes));
                   if let Some(addr) =
-                    A::try_from_with_filter(rtm.rtm_index as u32, ip, |addr| f(addr))
+                    A::try_from_with_filter(kernel_index(rtm.rtm_index), ip, |addr| f(addr))
                   {
                     if seen.insert((addr.index(), addr.addr())) {
                       results.push(addr);
@@ -155,9 +158,12 @@ where
                 let sa_in6: libc::sockaddr_in6 =
                   std::ptr::read_unaligned(cur.as_ptr() as *const libc::sockaddr_in6);
                 if !is_ipv6_unspecified(sa_in6.sin6_addr.s6_addr) {
-                  let ip = IpAddr::V6(Ipv6Addr::from(sa_in6.sin6_addr.s6_addr));
+                  // Link-local next hops (the usual case for IPv6
+                  // default routes) carry their zone embedded in the
+                  // address, like every other KAME sockaddr.
+                  let ip = IpAddr::V6(strip_embedded_scope(sa_in6.sin6_addr.s6_addr));
                   if let Some(addr) =
-                    A::try_from_with_filter(rtm.rtm_index as u32, ip, |addr| f(addr))
+                    A::try_from_with_filter(kernel_index(rtm.rtm_index), ip, |addr| f(addr))
                   {
                     if seen.insert((addr.index(), addr.addr())) {
                       results.push(addr);
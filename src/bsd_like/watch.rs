@@ -0,0 +1,109 @@
+use std::{
+  cell::RefCell,
+  collections::VecDeque,
+  io, mem,
+  net::IpAddr,
+  os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd},
+  time::Instant,
+};
+
+use ipnet::ip_mask_to_prefix;
+use libc::{AF_ROUTE, AF_UNSPEC, RTAX_IFA, RTAX_NETMASK, RTM_DELADDR, RTM_NEWADDR, RTM_VERSION};
+
+use crate::Event;
+
+use super::{ifa_msghdr, kernel_index, message_too_short, parse_addrs, IfNet, Net};
+
+/// A `PF_ROUTE` socket that keeps only `RTM_NEWADDR` / `RTM_DELADDR`.
+///
+/// Routing sockets have no portable per-type subscription, so every
+/// routing message still reaches the process; the others are dropped
+/// here without being surfaced.
+pub(crate) struct AddrWatcher {
+  fd: OwnedFd,
+  /// Events decoded from the last message but not handed out yet.
+  pending: RefCell<VecDeque<Event>>,
+}
+
+impl AddrWatcher {
+  pub(crate) fn new() -> io::Result<Self> {
+    // SAFETY: plain socket(2) call; the result is checked before use.
+    let fd = unsafe { libc::socket(AF_ROUTE, libc::SOCK_RAW, AF_UNSPEC) };
+    if fd < 0 {
+      return Err(io::Error::last_os_error());
+    }
+    Ok(Self {
+      // SAFETY: `fd` is a freshly opened descriptor we exclusively own.
+      fd: unsafe { OwnedFd::from_raw_fd(fd) },
+      pending: RefCell::new(VecDeque::new()),
+    })
+  }
+
+  pub(crate) fn recv(&self, deadline: Option<Instant>) -> io::Result<Event> {
+    const HEADER_SIZE: usize = mem::size_of::<ifa_msghdr>();
+
+    let mut buf = [0u8; 2048];
+    loop {
+      if let Some(event) = self.pending.borrow_mut().pop_front() {
+        return Ok(event);
+      }
+
+      if let Some(deadline) = deadline {
+        crate::utils::poll_readable(self.fd.as_fd(), deadline)?;
+      }
+      // SAFETY: `buf` is a writable local buffer of the given length.
+      let n = unsafe { libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+      if n < 0 {
+        let err = io::Error::last_os_error();
+        if err.kind() == io::ErrorKind::Interrupted {
+          continue;
+        }
+        return Err(err);
+      }
+
+      // A routing socket read returns exactly one message.
+      let b = &buf[..n as usize];
+      if b.len() < 4 || b[2] as i32 != RTM_VERSION {
+        continue;
+      }
+      let added = match b[3] as i32 {
+        RTM_NEWADDR => true,
+        RTM_DELADDR => false,
+        _ => continue,
+      };
+      if b.len() < HEADER_SIZE {
+        return Err(message_too_short());
+      }
+      // SAFETY: u8-aligned buffer; copy the header out before reading
+      // fields.
+      let ifam: ifa_msghdr = unsafe { core::ptr::read_unaligned(b.as_ptr() as *const ifa_msghdr) };
+      let len = (ifam.ifam_msglen as usize).min(b.len());
+      if len < HEADER_SIZE {
+        return Err(message_too_short());
+      }
+
+      let addrs = parse_addrs(ifam.ifam_addrs as u32, &b[HEADER_SIZE..len])?;
+      let Some(ip) = addrs[RTAX_IFA as usize] else {
+        continue;
+      };
+      // A missing netmask is treated as a host address rather than
+      // dropping the event: losing an `RTM_DELADDR` would leave the
+      // caller tracking an address that is gone.
+      let prefix = match addrs[RTAX_NETMASK as usize].map(ip_mask_to_prefix) {
+        Some(Ok(prefix)) => prefix,
+        Some(Err(_)) => continue,
+        None => match ip {
+          IpAddr::V4(_) => 32,
+          IpAddr::V6(_) => 128,
+        },
+      };
+      if let Some(net) = <IfNet as Net>::try_from(kernel_index(ifam.ifam_index), ip, prefix) {
+        self.pending.borrow_mut().push_back(if added {
+          Event::AddrAdded(net)
+        } else {
+          Event::AddrRemoved(net)
+        });
+      }
+    }
+  }
+}
@@ -0,0 +1,101 @@
+use super::Flags;
+
+bitflags::bitflags! {
+  /// The subset of interface flags every supported platform reports,
+  /// with the same bit values everywhere.
+  ///
+  /// [`Flags`] uses each platform's native bit layout, so a value
+  /// built on Linux does not mean the same thing on Windows. Convert
+  /// through `CommonFlags` to construct or compare flags portably.
+  #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+  pub struct CommonFlags: u32 {
+    /// Interface is administratively up
+    const UP = 0x1;
+    /// Interface supports broadcast access capability
+    const BROADCAST = 0x2;
+    /// Interface is a loopback net
+    const LOOPBACK = 0x4;
+    /// Interface is point-to-point link
+    const POINTOPOINT = 0x8;
+    /// Interface supports multicast access capability
+    const MULTICAST = 0x10;
+    /// Interface is in running state
+    const RUNNING = 0x20;
+  }
+}
+
+const MAPPING: [(CommonFlags, Flags); 6] = [
+  (CommonFlags::UP, Flags::UP),
+  (CommonFlags::BROADCAST, Flags::BROADCAST),
+  (CommonFlags::LOOPBACK, Flags::LOOPBACK),
+  (CommonFlags::POINTOPOINT, Flags::POINTOPOINT),
+  (CommonFlags::MULTICAST, Flags::MULTICAST),
+  (CommonFlags::RUNNING, Flags::RUNNING),
+];
+
+impl CommonFlags {
+  /// Converts to this platform's [`Flags`].
+  ///
+  /// Lossless: every common flag has a platform counterpart.
+  pub fn to_platform(self) -> Flags {
+    MAPPING
+      .iter()
+      .filter(|(common, _)| self.contains(*common))
+      .fold(Flags::empty(), |acc, (_, platform)| acc | *platform)
+  }
+}
+
+impl Flags {
+  /// Converts to the platform-independent [`CommonFlags`].
+  ///
+  /// Platform-specific bits with no common counterpart (e.g. Linux
+  /// `PROMISC`, BSD `SIMPLEX`) are dropped.
+  pub fn to_common(self) -> CommonFlags {
+    MAPPING
+      .iter()
+      .filter(|(_, platform)| self.contains(*platform))
+      .fold(CommonFlags::empty(), |acc, (common, _)| acc | *common)
+  }
+}
+
+impl From<CommonFlags> for Flags {
+  #[inline]
+  fn from(flags: CommonFlags) -> Self {
+    flags.to_platform()
+  }
+}
+
+impl From<Flags> for CommonFlags {
+  #[inline]
+  fn from(flags: Flags) -> Self {
+    flags.to_common()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Every combination of the six common bits must survive
+  // common → platform → common unchanged.
+  #[test]
+  fn common_round_trip() {
+    for bits in 0..=CommonFlags::all().bits() {
+      let common = CommonFlags::from_bits(bits).unwrap();
+      assert_eq!(Flags::from(common).to_common(), common);
+    }
+  }
+
+  // Platform-only bits are dropped on the way to `CommonFlags`, so the
+  // round trip keeps exactly the common part of the original value.
+  #[test]
+  fn platform_specific_bits_are_dropped() {
+    let common_mask = CommonFlags::all().to_platform();
+    let platform_only = Flags::all().difference(common_mask);
+    assert_eq!(platform_only.to_common(), CommonFlags::empty());
+
+    let flags = Flags::all();
+    assert_eq!(flags.to_common(), CommonFlags::all());
+    assert_eq!(flags.to_common().to_platform(), common_mask);
+  }
+}
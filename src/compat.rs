@@ -0,0 +1,10 @@
+//! Drop-in shapes of other crates' APIs, built on getifs, to ease
+//! migrating off them. Each shim sits behind its own feature.
+
+#[cfg(feature = "compat-network-interface")]
+#[cfg_attr(docsrs, doc(cfg(feature = "compat-network-interface")))]
+pub mod network_interface;
+
+#[cfg(feature = "compat-local-ip-address")]
+#[cfg_attr(docsrs, doc(cfg(feature = "compat-local-ip-address")))]
+pub mod local_ip_address;
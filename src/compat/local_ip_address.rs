@@ -0,0 +1,81 @@
+//! Functions shaped like the [`local-ip-address`](https://crates.io/crates/local-ip-address)
+//! crate's `local_ip` / `local_ipv6`, so callers can switch to getifs
+//! by changing their imports.
+
+use std::{io, net::IpAddr};
+
+use crate::{best_local_ipv4_addrs, best_local_ipv6_addrs, Error, Result};
+
+/// Returns the IPv4 address of the interface carrying the best default
+/// route, like `local_ip_address::local_ip`.
+///
+/// Built on [`best_local_ipv4_addrs`]; when that interface has several
+/// addresses the first one the OS reports is returned. Fails with an
+/// [`Error`] of kind [`io::ErrorKind::NotFound`] when there is no IPv4
+/// default route or its interface has no IPv4 address.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::compat::local_ip_address::local_ip;
+///
+/// println!("{}", local_ip().unwrap());
+/// ```
+pub fn local_ip() -> Result<IpAddr> {
+  best_local_ipv4_addrs()?
+    .first()
+    .map(|addr| IpAddr::V4(addr.addr()))
+    .ok_or_else(|| not_found("IPv4"))
+}
+
+/// Returns the IPv6 address of the interface carrying the best default
+/// route, like `local_ip_address::local_ipv6`.
+///
+/// Built on [`best_local_ipv6_addrs`]; when that interface has several
+/// addresses the first one the OS reports is returned. Fails with an
+/// [`Error`] of kind [`io::ErrorKind::NotFound`] when there is no IPv6
+/// default route or its interface has no IPv6 address.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::compat::local_ip_address::local_ipv6;
+///
+/// println!("{}", local_ipv6().unwrap());
+/// ```
+pub fn local_ipv6() -> Result<IpAddr> {
+  best_local_ipv6_addrs()?
+    .first()
+    .map(|addr| IpAddr::V6(addr.addr()))
+    .ok_or_else(|| not_found("IPv6"))
+}
+
+fn not_found(family: &str) -> Error {
+  Error::Io(io::Error::new(
+    io::ErrorKind::NotFound,
+    format!("no local {family} address on a default-route interface"),
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // On a host with a default route the result must be a real address of
+  // that family, never loopback; without one, only NotFound is allowed.
+  #[test]
+  fn local_ip_is_not_loopback() {
+    match local_ip() {
+      Ok(ip) => assert!(ip.is_ipv4() && !ip.is_loopback(), "{ip}"),
+      Err(e) => assert_eq!(e.kind(), io::ErrorKind::NotFound, "{e}"),
+    }
+  }
+
+  #[test]
+  fn local_ipv6_is_not_loopback() {
+    match local_ipv6() {
+      Ok(ip) => assert!(ip.is_ipv6() && !ip.is_loopback(), "{ip}"),
+      Err(e) => assert_eq!(e.kind(), io::ErrorKind::NotFound, "{e}"),
+    }
+  }
+}
@@ -0,0 +1,178 @@
+//! Types shaped like the [`network-interface`](https://crates.io/crates/network-interface)
+//! crate's, so code written against `NetworkInterface::show()` can
+//! switch to getifs by changing its imports.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{interface_addrs, interfaces, Flags, IfNet, Result};
+
+/// An interface and its addresses, like `network_interface::NetworkInterface`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NetworkInterface {
+  /// The interface name.
+  pub name: String,
+  /// The interface's unicast addresses.
+  pub addr: Vec<Addr>,
+  /// The hardware address, formatted as colon-separated lowercase hex.
+  pub mac_addr: Option<String>,
+  /// The interface index.
+  pub index: u32,
+}
+
+/// An interface address of either family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Addr {
+  /// An IPv4 address.
+  V4(V4IfAddr),
+  /// An IPv6 address.
+  V6(V6IfAddr),
+}
+
+impl Addr {
+  /// Returns the address itself.
+  #[inline]
+  pub const fn ip(&self) -> std::net::IpAddr {
+    match self {
+      Self::V4(addr) => std::net::IpAddr::V4(addr.ip),
+      Self::V6(addr) => std::net::IpAddr::V6(addr.ip),
+    }
+  }
+}
+
+/// An IPv4 interface address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct V4IfAddr {
+  /// The address.
+  pub ip: Ipv4Addr,
+  /// The directed broadcast address, for interfaces with
+  /// [`Flags::BROADCAST`] and a prefix shorter than `/31`.
+  pub broadcast: Option<Ipv4Addr>,
+  /// The netmask.
+  pub netmask: Option<Ipv4Addr>,
+}
+
+/// An IPv6 interface address. IPv6 has no broadcast, so `broadcast` is
+/// always `None`; it exists to match the original shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct V6IfAddr {
+  /// The address.
+  pub ip: Ipv6Addr,
+  /// Always `None`.
+  pub broadcast: Option<Ipv6Addr>,
+  /// The netmask.
+  pub netmask: Option<Ipv6Addr>,
+}
+
+impl NetworkInterface {
+  /// Returns every interface with its addresses, like
+  /// `NetworkInterfaceConfig::show`.
+  ///
+  /// Costs one interface dump and one address dump, joined by index.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::compat::network_interface::NetworkInterface;
+  ///
+  /// for ifi in NetworkInterface::show().unwrap() {
+  ///   println!("{}: {:?}", ifi.name, ifi.addr);
+  /// }
+  /// ```
+  pub fn show() -> Result<Vec<Self>> {
+    show()
+  }
+}
+
+/// Free-function form of [`NetworkInterface::show`].
+pub fn show() -> Result<Vec<NetworkInterface>> {
+  let addrs = interface_addrs()?;
+  Ok(
+    interfaces()?
+      .into_iter()
+      .map(|ifi| {
+        let broadcast = ifi.flags().contains(Flags::BROADCAST);
+        NetworkInterface {
+          name: ifi.name().to_string(),
+          addr: addrs
+            .iter()
+            .filter(|addr| addr.index() == ifi.index())
+            .map(|addr| to_addr(addr, broadcast))
+            .collect(),
+          mac_addr: ifi.mac_addr().map(|mac| mac.to_string()),
+          index: ifi.index(),
+        }
+      })
+      .collect(),
+  )
+}
+
+fn to_addr(addr: &IfNet, broadcast: bool) -> Addr {
+  match addr {
+    IfNet::V4(v4) => {
+      let net = v4.net();
+      Addr::V4(V4IfAddr {
+        ip: net.addr(),
+        // Prefer what the OS reports; derive it only where it has none.
+        broadcast: v4
+          .broadcast()
+          .or_else(|| (broadcast && net.prefix_len() < 31).then(|| net.broadcast())),
+        netmask: Some(net.netmask()),
+      })
+    }
+    IfNet::V6(v6) => Addr::V6(V6IfAddr {
+      ip: v6.addr(),
+      broadcast: None,
+      netmask: Some(v6.net().netmask()),
+    }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // The shim must list exactly the interfaces getifs itself sees.
+  #[test]
+  fn same_names_as_interfaces() {
+    let mut shim: Vec<String> = show().unwrap().into_iter().map(|ifi| ifi.name).collect();
+    let mut native: Vec<String> = interfaces()
+      .unwrap()
+      .into_iter()
+      .map(|ifi| ifi.name().to_string())
+      .collect();
+    shim.sort();
+    native.sort();
+    assert_eq!(shim, native);
+  }
+
+  // A /24 on a broadcast-capable link gets its directed broadcast; a
+  // point-to-point /31 and IPv6 never do.
+  #[test]
+  fn broadcast_and_netmask() {
+    let v4 = IfNet::from_net(1, "192.0.2.5/24".parse().unwrap());
+    assert_eq!(
+      to_addr(&v4, true),
+      Addr::V4(V4IfAddr {
+        ip: Ipv4Addr::new(192, 0, 2, 5),
+        broadcast: Some(Ipv4Addr::new(192, 0, 2, 255)),
+        netmask: Some(Ipv4Addr::new(255, 255, 255, 0)),
+      })
+    );
+    let p2p = IfNet::from_net(1, "192.0.2.0/31".parse().unwrap());
+    assert!(matches!(
+      to_addr(&p2p, true),
+      Addr::V4(V4IfAddr {
+        broadcast: None,
+        ..
+      })
+    ));
+    let v6 = IfNet::from_net(1, "2001:db8::1/64".parse().unwrap());
+    assert!(matches!(
+      to_addr(&v6, true),
+      Addr::V6(V6IfAddr {
+        broadcast: None,
+        ..
+      })
+    ));
+  }
+}
@@ -0,0 +1,234 @@
+use std::{io, net::IpAddr};
+
+use ipnet::IpNet;
+
+use super::{os, Error, Result};
+
+#[inline]
+fn invalid_input(msg: &'static str) -> Error {
+  io::Error::new(io::ErrorKind::InvalidInput, msg).into()
+}
+
+/// Spells out what a bare `EPERM` / `ERROR_ACCESS_DENIED` from a write
+/// operation means; other errors pass through unchanged.
+fn explain_permission(op: &str, err: io::Error) -> Error {
+  if err.kind() != io::ErrorKind::PermissionDenied {
+    return err.into();
+  }
+  let needs = if cfg!(windows) {
+    "an elevated process"
+  } else if cfg!(any(target_os = "linux", target_os = "android")) {
+    "root or CAP_NET_ADMIN"
+  } else {
+    "root"
+  };
+  io::Error::new(
+    io::ErrorKind::PermissionDenied,
+    format!("{op} requires {needs}: {err}"),
+  )
+  .into()
+}
+
+/// Sets the MTU of the interface with the given index.
+///
+/// This is the write counterpart of [`Interface::mtu`](crate::Interface::mtu)
+/// and needs the privileges to reconfigure interfaces (root or
+/// `CAP_NET_ADMIN` on Linux, root on BSD and macOS, an elevated process
+/// on Windows); without them it fails with
+/// [`io::ErrorKind::PermissionDenied`] and a message saying so.
+///
+/// - **Linux / Android, BSD, macOS**: `ioctl(SIOCSIFMTU)`.
+/// - **Windows**: `SetIpInterfaceEntry`, applied to both the IPv4 and
+///   the IPv6 configuration of the interface, whichever are present.
+///
+/// The driver decides which values it accepts; an MTU outside its
+/// range fails with the OS error. Index `0` and an MTU of `0` or above
+/// `i32::MAX` are rejected with [`io::ErrorKind::InvalidInput`] up
+/// front.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::{ifname_to_index, set_mtu};
+///
+/// let index = ifname_to_index("eth0").unwrap();
+/// set_mtu(index, 1400).unwrap();
+/// ```
+pub fn set_mtu(index: u32, mtu: u32) -> Result<()> {
+  if index == 0 {
+    return Err(invalid_input("interface index 0 names no interface"));
+  }
+  if mtu == 0 || mtu > i32::MAX as u32 {
+    return Err(invalid_input("MTU out of range"));
+  }
+  os::set_mtu(index, mtu).map_err(|e| explain_permission("setting the MTU", e))
+}
+
+/// Brings the interface with the given index administratively up or
+/// down.
+///
+/// Like [`set_mtu`] this needs the privileges to reconfigure
+/// interfaces, and fails with [`io::ErrorKind::PermissionDenied`]
+/// without them. The change shows up in
+/// [`Interface::flags`](crate::Interface::flags) as `Flags::UP`.
+///
+/// - **Linux / Android, BSD, macOS**: `ioctl(SIOCGIFFLAGS)`, then
+///   `ioctl(SIOCSIFFLAGS)` with `IFF_UP` set or cleared, so the other
+///   flags are written back unchanged.
+/// - **Windows**: `SetIfEntry` with the matching admin status.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::{ifname_to_index, set_interface_up};
+///
+/// let index = ifname_to_index("eth0").unwrap();
+/// set_interface_up(index, false).unwrap();
+/// set_interface_up(index, true).unwrap();
+/// ```
+pub fn set_interface_up(index: u32, up: bool) -> Result<()> {
+  if index == 0 {
+    return Err(invalid_input("interface index 0 names no interface"));
+  }
+  os::set_interface_up(index, up).map_err(|e| explain_permission("changing interface state", e))
+}
+
+/// Adds `net` to the interface with the given index: the address
+/// `net.addr()` with prefix length `net.prefix_len()`, which also
+/// installs the connected route for the prefix.
+///
+/// Adding an address that is already present fails (`EEXIST` on Linux,
+/// `ERROR_OBJECT_ALREADY_EXISTS` on Windows) except on the BSDs, where
+/// the kernel updates it in place. Needs the same privileges as
+/// [`set_mtu`].
+///
+/// - **Linux / Android**: netlink `RTM_NEWADDR`.
+/// - **BSD, macOS**: `ioctl(SIOCAIFADDR)` for IPv4 and
+///   `ioctl(SIOCAIFADDR_IN6)` for IPv6. IPv6 fails with
+///   [`Error::UnsupportedPlatform`] on Apple platforms other than
+///   macOS.
+/// - **Windows**: `CreateUnicastIpAddressEntry`.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::{add_addr, del_addr, ifname_to_index};
+///
+/// let index = ifname_to_index("eth0").unwrap();
+/// let net = "192.0.2.10/24".parse().unwrap();
+/// add_addr(index, net).unwrap();
+/// del_addr(index, net).unwrap();
+/// ```
+pub fn add_addr(index: u32, net: IpNet) -> Result<()> {
+  if index == 0 {
+    return Err(invalid_input("interface index 0 names no interface"));
+  }
+  os::add_addr(index, net).map_err(|e| explain_permission("adding an address", e))
+}
+
+/// Removes `net` from the interface with the given index; the inverse
+/// of [`add_addr`], with the same platform support. The prefix length
+/// has to match the one the address was added with on Linux.
+pub fn del_addr(index: u32, net: IpNet) -> Result<()> {
+  if index == 0 {
+    return Err(invalid_input("interface index 0 names no interface"));
+  }
+  os::del_addr(index, net).map_err(|e| explain_permission("removing an address", e))
+}
+
+/// Adds a route to `dest` through `gateway` and/or the interface with
+/// index `ifindex`, with the given `metric`.
+///
+/// This is the write counterpart of [`route_table`](crate::route_table).
+/// Host bits in `dest` are ignored, so `192.0.2.7/24` installs
+/// `192.0.2.0/24`. A route needs a next hop: either a `gateway` of the
+/// same family as `dest`, an interface (`ifindex` other than `0`), or
+/// both; a route without a gateway is on-link. Adding a route that
+/// already exists fails with [`io::ErrorKind::AlreadyExists`]. Needs
+/// the same privileges as [`set_mtu`].
+///
+/// - **Linux / Android**: netlink `RTM_NEWROUTE` into the main table,
+///   with protocol `static`.
+/// - **BSD, macOS**: an `RTM_ADD` message on a routing socket. The
+///   routing socket has no portable per-route metric, so `metric` is
+///   ignored.
+/// - **Windows**: `CreateIpForwardEntry2`. Windows needs the interface,
+///   so `ifindex` must not be `0` there.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::{add_route, del_route, ifname_to_index};
+///
+/// let index = ifname_to_index("eth0").unwrap();
+/// let dest = "198.51.100.0/24".parse().unwrap();
+/// add_route(dest, Some("192.0.2.1".parse().unwrap()), index, 100).unwrap();
+/// del_route(dest).unwrap();
+/// ```
+pub fn add_route(dest: IpNet, gateway: Option<IpAddr>, ifindex: u32, metric: u32) -> Result<()> {
+  match gateway {
+    Some(gw) if gw.is_ipv4() != dest.addr().is_ipv4() => {
+      return Err(invalid_input(
+        "gateway and destination address families differ",
+      ));
+    }
+    None if ifindex == 0 => {
+      return Err(invalid_input("a route needs a gateway or an interface"));
+    }
+    _ => {}
+  }
+  if cfg!(windows) && ifindex == 0 {
+    return Err(invalid_input("interface index 0 names no interface"));
+  }
+  os::add_route(dest.trunc(), gateway, ifindex, metric)
+    .map_err(|e| explain_permission("adding a route", e))
+}
+
+/// Removes the route to `dest`; the inverse of [`add_route`], with the
+/// same platform support.
+///
+/// Host bits in `dest` are ignored. When several routes share the
+/// destination (different gateways or metrics), the first one the
+/// kernel finds is removed; call again to remove the next. Removing a
+/// route that does not exist fails with the OS error (`ESRCH` on Linux
+/// and BSD, `ERROR_NOT_FOUND` on Windows).
+pub fn del_route(dest: IpNet) -> Result<()> {
+  os::del_route(dest.trunc()).map_err(|e| explain_permission("removing a route", e))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // A permission failure keeps its kind but names the missing
+  // privilege; anything else is returned untouched.
+  #[test]
+  fn explain_permission_only_rewrites_permission_denied() {
+    let err = explain_permission(
+      "setting the MTU",
+      io::Error::from(io::ErrorKind::PermissionDenied),
+    );
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert!(
+      err.to_string().starts_with("setting the MTU requires "),
+      "{err}"
+    );
+
+    let err = explain_permission("setting the MTU", io::Error::from(io::ErrorKind::NotFound));
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(!err.to_string().contains("requires"), "{err}");
+  }
+
+  // Routes with no usable next hop are refused before any OS call, so
+  // this needs no privileges.
+  #[test]
+  fn add_route_rejects_missing_or_mismatched_next_hop() {
+    let dest: IpNet = "198.51.100.0/24".parse().unwrap();
+    let err = add_route(dest, None, 0, 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let gateway = Some("2001:db8::1".parse().unwrap());
+    let err = add_route(dest, gateway, 1, 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+  }
+}
@@ -0,0 +1,135 @@
+use std::io;
+
+/// A [`Result`](core::result::Result) whose error defaults to [`Error`].
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// The error returned by the functions of this crate.
+///
+/// The platform code underneath talks to the kernel through
+/// [`io::Error`]; the variants other than [`Io`](Self::Io) single out the
+/// failures a caller may want to react to differently. `From` converts
+/// both ways, and converting an `Error` to an `io::Error` and back gives
+/// the same variant again.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+  /// The operating system call failed.
+  Io(io::Error),
+  /// A message from the kernel (a netlink reply, a routing socket or
+  /// `sysctl` record) could not be decoded.
+  Parse {
+    /// What was being decoded.
+    context: &'static str,
+  },
+  /// The operation is not available on this platform.
+  UnsupportedPlatform,
+  /// The named interface does not exist.
+  InterfaceNotFound,
+}
+
+impl Error {
+  /// Returns the [`io::ErrorKind`] this error converts to.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::Error;
+  /// use std::io;
+  ///
+  /// assert_eq!(Error::InterfaceNotFound.kind(), io::ErrorKind::NotFound);
+  /// ```
+  pub fn kind(&self) -> io::ErrorKind {
+    match self {
+      Self::Io(e) => e.kind(),
+      Self::Parse { .. } => io::ErrorKind::InvalidData,
+      Self::UnsupportedPlatform => io::ErrorKind::Unsupported,
+      Self::InterfaceNotFound => io::ErrorKind::NotFound,
+    }
+  }
+
+  /// Returns the OS error code of an [`Io`](Self::Io) error.
+  pub fn raw_os_error(&self) -> Option<i32> {
+    match self {
+      Self::Io(e) => e.raw_os_error(),
+      _ => None,
+    }
+  }
+
+  // Windows hands back structured adapter lists, so it never decodes
+  // raw kernel messages.
+  #[cfg_attr(windows, allow(dead_code))]
+  #[inline]
+  pub(crate) const fn parse(context: &'static str) -> Self {
+    Self::Parse { context }
+  }
+}
+
+impl core::fmt::Display for Error {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Io(e) => e.fmt(f),
+      Self::Parse { context } => write!(f, "malformed {context}"),
+      Self::UnsupportedPlatform => f.write_str("not supported on this platform"),
+      Self::InterfaceNotFound => f.write_str("interface not found"),
+    }
+  }
+}
+
+impl std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Self::Io(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+impl From<io::Error> for Error {
+  /// Unwraps an `Error` that was carried through an `io::Error`, and
+  /// wraps any other as [`Error::Io`].
+  fn from(e: io::Error) -> Self {
+    if !e.get_ref().is_some_and(|inner| inner.is::<Self>()) {
+      return Self::Io(e);
+    }
+    *e.into_inner()
+      .and_then(|inner| inner.downcast().ok())
+      .expect("checked to hold an `Error`")
+  }
+}
+
+impl From<Error> for io::Error {
+  fn from(e: Error) -> Self {
+    match e {
+      Error::Io(e) => e,
+      e => io::Error::new(e.kind(), e),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Every variant survives the trip through `io::Error`, which is how
+  // the platform code hands them up.
+  #[test]
+  fn round_trips_through_io_error() {
+    for e in [
+      Error::parse("netlink message"),
+      Error::UnsupportedPlatform,
+      Error::InterfaceNotFound,
+    ] {
+      let kind = e.kind();
+      let text = e.to_string();
+      let io = io::Error::from(e);
+      assert_eq!(io.kind(), kind);
+      let back = Error::from(io);
+      assert_eq!(back.kind(), kind);
+      assert_eq!(back.to_string(), text);
+    }
+
+    let back = Error::from(io::Error::new(io::ErrorKind::NotFound, "gone"));
+    assert!(matches!(back, Error::Io(_)));
+    assert_eq!(back.kind(), io::ErrorKind::NotFound);
+  }
+}
@@ -0,0 +1,155 @@
+#[cfg(target_os = "linux")]
+use smallvec_wrapper::SmallVec;
+use smol_str::SmolStr;
+
+/// Driver details of an interface, as returned by
+/// [`Interface::driver_info`](crate::Interface::driver_info).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct DriverInfo {
+  pub(crate) driver: SmolStr,
+  pub(crate) version: SmolStr,
+  pub(crate) bus_info: SmolStr,
+}
+
+impl DriverInfo {
+  /// Returns the name of the kernel driver bound to the interface
+  /// (`e1000e`, `virtio_net`, `veth`, ...).
+  #[inline]
+  pub fn driver(&self) -> &str {
+    &self.driver
+  }
+
+  /// Returns the driver version string, which in-tree drivers usually
+  /// leave as the kernel release.
+  #[inline]
+  pub fn version(&self) -> &str {
+    &self.version
+  }
+
+  /// Returns the bus address of the device (`0000:00:1f.6` for PCI),
+  /// or an empty string for virtual devices.
+  #[inline]
+  pub fn bus_info(&self) -> &str {
+    &self.bus_info
+  }
+}
+
+/// One generic offload feature of an interface, as reported by
+/// `ETHTOOL_GFEATURES`.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OffloadFeature {
+  pub(crate) name: SmolStr,
+  pub(crate) available: bool,
+  pub(crate) requested: bool,
+  pub(crate) active: bool,
+  pub(crate) fixed: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl OffloadFeature {
+  /// Returns the kernel's name for the feature, as `ethtool -k` prints
+  /// it (`rx-gro`, `tx-tcp-segmentation`, ...).
+  #[inline]
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Returns `true` if the driver lets the feature be switched on and
+  /// off.
+  #[inline]
+  pub const fn available(&self) -> bool {
+    self.available
+  }
+
+  /// Returns `true` if the feature was asked for, whether or not the
+  /// device could honour the request.
+  #[inline]
+  pub const fn requested(&self) -> bool {
+    self.requested
+  }
+
+  /// Returns `true` if the feature is currently in effect.
+  #[inline]
+  pub const fn active(&self) -> bool {
+    self.active
+  }
+
+  /// Returns `true` if the feature can never change on this device.
+  #[inline]
+  pub const fn fixed(&self) -> bool {
+    self.fixed
+  }
+}
+
+/// The generic offload features of an interface, as returned by
+/// [`Interface::offload_features`](crate::Interface::offload_features).
+///
+/// Features are listed in the kernel's bit order. Bit positions are
+/// not stable across kernel versions, so look features up by name.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct OffloadFeatures {
+  pub(crate) features: SmallVec<OffloadFeature>,
+}
+
+#[cfg(target_os = "linux")]
+impl OffloadFeatures {
+  /// Returns every feature the kernel knows about.
+  #[inline]
+  pub fn as_slice(&self) -> &[OffloadFeature] {
+    &self.features
+  }
+
+  /// Returns the feature called `name`, or `None` if this kernel has no
+  /// such feature.
+  pub fn get(&self, name: &str) -> Option<&OffloadFeature> {
+    self.features.iter().find(|f| f.name == name)
+  }
+
+  /// Returns `true` if any of `names` is active.
+  fn any_active(&self, names: &[&str]) -> bool {
+    names
+      .iter()
+      .any(|name| self.get(name).is_some_and(OffloadFeature::active))
+  }
+
+  /// Returns `true` if generic receive offload (`rx-gro`) is active.
+  #[inline]
+  pub fn gro(&self) -> bool {
+    self.any_active(&["rx-gro"])
+  }
+
+  /// Returns `true` if generic segmentation offload
+  /// (`tx-generic-segmentation`) is active.
+  #[inline]
+  pub fn gso(&self) -> bool {
+    self.any_active(&["tx-generic-segmentation"])
+  }
+
+  /// Returns `true` if TCP segmentation offload is active for IPv4 or
+  /// IPv6 (`tx-tcp-segmentation`, `tx-tcp6-segmentation`).
+  #[inline]
+  pub fn tso(&self) -> bool {
+    self.any_active(&["tx-tcp-segmentation", "tx-tcp6-segmentation"])
+  }
+
+  /// Returns `true` if receive checksum offload (`rx-checksum`) is
+  /// active.
+  #[inline]
+  pub fn rx_checksum(&self) -> bool {
+    self.any_active(&["rx-checksum"])
+  }
+
+  /// Returns `true` if any transmit checksum offload is active
+  /// (`tx-checksum-ipv4`, `tx-checksum-ip-generic`,
+  /// `tx-checksum-ipv6`).
+  #[inline]
+  pub fn tx_checksum(&self) -> bool {
+    self.any_active(&[
+      "tx-checksum-ipv4",
+      "tx-checksum-ip-generic",
+      "tx-checksum-ipv6",
+    ])
+  }
+}
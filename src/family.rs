@@ -0,0 +1,21 @@
+use std::net::IpAddr;
+
+/// An IP address family.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Family {
+  /// IPv4.
+  V4,
+  /// IPv6.
+  V6,
+}
+
+impl Family {
+  /// Returns the family of `addr`.
+  #[inline]
+  pub const fn of(addr: &IpAddr) -> Self {
+    match addr {
+      IpAddr::V4(_) => Self::V4,
+      IpAddr::V6(_) => Self::V6,
+    }
+  }
+}
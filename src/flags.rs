@@ -0,0 +1,64 @@
+use super::Flags;
+
+impl Flags {
+  /// Every bit this crate names on the current platform.
+  ///
+  /// Raw flag words from the OS (or from a log written by an older
+  /// version of this crate) may carry bits outside this mask; use
+  /// [`Flags::unknown_bits`] to find them.
+  pub const KNOWN: Self = Self::all();
+
+  /// Converts a raw OS flag word, dropping any bit the crate does not
+  /// model.
+  ///
+  /// Equivalent to [`Flags::from_bits_truncate`]; the bit layout is the
+  /// platform's own (`IFF_*` on Unix), so a value is only meaningful on
+  /// the platform that produced it.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::Flags;
+  ///
+  /// let flags = Flags::from_os_bits(Flags::UP.bits());
+  /// assert_eq!(flags, Flags::UP);
+  /// ```
+  #[inline]
+  pub const fn from_os_bits(raw: u32) -> Self {
+    Self::from_bits_truncate(raw)
+  }
+
+  /// Returns the bits of `raw` that [`Flags::from_os_bits`] would drop,
+  /// i.e. OS flags this crate does not yet name (such as Linux's
+  /// `IFF_LOWER_UP`).
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::Flags;
+  ///
+  /// assert_eq!(Flags::unknown_bits(Flags::UP.bits()), 0);
+  /// ```
+  #[inline]
+  pub const fn unknown_bits(raw: u32) -> u32 {
+    raw & !Self::KNOWN.bits()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // A word mixing modeled bits with an unmodeled high bit must keep the
+  // modeled part and report exactly the high bit as unknown.
+  #[test]
+  fn unmodeled_high_bit_is_reported() {
+    const HIGH: u32 = 0x8000_0000;
+    assert_eq!(Flags::KNOWN.bits() & HIGH, 0);
+
+    let raw = (Flags::UP | Flags::MULTICAST).bits() | HIGH;
+    assert_eq!(Flags::from_os_bits(raw), Flags::UP | Flags::MULTICAST);
+    assert_eq!(Flags::unknown_bits(raw), HIGH);
+    assert_eq!(Flags::unknown_bits(Flags::KNOWN.bits()), 0);
+  }
+}
@@ -0,0 +1,534 @@
+//! Plain-old-data views of interfaces, addresses and routes for
+//! binding getifs from other languages.
+//!
+//! The rich types carry [`SmolStr`](crate::SmolStr), `SmallVec` and
+//! enums with no stable layout. The `#[repr(C)]` structs here hold only
+//! fixed-size fields, and the `collect_*` functions fill a
+//! caller-provided array with them, so an FFI wrapper never has to hand
+//! Rust-owned memory across the boundary.
+//!
+//! Every `collect_*` function is `extern "C"`: it takes a pointer to
+//! `cap` slots, stores the total number of entries through `len`, and
+//! returns a status code. Only the first `min(cap, *len)` slots are
+//! written; [`STATUS_NEED_CAPACITY`] tells the caller to grow its
+//! buffer to `*len` and call again. Passing a null pointer with
+//! `cap == 0` is a size query. A negative status is the negated OS
+//! error code (`errno` on Unix, a Win32 error code on Windows).
+//!
+//! The C symbols carry a `getifs_` prefix (`getifs_collect_interfaces`,
+//! `getifs_collect_addrs`, `getifs_collect_routes`) so they do not
+//! collide with other libraries linked into the same binary; declare
+//! them under those names in a C header.
+
+use std::{
+  ffi::c_int,
+  io,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use ipnet::{Ipv4Net, Ipv6Net};
+
+use crate::{
+  interface_addrs, interfaces, route_table, Error, Flags, IfNet, Interface, IpRoute, Ipv4Route,
+  Ipv6Route, MacAddr, Result,
+};
+
+/// Capacity of [`FlatInterface::name`], including the terminating NUL.
+///
+/// Unix interface names are at most 15 bytes; Windows adapter names
+/// can be longer and are truncated on a character boundary.
+pub const NAME_CAPACITY: usize = 256;
+
+/// Returned by the `collect_*` functions when every entry was written.
+pub const STATUS_OK: c_int = 0;
+/// Returned by the `collect_*` functions when the buffer holds fewer
+/// slots than there are entries; the required count is in `*len`.
+pub const STATUS_NEED_CAPACITY: c_int = 1;
+
+/// [`FlatAddr::family`] / [`FlatRoute::family`] value for IPv4.
+pub const FAMILY_V4: u8 = 4;
+/// [`FlatAddr::family`] / [`FlatRoute::family`] value for IPv6.
+pub const FAMILY_V6: u8 = 6;
+
+/// A flat, `#[repr(C)]` copy of an [`Interface`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct FlatInterface {
+  /// The interface index.
+  pub index: u32,
+  /// The MTU.
+  pub mtu: u32,
+  /// The raw [`Flags`] bits.
+  pub flags: u32,
+  /// `1` if [`mac`](Self::mac) holds a hardware address, `0` otherwise.
+  pub has_mac: u8,
+  /// The hardware address, zeroed when there is none.
+  pub mac: [u8; 6],
+  /// Length of the name in bytes, excluding the NUL.
+  pub name_len: u16,
+  /// The UTF-8 name, NUL-terminated.
+  pub name: [u8; NAME_CAPACITY],
+}
+
+impl FlatInterface {
+  /// Returns the interface name.
+  #[inline]
+  pub fn name(&self) -> &str {
+    let len = (self.name_len as usize).min(NAME_CAPACITY - 1);
+    core::str::from_utf8(&self.name[..len]).unwrap_or_default()
+  }
+
+  /// Returns the hardware address, if any.
+  #[inline]
+  pub const fn mac_addr(&self) -> Option<MacAddr> {
+    match self.has_mac {
+      0 => None,
+      _ => Some(MacAddr::from_raw(self.mac)),
+    }
+  }
+
+  /// Returns the interface flags.
+  #[inline]
+  pub const fn flags(&self) -> Flags {
+    Flags::from_bits_retain(self.flags)
+  }
+}
+
+impl From<&Interface> for FlatInterface {
+  fn from(ifi: &Interface) -> Self {
+    let mut name = [0u8; NAME_CAPACITY];
+    let mut name_len = ifi.name().len().min(NAME_CAPACITY - 1);
+    while !ifi.name().is_char_boundary(name_len) {
+      name_len -= 1;
+    }
+    name[..name_len].copy_from_slice(&ifi.name().as_bytes()[..name_len]);
+
+    let (has_mac, mac) = match ifi.mac_addr() {
+      Some(mac) => (1, mac.as_bytes().try_into().unwrap()),
+      None => (0, [0; 6]),
+    };
+
+    Self {
+      index: ifi.index(),
+      mtu: ifi.mtu(),
+      flags: ifi.flags().bits(),
+      has_mac,
+      mac,
+      name_len: name_len as u16,
+      name,
+    }
+  }
+}
+
+/// A flat, `#[repr(C)]` copy of an [`IfNet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct FlatAddr {
+  /// The interface index.
+  pub index: u32,
+  /// [`FAMILY_V4`] or [`FAMILY_V6`].
+  pub family: u8,
+  /// The prefix length.
+  pub prefix_len: u8,
+  /// `1` if [`broadcast`](Self::broadcast) holds an address, `0`
+  /// otherwise. Always `0` for IPv6.
+  pub has_broadcast: u8,
+  /// The IPv4 broadcast address, zeroed when there is none.
+  pub broadcast: [u8; 4],
+  /// The address in network byte order; IPv4 uses the first 4 bytes.
+  pub addr: [u8; 16],
+}
+
+impl From<&IfNet> for FlatAddr {
+  fn from(net: &IfNet) -> Self {
+    let (family, addr) = flatten_ip(net.addr());
+    let (has_broadcast, broadcast) = match net {
+      IfNet::V4(v4) => match v4.broadcast() {
+        Some(b) => (1, b.octets()),
+        None => (0, [0; 4]),
+      },
+      IfNet::V6(_) => (0, [0; 4]),
+    };
+    Self {
+      index: net.index(),
+      family,
+      prefix_len: net.prefix_len(),
+      has_broadcast,
+      broadcast,
+      addr,
+    }
+  }
+}
+
+impl TryFrom<FlatAddr> for IfNet {
+  type Error = Error;
+
+  /// Fails with [`io::ErrorKind::InvalidData`] on an unknown family or
+  /// an out-of-range prefix length.
+  fn try_from(flat: FlatAddr) -> Result<Self> {
+    let ip = unflatten_ip(flat.family, &flat.addr)?;
+    let net = IfNet::with_prefix_len(flat.index, ip, flat.prefix_len).map_err(invalid_data)?;
+    let broadcast = (flat.has_broadcast != 0).then(|| Ipv4Addr::from(flat.broadcast));
+    Ok(crate::Net::with_broadcast(net, broadcast))
+  }
+}
+
+/// A flat, `#[repr(C)]` copy of an [`IpRoute`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct FlatRoute {
+  /// The output interface index.
+  pub index: u32,
+  /// The route metric.
+  pub metric: u32,
+  /// [`FAMILY_V4`] or [`FAMILY_V6`].
+  pub family: u8,
+  /// The destination prefix length.
+  pub prefix_len: u8,
+  /// `1` if [`gateway`](Self::gateway) holds a next hop, `0` for a
+  /// directly-connected route.
+  pub has_gateway: u8,
+  /// The destination network address in network byte order; IPv4
+  /// uses the first 4 bytes.
+  pub destination: [u8; 16],
+  /// The gateway in network byte order, zeroed when there is none.
+  pub gateway: [u8; 16],
+}
+
+impl From<&IpRoute> for FlatRoute {
+  fn from(route: &IpRoute) -> Self {
+    let destination = route.destination();
+    let (family, dst) = flatten_ip(destination.addr());
+    let (has_gateway, gateway) = match route.gateway() {
+      Some(gw) => (1, flatten_ip(gw).1),
+      None => (0, [0; 16]),
+    };
+    Self {
+      index: route.index(),
+      metric: route.metric(),
+      family,
+      prefix_len: destination.prefix_len(),
+      has_gateway,
+      destination: dst,
+      gateway,
+    }
+  }
+}
+
+impl TryFrom<FlatRoute> for IpRoute {
+  type Error = Error;
+
+  /// Fails with [`io::ErrorKind::InvalidData`] on an unknown family or
+  /// an out-of-range prefix length.
+  fn try_from(flat: FlatRoute) -> Result<Self> {
+    let has_gateway = flat.has_gateway != 0;
+    Ok(match unflatten_ip(flat.family, &flat.destination)? {
+      IpAddr::V4(dst) => {
+        let dst = Ipv4Net::new(dst, flat.prefix_len).map_err(invalid_data)?;
+        let gw = has_gateway.then(|| v4_of(&flat.gateway));
+        Ipv4Route::new(flat.index, dst, gw)
+          .with_metric(flat.metric)
+          .into()
+      }
+      IpAddr::V6(dst) => {
+        let dst = Ipv6Net::new(dst, flat.prefix_len).map_err(invalid_data)?;
+        let gw = has_gateway.then(|| Ipv6Addr::from(flat.gateway));
+        Ipv6Route::new(flat.index, dst, gw)
+          .with_metric(flat.metric)
+          .into()
+      }
+    })
+  }
+}
+
+/// Fills `ptr[..cap]` with the system's interfaces, as [`interfaces`]
+/// returns them, and stores their total count through `len`. Exported
+/// to C as `getifs_collect_interfaces`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for writes of `cap` consecutive
+/// [`FlatInterface`]s; it may be null only when `cap` is `0`. `len`
+/// must be valid for a write.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::flat::{collect_interfaces, FlatInterface};
+///
+/// let mut len = 0;
+/// unsafe { collect_interfaces(core::ptr::null_mut(), 0, &mut len) };
+///
+/// let mut buf = Vec::<FlatInterface>::with_capacity(len);
+/// unsafe {
+///   collect_interfaces(buf.as_mut_ptr(), buf.capacity(), &mut len);
+///   buf.set_len(len.min(buf.capacity()));
+/// }
+/// for ifi in &buf {
+///   println!("{}: {}", ifi.index, ifi.name());
+/// }
+/// ```
+#[export_name = "getifs_collect_interfaces"]
+pub unsafe extern "C" fn collect_interfaces(
+  ptr: *mut FlatInterface,
+  cap: usize,
+  len: *mut usize,
+) -> c_int {
+  let all = match interfaces() {
+    Ok(all) => all,
+    Err(e) => return status_of(e),
+  };
+  // SAFETY: forwarded from the caller.
+  unsafe { fill(all.iter().map(FlatInterface::from), ptr, cap, len) }
+}
+
+/// Fills `ptr[..cap]` with the system's unicast addresses, as
+/// [`interface_addrs`] returns them, and stores their total count
+/// through `len`. Exported to C as `getifs_collect_addrs`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for writes of `cap` consecutive [`FlatAddr`]s;
+/// it may be null only when `cap` is `0`. `len` must be valid for a
+/// write.
+#[export_name = "getifs_collect_addrs"]
+pub unsafe extern "C" fn collect_addrs(ptr: *mut FlatAddr, cap: usize, len: *mut usize) -> c_int {
+  let all = match interface_addrs() {
+    Ok(all) => all,
+    Err(e) => return status_of(e),
+  };
+  // SAFETY: forwarded from the caller.
+  unsafe { fill(all.iter().map(FlatAddr::from), ptr, cap, len) }
+}
+
+/// Fills `ptr[..cap]` with the kernel routing table, as
+/// [`route_table`] returns it, and stores the total number of routes
+/// through `len`. Exported to C as `getifs_collect_routes`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for writes of `cap` consecutive [`FlatRoute`]s;
+/// it may be null only when `cap` is `0`. `len` must be valid for a
+/// write.
+#[export_name = "getifs_collect_routes"]
+pub unsafe extern "C" fn collect_routes(ptr: *mut FlatRoute, cap: usize, len: *mut usize) -> c_int {
+  let all = match route_table() {
+    Ok(all) => all,
+    Err(e) => return status_of(e),
+  };
+  // SAFETY: forwarded from the caller.
+  unsafe { fill(all.iter().map(FlatRoute::from), ptr, cap, len) }
+}
+
+/// # Safety
+///
+/// See the `collect_*` functions.
+unsafe fn fill<T>(
+  items: impl ExactSizeIterator<Item = T>,
+  ptr: *mut T,
+  cap: usize,
+  len: *mut usize,
+) -> c_int {
+  if len.is_null() || (ptr.is_null() && cap != 0) {
+    return status_of(io::Error::from(io::ErrorKind::InvalidInput).into());
+  }
+  let total = items.len();
+  // SAFETY: `len` is non-null and the caller guarantees it is writable.
+  unsafe { *len = total };
+  for (i, item) in items.take(cap).enumerate() {
+    // SAFETY: `i < cap`, and the caller guarantees `cap` writable slots.
+    unsafe { ptr.add(i).write(item) };
+  }
+  if total > cap {
+    STATUS_NEED_CAPACITY
+  } else {
+    STATUS_OK
+  }
+}
+
+/// The negated OS error code for `e`. Errors that did not come from
+/// the OS get the closest code there is.
+fn status_of(e: Error) -> c_int {
+  if let Some(code) = e.raw_os_error() {
+    return -code.abs();
+  }
+
+  #[cfg(not(windows))]
+  use libc::{EINVAL as INVALID, EIO as FAILED, ENODEV as NOT_FOUND, ENOTSUP as UNSUPPORTED};
+  #[cfg(windows)]
+  use windows_sys::Win32::Foundation::{
+    ERROR_GEN_FAILURE as FAILED, ERROR_INVALID_DATA as INVALID, ERROR_NOT_FOUND as NOT_FOUND,
+    ERROR_NOT_SUPPORTED as UNSUPPORTED,
+  };
+
+  let code = match e.kind() {
+    io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput => INVALID,
+    io::ErrorKind::Unsupported => UNSUPPORTED,
+    io::ErrorKind::NotFound => NOT_FOUND,
+    _ => FAILED,
+  };
+  -(code as c_int)
+}
+
+fn flatten_ip(ip: IpAddr) -> (u8, [u8; 16]) {
+  let mut out = [0u8; 16];
+  match ip {
+    IpAddr::V4(ip) => {
+      out[..4].copy_from_slice(&ip.octets());
+      (FAMILY_V4, out)
+    }
+    IpAddr::V6(ip) => (FAMILY_V6, ip.octets()),
+  }
+}
+
+fn unflatten_ip(family: u8, bytes: &[u8; 16]) -> Result<IpAddr> {
+  match family {
+    FAMILY_V4 => Ok(IpAddr::V4(v4_of(bytes))),
+    FAMILY_V6 => Ok(IpAddr::V6(Ipv6Addr::from(*bytes))),
+    other => Err(invalid_data(format!("unknown address family: {other}"))),
+  }
+}
+
+#[inline]
+fn v4_of(bytes: &[u8; 16]) -> Ipv4Addr {
+  Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])
+}
+
+#[inline]
+fn invalid_data<E>(e: E) -> Error
+where
+  E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+  io::Error::new(io::ErrorKind::InvalidData, e).into()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Whatever the rich API reports must come back unchanged after a trip
+  // through the flat structs.
+  #[test]
+  fn round_trip_rich_types() {
+    let mut len = 0;
+    unsafe { collect_interfaces(core::ptr::null_mut(), 0, &mut len) };
+    let mut buf = Vec::<FlatInterface>::with_capacity(len);
+    unsafe {
+      collect_interfaces(buf.as_mut_ptr(), buf.capacity(), &mut len);
+      buf.set_len(len.min(buf.capacity()));
+    }
+    for (flat, ifi) in buf.iter().zip(interfaces().unwrap().iter()) {
+      assert_eq!(flat.index, ifi.index());
+      assert_eq!(flat.name(), ifi.name());
+      assert_eq!(flat.mac_addr(), ifi.mac_addr());
+      assert_eq!(flat.flags(), ifi.flags());
+      assert_eq!(flat.mtu, ifi.mtu());
+    }
+
+    for net in interface_addrs().unwrap() {
+      let back = IfNet::try_from(FlatAddr::from(&net)).unwrap();
+      assert_eq!(back, net);
+      assert_eq!(back.net(), net.net());
+      if let (IfNet::V4(a), IfNet::V4(b)) = (back, net) {
+        assert_eq!(a.broadcast(), b.broadcast());
+      }
+    }
+
+    for route in route_table().unwrap() {
+      let back = IpRoute::try_from(FlatRoute::from(&route)).unwrap();
+      assert_eq!(back.index(), route.index());
+      assert_eq!(back.destination(), route.destination());
+      assert_eq!(back.gateway(), route.gateway());
+      assert_eq!(back.metric(), route.metric());
+    }
+  }
+
+  // A buffer smaller than the result is filled to capacity and `len`
+  // still reports the full count.
+  #[test]
+  fn short_buffer_reports_total() {
+    let total = interface_addrs().unwrap().len();
+    let mut buf = [FlatAddr {
+      index: 0,
+      family: 0,
+      prefix_len: 0,
+      has_broadcast: 0,
+      broadcast: [0; 4],
+      addr: [0; 16],
+    }; 1];
+    let mut len = 0;
+    let status = unsafe { collect_addrs(buf.as_mut_ptr(), buf.len(), &mut len) };
+    assert_eq!(len, total);
+    if total > 1 {
+      assert_eq!(status, STATUS_NEED_CAPACITY);
+    }
+    if total > 0 {
+      assert_ne!(buf[0].family, 0);
+    }
+  }
+
+  // What a C caller sees: the collectors through `extern "C"` function
+  // pointers, a size query, then fills that grow the buffer until one
+  // reports `STATUS_OK`.
+  #[test]
+  fn callable_through_c_abi() {
+    unsafe fn query_then_fill<T>(f: unsafe extern "C" fn(*mut T, usize, *mut usize) -> c_int) {
+      let mut len = usize::MAX;
+      let status = unsafe { f(core::ptr::null_mut(), 0, &mut len) };
+      assert!(
+        status == STATUS_OK || status == STATUS_NEED_CAPACITY,
+        "{status}"
+      );
+      assert_eq!(status == STATUS_OK, len == 0);
+
+      let mut buf = Vec::<T>::new();
+      loop {
+        buf.reserve(len);
+        let status = unsafe { f(buf.as_mut_ptr(), buf.capacity(), &mut len) };
+        if status == STATUS_OK {
+          break;
+        }
+        assert_eq!(status, STATUS_NEED_CAPACITY);
+      }
+      assert!(len <= buf.capacity());
+      unsafe { buf.set_len(len) };
+
+      assert!(unsafe { f(core::ptr::null_mut(), 0, core::ptr::null_mut()) } < 0);
+    }
+
+    unsafe {
+      query_then_fill::<FlatInterface>(collect_interfaces);
+      query_then_fill::<FlatAddr>(collect_addrs);
+      query_then_fill::<FlatRoute>(collect_routes);
+    }
+  }
+
+  // Typed errors without an OS code still come out negative.
+  #[test]
+  fn errors_map_to_negative_status() {
+    for e in [
+      Error::parse("netlink message"),
+      Error::UnsupportedPlatform,
+      Error::InterfaceNotFound,
+      io::Error::from_raw_os_error(5).into(),
+    ] {
+      assert!(status_of(e) < 0);
+    }
+  }
+
+  #[test]
+  fn unknown_family_is_invalid_data() {
+    let flat = FlatAddr {
+      index: 1,
+      family: 5,
+      prefix_len: 0,
+      has_broadcast: 0,
+      broadcast: [0; 4],
+      addr: [0; 16],
+    };
+    assert_eq!(
+      IfNet::try_from(flat).unwrap_err().kind(),
+      io::ErrorKind::InvalidData
+    );
+  }
+}
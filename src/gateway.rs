@@ -1,13 +1,22 @@
-use std::io;
+use std::{collections::HashSet, net::IpAddr};
 
 use smallvec_wrapper::SmallVec;
 
-use super::{os, IfAddr, Ifv4Addr, Ifv6Addr};
+use super::{os, Family, IfAddr, Ifv4Addr, Ifv6Addr, MacAddr, Neighbor, Result};
 
 /// Returns all gateway IP addresses (both IPv4 and IPv6) configured on the system.
 /// Only returns addresses from interfaces that have valid routes and
 /// excludes any addresses that are not configured as gateways.
 ///
+/// IPv4 gateways come first, followed by IPv6 gateways. Each gateway is
+/// reported once per interface, however many routes point at it; an
+/// IPv4-mapped IPv6 next hop (`::ffff:a.b.c.d`) is reported as the IPv4
+/// gateway it names, and coalesced with it.
+///
+/// Returns an empty list when no route has a gateway, including when a
+/// family's stack is not running; an `Err` means the routing table
+/// could not be read.
+///
 /// ## Example
 ///
 /// ```rust
@@ -18,8 +27,54 @@ use super::{os, IfAddr, Ifv4Addr, Ifv6Addr};
 ///   println!("Gateway: {}", gw);
 /// }
 /// ```
-pub fn gateway_addrs() -> io::Result<SmallVec<IfAddr>> {
+pub fn gateway_addrs() -> Result<SmallVec<IfAddr>> {
   os::gateway_addrs()
+    .map(coalesce_gateways)
+    .map_err(Into::into)
+}
+
+/// Returns the gateways of the routes in one Linux routing table, such
+/// as a table a VPN or `ip rule` policy installs its routes in.
+///
+/// [`gateway_addrs`] reports the gateways of every table; this looks at
+/// `table` alone, with the same ordering and coalescing. Table `0`
+/// (`RT_TABLE_UNSPEC`) names no table and fails with
+/// [`io::ErrorKind::InvalidInput`](std::io::ErrorKind::InvalidInput).
+/// Unlike [`gateway_addrs`] there is no `/proc/net` fallback when the
+/// netlink socket is denied, since procfs does not say which table a
+/// route is in.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::gateway_addrs_in;
+///
+/// // The `main` table.
+/// for gw in gateway_addrs_in(254).unwrap() {
+///   println!("Gateway: {}", gw);
+/// }
+/// ```
+#[cfg(linux_like)]
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn gateway_addrs_in(table: u32) -> Result<SmallVec<IfAddr>> {
+  os::gateway_addrs_in(table)
+    .map(coalesce_gateways)
+    .map_err(Into::into)
+}
+
+/// Rewrites IPv4-mapped IPv6 gateways as the IPv4 address they embed,
+/// orders `gateways` IPv4 first and drops entries that repeat an
+/// earlier `(index, address)` pair.
+fn coalesce_gateways(gateways: SmallVec<IfAddr>) -> SmallVec<IfAddr> {
+  let mut gateways: SmallVec<IfAddr> = gateways
+    .into_iter()
+    .map(|gw| IfAddr::new(gw.index(), gw.addr().to_canonical()))
+    .collect();
+  // Stable, so each family keeps the order the routing table gave.
+  gateways.sort_by_key(|gw| !gw.addr().is_ipv4());
+  let mut seen: HashSet<(u32, IpAddr)> = HashSet::new();
+  gateways.retain(|gw| seen.insert((gw.index(), gw.addr())));
+  gateways
 }
 
 /// Returns all IPv4 gateway addresses configured on the system.
@@ -36,8 +91,8 @@ pub fn gateway_addrs() -> io::Result<SmallVec<IfAddr>> {
 ///   println!("IPv4 Gateway: {}", gw);
 /// }
 /// ```
-pub fn gateway_ipv4_addrs() -> io::Result<SmallVec<Ifv4Addr>> {
-  os::gateway_ipv4_addrs()
+pub fn gateway_ipv4_addrs() -> Result<SmallVec<Ifv4Addr>> {
+  os::gateway_ipv4_addrs().map_err(Into::into)
 }
 
 /// Returns all IPv6 gateway addresses configured on the system.
@@ -54,8 +109,52 @@ pub fn gateway_ipv4_addrs() -> io::Result<SmallVec<Ifv4Addr>> {
 ///   println!("IPv6 Gateway: {}", gw);
 /// }
 /// ```
-pub fn gateway_ipv6_addrs() -> io::Result<SmallVec<Ifv6Addr>> {
-  os::gateway_ipv6_addrs()
+pub fn gateway_ipv6_addrs() -> Result<SmallVec<Ifv6Addr>> {
+  os::gateway_ipv6_addrs().map_err(Into::into)
+}
+
+/// Returns the IPv6 gateway addresses that are not link-local.
+///
+/// IPv6 default routes learned from router advertisements almost always
+/// use the router's `fe80::/10` address as next hop, which is only
+/// meaningful together with its interface. Callers that want routable
+/// next hops only can use this instead of [`gateway_ipv6_addrs`]; the
+/// link-local ones it returns already carry the interface index (their
+/// zone) via [`Ifv6Addr::index`].
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::gateway_ipv6_addrs_global;
+///
+/// for gw in gateway_ipv6_addrs_global().unwrap() {
+///   println!("IPv6 Gateway: {}", gw);
+/// }
+/// ```
+pub fn gateway_ipv6_addrs_global() -> Result<SmallVec<Ifv6Addr>> {
+  os::gateway_ipv6_addrs_by_filter(|addr| !addr.is_unicast_link_local()).map_err(Into::into)
+}
+
+/// Returns the link-layer address of `gateway` from the neighbour
+/// (ARP / NDP) table, or `None` if the table has no resolved entry
+/// for it.
+///
+/// The kernel only resolves a next hop once traffic has been sent
+/// through it, so an idle gateway may report `None`. A link-local IPv6
+/// gateway is matched on the first interface that has an entry for it.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{gateway_addrs, gateway_mac};
+///
+/// for gw in gateway_addrs().unwrap() {
+///   println!("{}: {:?}", gw, gateway_mac(gw.addr()).unwrap());
+/// }
+/// ```
+pub fn gateway_mac(gateway: IpAddr) -> Result<Option<MacAddr>> {
+  let neighbors = os::neighbor_table(Some(Family::of(&gateway)), |ip| *ip == gateway)?;
+  Ok(neighbors.iter().find_map(Neighbor::mac_addr))
 }
 
 /// Returns all gateway IP addresses (both IPv4 and IPv6) configured on the system
@@ -80,11 +179,11 @@ pub fn gateway_ipv6_addrs() -> io::Result<SmallVec<Ifv6Addr>> {
 ///   println!("Gateway: {}", gw);
 /// }
 /// ```
-pub fn gateway_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<IfAddr>>
+pub fn gateway_addrs_by_filter<F>(f: F) -> Result<SmallVec<IfAddr>>
 where
   F: FnMut(&std::net::IpAddr) -> bool,
 {
-  os::gateway_addrs_by_filter(f)
+  os::gateway_addrs_by_filter(f).map_err(Into::into)
 }
 
 /// Returns all IPv4 gateway addresses configured on the system
@@ -105,11 +204,11 @@ where
 ///   println!("IPv4 Gateway: {}", gw);
 /// }
 /// ```
-pub fn gateway_ipv4_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<Ifv4Addr>>
+pub fn gateway_ipv4_addrs_by_filter<F>(f: F) -> Result<SmallVec<Ifv4Addr>>
 where
   F: FnMut(&std::net::Ipv4Addr) -> bool,
 {
-  os::gateway_ipv4_addrs_by_filter(f)
+  os::gateway_ipv4_addrs_by_filter(f).map_err(Into::into)
 }
 
 /// Returns all IPv6 gateway addresses configured on the system
@@ -130,9 +229,41 @@ where
 ///   println!("IPv6 Gateway: {}", gw);
 /// }
 /// ```
-pub fn gateway_ipv6_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<Ifv6Addr>>
+pub fn gateway_ipv6_addrs_by_filter<F>(f: F) -> Result<SmallVec<Ifv6Addr>>
 where
   F: FnMut(&std::net::Ipv6Addr) -> bool,
 {
-  os::gateway_ipv6_addrs_by_filter(f)
+  os::gateway_ipv6_addrs_by_filter(f).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Repeats collapse per interface, a mapped next hop folds into its
+  // IPv4 gateway or, alone, becomes one, and IPv4 entries sort ahead of
+  // IPv6 ones.
+  #[test]
+  fn coalesce_gateways_dedups_across_families() {
+    let gw = |index, addr: &str| IfAddr::new(index, addr.parse().unwrap());
+    let mut input = SmallVec::new();
+    input.push(gw(2, "fe80::1"));
+    input.push(gw(2, "192.0.2.1"));
+    input.push(gw(2, "::ffff:192.0.2.1"));
+    input.push(gw(3, "192.0.2.1"));
+    input.push(gw(2, "fe80::1"));
+    input.push(gw(2, "198.51.100.1"));
+    input.push(gw(4, "::ffff:203.0.113.1"));
+
+    let out = coalesce_gateways(input);
+    let expected = [
+      gw(2, "192.0.2.1"),
+      gw(3, "192.0.2.1"),
+      gw(2, "198.51.100.1"),
+      gw(4, "203.0.113.1"),
+      gw(2, "fe80::1"),
+    ];
+    assert_eq!(out.as_slice(), expected.as_slice());
+    assert!(out[..4].iter().all(|gw| matches!(gw, IfAddr::V4(_))));
+  }
 }
@@ -1,6 +1,8 @@
 use smol_str::SmolStr;
 use std::io;
 
+use super::Result;
+
 /// Returns the name of the interface by the given index.
 ///
 /// ## Example
@@ -13,12 +15,32 @@ use std::io;
 ///
 /// assert_eq!(interface.name(), &name);
 /// ```
-pub fn ifindex_to_name(idx: u32) -> io::Result<SmolStr> {
-  ifindex_to_name_in(idx)
+pub fn ifindex_to_name(idx: u32) -> Result<SmolStr> {
+  ifindex_to_name_in(idx).map_err(Into::into)
+}
+
+/// Backs the `new_checked` constructors: confirms `idx` names a live
+/// interface in debug builds or with the `validate` feature, and is a
+/// no-op otherwise.
+#[inline]
+pub(crate) fn check_index(idx: u32) -> io::Result<()> {
+  #[cfg(any(debug_assertions, feature = "validate"))]
+  ifindex_to_name_in(idx)?;
+  #[cfg(not(any(debug_assertions, feature = "validate")))]
+  let _ = idx;
+  Ok(())
 }
 
 #[cfg(bsd_like)]
 fn ifindex_to_name_in(idx: u32) -> io::Result<SmolStr> {
+  if_indextoname_lossy(idx)
+}
+
+/// `if_indextoname(3)` with a lossy UTF-8 conversion, so a name with
+/// non-UTF8 bytes comes back with replacement characters — the same
+/// name the interface enumeration reports for it — instead of failing.
+#[cfg(any(bsd_like, linux_like))]
+fn if_indextoname_lossy(idx: u32) -> io::Result<SmolStr> {
   use std::ffi::CStr;
 
   let mut ifname = [0u8; libc::IF_NAMESIZE + 1];
@@ -31,11 +53,7 @@ fn ifindex_to_name_in(idx: u32) -> io::Result<SmolStr> {
   // Use CStr to handle null-terminated string
   let name = unsafe { CStr::from_ptr(ifname.as_ptr() as *const libc::c_char) };
 
-  // Convert to string and then to SmolStr
-  name
-    .to_str()
-    .map(SmolStr::new)
-    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  Ok(SmolStr::new(name.to_string_lossy()))
 }
 
 #[cfg(linux_like)]
@@ -48,9 +66,13 @@ fn ifindex_to_name_in(idx: u32) -> io::Result<SmolStr> {
   // `InlinedName` — no intermediate `String` on the heap. Interface
   // names are bounded by `IF_NAMESIZE` (16), which fits within
   // `SmolStr`'s inline capacity (23), so this stays allocation-free.
-  index_to_name_inlined(socket_fd, idx)
-    .map(|name| SmolStr::new(name.as_str()))
-    .map_err(Into::into)
+  match index_to_name_inlined(socket_fd, idx) {
+    Ok(name) => Ok(SmolStr::new(name.as_str())),
+    // rustix rejects names that are not valid UTF-8; the kernel does
+    // not, so ask libc for the raw bytes and convert lossily.
+    Err(rustix::io::Errno::ILSEQ) => if_indextoname_lossy(idx),
+    Err(e) => Err(e.into()),
+  }
 }
 
 /// Returns the name of the interface by the given index.
@@ -1,13 +1,50 @@
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
+use super::Ipv6AddrExt;
+
 macro_rules! if_addr {
-  ($kind:literal) => {
+  ($kind:literal $(, $field:ident: $ty:ty = $default:expr)* $(,)?) => {
     paste::paste! {
       #[doc = "An interface IP" $kind " address."]
-      #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+      #[derive(Copy, Clone, Debug)]
       pub struct [<If $kind Addr>] {
         index: u32,
         addr: [<Ip $kind Addr>],
+        $($field: $ty,)*
+      }
+
+      // Identity is the `(index, addr)` pair, as for the `If*Net` types:
+      // extra details the kernel reports do not take part, so a value
+      // built by hand compares equal to the one the OS returns.
+      impl PartialEq for [<If $kind Addr>] {
+        #[inline]
+        fn eq(&self, other: &Self) -> bool {
+          self.index == other.index && self.addr == other.addr
+        }
+      }
+
+      impl Eq for [<If $kind Addr>] {}
+
+      impl PartialOrd for [<If $kind Addr>] {
+        #[inline]
+        fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+          Some(self.cmp(other))
+        }
+      }
+
+      impl Ord for [<If $kind Addr>] {
+        #[inline]
+        fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+          (self.index, self.addr).cmp(&(other.index, other.addr))
+        }
+      }
+
+      impl core::hash::Hash for [<If $kind Addr>] {
+        #[inline]
+        fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+          self.index.hash(state);
+          self.addr.hash(state);
+        }
       }
 
       impl core::fmt::Display for [<If $kind Addr>] {
@@ -32,6 +69,7 @@ macro_rules! if_addr {
           Self {
             index,
             addr,
+            $($field: $default,)*
           }
         }
 
@@ -44,7 +82,7 @@ macro_rules! if_addr {
         /// Returns the name of the interface.
         ///
         /// This method will invoke the `if_indextoname` function to get the name of the interface internally.
-        pub fn name(&self) -> std::io::Result<smol_str::SmolStr> {
+        pub fn name(&self) -> crate::Result<smol_str::SmolStr> {
           crate::idx_to_name::ifindex_to_name(self.index)
         }
 
@@ -53,13 +91,70 @@ macro_rules! if_addr {
         pub const fn addr(&self) -> [<Ip $kind Addr>] {
           self.addr
         }
+
+        /// Returns the address as a host-route `addr/prefix` string, without
+        /// the `(index)` suffix that [`Display`](core::fmt::Display) appends.
+        #[inline]
+        pub fn to_cidr_string(&self) -> std::string::String {
+          ipnet::[<Ip $kind Net>]::from(self.addr).to_string()
+        }
       }
     }
   };
 }
 
 if_addr!("v4");
-if_addr!("v6");
+// `0` is "no scope", as in `sockaddr_in6::sin6_scope_id`.
+if_addr!("v6", scope_id: u32 = 0);
+
+impl Ifv6Addr {
+  /// Creates a new `Ifv6Addr` carrying the zone (scope id) of a scoped
+  /// address, such as a link-local `fe80::/10` one. A `scope_id` of `0`
+  /// means the address has none.
+  #[inline]
+  pub const fn with_scope_id(index: u32, addr: Ipv6Addr, scope_id: u32) -> Self {
+    Self {
+      index,
+      addr,
+      scope_id,
+    }
+  }
+
+  /// Creates a new `Ifv6Addr` as the OS reports it: link-local unicast
+  /// and interface- / link-local multicast addresses are only
+  /// meaningful within their zone, which on every supported platform is
+  /// the index of the interface they were seen on.
+  #[inline]
+  pub(crate) fn zoned(index: u32, addr: Ipv6Addr) -> Self {
+    let scoped = Ipv6AddrExt::is_unicast_link_local(&addr)
+      || (addr.is_multicast() && matches!(addr.segments()[0] & 0x000f, 0x1 | 0x2));
+    Self::with_scope_id(index, addr, if scoped { index } else { 0 })
+  }
+
+  /// Returns the zone (scope id) the kernel reported for this address,
+  /// or `None` for an unscoped address.
+  ///
+  /// [`addr`](Self::addr) stays the bare address; combine the two to
+  /// hand a link-local address to `std::net`:
+  ///
+  /// ```rust
+  /// use getifs::gateway_ipv6_addrs;
+  /// use std::net::SocketAddrV6;
+  ///
+  /// for gw in gateway_ipv6_addrs().unwrap() {
+  ///   let sa = SocketAddrV6::new(gw.addr(), 53, 0, gw.scope_id().unwrap_or(0));
+  ///   println!("{sa}");
+  /// }
+  /// ```
+  #[inline]
+  pub const fn scope_id(&self) -> Option<u32> {
+    if self.scope_id == 0 {
+      None
+    } else {
+      Some(self.scope_id)
+    }
+  }
+}
 
 /// An interface address.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -101,6 +196,18 @@ impl IfAddr {
     }
   }
 
+  /// Creates a new `IfAddr` from an [`IpAddr`], verifying that `index`
+  /// belongs to an existing interface.
+  ///
+  /// In debug builds, or always with the `validate` feature, `index`
+  /// is first checked against the live interfaces and an error is
+  /// returned if no interface has it. Release builds without the
+  /// feature skip the lookup and never fail.
+  pub fn new_checked(index: u32, addr: IpAddr) -> crate::Result<Self> {
+    crate::idx_to_name::check_index(index)?;
+    Ok(Self::new(index, addr))
+  }
+
   /// Returns the index of the interface.
   #[inline]
   pub const fn index(&self) -> u32 {
@@ -113,7 +220,7 @@ impl IfAddr {
   /// Returns the name of the interface.
   ///
   /// This method will invoke the `if_indextoname` function to get the name of the interface internally.
-  pub fn name(&self) -> std::io::Result<smol_str::SmolStr> {
+  pub fn name(&self) -> crate::Result<smol_str::SmolStr> {
     crate::idx_to_name::ifindex_to_name(self.index())
   }
 
@@ -125,6 +232,16 @@ impl IfAddr {
       Self::V6(addr) => IpAddr::V6(addr.addr()),
     }
   }
+
+  /// Returns the address as a host-route `addr/prefix` string, without
+  /// the `(index)` suffix that [`Display`](core::fmt::Display) appends.
+  #[inline]
+  pub fn to_cidr_string(&self) -> String {
+    match self {
+      Self::V4(addr) => addr.to_cidr_string(),
+      Self::V6(addr) => addr.to_cidr_string(),
+    }
+  }
 }
 
 #[cfg(test)]
@@ -166,4 +283,52 @@ mod tests {
     assert!(addr.name().is_ok());
     println!("{addr}");
   }
+
+  // An address has no prefix of its own, so `to_cidr_string` renders
+  // it as a host route: the `Display` address with `/32` or `/128`.
+  #[test]
+  fn to_cidr_string_matches_display() {
+    let v4 = IfAddr::new(3, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)));
+    let v6 = IfAddr::new(
+      3,
+      IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+    );
+    for (addr, prefix) in [(v4, 32), (v6, 128)] {
+      let display = addr.to_string();
+      let bare = display.strip_suffix(" (3)").unwrap();
+      assert_eq!(addr.to_cidr_string(), format!("{bare}/{prefix}"));
+    }
+  }
+
+  // Only scoped addresses pick up the interface index as their zone,
+  // and an explicit scope id round-trips through the accessor without
+  // taking part in equality.
+  #[test]
+  fn ifv6addr_scope_id() {
+    let zoned = |addr: &str| Ifv6Addr::zoned(4, addr.parse().unwrap()).scope_id();
+    assert_eq!(zoned("fe80::1"), Some(4));
+    assert_eq!(zoned("ff02::1"), Some(4));
+    assert_eq!(zoned("ff01::1"), Some(4));
+    assert_eq!(zoned("ff05::2"), None);
+    assert_eq!(zoned("2001:db8::1"), None);
+    assert_eq!(zoned("::1"), None);
+
+    let addr = Ifv6Addr::with_scope_id(2, "fe80::1".parse().unwrap(), 7);
+    assert_eq!(addr.scope_id(), Some(7));
+    assert_eq!(addr.addr(), "fe80::1".parse::<Ipv6Addr>().unwrap());
+    assert_eq!(Ifv6Addr::new(2, addr.addr()).scope_id(), None);
+    assert_eq!(Ifv6Addr::new(2, addr.addr()), addr);
+  }
+
+  #[cfg(any(debug_assertions, feature = "validate"))]
+  #[test]
+  fn new_checked_validates_index() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    assert_eq!(
+      IfAddr::new_checked(lo.index(), addr).unwrap(),
+      IfAddr::new(lo.index(), addr)
+    );
+    assert!(IfAddr::new_checked(0xFFFE_FFFE, addr).is_err());
+  }
 }
@@ -3,13 +3,60 @@ use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use ipnet::{IpNet, Ipv4Net, Ipv6Net, PrefixLenError};
 
 macro_rules! if_net {
-  ($kind:literal) => {
+  ($kind:literal $(, $extra:ident: $extra_ty:ty = $extra_init:expr)*) => {
     paste::paste! {
       #[doc = "An interface IP" $kind " network."]
-      #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+      #[derive(Copy, Clone)]
       pub struct [<If $kind Net>] {
         index: u32,
         addr: [<Ip $kind Net>],
+        scope: Option<crate::AddrScope>,
+        $($extra: $extra_ty,)*
+      }
+
+      // Identity is the `(index, addr)` pair; extra details the kernel
+      // reports alongside an address do not take part, so a value
+      // built by hand compares equal to the one the OS returns.
+      impl PartialEq for [<If $kind Net>] {
+        #[inline]
+        fn eq(&self, other: &Self) -> bool {
+          self.index == other.index && self.addr == other.addr
+        }
+      }
+
+      impl Eq for [<If $kind Net>] {}
+
+      impl PartialOrd for [<If $kind Net>] {
+        #[inline]
+        fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+          Some(self.cmp(other))
+        }
+      }
+
+      impl Ord for [<If $kind Net>] {
+        #[inline]
+        fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+          (self.index, self.addr).cmp(&(other.index, other.addr))
+        }
+      }
+
+      impl core::hash::Hash for [<If $kind Net>] {
+        #[inline]
+        fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+          self.index.hash(state);
+          self.addr.hash(state);
+        }
+      }
+
+      impl core::fmt::Debug for [<If $kind Net>] {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+          f.debug_struct(stringify!([<If $kind Net>]))
+            .field("index", &self.index)
+            .field("addr", &self.addr)
+            .field("scope", &self.scope)
+            $(.field(stringify!($extra), &self.$extra))*
+            .finish()
+        }
       }
 
       impl core::fmt::Display for [<If $kind Net>] {
@@ -34,6 +81,8 @@ macro_rules! if_net {
           Self {
             index,
             addr,
+            scope: None,
+            $($extra: $extra_init,)*
           }
         }
 
@@ -51,7 +100,12 @@ macro_rules! if_net {
         /// Otherwise it will panic at runtime if prefix length is not less then or equal to 32.
         #[inline]
         pub const fn with_prefix_len_assert(index: u32, addr: [<Ip $kind Addr>], prefix_len: u8) -> Self {
-          Self { index, addr: [<Ip $kind Net>]::new_assert(addr, prefix_len) }
+          Self {
+            index,
+            addr: [<Ip $kind Net>]::new_assert(addr, prefix_len),
+            scope: None,
+            $($extra: $extra_init,)*
+          }
         }
 
         /// Returns the index of the interface.
@@ -63,7 +117,7 @@ macro_rules! if_net {
         /// Returns the name of the interface.
         ///
         /// This method will invoke the `if_indextoname` function to get the name of the interface internally.
-        pub fn name(&self) -> std::io::Result<smol_str::SmolStr> {
+        pub fn name(&self) -> crate::Result<smol_str::SmolStr> {
           crate::idx_to_name::ifindex_to_name(self.index)
         }
 
@@ -90,14 +144,155 @@ macro_rules! if_net {
         pub const fn max_prefix_len(&self) -> u8 {
           self.addr.max_prefix_len()
         }
+
+        /// Returns the [`AddrScope`](crate::AddrScope) of the interface
+        /// address: the one the OS reports where it reports one, else
+        /// inferred from the address with [`AddrScope::of`](crate::AddrScope::of).
+        #[inline]
+        pub fn scope(&self) -> crate::AddrScope {
+          match self.scope {
+            Some(scope) => scope,
+            None => crate::AddrScope::of(&self.addr().into()),
+          }
+        }
+
+        #[inline]
+        pub(crate) const fn with_scope(mut self, scope: Option<crate::AddrScope>) -> Self {
+          self.scope = scope;
+          self
+        }
+
+        /// Returns the network as a bare `addr/prefix` string, without the
+        /// `(index)` suffix that [`Display`](core::fmt::Display) appends.
+        #[inline]
+        pub fn to_cidr_string(&self) -> std::string::String {
+          self.addr.to_string()
+        }
+
+        /// Returns an iterator over the host addresses of the network the
+        #[doc = "interface address belongs to, as [`Ip" $kind "Net::hosts`](ipnet::Ip" $kind "Net::hosts)"]
+        /// yields them.
+        ///
+        /// Fails with [`InvalidInput`](std::io::ErrorKind::InvalidInput)
+        /// if the network holds more than `max_hosts` addresses, so a
+        /// `/8` (or any IPv6 `/64`) is not walked by accident.
+        pub fn addresses(&self, max_hosts: u64) -> crate::Result<ipnet::[<Ip $kind AddrRange>]> {
+          check_host_count(IpNet::from(self.addr), max_hosts)?;
+          Ok(self.addr.hosts())
+        }
+
+        /// Returns a [`Debug`](core::fmt::Debug) view that omits the
+        /// interface index, for snapshot tests whose indices differ
+        /// between hosts. See [`StableDebug`].
+        #[inline]
+        pub const fn debug_stable(&self) -> StableDebug<'_, Self> {
+          StableDebug(self)
+        }
+
+        /// Returns a [`Display`](core::fmt::Display) view that shows the
+        /// interface name instead of its index. See [`NamedDisplay`].
+        #[inline]
+        pub const fn display_with_name(&self) -> NamedDisplay<'_, Self> {
+          NamedDisplay(self)
+        }
+      }
+
+      impl core::fmt::Debug for StableDebug<'_, [<If $kind Net>]> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+          f.debug_struct(stringify!([<If $kind Net>]))
+            .field("addr", &self.0.addr)
+            .finish()
+        }
+      }
+
+      impl core::fmt::Display for NamedDisplay<'_, [<If $kind Net>]> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+          match self.0.name() {
+            Ok(name) => write!(f, "{} ({})", self.0.addr, name),
+            Err(_) => write!(f, "{}", self.0),
+          }
+        }
       }
     }
   };
 }
 
-if_net!("v4");
+/// A [`Debug`](core::fmt::Debug) wrapper that formats an interface
+/// network without its interface index.
+///
+/// Indices are assigned by the OS and vary between hosts and runs, so
+/// the derived `Debug` output is unsuitable for snapshot tests. The
+/// wrapper prints the same shape minus the `index` field, e.g.
+/// `Ifv4Net { addr: 192.168.1.1/24 }`. Obtain one with
+/// [`IfNet::debug_stable`], [`Ifv4Net::debug_stable`] or
+/// [`Ifv6Net::debug_stable`].
+#[derive(Copy, Clone)]
+pub struct StableDebug<'a, T>(&'a T);
+
+/// A [`Display`](core::fmt::Display) wrapper that formats an interface
+/// network with its interface name in place of the index, e.g.
+/// `192.168.1.1/24 (eth0)`.
+///
+/// The name is resolved with `if_indextoname` each time the wrapper is
+/// formatted, which is why this is not the default `Display`. If the
+/// interface has gone away, the index is printed instead. Obtain one
+/// with [`IfNet::display_with_name`], [`Ifv4Net::display_with_name`] or
+/// [`Ifv6Net::display_with_name`].
+#[derive(Copy, Clone)]
+pub struct NamedDisplay<'a, T>(&'a T);
+
+/// Rejects a network with more than `max_hosts` host addresses. IPv4
+/// networks wider than `/31` lose the network and broadcast addresses,
+/// matching what `hosts()` yields.
+fn check_host_count(net: IpNet, max_hosts: u64) -> std::io::Result<()> {
+  let free = u32::from(net.max_prefix_len() - net.prefix_len());
+  let mut count = 1u128.checked_shl(free).unwrap_or(u128::MAX);
+  if matches!(net, IpNet::V4(_)) && free >= 2 {
+    count -= 2;
+  }
+  if count > u128::from(max_hosts) {
+    return Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidInput,
+      format!("{net} holds {count} host addresses, more than the limit of {max_hosts}"),
+    ));
+  }
+  Ok(())
+}
+
+if_net!("v4", broadcast: Option<Ipv4Addr> = None);
 if_net!("v6");
 
+impl Ifv4Net {
+  /// Returns the broadcast address the OS reports for this address,
+  /// or `None` if it reports none.
+  ///
+  /// - **Linux / Android**: the `IFA_BROADCAST` attribute, which is
+  ///   present only when one was configured (`ip address add ...
+  ///   broadcast ...` or `brd +`; DHCP clients normally set it).
+  /// - **Windows**: derived from the prefix for addresses on
+  ///   broadcast-capable adapters (Ethernet, Wi-Fi, ...), except `/31`
+  ///   and `/32`.
+  /// - **BSD-like / macOS**: the `RTAX_BRD` address of the interface
+  ///   address message, unless it is a point-to-point peer outside the
+  ///   address's subnet.
+  ///
+  /// Values built with the constructors carry no broadcast address, and
+  /// the broadcast address does not take part in comparisons. Note that
+  /// this shadows [`Ipv4Net::broadcast`], which computes the
+  /// all-ones host address from the prefix and remains reachable via
+  /// [`net`](Self::net).
+  #[inline]
+  pub const fn broadcast(&self) -> Option<Ipv4Addr> {
+    self.broadcast
+  }
+
+  #[inline]
+  pub(crate) const fn with_broadcast(mut self, broadcast: Option<Ipv4Addr>) -> Self {
+    self.broadcast = broadcast;
+    self
+  }
+}
+
 /// An interface network.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum IfNet {
@@ -138,6 +333,18 @@ impl IfNet {
     }
   }
 
+  /// Creates a new `IfNet` from an [`IpNet`], verifying that `index`
+  /// belongs to an existing interface.
+  ///
+  /// In debug builds, or always with the `validate` feature, `index`
+  /// is first checked against the live interfaces and an error is
+  /// returned if no interface has it. Release builds without the
+  /// feature skip the lookup and never fail.
+  pub fn new_checked(index: u32, addr: IpNet) -> crate::Result<Self> {
+    crate::idx_to_name::check_index(index)?;
+    Ok(Self::from_net(index, addr))
+  }
+
   /// Creates a new IP interface address from an index, [`IpAddr`] and prefix length.
   #[inline]
   pub const fn with_prefix_len(
@@ -180,7 +387,7 @@ impl IfNet {
   /// Returns the name of the interface.
   ///
   /// This method will invoke the `if_indextoname` function to get the name of the interface internally.
-  pub fn name(&self) -> std::io::Result<smol_str::SmolStr> {
+  pub fn name(&self) -> crate::Result<smol_str::SmolStr> {
     crate::idx_to_name::ifindex_to_name(self.index())
   }
 
@@ -219,12 +426,121 @@ impl IfNet {
       Self::V6(addr) => addr.addr.max_prefix_len(),
     }
   }
+
+  /// Returns the network as a bare `addr/prefix` string, without the
+  /// `(index)` suffix that [`Display`](core::fmt::Display) appends.
+  #[inline]
+  pub fn to_cidr_string(&self) -> String {
+    match self {
+      Self::V4(addr) => addr.to_cidr_string(),
+      Self::V6(addr) => addr.to_cidr_string(),
+    }
+  }
+
+  /// Returns an iterator over the host addresses of the network the
+  /// interface address belongs to, refusing networks with more than
+  /// `max_hosts` of them. See [`Ifv4Net::addresses`] and
+  /// [`Ifv6Net::addresses`].
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::IfNet;
+  ///
+  /// let net = IfNet::with_prefix_len(1, "192.0.2.1".parse().unwrap(), 30).unwrap();
+  /// let hosts: Vec<_> = net.addresses(1024).unwrap().collect();
+  /// assert_eq!(hosts, ["192.0.2.1".parse::<std::net::IpAddr>().unwrap(), "192.0.2.2".parse().unwrap()]);
+  /// ```
+  pub fn addresses(&self, max_hosts: u64) -> crate::Result<ipnet::IpAddrRange> {
+    check_host_count(self.net(), max_hosts)?;
+    Ok(self.net().hosts())
+  }
+
+  /// Returns a [`Debug`](core::fmt::Debug) view that omits the
+  /// interface index, for snapshot tests whose indices differ between
+  /// hosts. See [`StableDebug`].
+  #[inline]
+  pub const fn debug_stable(&self) -> StableDebug<'_, Self> {
+    StableDebug(self)
+  }
+
+  /// Returns a [`Display`](core::fmt::Display) view that shows the
+  /// interface name instead of its index. See [`NamedDisplay`].
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interface_addrs;
+  ///
+  /// for addr in interface_addrs().unwrap() {
+  ///   // e.g. `192.168.1.10/24 (eth0)`
+  ///   println!("{}", addr.display_with_name());
+  /// }
+  /// ```
+  #[inline]
+  pub const fn display_with_name(&self) -> NamedDisplay<'_, Self> {
+    NamedDisplay(self)
+  }
+}
+
+impl core::fmt::Debug for StableDebug<'_, IfNet> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self.0 {
+      IfNet::V4(addr) => f.debug_tuple("V4").field(&addr.debug_stable()).finish(),
+      IfNet::V6(addr) => f.debug_tuple("V6").field(&addr.debug_stable()).finish(),
+    }
+  }
+}
+
+impl core::fmt::Display for NamedDisplay<'_, IfNet> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self.0 {
+      IfNet::V4(addr) => write!(f, "{}", addr.display_with_name()),
+      IfNet::V6(addr) => write!(f, "{}", addr.display_with_name()),
+    }
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  // A kernel-reported broadcast address is carried along but never
+  // makes two otherwise identical addresses compare unequal.
+  #[test]
+  fn broadcast_is_not_identity() {
+    let plain = Ifv4Net::with_prefix_len_assert(1, Ipv4Addr::new(192, 0, 2, 5), 24);
+    let with = plain.with_broadcast(Some(Ipv4Addr::new(192, 0, 2, 127)));
+    assert_eq!(plain.broadcast(), None);
+    assert_eq!(with.broadcast(), Some(Ipv4Addr::new(192, 0, 2, 127)));
+    assert_eq!(plain, with);
+    assert_eq!(IfNet::V4(plain), IfNet::V4(with));
+  }
+
+  // A `/30` yields its two usable hosts; a `/8` is refused rather than
+  // walked, and the limit itself is inclusive.
+  #[test]
+  fn addresses_respects_limit() {
+    let net = Ifv4Net::with_prefix_len_assert(1, Ipv4Addr::new(192, 0, 2, 2), 30);
+    let hosts: Vec<_> = net.addresses(2).unwrap().collect();
+    assert_eq!(
+      hosts,
+      [Ipv4Addr::new(192, 0, 2, 1), Ipv4Addr::new(192, 0, 2, 2)]
+    );
+    assert!(net.addresses(1).is_err());
+
+    let wide = IfNet::with_prefix_len_assert(1, IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)), 8);
+    assert_eq!(
+      wide.addresses(65536).unwrap_err().kind(),
+      std::io::ErrorKind::InvalidInput
+    );
+
+    let v6 = IfNet::with_prefix_len_assert(1, IpAddr::V6(Ipv6Addr::LOCALHOST), 126);
+    assert_eq!(v6.addresses(4).unwrap().count(), 4);
+    let v6 = IfNet::with_prefix_len_assert(1, IpAddr::V6(Ipv6Addr::LOCALHOST), 0);
+    assert!(v6.addresses(u64::MAX).is_err());
+  }
+
   #[test]
   fn test_ifv4_net() {
     let addr = Ipv4Addr::new(192, 168, 1, 1);
@@ -293,4 +609,86 @@ mod tests {
       ))
     );
   }
+
+  // `to_cidr_string` must be exactly the `Display` output with the
+  // trailing ` (index)` removed.
+  #[test]
+  fn to_cidr_string_matches_display() {
+    let nets = [
+      IfNet::with_prefix_len_assert(7, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 8),
+      IfNet::with_prefix_len_assert(
+        7,
+        IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)),
+        64,
+      ),
+    ];
+    for net in nets {
+      let display = net.to_string();
+      assert_eq!(
+        display.strip_suffix(" (7)"),
+        Some(net.to_cidr_string().as_str())
+      );
+    }
+    assert_eq!(nets[0].to_cidr_string(), "10.0.0.1/8");
+    assert_eq!(nets[1].to_cidr_string(), "fe80::1/64");
+  }
+
+  // The named view swaps the `(index)` suffix for the name
+  // `ifindex_to_name` reports, and falls back to the index when the
+  // interface does not exist.
+  #[test]
+  fn display_with_name_uses_ifindex_to_name() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    let name = crate::ifindex_to_name(lo.index()).unwrap();
+    let net = IfNet::with_prefix_len_assert(lo.index(), IpAddr::V4(Ipv4Addr::LOCALHOST), 8);
+    assert_eq!(
+      net.display_with_name().to_string(),
+      format!("127.0.0.1/8 ({name})")
+    );
+
+    let gone = Ifv6Net::with_prefix_len_assert(0xFFFE_FFFE, Ipv6Addr::LOCALHOST, 128);
+    assert_eq!(gone.display_with_name().to_string(), gone.to_string());
+  }
+
+  // The stable view must not depend on the index, yet must still tell
+  // different networks apart.
+  #[test]
+  fn debug_stable_ignores_index() {
+    let addr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+    let a = IfNet::with_prefix_len_assert(2, addr, 24);
+    let b = IfNet::with_prefix_len_assert(17, addr, 24);
+    assert_ne!(format!("{a:?}"), format!("{b:?}"));
+    assert_eq!(
+      format!("{:?}", a.debug_stable()),
+      format!("{:?}", b.debug_stable())
+    );
+    assert_eq!(
+      format!("{:?}", a.debug_stable()),
+      "V4(Ifv4Net { addr: 192.168.1.1/24 })"
+    );
+
+    let v6 = Ifv6Net::with_prefix_len_assert(3, Ipv6Addr::LOCALHOST, 128);
+    assert_eq!(
+      format!("{:?}", v6.debug_stable()),
+      "Ifv6Net { addr: ::1/128 }"
+    );
+
+    let c = IfNet::with_prefix_len_assert(2, addr, 16);
+    assert_ne!(
+      format!("{:?}", a.debug_stable()),
+      format!("{:?}", c.debug_stable())
+    );
+  }
+
+  // With validation on (always the case for `cargo test`'s debug
+  // profile), a live index is accepted and a bogus one refused.
+  #[cfg(any(debug_assertions, feature = "validate"))]
+  #[test]
+  fn new_checked_validates_index() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    let net = IpNet::V4(Ipv4Net::new_assert(Ipv4Addr::LOCALHOST, 8));
+    let checked = IfNet::new_checked(lo.index(), net).unwrap();
+    assert_eq!(checked, IfNet::from_net(lo.index(), net));
+    assert!(IfNet::new_checked(0xFFFE_FFFE, net).is_err());
+  }
 }
@@ -1,18 +1,22 @@
 use std::{
   io,
   net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  ops::ControlFlow,
+  time::SystemTime,
 };
 
 use hardware_address::MacAddr;
+use ipnet::IpNet;
 use smallvec_wrapper::{SmallVec, TinyVec};
 use smol_str::SmolStr;
 
 use super::{
-  ifname_to_index, ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter, os, Flags, IfNet, Ifv4Net,
-  Ifv6Net,
+  ifname_to_index, interface_exists, ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter,
+  local_ip_filter, os, CommonFlags, Error, Family, Flags, IfNet, Ifv4Addr, Ifv4Net, Ifv6Net,
+  InterfaceStats, Ipv6AddrExt, Result,
 };
 
-// `IfAddr` / `Ifv4Addr` / `Ifv6Addr` appear only inside `cfg_multicast!`
+// `IfAddr` / `Ifv6Addr` appear only inside `cfg_multicast!`
 // blocks. Keep this import gate in lock-step with `cfg_multicast!`
 // (src/macros.rs).
 #[cfg(any(
@@ -23,9 +27,16 @@ use super::{
   target_os = "android",
   windows
 ))]
-use super::{IfAddr, Ifv4Addr, Ifv6Addr};
+use super::{IfAddr, Ifv6Addr};
 
 /// The interface struct
+///
+/// `PartialEq`, `Eq` and `Hash` compare snapshots: every field takes
+/// part, including volatile state such as the carrier, the operational
+/// status, the link speed and the last change time. Two reads of the
+/// same interface therefore compare unequal once any of that changes.
+/// To key or match interfaces by identity use [`index`](Self::index)
+/// or [`stable_id`](Self::stable_id).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Interface {
   pub(super) index: u32,
@@ -33,6 +44,172 @@ pub struct Interface {
   pub(super) name: SmolStr,
   pub(super) mac_addr: Option<MacAddr>,
   pub(super) flags: Flags,
+  pub(super) carrier: Option<bool>,
+  pub(super) oper_status: OperStatus,
+  pub(super) media_type: Option<&'static str>,
+  pub(super) kind: InterfaceKind,
+  #[cfg(any(windows, bsd_like))]
+  pub(super) link_speed: Option<u64>,
+  #[cfg(windows)]
+  pub(super) dns_servers: SmallVec<IpAddr>,
+  #[cfg(windows)]
+  pub(super) physical_address: SmallVec<u8>,
+  #[cfg(linux_like)]
+  pub(super) master: Option<u32>,
+  #[cfg(bsd_like)]
+  pub(super) last_change: Option<SystemTime>,
+}
+
+/// A comparable identity for an interface that survives index
+/// renumbering. Obtained from [`Interface::stable_id`].
+///
+/// Interface indices are reassigned when a driver reloads or a virtual
+/// device is recreated, so they make poor keys when matching links
+/// across two captures. `StableId` prefers the hardware address, falls
+/// back to the name, and only uses the index as a last resort.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StableId {
+  /// The interface's non-zero hardware address.
+  Mac(MacAddr),
+  /// The interface name, used when there is no usable hardware address.
+  Name(SmolStr),
+  /// The interface index, used when the interface has neither.
+  Index(u32),
+}
+
+impl core::fmt::Display for StableId {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Mac(mac) => write!(f, "{mac}"),
+      Self::Name(name) => write!(f, "{name}"),
+      Self::Index(index) => write!(f, "{index}"),
+    }
+  }
+}
+
+/// The operational state of an interface, as defined by RFC 2863
+/// (`ifOperStatus`). Obtained from [`Interface::oper_status`].
+///
+/// Unlike [`Flags::UP`], which only reflects the administrative state,
+/// this tells *why* an administratively-up interface cannot pass
+/// traffic — no carrier, a lower layer down, waiting for an external
+/// event, and so on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OperStatus {
+  /// Ready to pass packets.
+  Up,
+  /// Not ready to pass packets: administratively down, or up without
+  /// a carrier.
+  Down,
+  /// In a test mode; no operational packets can be passed.
+  Testing,
+  /// Waiting for an external event (e.g. 802.1X authentication or an
+  /// incoming call) before it can pass packets.
+  Dormant,
+  /// A component, typically hardware, is missing.
+  NotPresent,
+  /// Down because a lower-layer interface it runs over is down.
+  LowerLayerDown,
+  /// The state cannot be determined. Some virtual interfaces, such as
+  /// loopback on Linux, always report this.
+  Unknown,
+}
+
+impl core::fmt::Display for OperStatus {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str(match self {
+      Self::Up => "up",
+      Self::Down => "down",
+      Self::Testing => "testing",
+      Self::Dormant => "dormant",
+      Self::NotPresent => "not present",
+      Self::LowerLayerDown => "lower layer down",
+      Self::Unknown => "unknown",
+    })
+  }
+}
+
+/// The hardware type of an interface. Obtained from
+/// [`Interface::kind`].
+///
+/// Wi-Fi is only told apart where the OS reports it as such: Windows
+/// does, while Linux and macOS drivers mostly present Wi-Fi as
+/// [`Ethernet`](Self::Ethernet).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InterfaceKind {
+  /// Ethernet, and anything that presents itself as Ethernet (veth,
+  /// bridges and most virtual NICs).
+  Ethernet,
+  /// IEEE 802.11 wireless.
+  Wifi,
+  /// The loopback interface.
+  Loopback,
+  /// A PPP link.
+  Ppp,
+  /// An IP-in-IP, GRE, SIT or similar tunnel.
+  Tunnel,
+  /// ATM.
+  Atm,
+  /// IEEE 802.5 token ring.
+  TokenRing,
+  /// Any other type, carrying the platform's raw code: the `ARPHRD_*`
+  /// value on Linux / Android and the IANA `ifType` elsewhere. The
+  /// codes differ between platforms, so compare them only within one.
+  /// `Other(0)` means the type was not reported.
+  Other(u16),
+}
+
+/// Apple-specific role of an interface, inferred from its name.
+/// Obtained from [`Interface::apple_hint`].
+///
+/// Apple platforms create several interfaces that look like ordinary
+/// links but should not carry general traffic, or carry it only
+/// through a VPN.
+#[cfg(apple)]
+#[cfg_attr(docsrs, doc(cfg(target_vendor = "apple")))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AppleInterfaceHint {
+  /// A `utunN` point-to-point tunnel, used by VPN clients and system
+  /// services such as iCloud Private Relay. [`Interface::kind`] reports
+  /// these as [`InterfaceKind::Tunnel`] as well; this variant singles
+  /// out `utun` among tunnels of any kind (`gif`, `stf`).
+  Tunnel,
+  /// `awdlN`, Apple Wireless Direct Link (AirDrop, AirPlay, Sidecar).
+  /// Peer-to-peer only; never the path to a router.
+  Awdl,
+  /// `llwN`, the low-latency WLAN companion to `awdl`. Peer-to-peer
+  /// only, like [`Awdl`](Self::Awdl).
+  LowLatencyWlan,
+}
+
+#[cfg(apple)]
+impl AppleInterfaceHint {
+  /// Classifies an interface name, or returns `None` for a name with
+  /// no Apple-specific role.
+  pub fn from_name(name: &str) -> Option<Self> {
+    let unit = |prefix: &str| {
+      name
+        .strip_prefix(prefix)
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+    };
+    if unit("utun") {
+      Some(Self::Tunnel)
+    } else if unit("awdl") {
+      Some(Self::Awdl)
+    } else if unit("llw") {
+      Some(Self::LowLatencyWlan)
+    } else {
+      None
+    }
+  }
+
+  /// Returns `true` for the peer-to-peer links ([`Awdl`](Self::Awdl)
+  /// and [`LowLatencyWlan`](Self::LowLatencyWlan)) that should not be
+  /// used for normal traffic.
+  #[inline]
+  pub const fn is_peer_to_peer(&self) -> bool {
+    matches!(self, Self::Awdl | Self::LowLatencyWlan)
+  }
 }
 
 impl Interface {
@@ -49,32 +226,667 @@ impl Interface {
   }
 
   /// Returns the interface MTU.
+  ///
+  /// The value is the kernel's own, widened to `u32` without clamping,
+  /// so large MTUs such as the Linux loopback's `65536` come back
+  /// exactly. `0` means the platform did not report one; Windows maps
+  /// its "unknown" sentinel (`0xffffffff`) to `0` as well.
+  ///
+  /// This is the link-level value; see [`ipv4_mtu`](Self::ipv4_mtu)
+  /// and [`ipv6_mtu`](Self::ipv6_mtu) for what each IP family uses.
   #[inline]
   pub const fn mtu(&self) -> u32 {
     self.mtu
   }
 
-  /// Returns the hardware address of the interface.
-  #[inline]
-  pub const fn mac_addr(&self) -> Option<MacAddr> {
-    self.mac_addr
+  /// Returns `true` if the MTU is larger than the standard Ethernet
+  /// payload of 1500 bytes.
+  ///
+  /// Loopback and some virtual interfaces report large MTUs too, so
+  /// combine this with [`Flags::LOOPBACK`] if only jumbo-frame NICs are
+  /// of interest.
+  #[inline]
+  pub const fn supports_jumbo(&self) -> bool {
+    self.mtu > 1500
+  }
+
+  /// Returns the MTU IPv4 uses on this interface, or `None` if it has
+  /// none.
+  ///
+  /// - **Windows**: the IPv4 interface's `NlMtu`; `None` when IPv4 is
+  ///   not bound to the adapter.
+  /// - **Other platforms**: IPv4 has no MTU of its own, so this is
+  ///   [`mtu`](Self::mtu), or `None` if that is `0`.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:?}", interface.name(), interface.ipv4_mtu());
+  /// }
+  /// ```
+  pub fn ipv4_mtu(&self) -> Result<Option<u32>> {
+    cfg_if::cfg_if! {
+      if #[cfg(windows)] {
+        os::family_mtu(self.index, Family::V4).map_err(Into::into)
+      } else {
+        Ok((self.mtu != 0).then_some(self.mtu))
+      }
+    }
+  }
+
+  /// Returns the MTU IPv6 uses on this interface, which tunnels and
+  /// router advertisements can set below the link MTU, or `None` if
+  /// IPv6 is not in use on it.
+  ///
+  /// - **Linux / Android**: `/proc/sys/net/ipv6/conf/<name>/mtu`;
+  ///   `None` without kernel IPv6 support or when the link MTU is below
+  ///   the IPv6 minimum of 1280.
+  /// - **Windows**: the IPv6 interface's `NlMtu`; `None` when IPv6 is
+  ///   not bound to the adapter.
+  /// - **Other platforms**: [`mtu`](Self::mtu), or `None` if that is
+  ///   `0`.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:?}", interface.name(), interface.ipv6_mtu());
+  /// }
+  /// ```
+  pub fn ipv6_mtu(&self) -> Result<Option<u32>> {
+    cfg_if::cfg_if! {
+      if #[cfg(linux_like)] {
+        os::ipv6_mtu(&self.name).map_err(Into::into)
+      } else if #[cfg(windows)] {
+        os::family_mtu(self.index, Family::V6).map_err(Into::into)
+      } else {
+        Ok((self.mtu != 0).then_some(self.mtu))
+      }
+    }
+  }
+
+  /// Returns the hardware address of the interface.
+  ///
+  /// On Windows this is `Some` only when the adapter reports a
+  /// physical address of exactly six bytes; see
+  /// `Interface::raw_physical_address` (Windows only) for other
+  /// lengths.
+  #[inline]
+  pub const fn mac_addr(&self) -> Option<MacAddr> {
+    self.mac_addr
+  }
+
+  /// Returns the adapter's physical address exactly as reported
+  /// (`PhysicalAddress[..PhysicalAddressLength]`).
+  ///
+  /// Empty for loopback and most tunnels. Useful for the adapters
+  /// whose address isn't six bytes long, for which
+  /// [`Interface::mac_addr`] is `None`.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:02x?}", interface.name(), interface.raw_physical_address());
+  /// }
+  /// ```
+  #[cfg(windows)]
+  #[cfg_attr(docsrs, doc(cfg(windows)))]
+  #[inline]
+  pub fn raw_physical_address(&self) -> &[u8] {
+    &self.physical_address
+  }
+
+  /// Returns a fresh snapshot of this interface's traffic counters.
+  ///
+  /// Every call queries the kernel; nothing is cached on the
+  /// `Interface`. Diff two snapshots, or use a
+  /// [`StatsSampler`](crate::StatsSampler), to get a rate.
+  ///
+  /// - **Linux / Android**: the `IFLA_STATS64` attribute of the link's
+  ///   `RTM_NEWLINK`. Apps on Android 11+ are denied `RTM_GETLINK` and
+  ///   get a `PermissionDenied` error.
+  /// - **Windows**: `GetIfEntry2`, with unicast and non-unicast packets
+  ///   added together.
+  /// - **macOS**: the `if_data64` block of `NET_RT_IFLIST2`, so the
+  ///   counters don't wrap at 32 bits like the legacy `if_data` ones.
+  /// - **Other BSDs**: the `if_data` of the `RTM_IFINFO` record.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   let stats = interface.statistics().unwrap();
+  ///   println!("{}: rx {} B, tx {} B", interface.name(), stats.rx_bytes(), stats.tx_bytes());
+  /// }
+  /// ```
+  pub fn statistics(&self) -> Result<InterfaceStats> {
+    os::interface_stats(self.index).map_err(Into::into)
+  }
+
+  /// Returns when the interface last changed state, where the OS
+  /// records it.
+  ///
+  /// - **BSD-like / macOS**: the kernel's `ifi_lastchange`, captured
+  ///   when the interface was enumerated. It is bumped by
+  ///   administrative and link-state changes. macOS records it as time
+  ///   since boot, which is added to `kern.boottime`.
+  /// - **Windows**: queried on each call from `GetIfEntry`'s
+  ///   `dwLastChange`, the moment the adapter entered its current
+  ///   operational state, converted from time-since-boot.
+  /// - **Linux / Android**: always `None`; neither netlink nor `/proc`
+  ///   exposes a change timestamp.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:?}", interface.name(), interface.last_change());
+  /// }
+  /// ```
+  pub fn last_change(&self) -> Option<SystemTime> {
+    cfg_if::cfg_if! {
+      if #[cfg(bsd_like)] {
+        self.last_change
+      } else if #[cfg(windows)] {
+        os::last_change(self.index)
+      } else {
+        None
+      }
+    }
+  }
+
+  /// Returns the flags of the interface.
+  #[inline]
+  pub const fn flags(&self) -> Flags {
+    self.flags
+  }
+
+  /// Returns the part of [`flags`](Self::flags) every platform
+  /// reports, with the same bit values everywhere.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::{loopback_interface, CommonFlags};
+  ///
+  /// let lo = loopback_interface().unwrap().unwrap();
+  /// assert!(lo.common_flags().contains(CommonFlags::LOOPBACK));
+  /// ```
+  #[inline]
+  pub fn common_flags(&self) -> CommonFlags {
+    self.flags.to_common()
+  }
+
+  /// Returns whether the physical link reports a carrier (cable
+  /// plugged in, wireless association up, …), independently of the
+  /// administrative [`Flags::UP`] state.
+  ///
+  /// Returns `None` when the platform does not report link presence
+  /// for this interface:
+  ///
+  /// - **Linux**: `IFLA_CARRIER`. `None` on the Android ioctl fallback
+  ///   path (see [`interfaces`]).
+  /// - **Windows**: `MediaConnectState`; `None` when the state is
+  ///   unknown.
+  /// - **FreeBSD / DragonFly / NetBSD / OpenBSD**: the kernel link
+  ///   state (`ifi_link_state`, derived from the media `IFM_ACTIVE`
+  ///   status); `None` when the driver reports it as unknown.
+  /// - **Apple**: always `None` — `if_data` carries no link state.
+  #[inline]
+  pub const fn has_carrier(&self) -> Option<bool> {
+    self.carrier
+  }
+
+  /// Returns the operational state of the interface.
+  ///
+  /// [`flags`](Self::flags) is unchanged and still reports the
+  /// administrative state; combine the two to tell an interface that
+  /// was brought down (`!UP`, [`OperStatus::Down`]) from one whose
+  /// cable is unplugged (`UP`, [`OperStatus::Down`] or
+  /// [`OperStatus::LowerLayerDown`]).
+  ///
+  /// - **Linux**: `IFLA_OPERSTATE`. [`OperStatus::Unknown`] on the
+  ///   Android ioctl fallback path (see [`interfaces`]).
+  /// - **Windows**: the adapter's `OperStatus`.
+  /// - **FreeBSD / DragonFly / NetBSD / OpenBSD**: derived from
+  ///   [`Flags::UP`] and the kernel link state, so only
+  ///   [`Up`](OperStatus::Up), [`Down`](OperStatus::Down) and
+  ///   [`Unknown`](OperStatus::Unknown) are reported.
+  /// - **Apple**: [`OperStatus::Down`] when not administratively up,
+  ///   otherwise [`OperStatus::Unknown`] — `if_data` carries no link
+  ///   state.
+  #[inline]
+  pub const fn oper_status(&self) -> OperStatus {
+    self.oper_status
+  }
+
+  /// Returns a short, lowercase description of the interface's
+  /// hardware type, such as `"ethernet"`, `"wireless"`, `"loopback"`,
+  /// `"gre"`, `"tunnel"` or `"ppp"`, or `None` when the type is not one
+  /// getifs names.
+  ///
+  /// Meant for display; the same kind of link reads the same on every
+  /// platform, but the set of strings may grow.
+  ///
+  /// - **Linux / Android**: the link's `ARPHRD_*` type. Most Wi-Fi
+  ///   drivers register as `ARPHRD_ETHER` and so read as
+  ///   `"ethernet"`. The Android ioctl fallback path (see
+  ///   [`interfaces`]) only recognises loopback.
+  /// - **Windows**: the adapter's IANA `IfType`.
+  /// - **BSD-like / macOS**: the kernel's `ifi_type`, also an IANA
+  ///   `ifType`. macOS reports Wi-Fi as Ethernet too, and `utunN` is
+  ///   `IFT_OTHER` to the kernel and so reads as `None`; see
+  ///   [`kind`](Self::kind), which recognises it by name.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   let kind = interface.media_type();
+  ///   println!("{}: {}", interface.name(), kind.as_deref().unwrap_or("unknown"));
+  /// }
+  /// ```
+  #[inline]
+  pub fn media_type(&self) -> Option<SmolStr> {
+    self.media_type.map(SmolStr::new_static)
+  }
+
+  /// Returns the hardware type of the interface, for picking e.g. a
+  /// wired link over a wireless one without matching on names.
+  ///
+  /// - **Linux / Android**: the link's `ARPHRD_*` type. Most Wi-Fi
+  ///   drivers register as `ARPHRD_ETHER` and so read as
+  ///   [`Ethernet`](InterfaceKind::Ethernet). The Android ioctl
+  ///   fallback path (see [`interfaces`]) only recognises loopback.
+  /// - **Windows**: the adapter's IANA `IfType`.
+  /// - **BSD-like / macOS**: the kernel's `ifi_type`, also an IANA
+  ///   `ifType`. macOS reports Wi-Fi as Ethernet too, and `utunN` —
+  ///   `IFT_OTHER` to the kernel — as [`Tunnel`](InterfaceKind::Tunnel)
+  ///   by its name.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::{interfaces, InterfaceKind};
+  ///
+  /// let wired = interfaces()
+  ///   .unwrap()
+  ///   .into_iter()
+  ///   .filter(|interface| interface.kind() == InterfaceKind::Ethernet);
+  /// for interface in wired {
+  ///   println!("{}", interface.name());
+  /// }
+  /// ```
+  #[inline]
+  pub const fn kind(&self) -> InterfaceKind {
+    self.kind
+  }
+
+  /// Returns the negotiated link speed in bits per second, or `None`
+  /// when the interface does not report one (most virtual interfaces,
+  /// and loopback everywhere but Windows).
+  ///
+  /// - **Linux / Android**: read on each call from
+  ///   `/sys/class/net/<name>/speed`. The kernel only knows the speed
+  ///   while the link is up.
+  /// - **Windows**: the adapter's `TransmitLinkSpeed`, or
+  ///   `ReceiveLinkSpeed` when only that one is known, captured when
+  ///   the interface was enumerated.
+  /// - **BSD-like / macOS**: the kernel's `ifi_baudrate`, captured
+  ///   when the interface was enumerated.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:?} bit/s", interface.name(), interface.link_speed());
+  /// }
+  /// ```
+  pub fn link_speed(&self) -> Result<Option<u64>> {
+    cfg_if::cfg_if! {
+      if #[cfg(linux_like)] {
+        os::link_speed(&self.name).map_err(Into::into)
+      } else if #[cfg(any(windows, bsd_like))] {
+        Ok(self.link_speed)
+      } else {
+        Ok(None)
+      }
+    }
+  }
+
+  /// Returns the DNS servers configured on this adapter, in the order
+  /// the adapter reports them (`FirstDnsServerAddress`).
+  ///
+  /// The list is captured when the interface is enumerated and may
+  /// hold both IPv4 and IPv6 servers. It is empty when the adapter has
+  /// no DNS configuration (e.g. the loopback pseudo-interface).
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:?}", interface.name(), interface.dns_servers());
+  /// }
+  /// ```
+  #[cfg(windows)]
+  #[cfg_attr(docsrs, doc(cfg(windows)))]
+  #[inline]
+  pub fn dns_servers(&self) -> &[IpAddr] {
+    &self.dns_servers
+  }
+
+  /// Returns whether IPv6 is enabled on this interface.
+  ///
+  /// - **Linux / Android**: reads
+  ///   `/proc/sys/net/ipv6/conf/<name>/disable_ipv6`, which can switch
+  ///   v6 off for one interface while the stack itself is available.
+  ///   A kernel without IPv6 support reports `false` for every
+  ///   interface.
+  /// - **Other platforms**: there is no equivalent per-interface
+  ///   switch, so this reports whether the interface currently holds
+  ///   any IPv6 address (every v6-enabled interface carries at least a
+  ///   link-local one).
+  ///
+  /// See [`probe`](crate::probe) for whether the host supports IPv6 at
+  /// all.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:?}", interface.name(), interface.ipv6_enabled());
+  /// }
+  /// ```
+  pub fn ipv6_enabled(&self) -> Result<bool> {
+    cfg_if::cfg_if! {
+      if #[cfg(linux_like)] {
+        os::ipv6_enabled(&self.name).map_err(Into::into)
+      } else {
+        self.ipv6_addrs().map(|addrs| !addrs.is_empty())
+      }
+    }
+  }
+
+  /// Returns the kernel driver bound to this interface, its version
+  /// and the bus address of the device, read with `ETHTOOL_GDRVINFO`.
+  ///
+  /// Devices with no driver to report, loopback among them, fail with
+  /// `EOPNOTSUPP`. Only Linux exposes this; other platforms return
+  /// [`Error::UnsupportedPlatform`].
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   if let Ok(info) = interface.driver_info() {
+  ///     println!("{}: {} at {}", interface.name(), info.driver(), info.bus_info());
+  ///   }
+  /// }
+  /// ```
+  #[cfg(feature = "ethtool")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "ethtool")))]
+  pub fn driver_info(&self) -> Result<crate::DriverInfo> {
+    cfg_if::cfg_if! {
+      if #[cfg(target_os = "linux")] {
+        os::driver_info(&self.name).map_err(Into::into)
+      } else {
+        Err(Error::UnsupportedPlatform)
+      }
+    }
+  }
+
+  /// Returns the generic offload features of this interface (GRO, GSO,
+  /// TSO, checksum offload, ...), read with `ETHTOOL_GFEATURES`.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   let features = interface.offload_features().unwrap();
+  ///   println!("{}: gro={} tso={}", interface.name(), features.gro(), features.tso());
+  /// }
+  /// ```
+  #[cfg(all(target_os = "linux", feature = "ethtool"))]
+  #[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "ethtool"))))]
+  pub fn offload_features(&self) -> Result<crate::OffloadFeatures> {
+    os::offload_features(&self.name).map_err(Into::into)
+  }
+
+  /// Returns the Apple-specific role of this interface, if its name
+  /// marks it as a `utun` tunnel or an `awdl` / `llw` peer-to-peer
+  /// link.
+  #[cfg(apple)]
+  #[cfg_attr(docsrs, doc(cfg(target_vendor = "apple")))]
+  #[inline]
+  pub fn apple_hint(&self) -> Option<AppleInterfaceHint> {
+    AppleInterfaceHint::from_name(&self.name)
+  }
+
+  /// Returns the index of the interface this one is enslaved to — the
+  /// bond, team or bridge it is a member of (`IFLA_MASTER`) — or `None`
+  /// if it has no master.
+  ///
+  /// Always `None` on the Android ioctl fallback path (see
+  /// [`interfaces`]).
+  #[cfg(linux_like)]
+  #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+  #[inline]
+  pub const fn master_index(&self) -> Option<u32> {
+    self.master
+  }
+
+  /// Returns the indices of the interfaces enslaved to this one, i.e.
+  /// every interface whose [`master_index`](Self::master_index) is this
+  /// interface's index. Empty for anything that is not a bond, team or
+  /// bridge.
+  ///
+  /// This takes a fresh interface dump on every call.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   let slaves = interface.slaves().unwrap();
+  ///   if !slaves.is_empty() {
+  ///     println!("{} members: {:?}", interface.name(), slaves);
+  ///   }
+  /// }
+  /// ```
+  #[cfg(linux_like)]
+  #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+  pub fn slaves(&self) -> Result<SmallVec<u32>> {
+    Ok(
+      interfaces()?
+        .into_iter()
+        .filter(|ifi| ifi.master == Some(self.index))
+        .map(|ifi| ifi.index)
+        .collect(),
+    )
+  }
+
+  /// Returns a [`StableId`] for this interface: its hardware address
+  /// if it has a non-zero one, otherwise its name, otherwise its index.
+  ///
+  /// Two captures of the same NIC compare equal even if the kernel gave
+  /// it a different index in between.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {}", interface.index(), interface.stable_id());
+  /// }
+  /// ```
+  pub fn stable_id(&self) -> StableId {
+    match self.mac_addr {
+      Some(mac) if mac.as_bytes().iter().any(|&b| b != 0) => StableId::Mac(mac),
+      _ if !self.name.is_empty() => StableId::Name(self.name.clone()),
+      _ => StableId::Index(self.index),
+    }
+  }
+
+  /// Returns the names of the interface groups this interface belongs to.
+  ///
+  /// On FreeBSD, OpenBSD and DragonFly this reads `SIOCGIFGROUP`; every
+  /// interface is in the group named after its driver family (`lo`,
+  /// `em`, ...), and OpenBSD additionally puts the default-route
+  /// interface in `egress`. Other platforms have no interface groups and
+  /// return an empty list.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:?}", interface.name(), interface.groups().unwrap());
+  /// }
+  /// ```
+  pub fn groups(&self) -> Result<SmallVec<SmolStr>> {
+    cfg_if::cfg_if! {
+      if #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "dragonfly"))] {
+        os::interface_groups(&self.name).map_err(Into::into)
+      } else {
+        Ok(SmallVec::new())
+      }
+    }
+  }
+
+  /// Returns the default hop limit for IPv6 packets sent out this
+  /// interface.
+  ///
+  /// On Linux / Android this reads
+  /// `/proc/sys/net/ipv6/conf/<name>/hop_limit`, which router
+  /// advertisements can change per link. Returns `None` when the kernel
+  /// has no IPv6 support, and on other platforms, which have no
+  /// per-interface equivalent.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:?}", interface.name(), interface.ipv6_hop_limit());
+  /// }
+  /// ```
+  pub fn ipv6_hop_limit(&self) -> Result<Option<u8>> {
+    cfg_if::cfg_if! {
+      if #[cfg(linux_like)] {
+        os::ipv6_hop_limit(&self.name).map_err(Into::into)
+      } else {
+        Ok(None)
+      }
+    }
+  }
+
+  /// Returns whether this interface accepts IPv6 router advertisements,
+  /// which is what lets SLAAC configure addresses and default routes
+  /// on it.
+  ///
+  /// Reads `/proc/sys/net/ipv6/conf/<name>/accept_ra`; both `1` and
+  /// `2` (accept even while forwarding) count as accepting. Returns
+  /// `false` when the kernel has no IPv6 support.
+  ///
+  /// Only Linux / Android expose this per interface; other platforms
+  /// return [`Error::UnsupportedPlatform`].
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// for interface in interfaces().unwrap() {
+  ///   println!("{}: {:?}", interface.name(), interface.accepts_ra());
+  /// }
+  /// ```
+  pub fn accepts_ra(&self) -> Result<bool> {
+    cfg_if::cfg_if! {
+      if #[cfg(linux_like)] {
+        os::accepts_ra(&self.name).map_err(Into::into)
+      } else {
+        Err(Error::UnsupportedPlatform)
+      }
+    }
   }
 
-  /// Returns the flags of the interface.
-  #[inline]
-  pub const fn flags(&self) -> Flags {
-    self.flags
+  /// Returns the address of the given family that the kernel would pick
+  /// as source when sending out this interface to a destination
+  /// without a more specific match.
+  ///
+  /// Among the interface's unicast addresses of `family` the one with
+  /// the widest [`AddrScope`](crate::AddrScope) wins (RFC 6724 rule 2,
+  /// restricted to this interface), so an IPv6 interface with only a
+  /// link-local address falls back to it. Within a scope a preferred
+  /// address beats a deprecated one (rule 3), and a public address
+  /// beats a temporary one, so the result is stable across privacy
+  /// address rotations; `best_local_ipv6_addr_for_outbound` (macOS
+  /// only) is the one that prefers temporary addresses (rule 7).
+  /// Linux, Windows and macOS report those states; the other BSDs
+  /// decide on scope alone. Ties keep the order the kernel reports,
+  /// which on every supported platform lists the primary address
+  /// first. Returns `None` if the interface has no address of that
+  /// family.
+  ///
+  /// To ask which address the kernel would use for a particular
+  /// destination, whatever the interface, see
+  /// [`source_addr_for`](crate::source_addr_for).
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::{loopback_interface, Family};
+  ///
+  /// let lo = loopback_interface().unwrap().unwrap();
+  /// println!("{:?}", lo.source_addr(Family::V4).unwrap());
+  /// ```
+  pub fn source_addr(&self, family: Family) -> Result<Option<IpAddr>> {
+    if self.is_wildcard() {
+      return Ok(None);
+    }
+    Ok(os::interface_source_addr(self.index, family)?.map(|net| net.addr()))
   }
 
   /// Returns a list of unicast interface addrs for a specific
   /// interface.
   #[inline]
-  pub fn addrs(&self) -> io::Result<SmallVec<IfNet>> {
+  pub fn addrs(&self) -> Result<SmallVec<IfNet>> {
+    if self.is_wildcard() {
+      return Ok(SmallVec::new());
+    }
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_addresses(Some(self.index), |_| true)
+        os::interface_addresses(Some(self.index), |_| true).map_err(Into::into)
       } else {
-        os::interface_addresses(self.index, |_| true)
+        os::interface_addresses(self.index, |_| true).map_err(Into::into)
       }
     }
   }
@@ -96,15 +908,18 @@ impl Interface {
   /// }
   /// ```
   #[inline]
-  pub fn addrs_by_filter<F>(&self, f: F) -> io::Result<SmallVec<IfNet>>
+  pub fn addrs_by_filter<F>(&self, f: F) -> Result<SmallVec<IfNet>>
   where
     F: FnMut(&IpAddr) -> bool,
   {
+    if self.is_wildcard() {
+      return Ok(SmallVec::new());
+    }
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_addresses(Some(self.index), f)
+        os::interface_addresses(Some(self.index), f).map_err(Into::into)
       } else {
-        os::interface_addresses(self.index, f)
+        os::interface_addresses(self.index, f).map_err(Into::into)
       }
     }
   }
@@ -126,12 +941,15 @@ impl Interface {
   /// }
   /// ```
   #[inline]
-  pub fn ipv4_addrs(&self) -> io::Result<SmallVec<Ifv4Net>> {
+  pub fn ipv4_addrs(&self) -> Result<SmallVec<Ifv4Net>> {
+    if self.is_wildcard() {
+      return Ok(SmallVec::new());
+    }
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_ipv4_addresses(Some(self.index), |_| true)
+        os::interface_ipv4_addresses(Some(self.index), |_| true).map_err(Into::into)
       } else {
-        os::interface_ipv4_addresses(self.index, |_| true)
+        os::interface_ipv4_addresses(self.index, |_| true).map_err(Into::into)
       }
     }
   }
@@ -156,15 +974,18 @@ impl Interface {
   /// }
   /// ```
   #[inline]
-  pub fn ipv4_addrs_by_filter<F>(&self, f: F) -> io::Result<SmallVec<Ifv4Net>>
+  pub fn ipv4_addrs_by_filter<F>(&self, f: F) -> Result<SmallVec<Ifv4Net>>
   where
     F: FnMut(&Ipv4Addr) -> bool,
   {
+    if self.is_wildcard() {
+      return Ok(SmallVec::new());
+    }
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_ipv4_addresses(Some(self.index), ipv4_filter_to_ip_filter(f))
+        os::interface_ipv4_addresses(Some(self.index), ipv4_filter_to_ip_filter(f)).map_err(Into::into)
       } else {
-        os::interface_ipv4_addresses(self.index, ipv4_filter_to_ip_filter(f))
+        os::interface_ipv4_addresses(self.index, ipv4_filter_to_ip_filter(f)).map_err(Into::into)
       }
     }
   }
@@ -186,12 +1007,15 @@ impl Interface {
   /// }
   /// ```
   #[inline]
-  pub fn ipv6_addrs(&self) -> io::Result<SmallVec<Ifv6Net>> {
+  pub fn ipv6_addrs(&self) -> Result<SmallVec<Ifv6Net>> {
+    if self.is_wildcard() {
+      return Ok(SmallVec::new());
+    }
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_ipv6_addresses(Some(self.index), |_| true)
+        os::interface_ipv6_addresses(Some(self.index), |_| true).map_err(Into::into)
       } else {
-        os::interface_ipv6_addresses(self.index, |_| true)
+        os::interface_ipv6_addresses(self.index, |_| true).map_err(Into::into)
       }
     }
   }
@@ -216,19 +1040,44 @@ impl Interface {
   /// }
   /// ```
   #[inline]
-  pub fn ipv6_addrs_by_filter<F>(&self, f: F) -> io::Result<SmallVec<Ifv6Net>>
+  pub fn ipv6_addrs_by_filter<F>(&self, f: F) -> Result<SmallVec<Ifv6Net>>
   where
     F: FnMut(&Ipv6Addr) -> bool,
   {
+    if self.is_wildcard() {
+      return Ok(SmallVec::new());
+    }
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_ipv6_addresses(Some(self.index), ipv6_filter_to_ip_filter(f))
+        os::interface_ipv6_addresses(Some(self.index), ipv6_filter_to_ip_filter(f)).map_err(Into::into)
       } else {
-        os::interface_ipv6_addresses(self.index, ipv6_filter_to_ip_filter(f))
+        os::interface_ipv6_addresses(self.index, ipv6_filter_to_ip_filter(f)).map_err(Into::into)
       }
     }
   }
 
+  /// Returns the IPv4 broadcast addresses the OS reports for this
+  /// interface's addresses, one entry per distinct broadcast address.
+  ///
+  /// See [`Ifv4Net::broadcast`] for where each platform takes them
+  /// from; addresses without one are skipped.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::interfaces;
+  ///
+  /// let interface = interfaces().unwrap().into_iter().next().unwrap();
+  ///
+  /// for addr in interface.ipv4_broadcast_addrs().unwrap() {
+  ///   println!("Broadcast Addr: {}", addr);
+  /// }
+  /// ```
+  #[inline]
+  pub fn ipv4_broadcast_addrs(&self) -> Result<SmallVec<Ifv4Addr>> {
+    self.ipv4_addrs().map(broadcast_addrs)
+  }
+
   cfg_multicast!(
     /// Returns a list of multicast, joined group addrs
     /// for a specific interface.
@@ -246,12 +1095,15 @@ impl Interface {
     ///   println!("Multicast Addr: {}", addr);
     /// }
     /// ```
-    pub fn multicast_addrs(&self) -> io::Result<SmallVec<IfAddr>> {
+    pub fn multicast_addrs(&self) -> Result<SmallVec<IfAddr>> {
+      if self.is_wildcard() {
+        return Ok(SmallVec::new());
+      }
       cfg_if::cfg_if! {
         if #[cfg(windows)] {
-          os::interface_multicast_addresses(Some(self.index), |_| true)
+          os::interface_multicast_addresses(Some(self.index), |_| true).map_err(Into::into)
         } else {
-          os::interface_multicast_addresses(self.index, |_| true)
+          os::interface_multicast_addresses(self.index, |_| true).map_err(Into::into)
         }
       }
     }
@@ -275,15 +1127,18 @@ impl Interface {
     ///   println!("Multicast Addr: {}", addr);
     /// }
     /// ```
-    pub fn multicast_addrs_by_filter<F>(&self, f: F) -> io::Result<SmallVec<IfAddr>>
+    pub fn multicast_addrs_by_filter<F>(&self, f: F) -> Result<SmallVec<IfAddr>>
     where
       F: FnMut(&IpAddr) -> bool,
     {
+      if self.is_wildcard() {
+        return Ok(SmallVec::new());
+      }
       cfg_if::cfg_if! {
         if #[cfg(windows)] {
-          os::interface_multicast_addresses(Some(self.index), f)
+          os::interface_multicast_addresses(Some(self.index), f).map_err(Into::into)
         } else {
-          os::interface_multicast_addresses(self.index, f)
+          os::interface_multicast_addresses(self.index, f).map_err(Into::into)
         }
       }
     }
@@ -304,12 +1159,15 @@ impl Interface {
     ///   println!("Multicast IPv4 Addr: {}", addr);
     /// }
     /// ```
-    pub fn ipv4_multicast_addrs(&self) -> io::Result<SmallVec<Ifv4Addr>> {
+    pub fn ipv4_multicast_addrs(&self) -> Result<SmallVec<Ifv4Addr>> {
+      if self.is_wildcard() {
+        return Ok(SmallVec::new());
+      }
       cfg_if::cfg_if! {
         if #[cfg(windows)] {
-          os::interface_multicast_ipv4_addresses(Some(self.index), |_| true)
+          os::interface_multicast_ipv4_addresses(Some(self.index), |_| true).map_err(Into::into)
         } else {
-          os::interface_multicast_ipv4_addresses(self.index, |_| true)
+          os::interface_multicast_ipv4_addresses(self.index, |_| true).map_err(Into::into)
         }
       }
     }
@@ -333,15 +1191,18 @@ impl Interface {
     ///   println!("Multicast IPv4 Addr: {}", addr);
     /// }
     /// ```
-    pub fn ipv4_multicast_addrs_by_filter<F>(&self, f: F) -> io::Result<SmallVec<Ifv4Addr>>
+    pub fn ipv4_multicast_addrs_by_filter<F>(&self, f: F) -> Result<SmallVec<Ifv4Addr>>
     where
       F: FnMut(&Ipv4Addr) -> bool,
     {
+      if self.is_wildcard() {
+        return Ok(SmallVec::new());
+      }
       cfg_if::cfg_if! {
         if #[cfg(windows)] {
-          os::interface_multicast_ipv4_addresses(Some(self.index), f)
+          os::interface_multicast_ipv4_addresses(Some(self.index), f).map_err(Into::into)
         } else {
-          os::interface_multicast_ipv4_addresses(self.index, f)
+          os::interface_multicast_ipv4_addresses(self.index, f).map_err(Into::into)
         }
       }
     }
@@ -362,12 +1223,15 @@ impl Interface {
     ///   println!("Multicast IPv6 Addr: {}", addr);
     /// }
     /// ```
-    pub fn ipv6_multicast_addrs(&self) -> io::Result<SmallVec<Ifv6Addr>> {
+    pub fn ipv6_multicast_addrs(&self) -> Result<SmallVec<Ifv6Addr>> {
+      if self.is_wildcard() {
+        return Ok(SmallVec::new());
+      }
       cfg_if::cfg_if! {
         if #[cfg(windows)] {
-          os::interface_multicast_ipv6_addresses(Some(self.index), |_| true)
+          os::interface_multicast_ipv6_addresses(Some(self.index), |_| true).map_err(Into::into)
         } else {
-          os::interface_multicast_ipv6_addresses(self.index, |_| true)
+          os::interface_multicast_ipv6_addresses(self.index, |_| true).map_err(Into::into)
         }
       }
     }
@@ -391,100 +1255,659 @@ impl Interface {
     ///   println!("Multicast IPv6 Addr: {}", addr);
     /// }
     /// ```
-    pub fn ipv6_multicast_addrs_by_filter<F>(&self, f: F) -> io::Result<SmallVec<Ifv6Addr>>
+    pub fn ipv6_multicast_addrs_by_filter<F>(&self, f: F) -> Result<SmallVec<Ifv6Addr>>
     where
       F: FnMut(&Ipv6Addr) -> bool,
     {
+      if self.is_wildcard() {
+        return Ok(SmallVec::new());
+      }
       cfg_if::cfg_if! {
         if #[cfg(windows)] {
-          os::interface_multicast_ipv6_addresses(Some(self.index), f)
+          os::interface_multicast_ipv6_addresses(Some(self.index), f).map_err(Into::into)
         } else {
-          os::interface_multicast_ipv6_addresses(self.index, f)
+          os::interface_multicast_ipv6_addresses(self.index, f).map_err(Into::into)
         }
       }
     }
   );
 }
 
-/// Returns a list of the system's network interfaces.
+/// Returns a list of the system's network interfaces.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::interfaces;
+///
+/// let interfaces = interfaces().unwrap();
+///
+/// for interface in interfaces {
+///   println!("Interface: {:?}", interface);
+/// }
+/// ```
+///
+/// ## Android
+///
+/// On Android 11+ an app in the `untrusted_app` SELinux domain is denied the
+/// `RTM_GETLINK` netlink dump that enumerates interfaces, so getifs falls
+/// back to listing the interfaces discovered from their addresses
+/// (`RTM_GETADDR`, which stays permitted). On those versions this therefore
+/// returns only interfaces that currently have at least one address, and
+/// their [`Interface::mac_addr`] is `None`. [`interface_by_index`] and
+/// [`interface_by_name`] resolve a known interface directly and are not
+/// affected by this limitation. This fallback also requires the app to hold
+/// `android.permission.INTERNET` (it opens a datagram socket to issue the
+/// `SIOCGIF*` ioctls).
+pub fn interfaces() -> Result<TinyVec<Interface>> {
+  cfg_if::cfg_if! {
+    if #[cfg(windows)] {
+      os::interface_table(None).map_err(Into::into)
+    } else {
+      os::interface_table(0).map_err(Into::into)
+    }
+  }
+}
+
+/// Returns an iterator over the system's network interfaces that
+/// decodes them one at a time.
+///
+/// The kernel's interface dump (the netlink reply, the `sysctl`
+/// buffer, the `GetAdaptersAddresses` list) is fetched up front and
+/// owned by the iterator, but each [`Interface`] is only built when the
+/// iterator reaches it. Code that stops at the first match, like
+/// `interfaces_iter()?.find(...)`, skips decoding the rest and never
+/// allocates the whole list [`interfaces`] returns.
+///
+/// Failing to fetch the dump is reported up front. An individual
+/// interface record that fails to decode is skipped;
+/// [`try_interfaces`] reports such records instead.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::interfaces_iter;
+///
+/// let loopback = interfaces_iter()
+///   .unwrap()
+///   .find(|interface| interface.flags().contains(getifs::Flags::LOOPBACK));
+/// println!("{loopback:?}");
+/// ```
+pub fn interfaces_iter() -> Result<impl Iterator<Item = Interface>> {
+  cfg_if::cfg_if! {
+    if #[cfg(windows)] {
+      let iter = os::interface_iter(None)?;
+    } else {
+      let iter = os::interface_iter(0)?;
+    }
+  }
+  Ok(iter.filter_map(Result::ok))
+}
+
+/// Returns the first interface `iter` yields that satisfies `f`. Unlike
+/// [`interfaces_iter`], a record that fails to decode before the match
+/// fails the lookup, as it fails [`interfaces`].
+fn find_interface<I, F>(iter: I, mut f: F) -> io::Result<Option<Interface>>
+where
+  I: Iterator<Item = Result<Interface, EnumerationError>>,
+  F: FnMut(&Interface) -> bool,
+{
+  for interface in iter {
+    let interface = interface.map_err(EnumerationError::into_error)?;
+    if f(&interface) {
+      return Ok(Some(interface));
+    }
+  }
+  Ok(None)
+}
+
+/// Returns [`interfaces`], optionally preceded by a synthetic wildcard
+/// entry standing for "all interfaces" (`0.0.0.0` / `::`).
+///
+/// The wildcard has index `0`, the name `"*"`, no hardware address, an
+/// MTU of `0` and empty [`Flags`]; it corresponds to no real link and
+/// has no addresses, so its address queries return empty lists. It exists for UI pickers that offer "bind to all"
+/// next to the real interfaces. With `with_wildcard == false` this is
+/// exactly [`interfaces`].
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::interfaces_with_wildcard;
+///
+/// let all = interfaces_with_wildcard(true).unwrap();
+/// assert_eq!(all[0].index(), 0);
+/// assert_eq!(all[0].name(), "*");
+/// ```
+pub fn interfaces_with_wildcard(with_wildcard: bool) -> Result<TinyVec<Interface>> {
+  let ifs = interfaces()?;
+  if !with_wildcard {
+    return Ok(ifs);
+  }
+
+  let mut out = TinyVec::with_capacity(ifs.len() + 1);
+  out.push(Interface::wildcard());
+  out.extend(ifs);
+  Ok(out)
+}
+
+/// Returns [`interfaces`], failing with [`io::ErrorKind::NotFound`] if
+/// the list is empty.
+///
+/// Every system has at least a loopback interface, so an empty list
+/// means the enumeration went wrong without reporting it — the records
+/// were filtered out or a sandbox hid them — rather than that there is
+/// nothing to list. [`runtime_hints`](crate::runtime_hints) can help
+/// tell which.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::interfaces_require_nonempty;
+///
+/// let interfaces = interfaces_require_nonempty().unwrap();
+/// assert!(!interfaces.is_empty());
+/// ```
+pub fn interfaces_require_nonempty() -> Result<TinyVec<Interface>> {
+  let ifs = interfaces()?;
+  if ifs.is_empty() {
+    return Err(
+      io::Error::new(
+        io::ErrorKind::NotFound,
+        "interface enumeration returned no interfaces, not even loopback",
+      )
+      .into(),
+    );
+  }
+  Ok(ifs)
+}
+
+impl Interface {
+  /// The OS address lookups read index `0` as "every interface", so the
+  /// per-interface address queries answer for the wildcard themselves.
+  #[inline]
+  const fn is_wildcard(&self) -> bool {
+    self.index == 0
+  }
+
+  fn wildcard() -> Self {
+    Self {
+      index: 0,
+      mtu: 0,
+      name: SmolStr::new_static("*"),
+      mac_addr: None,
+      flags: Flags::empty(),
+      carrier: None,
+      oper_status: OperStatus::Unknown,
+      media_type: None,
+      kind: InterfaceKind::Other(0),
+      #[cfg(any(windows, bsd_like))]
+      link_speed: None,
+      #[cfg(windows)]
+      dns_servers: SmallVec::new(),
+      #[cfg(windows)]
+      physical_address: SmallVec::new(),
+      #[cfg(linux_like)]
+      master: None,
+      #[cfg(bsd_like)]
+      last_change: None,
+    }
+  }
+}
+
+/// A per-interface failure collected by [`try_interfaces`].
+#[derive(Debug)]
+pub struct EnumerationError {
+  index: Option<u32>,
+  error: Error,
+}
+
+impl EnumerationError {
+  #[inline]
+  pub(crate) fn new(index: Option<u32>, error: io::Error) -> Self {
+    Self {
+      index,
+      error: error.into(),
+    }
+  }
+
+  /// Returns the index of the interface whose record failed to parse,
+  /// or `None` if the record was too damaged to carry one (or the
+  /// failure was not tied to a single interface).
+  #[inline]
+  pub const fn index(&self) -> Option<u32> {
+    self.index
+  }
+
+  /// Returns the underlying error.
+  #[inline]
+  pub const fn error(&self) -> &Error {
+    &self.error
+  }
+
+  /// Consumes `self`, returning the underlying error.
+  #[inline]
+  pub fn into_error(self) -> Error {
+    self.error
+  }
+}
+
+impl core::fmt::Display for EnumerationError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self.index {
+      Some(index) => write!(f, "interface {index}: {}", self.error),
+      None => write!(f, "{}", self.error),
+    }
+  }
+}
+
+impl std::error::Error for EnumerationError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    Some(&self.error)
+  }
+}
+
+/// Returns the system's network interfaces, skipping records that fail
+/// to parse instead of failing the whole enumeration.
+///
+/// [`interfaces`] returns `Err` as soon as one kernel record is
+/// malformed. This variant keeps every interface that did parse and
+/// reports each skipped record as an [`EnumerationError`]. If the
+/// enumeration cannot run at all (the socket or sysctl itself fails),
+/// the returned list is empty and the single error has no index.
+///
+/// Only Linux (netlink) and the BSDs (routing socket) parse raw kernel
+/// records per interface; elsewhere the error list is empty unless the
+/// enumeration as a whole fails.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::try_interfaces;
+///
+/// let (interfaces, errors) = try_interfaces();
+///
+/// for interface in interfaces {
+///   println!("Interface: {:?}", interface);
+/// }
+/// for err in errors {
+///   eprintln!("skipped: {err}");
+/// }
+/// ```
+pub fn try_interfaces() -> (TinyVec<Interface>, Vec<EnumerationError>) {
+  let mut errors = Vec::new();
+  match os::interface_table_lossy(&mut errors) {
+    Ok(interfaces) => (interfaces, errors),
+    Err(e) => {
+      errors.push(EnumerationError::new(None, e));
+      (TinyVec::new(), errors)
+    }
+  }
+}
+
+/// Returns every interface paired with its unicast addresses,
+/// resolving the per-interface address queries concurrently on the
+/// rayon thread pool.
+///
+/// The interface list is fetched once, then each interface's
+/// addresses are queried on its own netlink socket / routing socket /
+/// adapter snapshot, so the queries do not interfere with one another.
+/// The output is in the same order as [`interfaces`] regardless of how
+/// the work was scheduled. An interface that disappears between the
+/// two steps is reported with whatever its address query returned
+/// (usually an empty list); the first query error aborts the call.
+///
+/// On hosts with only a handful of interfaces a single
+/// [`interface_addrs`] dump is cheaper; this pays off when there are
+/// hundreds.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::interfaces_with_addrs_parallel;
+///
+/// for (interface, addrs) in interfaces_with_addrs_parallel().unwrap() {
+///   println!("{}: {:?}", interface.name(), addrs);
+/// }
+/// ```
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub fn interfaces_with_addrs_parallel() -> Result<Vec<(Interface, SmallVec<IfNet>)>> {
+  use rayon::prelude::*;
+
+  interfaces()?
+    .into_iter()
+    .collect::<Vec<_>>()
+    .into_par_iter()
+    .map(|ifi| ifi.addrs().map(|addrs| (ifi, addrs)))
+    .collect()
+}
+
+/// Returns the interface table, every unicast address and the routing
+/// table in one go, as [`interfaces`], [`interface_addrs`] and
+/// [`route_table`](crate::route_table) would.
+///
+/// The three netlink dumps are sent one after another over a single
+/// socket, each under its own sequence number, instead of opening a
+/// socket per call. The results are still three separate snapshots: a
+/// change in between can show up in one and not the others.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::capture_linux;
+///
+/// let (interfaces, addrs, routes) = capture_linux().unwrap();
+/// for ifi in &interfaces {
+///   let count = addrs.iter().filter(|addr| addr.index() == ifi.index()).count();
+///   println!("{}: {count} addrs", ifi.name());
+/// }
+/// println!("{} routes", routes.len());
+/// ```
+#[cfg(linux_like)]
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn capture_linux() -> Result<(
+  TinyVec<Interface>,
+  SmallVec<IfNet>,
+  SmallVec<crate::IpRoute>,
+)> {
+  os::capture().map_err(Into::into)
+}
+
+/// Returns the interface specified by index.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{interface_by_index, local_addrs};
+///
+/// let local_addr = local_addrs().unwrap().into_iter().next().unwrap();
+/// let interface = interface_by_index(local_addr.index()).unwrap();
+///
+/// println!("{:?}", interface);
+/// ```
+pub fn interface_by_index(index: u32) -> Result<Option<Interface>> {
+  cfg_if::cfg_if! {
+    if #[cfg(windows)] {
+      find_interface(os::interface_iter(Some(index))?, |ifi| ifi.index == index).map_err(Into::into)
+    } else {
+      find_interface(os::interface_iter(index)?, |ifi| ifi.index == index).map_err(Into::into)
+    }
+  }
+}
+
+/// Returns the interface specified by name, or `None` if no interface
+/// has that name.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{interface_by_name, ifindex_to_name, local_addrs};
+///
+/// let local_addr = local_addrs().unwrap().into_iter().next().unwrap();
+/// let name = ifindex_to_name(local_addr.index()).unwrap();
+/// let interface = interface_by_name(&name).unwrap();
+/// println!("{:?}", interface);
+/// ```
+pub fn interface_by_name(name: &str) -> Result<Option<Interface>> {
+  let idx = match ifname_to_index(name) {
+    Ok(idx) => idx,
+    // The lookup's error codes for an unknown name differ per platform;
+    // `interface_exists` already tells them apart from real failures.
+    Err(e) => {
+      return if interface_exists(name)? {
+        Err(e)
+      } else {
+        Ok(None)
+      }
+    }
+  };
+  cfg_if::cfg_if! {
+    if #[cfg(windows)] {
+      find_interface(os::interface_iter(Some(idx))?, |ifi| ifi.index == idx).map_err(Into::into)
+    } else {
+      find_interface(os::interface_iter(idx)?, |ifi| ifi.name == name).map_err(Into::into)
+    }
+  }
+}
+
+/// Returns the interface a zoned IPv6 link-local address belongs to.
+///
+/// A link-local address (`fe80::/10`) is only meaningful together with
+/// its zone, which on every supported platform is the interface index —
+/// the `%eth0` / `%4` suffix of a scoped address, and what
+/// [`Ifv6Addr::index`](crate::Ifv6Addr::index) carries for neighbours
+/// and gateways. This resolves the zone, so it also maps a link-local
+/// *peer* back to the interface it was seen on. Zone `0` names no
+/// interface and yields `None`.
+///
+/// Fails with [`io::ErrorKind::InvalidInput`] if `addr` is not a
+/// link-local unicast address.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{interface_by_link_local, interface_ipv6_addrs};
+///
+/// for addr in interface_ipv6_addrs().unwrap() {
+///   if addr.addr().segments()[0] & 0xffc0 == 0xfe80 {
+///     let ifi = interface_by_link_local(addr.addr(), addr.index()).unwrap();
+///     println!("{}: {:?}", addr, ifi.map(|ifi| ifi.name().clone()));
+///   }
+/// }
+/// ```
+pub fn interface_by_link_local(addr: Ipv6Addr, zone: u32) -> Result<Option<Interface>> {
+  if !Ipv6AddrExt::is_unicast_link_local(&addr) {
+    return Err(
+      io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("{addr} is not a link-local address"),
+      )
+      .into(),
+    );
+  }
+  if zone == 0 {
+    return Ok(None);
+  }
+  interface_by_index(zone)
+}
+
+/// Returns the link-local IPv6 addresses in one zone — those assigned
+/// to the interface whose index is `zone`.
+///
+/// This is what binding a link-local listener on a single interface
+/// needs: each returned address, together with `zone` as its scope id,
+/// is a valid `SocketAddrV6` to bind. Zone `0` names no interface and
+/// yields an empty list.
 ///
 /// ## Example
 ///
 /// ```rust
-/// use getifs::interfaces;
-///
-/// let interfaces = interfaces().unwrap();
+/// use getifs::{interfaces, ipv6_addrs_in_zone};
 ///
-/// for interface in interfaces {
-///   println!("Interface: {:?}", interface);
+/// for ifi in interfaces().unwrap() {
+///   for addr in ipv6_addrs_in_zone(ifi.index()).unwrap() {
+///     println!("{}%{}", addr.addr(), ifi.index());
+///   }
 /// }
 /// ```
-///
-/// ## Android
-///
-/// On Android 11+ an app in the `untrusted_app` SELinux domain is denied the
-/// `RTM_GETLINK` netlink dump that enumerates interfaces, so getifs falls
-/// back to listing the interfaces discovered from their addresses
-/// (`RTM_GETADDR`, which stays permitted). On those versions this therefore
-/// returns only interfaces that currently have at least one address, and
-/// their [`Interface::mac_addr`] is `None`. [`interface_by_index`] and
-/// [`interface_by_name`] resolve a known interface directly and are not
-/// affected by this limitation. This fallback also requires the app to hold
-/// `android.permission.INTERNET` (it opens a datagram socket to issue the
-/// `SIOCGIF*` ioctls).
-pub fn interfaces() -> io::Result<TinyVec<Interface>> {
+pub fn ipv6_addrs_in_zone(zone: u32) -> Result<SmallVec<Ifv6Net>> {
+  if zone == 0 {
+    return Ok(SmallVec::new());
+  }
+  let f = |addr: &Ipv6Addr| Ipv6AddrExt::is_unicast_link_local(addr);
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_table(None)
+      os::interface_ipv6_addresses(Some(zone), ipv6_filter_to_ip_filter(f)).map_err(Into::into)
     } else {
-      os::interface_table(0)
+      os::interface_ipv6_addresses(zone, ipv6_filter_to_ip_filter(f)).map_err(Into::into)
     }
   }
 }
 
-/// Returns the interface specified by index.
+/// Resolves an interface by index, like [`interface_by_index`], with a
+/// missing interface reported as [`Error::InterfaceNotFound`].
 ///
 /// ## Example
 ///
 /// ```rust
-/// use getifs::{interface_by_index, local_addrs};
+/// use getifs::{interfaces, Interface};
 ///
-/// let local_addr = local_addrs().unwrap().into_iter().next().unwrap();
-/// let interface = interface_by_index(local_addr.index()).unwrap();
+/// let first = interfaces().unwrap().into_iter().next().unwrap();
+/// let interface = Interface::try_from(first.index()).unwrap();
+/// assert_eq!(interface.name(), first.name());
 ///
-/// println!("{:?}", interface);
+/// let err = Interface::try_from(u32::MAX).unwrap_err();
+/// assert!(matches!(err, getifs::Error::InterfaceNotFound));
 /// ```
-pub fn interface_by_index(index: u32) -> io::Result<Option<Interface>> {
-  cfg_if::cfg_if! {
-    if #[cfg(windows)] {
-      os::interface_table(Some(index)).map(|v| v.into_iter().find(|ifi| ifi.index == index))
-    } else {
-      os::interface_table(index).map(|v| v.into_iter().find(|ifi| ifi.index == index))
+impl TryFrom<u32> for Interface {
+  type Error = Error;
+
+  fn try_from(index: u32) -> Result<Self> {
+    interface_by_index(index)?.ok_or(Error::InterfaceNotFound)
+  }
+}
+
+/// Resolves an interface by name, like [`interface_by_name`], with a
+/// missing interface reported as [`Error::InterfaceNotFound`].
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{interfaces, Interface};
+///
+/// let first = interfaces().unwrap().into_iter().next().unwrap();
+/// let interface = Interface::try_from(first.name().as_str()).unwrap();
+/// assert_eq!(interface.index(), first.index());
+///
+/// let err = Interface::try_from("no-such-if0").unwrap_err();
+/// assert!(matches!(err, getifs::Error::InterfaceNotFound));
+/// ```
+impl TryFrom<&str> for Interface {
+  type Error = Error;
+
+  fn try_from(name: &str) -> Result<Self> {
+    match interface_by_name(name) {
+      Ok(Some(interface)) => Ok(interface),
+      Ok(None) => Err(Error::InterfaceNotFound),
+      // Only the failure path pays for the second lookup that tells an
+      // unknown name apart from a real error.
+      Err(_) if !crate::interface_exists(name)? => Err(Error::InterfaceNotFound),
+      Err(e) => Err(e),
+    }
+  }
+}
+
+/// Collects the OS-reported broadcast addresses of `nets`, dropping
+/// repeats from several addresses on the same subnet.
+fn broadcast_addrs(nets: SmallVec<Ifv4Net>) -> SmallVec<Ifv4Addr> {
+  let mut out = SmallVec::new();
+  for net in nets {
+    if let Some(broadcast) = net.broadcast() {
+      let addr = Ifv4Addr::new(net.index(), broadcast);
+      if !out.contains(&addr) {
+        out.push(addr);
+      }
     }
   }
+  out
 }
 
-/// Returns the interface specified by name.
+/// Returns the loopback interface, if the host has one.
+///
+/// On Linux and Android this looks up `lo` directly and on the BSDs
+/// and Apple platforms `lo0`, so only that one interface is queried;
+/// the full table is dumped only if the conventional name is missing
+/// or does not carry the `LOOPBACK` flag. Windows has no such name, so
+/// the adapter list is scanned for the software loopback adapter.
 ///
 /// ## Example
 ///
 /// ```rust
-/// use getifs::{interface_by_name, ifindex_to_name, local_addrs};
+/// use getifs::loopback_interface;
 ///
-/// let local_addr = local_addrs().unwrap().into_iter().next().unwrap();
-/// let name = ifindex_to_name(local_addr.index()).unwrap();
-/// let interface = interface_by_name(&name).unwrap();
-/// println!("{:?}", interface);
+/// if let Some(lo) = loopback_interface().unwrap() {
+///   println!("Loopback: {} (index: {})", lo.name(), lo.index());
+/// }
 /// ```
-pub fn interface_by_name(name: &str) -> io::Result<Option<Interface>> {
-  let idx = ifname_to_index(name)?;
-  cfg_if::cfg_if! {
-    if #[cfg(windows)] {
-      os::interface_table(Some(idx)).map(|v| v.into_iter().find(|ifi| ifi.index == idx))
-    } else {
-      os::interface_table(idx).map(|v| v.into_iter().find(|ifi| ifi.name == name))
+pub fn loopback_interface() -> Result<Option<Interface>> {
+  #[cfg(not(windows))]
+  {
+    #[cfg(linux_like)]
+    const LOOPBACK_NAME: &str = "lo";
+    #[cfg(bsd_like)]
+    const LOOPBACK_NAME: &str = "lo0";
+
+    match interface_by_name(LOOPBACK_NAME) {
+      Ok(Some(ifi)) if ifi.flags.contains(Flags::LOOPBACK) => return Ok(Some(ifi)),
+      Ok(_) => {}
+      Err(e) => return Err(e),
     }
   }
+
+  Ok(
+    interfaces()?
+      .into_iter()
+      .find(|ifi| ifi.flags.contains(Flags::LOOPBACK)),
+  )
+}
+
+/// Returns the interface carrying the best default route: the egress
+/// interface for traffic without a more specific route.
+///
+/// The IPv4 default wins when there is one; otherwise the IPv6 default
+/// is used. Route selection is the same as [`best_local_addrs`](crate::best_local_addrs)'s,
+/// with the same per-platform caveats; when several interfaces tie
+/// for best, the one with the lowest index is returned. On OpenBSD
+/// this is a member of the `egress` interface group. Returns `None`
+/// when there is no usable default route.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::default_interface;
+///
+/// if let Some(interface) = default_interface().unwrap() {
+///   println!("egress: {}", interface.name());
+/// }
+/// ```
+pub fn default_interface() -> Result<Option<Interface>> {
+  match os::default_route_index()? {
+    Some(index) => interface_by_index(index),
+    None => Ok(None),
+  }
+}
+
+/// Returns the non-loopback interfaces that are `UP` and carry at
+/// least one routable unicast address — that is, an address that is
+/// neither loopback nor link-local (the same classification
+/// [`local_addrs`](crate::local_addrs) uses).
+///
+/// This is the practical "which NICs can reach beyond this host" list.
+/// It is built from one interface dump and one address dump joined in
+/// memory, rather than a per-interface address query.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::routable_interfaces;
+///
+/// for interface in routable_interfaces().unwrap() {
+///   println!("Routable: {} (index: {})", interface.name(), interface.index());
+/// }
+/// ```
+pub fn routable_interfaces() -> Result<TinyVec<Interface>> {
+  let addrs = interface_addrs_by_filter(local_ip_filter)?;
+  Ok(
+    interfaces()?
+      .into_iter()
+      .filter(|ifi| {
+        ifi.flags.contains(Flags::UP)
+          && !ifi.flags.contains(Flags::LOOPBACK)
+          && addrs.iter().any(|addr| addr.index() == ifi.index)
+      })
+      .collect(),
+  )
 }
 
 /// Returns a list of the system's unicast interface
@@ -493,6 +1916,10 @@ pub fn interface_by_name(name: &str) -> io::Result<Option<Interface>> {
 /// The returned list does not identify the associated interface; use
 /// [`interfaces`] and [`Interface::addrs`] for more detail.
 ///
+/// Addresses assigned to interfaces that are administratively down are
+/// included — the kernel keeps them configured while the link is down.
+/// Use [`up_interface_addrs`] to restrict the list to `UP` interfaces.
+///
 /// ## Example
 ///
 /// ```rust
@@ -504,14 +1931,324 @@ pub fn interface_by_name(name: &str) -> io::Result<Option<Interface>> {
 ///   println!("Addr: {:?}", addr);
 /// }
 /// ```
-pub fn interface_addrs() -> io::Result<SmallVec<IfNet>> {
+pub fn interface_addrs() -> Result<SmallVec<IfNet>> {
+  cfg_if::cfg_if! {
+    if #[cfg(windows)] {
+      os::interface_addresses(None, |_| true).map_err(Into::into)
+    } else {
+      os::interface_addresses(0, |_| true).map_err(Into::into)
+    }
+  }
+}
+
+/// Returns the system's unicast interface addrs, restricted to
+/// interfaces whose [`Flags::UP`] is set.
+///
+/// Unlike [`interface_addrs`], which reports every configured address
+/// regardless of link state, this joins the address dump with one
+/// interface dump and drops addresses on down interfaces.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::up_interface_addrs;
+///
+/// for addr in up_interface_addrs().unwrap() {
+///   println!("Addr: {}", addr);
+/// }
+/// ```
+pub fn up_interface_addrs() -> Result<SmallVec<IfNet>> {
+  let up: SmallVec<u32> = interfaces()?
+    .into_iter()
+    .filter(|ifi| ifi.flags.contains(Flags::UP))
+    .map(|ifi| ifi.index)
+    .collect();
+  let mut addrs = interface_addrs()?;
+  addrs.retain(|addr| up.contains(&addr.index()));
+  Ok(addrs)
+}
+
+/// Returns the system's unicast interface addrs ordered by
+/// [`AddrScope`](crate::AddrScope): global first, then site, link and host.
+///
+/// This is the order most servers want to advertise addresses in.
+/// Addresses of equal scope keep the order [`interface_addrs`]
+/// reports them in.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::interface_addrs_scope_sorted;
+///
+/// for addr in interface_addrs_scope_sorted().unwrap() {
+///   println!("{:?}: {}", addr.scope(), addr);
+/// }
+/// ```
+pub fn interface_addrs_scope_sorted() -> Result<SmallVec<IfNet>> {
+  let mut addrs = interface_addrs()?;
+  addrs.sort_by_key(IfNet::scope);
+  Ok(addrs)
+}
+
+/// Returns the unicast addresses of every interface whose hardware
+/// address starts with the given OUI (the first three octets of the
+/// MAC, which identify the NIC vendor).
+///
+/// Interfaces without a hardware address, such as loopback and most
+/// tunnels, never match. Like [`up_interface_addrs`], this joins one
+/// interface dump with one address dump.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::addrs_for_mac_prefix;
+///
+/// // Addresses on interfaces with a locally administered QEMU/KVM MAC.
+/// for addr in addrs_for_mac_prefix([0x52, 0x54, 0x00]).unwrap() {
+///   println!("Addr: {}", addr);
+/// }
+/// ```
+pub fn addrs_for_mac_prefix(oui: [u8; 3]) -> Result<SmallVec<IfNet>> {
+  let matching: SmallVec<u32> = interfaces()?
+    .into_iter()
+    .filter(|ifi| {
+      ifi
+        .mac_addr
+        .is_some_and(|mac| mac.as_bytes().starts_with(&oui))
+    })
+    .map(|ifi| ifi.index)
+    .collect();
+  if matching.is_empty() {
+    return Ok(SmallVec::new());
+  }
+
+  let mut addrs = interface_addrs()?;
+  addrs.retain(|addr| matching.contains(&addr.index()));
+  Ok(addrs)
+}
+
+/// Returns the unicast addresses contained in any of `subnets`.
+///
+/// Answers questions like "which of my addresses are in the corporate
+/// ranges" with a single address dump. An empty `subnets` matches
+/// nothing. For a single subnet, [`addrs_within`] avoids dumping
+/// addresses on unrelated interfaces.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{interface_addrs_in_any, ipnet::IpNet};
+///
+/// let ranges: [IpNet; 3] = [
+///   "10.0.0.0/8".parse().unwrap(),
+///   "172.16.0.0/12".parse().unwrap(),
+///   "192.168.0.0/16".parse().unwrap(),
+/// ];
+/// for addr in interface_addrs_in_any(&ranges).unwrap() {
+///   println!("Addr: {}", addr);
+/// }
+/// ```
+pub fn interface_addrs_in_any(subnets: &[IpNet]) -> Result<SmallVec<IfNet>> {
+  if subnets.is_empty() {
+    return Ok(SmallVec::new());
+  }
+  interface_addrs_by_filter(|addr| subnets.iter().any(|net| net.contains(addr)))
+}
+
+/// Returns at most one IPv4 and one IPv6 address per interface: the
+/// one the platform treats as the interface's primary address.
+///
+/// On Linux this is the first IPv4 address the kernel does not flag
+/// `IFA_F_SECONDARY`; on Windows and the BSDs it is the first address
+/// of each family in the order [`interface_addrs`] reports them. IPv6
+/// has no notion of secondaries, so it is always the first address.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::primary_addrs_per_family;
+///
+/// for addr in primary_addrs_per_family().unwrap() {
+///   println!("{}: {}", addr.index(), addr);
+/// }
+/// ```
+pub fn primary_addrs_per_family() -> Result<SmallVec<IfNet>> {
+  let mut addrs = {
+    cfg_if::cfg_if! {
+      if #[cfg(linux_like)] {
+        os::interface_primary_addresses()?
+      } else {
+        interface_addrs()?
+      }
+    }
+  };
+
+  let mut seen: SmallVec<(u32, bool)> = SmallVec::new();
+  addrs.retain(|addr| {
+    let key = (addr.index(), addr.addr().is_ipv4());
+    if seen.contains(&key) {
+      return false;
+    }
+    seen.push(key);
+    true
+  });
+  Ok(addrs)
+}
+
+/// Returns whether `ip` is configured on any interface.
+///
+/// This is an exact match against the addresses [`interface_addrs`]
+/// reports, so it answers "is this one of the host's own addresses",
+/// not "would traffic to it stay on this host".
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::is_local_addr;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// assert!(is_local_addr(IpAddr::V4(Ipv4Addr::LOCALHOST)).unwrap());
+/// ```
+pub fn is_local_addr(ip: IpAddr) -> Result<bool> {
+  interface_addrs_by_filter(|addr| *addr == ip).map(|addrs| !addrs.is_empty())
+}
+
+/// Returns whether `ip` is configured on the interface with index
+/// `ifindex`. Index `0` names no interface and always yields `false`.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{is_local_addr_on, loopback_interface};
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let lo = loopback_interface().unwrap().unwrap();
+/// assert!(is_local_addr_on(IpAddr::V4(Ipv4Addr::LOCALHOST), lo.index()).unwrap());
+/// ```
+pub fn is_local_addr_on(ip: IpAddr, ifindex: u32) -> Result<bool> {
+  if ifindex == 0 {
+    return Ok(false);
+  }
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_addresses(None, |_| true)
+      let found = os::interface_addresses(Some(ifindex), |addr| *addr == ip)?;
     } else {
-      os::interface_addresses(0, |_| true)
+      let found = os::interface_addresses(ifindex, |addr| *addr == ip)?;
+    }
+  }
+  Ok(found.iter().any(|addr| addr.index() == ifindex))
+}
+
+/// Returns the unicast addresses that fall inside `net`.
+///
+/// Rather than dumping every address on the system, this first walks
+/// the routing table for routes whose destination overlaps `net` —
+/// every configured address has a connected or local route on its
+/// interface — and then asks only those interfaces for their
+/// addresses. On Linux the per-interface query is filtered by the
+/// kernel (4.20+), so hosts carrying thousands of addresses elsewhere
+/// don't pay to ship and decode them. Other platforms still filter
+/// client-side per interface.
+///
+/// Addresses on interfaces that have no route at all, typically ones
+/// that are administratively down, are not found this way; use
+/// [`interface_addrs_by_filter`] when those matter. If the routing
+/// table can't be read, this falls back to that full scan.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{addrs_within, ipnet::IpNet};
+///
+/// let net: IpNet = "127.0.0.0/8".parse().unwrap();
+/// for addr in addrs_within(net).unwrap() {
+///   println!("Addr: {}", addr);
+/// }
+/// ```
+pub fn addrs_within(net: IpNet) -> Result<SmallVec<IfNet>> {
+  let routes = match crate::route_table_by_filter(|route| {
+    // Two prefixes overlap exactly when one contains the other. A
+    // default route says nothing about which addresses live behind it.
+    let dst = route.destination();
+    !route.is_default() && (dst.contains(&net) || net.contains(&dst))
+  }) {
+    Ok(routes) => routes,
+    Err(_) => return interface_addrs_by_filter(|addr| net.contains(addr)),
+  };
+
+  let mut candidates: SmallVec<u32> = SmallVec::new();
+  for route in routes {
+    if !candidates.contains(&route.index()) {
+      candidates.push(route.index());
+    }
+  }
+
+  let mut addrs = SmallVec::new();
+  for index in candidates {
+    cfg_if::cfg_if! {
+      if #[cfg(windows)] {
+        let found = os::interface_addresses(Some(index), |addr| net.contains(addr))?;
+      } else {
+        let found = os::interface_addresses(index, |addr| net.contains(addr))?;
+      }
+    }
+    addrs.extend(found);
+  }
+  Ok(addrs)
+}
+
+/// Reports addresses whose subnet overlaps a subnet configured on some
+/// other interface.
+///
+/// Two interfaces claiming overlapping prefixes leave the kernel to pick
+/// one of them for traffic to the shared range, which is rarely what
+/// was intended. Each entry pairs an address with the sorted indices of
+/// the other interfaces involved; addresses with no overlap are left
+/// out, so a healthy host yields an empty list. Loopback addresses and
+/// IPv6 link-local addresses are skipped, since every interface carries
+/// its own `fe80::/64` by design.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::overlapping_subnets;
+///
+/// for (addr, others) in overlapping_subnets().unwrap() {
+///   println!("{addr} overlaps interfaces {others:?}");
+/// }
+/// ```
+pub fn overlapping_subnets() -> Result<SmallVec<(IfNet, SmallVec<u32>)>> {
+  let addrs = interface_addrs()?;
+  let candidates: SmallVec<&IfNet> = addrs
+    .iter()
+    .filter(|addr| match addr.addr() {
+      IpAddr::V4(ip) => !ip.is_loopback(),
+      IpAddr::V6(ip) => !(ip.is_loopback() || Ipv6AddrExt::is_unicast_link_local(&ip)),
+    })
+    .collect();
+
+  let mut report = SmallVec::new();
+  for addr in candidates.iter() {
+    let net = addr.net().trunc();
+    let mut others: SmallVec<u32> = SmallVec::new();
+    for other in candidates.iter() {
+      if other.index() == addr.index() {
+        continue;
+      }
+      // Two prefixes overlap exactly when one contains the other;
+      // `contains` is false across families.
+      let other_net = other.net().trunc();
+      if net.contains(&other_net) || other_net.contains(&net) {
+        others.push(other.index());
+      }
+    }
+    if !others.is_empty() {
+      others.sort_unstable();
+      others.dedup();
+      report.push((**addr, others));
     }
   }
+  Ok(report)
 }
 
 /// Returns a list of the system's unicast, IPv4 interface
@@ -531,16 +2268,35 @@ pub fn interface_addrs() -> io::Result<SmallVec<IfNet>> {
 ///   println!("IPv4 Addr: {:?}", addr);
 /// }
 /// ```
-pub fn interface_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
+pub fn interface_ipv4_addrs() -> Result<SmallVec<Ifv4Net>> {
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_ipv4_addresses(None, |_| true)
+      os::interface_ipv4_addresses(None, |_| true).map_err(Into::into)
     } else {
-      os::interface_ipv4_addresses(0, |_| true)
+      os::interface_ipv4_addresses(0, |_| true).map_err(Into::into)
     }
   }
 }
 
+/// Returns the IPv4 broadcast addresses the OS reports across every
+/// interface, one entry per interface and distinct broadcast address.
+///
+/// See [`Ifv4Net::broadcast`] for where each platform takes them
+/// from; addresses without one are skipped.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::interface_broadcast_addrs;
+///
+/// for addr in interface_broadcast_addrs().unwrap() {
+///   println!("Broadcast Addr: {} on {}", addr.addr(), addr.index());
+/// }
+/// ```
+pub fn interface_broadcast_addrs() -> Result<SmallVec<Ifv4Addr>> {
+  interface_ipv4_addrs().map(broadcast_addrs)
+}
+
 /// Returns a list of the system's unicast, IPv6 interface
 /// addrs.
 ///
@@ -558,12 +2314,12 @@ pub fn interface_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
 ///   println!("IPv6 Addr: {:?}", addr);
 /// }
 /// ```
-pub fn interface_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
+pub fn interface_ipv6_addrs() -> Result<SmallVec<Ifv6Net>> {
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_ipv6_addresses(None, |_| true)
+      os::interface_ipv6_addresses(None, |_| true).map_err(Into::into)
     } else {
-      os::interface_ipv6_addresses(0, |_| true)
+      os::interface_ipv6_addresses(0, |_| true).map_err(Into::into)
     }
   }
 }
@@ -585,19 +2341,77 @@ pub fn interface_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
 ///   println!("Addr: {:?}", addr);
 /// }
 /// ```
-pub fn interface_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<IfNet>>
+pub fn interface_addrs_by_filter<F>(f: F) -> Result<SmallVec<IfNet>>
 where
   F: FnMut(&IpAddr) -> bool,
 {
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_addresses(None, f)
+      os::interface_addresses(None, f).map_err(Into::into)
     } else {
-      os::interface_addresses(0, f)
+      os::interface_addresses(0, f).map_err(Into::into)
     }
   }
 }
 
+/// Like [`interface_addrs_by_filter`], but the filter sees the whole
+/// [`IfNet`], so it can also select by prefix length or interface
+/// index.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{interface_addrs_by_net_filter, loopback_interface};
+///
+/// let lo = loopback_interface().unwrap().unwrap();
+/// let addrs =
+///   interface_addrs_by_net_filter(|net| net.prefix_len() >= 24 && net.index() == lo.index())
+///     .unwrap();
+///
+/// for addr in addrs {
+///   println!("Addr: {:?}", addr);
+/// }
+/// ```
+pub fn interface_addrs_by_net_filter<F>(mut f: F) -> Result<SmallVec<IfNet>>
+where
+  F: FnMut(&IfNet) -> bool,
+{
+  let mut addrs = interface_addrs()?;
+  addrs.retain(|net| f(net));
+  Ok(addrs)
+}
+
+/// Calls `f` with each of the system's unicast interface addrs as it
+/// is decoded from the kernel's reply, without collecting them first.
+///
+/// Returning [`ControlFlow::Break`] stops the walk, so searching or
+/// counting needs no intermediate list. Sees the same addresses as
+/// [`interface_addrs`], in the same order.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::for_each_interface_addr;
+/// use std::ops::ControlFlow;
+///
+/// let mut loopback = None;
+/// for_each_interface_addr(|net| {
+///   if net.addr().is_loopback() {
+///     loopback = Some(*net);
+///     return ControlFlow::Break(());
+///   }
+///   ControlFlow::Continue(())
+/// })
+/// .unwrap();
+/// println!("{loopback:?}");
+/// ```
+pub fn for_each_interface_addr<F>(f: F) -> Result<()>
+where
+  F: FnMut(&IfNet) -> ControlFlow<()>,
+{
+  os::for_each_interface_addr(f).map_err(Into::into)
+}
+
 /// Returns a list of the system's unicast, IPv4 interface
 /// addrs.
 ///
@@ -615,15 +2429,15 @@ where
 ///   println!("IPv4 Addr: {:?}", addr);
 /// }
 /// ```
-pub fn interface_ipv4_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<Ifv4Net>>
+pub fn interface_ipv4_addrs_by_filter<F>(f: F) -> Result<SmallVec<Ifv4Net>>
 where
   F: FnMut(&Ipv4Addr) -> bool,
 {
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_ipv4_addresses(None, ipv4_filter_to_ip_filter(f))
+      os::interface_ipv4_addresses(None, ipv4_filter_to_ip_filter(f)).map_err(Into::into)
     } else {
-      os::interface_ipv4_addresses(0, ipv4_filter_to_ip_filter(f))
+      os::interface_ipv4_addresses(0, ipv4_filter_to_ip_filter(f)).map_err(Into::into)
     }
   }
 }
@@ -647,15 +2461,15 @@ where
 ///   println!("IPv6 Addr: {:?}", addr);
 /// }
 /// ```
-pub fn interface_ipv6_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<Ifv6Net>>
+pub fn interface_ipv6_addrs_by_filter<F>(f: F) -> Result<SmallVec<Ifv6Net>>
 where
   F: FnMut(&Ipv6Addr) -> bool,
 {
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_ipv6_addresses(None, ipv6_filter_to_ip_filter(f))
+      os::interface_ipv6_addresses(None, ipv6_filter_to_ip_filter(f)).map_err(Into::into)
     } else {
-      os::interface_ipv6_addresses(0, ipv6_filter_to_ip_filter(f))
+      os::interface_ipv6_addresses(0, ipv6_filter_to_ip_filter(f)).map_err(Into::into)
     }
   }
 }
@@ -667,6 +2481,10 @@ cfg_multicast!(
   /// The returned list does not identify the associated interface; use
   /// [`interfaces`] and [`Interface::multicast_addrs`] for more detail.
   ///
+  /// A family whose stack is not running contributes no groups. On
+  /// Linux an `Err` with [`io::ErrorKind::NotFound`] means `/proc` is
+  /// not mounted, so membership could not be read.
+  ///
   /// ## Example
   ///
   /// ```rust
@@ -678,12 +2496,12 @@ cfg_multicast!(
   ///   println!("Multicast Addr: {:?}", addr);
   /// }
   /// ```
-  pub fn interface_multicast_addrs() -> io::Result<SmallVec<IfAddr>> {
+  pub fn interface_multicast_addrs() -> Result<SmallVec<IfAddr>> {
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_multicast_addresses(None, |_| true)
+        os::interface_multicast_addresses(None, |_| true).map_err(Into::into)
       } else {
-        os::interface_multicast_addresses(0, |_| true)
+        os::interface_multicast_addresses(0, |_| true).map_err(Into::into)
       }
     }
   }
@@ -704,15 +2522,15 @@ cfg_multicast!(
   ///  !addr.is_loopback()
   /// }).unwrap();
   /// ```
-  pub fn interface_multicast_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<IfAddr>>
+  pub fn interface_multicast_addrs_by_filter<F>(f: F) -> Result<SmallVec<IfAddr>>
   where
     F: FnMut(&IpAddr) -> bool,
   {
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_multicast_addresses(None, f)
+        os::interface_multicast_addresses(None, f).map_err(Into::into)
       } else {
-        os::interface_multicast_addresses(0, f)
+        os::interface_multicast_addresses(0, f).map_err(Into::into)
       }
     }
   }
@@ -734,12 +2552,12 @@ cfg_multicast!(
   ///  println!("Multicast IPv4 Addr: {:?}", addr);
   /// }
   /// ```
-  pub fn interface_multicast_ipv4_addrs() -> io::Result<SmallVec<Ifv4Addr>> {
+  pub fn interface_multicast_ipv4_addrs() -> Result<SmallVec<Ifv4Addr>> {
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_multicast_ipv4_addresses(None, |_| true)
+        os::interface_multicast_ipv4_addresses(None, |_| true).map_err(Into::into)
       } else {
-        os::interface_multicast_ipv4_addresses(0, |_| true)
+        os::interface_multicast_ipv4_addresses(0, |_| true).map_err(Into::into)
       }
     }
   }
@@ -760,15 +2578,15 @@ cfg_multicast!(
   ///   !addr.is_loopback()
   /// }).unwrap();
   /// ```
-  pub fn interface_multicast_ipv4_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<Ifv4Addr>>
+  pub fn interface_multicast_ipv4_addrs_by_filter<F>(f: F) -> Result<SmallVec<Ifv4Addr>>
   where
     F: FnMut(&Ipv4Addr) -> bool,
   {
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_multicast_ipv4_addresses(None, f)
+        os::interface_multicast_ipv4_addresses(None, f).map_err(Into::into)
       } else {
-        os::interface_multicast_ipv4_addresses(0, f)
+        os::interface_multicast_ipv4_addresses(0, f).map_err(Into::into)
       }
     }
   }
@@ -790,12 +2608,12 @@ cfg_multicast!(
   ///   println!("Multicast IPv6 Addr: {:?}", addr);
   /// }
   /// ```
-  pub fn interface_multicast_ipv6_addrs() -> io::Result<SmallVec<Ifv6Addr>> {
+  pub fn interface_multicast_ipv6_addrs() -> Result<SmallVec<Ifv6Addr>> {
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_multicast_ipv6_addresses(None, |_| true)
+        os::interface_multicast_ipv6_addresses(None, |_| true).map_err(Into::into)
       } else {
-        os::interface_multicast_ipv6_addresses(0, |_| true)
+        os::interface_multicast_ipv6_addresses(0, |_| true).map_err(Into::into)
       }
     }
   }
@@ -816,16 +2634,103 @@ cfg_multicast!(
   ///   !addr.is_loopback()
   /// }).unwrap();
   /// ```
-  pub fn interface_multicast_ipv6_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<Ifv6Addr>>
+  pub fn interface_multicast_ipv6_addrs_by_filter<F>(f: F) -> Result<SmallVec<Ifv6Addr>>
   where
     F: FnMut(&Ipv6Addr) -> bool,
   {
     cfg_if::cfg_if! {
       if #[cfg(windows)] {
-        os::interface_multicast_ipv6_addresses(None, f)
+        os::interface_multicast_ipv6_addresses(None, f).map_err(Into::into)
       } else {
-        os::interface_multicast_ipv6_addresses(0, f)
+        os::interface_multicast_ipv6_addresses(0, f).map_err(Into::into)
       }
     }
   }
 );
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn any_interface() -> Interface {
+    interfaces().unwrap().into_iter().next().unwrap()
+  }
+
+  // The same NIC seen in two captures with different indices (driver
+  // reload, device recreated) must keep its identity via the MAC, even
+  // if it was also renamed.
+  #[test]
+  fn stable_id_survives_renumbering() {
+    let mut before = any_interface();
+    before.mac_addr = Some(MacAddr::from_raw([0x02, 0, 0, 0, 0, 0x01]));
+    let mut after = before.clone();
+    after.index += 100;
+    after.name = SmolStr::new("renamed0");
+
+    assert_eq!(before.stable_id(), after.stable_id());
+    assert!(matches!(before.stable_id(), StableId::Mac(_)));
+  }
+
+  // Without a usable MAC (absent or all-zero, as on loopback) the name
+  // carries the identity, and the index only when there is no name.
+  #[test]
+  fn stable_id_fallbacks() {
+    let mut before = any_interface();
+    before.mac_addr = Some(MacAddr::from_raw([0; crate::MAC_ADDRESS_SIZE]));
+    let mut after = before.clone();
+    after.index += 100;
+    after.mac_addr = None;
+    assert_eq!(before.stable_id(), after.stable_id());
+    assert_eq!(before.stable_id(), StableId::Name(before.name.clone()));
+
+    before.name = SmolStr::default();
+    assert_eq!(before.stable_id(), StableId::Index(before.index));
+  }
+
+  #[cfg(apple)]
+  #[test]
+  fn apple_hint_names() {
+    use AppleInterfaceHint::*;
+
+    assert_eq!(AppleInterfaceHint::from_name("utun0"), Some(Tunnel));
+    assert_eq!(AppleInterfaceHint::from_name("utun12"), Some(Tunnel));
+    assert_eq!(AppleInterfaceHint::from_name("awdl0"), Some(Awdl));
+    assert_eq!(AppleInterfaceHint::from_name("llw0"), Some(LowLatencyWlan));
+    for name in ["en0", "lo0", "utun", "awdl", "utunx", "bridge0"] {
+      assert_eq!(AppleInterfaceHint::from_name(name), None, "{name}");
+    }
+    assert!(Awdl.is_peer_to_peer() && LowLatencyWlan.is_peer_to_peer());
+    assert!(!Tunnel.is_peer_to_peer());
+  }
+
+  // Loopback traffic we generate ourselves must show up in lo0's
+  // counters, both as sent and as received.
+  #[cfg(apple)]
+  #[test]
+  fn loopback_statistics() {
+    use std::net::UdpSocket;
+
+    let lo = loopback_interface().unwrap().unwrap();
+    let before = lo.statistics().unwrap();
+
+    let rx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let tx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for _ in 0..8 {
+      tx.send_to(&[0u8; 512], rx.local_addr().unwrap()).unwrap();
+    }
+
+    let after = lo.statistics().unwrap();
+    assert!(
+      after.tx_packets() >= before.tx_packets() + 8,
+      "{before:?} -> {after:?}"
+    );
+    assert!(
+      after.rx_packets() >= before.rx_packets() + 8,
+      "{before:?} -> {after:?}"
+    );
+    assert!(
+      after.rx_bytes() >= before.rx_bytes() + 8 * 512,
+      "{before:?} -> {after:?}"
+    );
+  }
+}
@@ -0,0 +1,113 @@
+use std::io;
+
+use serde::Serialize;
+
+use super::{interface_addrs, interfaces, IfNet, Interface, Result};
+
+/// Returns every interface with its unicast addresses as a pretty
+/// printed JSON array, for `--json` style CLI output.
+///
+/// Each element looks like:
+///
+/// ```json
+/// {
+///   "index": 1,
+///   "name": "lo",
+///   "mtu": 65536,
+///   "mac_addr": null,
+///   "flags": ["UP", "LOOPBACK", "RUNNING"],
+///   "oper_status": "unknown",
+///   "media_type": "loopback",
+///   "addrs": [{ "addr": "127.0.0.1", "prefix_len": 8 }]
+/// }
+/// ```
+///
+/// `flags` holds the names of the platform's [`Flags`](crate::Flags)
+/// that are set, so the exact set differs between platforms. The
+/// addresses come from one [`interface_addrs`] dump joined by index.
+///
+/// ## Example
+///
+/// ```rust
+/// println!("{}", getifs::interfaces_json().unwrap());
+/// ```
+pub fn interfaces_json() -> Result<String> {
+  let ifs = interfaces()?;
+  let addrs = interface_addrs()?;
+  let view: Vec<_> = ifs
+    .iter()
+    .map(|ifi| InterfaceView::new(ifi, &addrs))
+    .collect();
+  serde_json::to_string_pretty(&view).map_err(|e| io::Error::other(e).into())
+}
+
+#[derive(Serialize)]
+struct InterfaceView<'a> {
+  index: u32,
+  name: &'a str,
+  mtu: u32,
+  mac_addr: Option<String>,
+  flags: Vec<&'static str>,
+  oper_status: String,
+  media_type: Option<&'static str>,
+  addrs: Vec<AddrView>,
+}
+
+#[derive(Serialize)]
+struct AddrView {
+  addr: String,
+  prefix_len: u8,
+}
+
+impl<'a> InterfaceView<'a> {
+  fn new(ifi: &'a Interface, addrs: &[IfNet]) -> Self {
+    Self {
+      index: ifi.index(),
+      name: ifi.name(),
+      mtu: ifi.mtu(),
+      mac_addr: ifi.mac_addr().map(|mac| mac.to_string()),
+      flags: ifi.flags().iter_names().map(|(name, _)| name).collect(),
+      oper_status: ifi.oper_status().to_string(),
+      media_type: ifi.media_type,
+      addrs: addrs
+        .iter()
+        .filter(|net| net.index() == ifi.index())
+        .map(|net| AddrView {
+          addr: net.addr().to_string(),
+          prefix_len: net.prefix_len(),
+        })
+        .collect(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // The output is a JSON array with one object per interface, the
+  // loopback among them carrying its loopback address.
+  #[test]
+  fn interfaces_json_round_trips() {
+    let json: serde_json::Value = serde_json::from_str(&interfaces_json().unwrap()).unwrap();
+    let list = json.as_array().unwrap();
+    assert_eq!(list.len(), interfaces().unwrap().len());
+
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    let entry = list
+      .iter()
+      .find(|ifi| ifi["index"] == lo.index())
+      .unwrap_or_else(|| panic!("no loopback in {json}"));
+    assert_eq!(entry["name"], lo.name().as_str());
+    assert!(entry["flags"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .any(|flag| flag == "LOOPBACK"));
+    for addr in entry["addrs"].as_array().unwrap() {
+      let ip: std::net::IpAddr = addr["addr"].as_str().unwrap().parse().unwrap();
+      assert!(ip.is_loopback() || ip.is_ipv6(), "{addr}");
+      assert!(addr["prefix_len"].is_u64(), "{addr}");
+    }
+  }
+}
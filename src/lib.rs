@@ -8,6 +8,18 @@ mod macros;
 
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
+pub use common_flags::CommonFlags;
+#[cfg(feature = "configure")]
+#[cfg_attr(docsrs, doc(cfg(feature = "configure")))]
+pub use configure::*;
+pub use error::{Error, Result};
+#[cfg(feature = "ethtool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ethtool")))]
+pub use ethtool::DriverInfo;
+#[cfg(all(target_os = "linux", feature = "ethtool"))]
+#[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "ethtool"))))]
+pub use ethtool::{OffloadFeature, OffloadFeatures};
+pub use family::Family;
 pub use gateway::*;
 pub use hardware_address::{MacAddr, ParseMacAddrError};
 pub use idx_to_name::ifindex_to_name;
@@ -21,31 +33,80 @@ pub use iprfc as rfc;
 /// IP protocol probing
 #[doc(inline)]
 pub use iprobe as probe;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub use json::interfaces_json;
 pub use local_addrs::*;
+#[cfg(windows)]
+#[cfg_attr(docsrs, doc(cfg(windows)))]
+pub use luid::{ifindex_to_luid, ifluid_to_index};
 pub use mtu::*;
-pub use name_to_idx::ifname_to_index;
+pub use name_to_idx::{ifname_to_index, interface_exists};
 pub use name_to_iface::{ifname_to_iface, ifname_to_v4_iface, ifname_to_v6_iface};
+pub use neighbor::*;
 pub use os::Flags;
 pub use private_ip_addrs::*;
 pub use public_ip_addrs::*;
 pub use route::*;
+pub use runtime::{runtime_hints, RuntimeHints};
+pub use scope::AddrScope;
 pub use smol_str::SmolStr;
+pub use stats::{InterfaceStats, StatsDelta, StatsSampler};
+pub use wait::{wait_for_addr, wait_for_up, InterfaceRef};
+#[cfg(any(linux_like, windows))]
+pub use watch::watch;
+pub use watch::{watch_addrs, Event, Watcher};
 
 // #[cfg(feature = "serde")]
 // mod serde_impl;
+mod common_flags;
+#[cfg(feature = "configure")]
+mod configure;
+mod error;
+#[cfg(feature = "ethtool")]
+mod ethtool;
+mod family;
+mod flags;
 mod gateway;
 mod idx_to_name;
 mod ifaddr;
 mod ifnet;
 mod interfaces;
+#[cfg(feature = "serde")]
+mod json;
 mod local_addrs;
+#[cfg(windows)]
+mod luid;
 mod mtu;
 mod name_to_idx;
 mod name_to_iface;
+mod neighbor;
 mod private_ip_addrs;
 mod public_ip_addrs;
 mod route;
+mod runtime;
+mod scope;
+mod stats;
 mod utils;
+mod wait;
+mod watch;
+
+#[cfg(any(
+  feature = "compat-network-interface",
+  feature = "compat-local-ip-address"
+))]
+#[cfg_attr(
+  docsrs,
+  doc(cfg(any(
+    feature = "compat-network-interface",
+    feature = "compat-local-ip-address"
+  )))
+)]
+pub mod compat;
+
+#[cfg(feature = "ffi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ffi")))]
+pub mod flat;
 
 #[cfg(linux_like)]
 #[path = "linux.rs"]
@@ -87,7 +148,10 @@ trait Address: Sized {
 impl Address for IfAddr {
   #[inline]
   fn try_from(index: u32, addr: IpAddr) -> Option<Self> {
-    Some(IfAddr::new(index, addr))
+    Some(match addr {
+      IpAddr::V4(ip) => IfAddr::V4(Ifv4Addr::new(index, ip)),
+      IpAddr::V6(ip) => IfAddr::V6(Ifv6Addr::zoned(index, ip)),
+    })
   }
 
   #[inline]
@@ -125,7 +189,7 @@ impl Address for Ifv6Addr {
   #[inline]
   fn try_from(index: u32, addr: IpAddr) -> Option<Self> {
     match addr {
-      IpAddr::V6(ip) => Some(Ifv6Addr::new(index, ip)),
+      IpAddr::V6(ip) => Some(Ifv6Addr::zoned(index, ip)),
       _ => None,
     }
   }
@@ -143,6 +207,10 @@ impl Address for Ifv6Addr {
 
 #[allow(dead_code)]
 trait Net: Sized {
+  /// Returns `None` for a wrong-family address or a prefix longer than
+  /// the family allows, so a malformed kernel record is skipped rather
+  /// than panicking. Host (`/32`, `/128`) and RFC 3021 `/31`
+  /// point-to-point prefixes are valid.
   fn try_from(index: u32, addr: IpAddr, prefix: u8) -> Option<Self>;
 
   fn try_from_with_filter<F>(index: u32, addr: IpAddr, prefix: u8, mut f: F) -> Option<Self>
@@ -159,12 +227,40 @@ trait Net: Sized {
   fn addr(&self) -> IpAddr;
 
   fn index(&self) -> u32;
+  /// Attaches the OS-reported IPv4 broadcast address; a no-op for
+  /// types that cannot carry one.
+  #[inline]
+  fn with_broadcast(self, _broadcast: Option<Ipv4Addr>) -> Self {
+    self
+  }
+  /// Attaches the scope the OS reports for the address; a no-op for
+  /// types that cannot carry one.
+  #[inline]
+  fn with_scope(self, _scope: Option<AddrScope>) -> Self {
+    self
+  }
 }
 
 impl Net for IfNet {
   #[inline]
   fn try_from(index: u32, addr: IpAddr, prefix: u8) -> Option<Self> {
-    Some(IfNet::with_prefix_len_assert(index, addr, prefix))
+    IfNet::with_prefix_len(index, addr, prefix).ok()
+  }
+
+  #[inline]
+  fn with_broadcast(self, broadcast: Option<Ipv4Addr>) -> Self {
+    match self {
+      IfNet::V4(net) => IfNet::V4(net.with_broadcast(broadcast)),
+      net => net,
+    }
+  }
+
+  #[inline]
+  fn with_scope(self, scope: Option<AddrScope>) -> Self {
+    match self {
+      IfNet::V4(net) => IfNet::V4(net.with_scope(scope)),
+      IfNet::V6(net) => IfNet::V6(net.with_scope(scope)),
+    }
   }
 
   #[inline]
@@ -182,11 +278,21 @@ impl Net for Ifv4Net {
   #[inline]
   fn try_from(index: u32, addr: IpAddr, prefix: u8) -> Option<Self> {
     match addr {
-      IpAddr::V4(ip) => Some(Ifv4Net::with_prefix_len_assert(index, ip, prefix)),
+      IpAddr::V4(ip) => Ifv4Net::with_prefix_len(index, ip, prefix).ok(),
       _ => None,
     }
   }
 
+  #[inline]
+  fn with_broadcast(self, broadcast: Option<Ipv4Addr>) -> Self {
+    Ifv4Net::with_broadcast(self, broadcast)
+  }
+
+  #[inline]
+  fn with_scope(self, scope: Option<AddrScope>) -> Self {
+    Ifv4Net::with_scope(self, scope)
+  }
+
   #[inline]
   fn addr(&self) -> IpAddr {
     self.addr().into()
@@ -202,11 +308,16 @@ impl Net for Ifv6Net {
   #[inline]
   fn try_from(index: u32, addr: IpAddr, prefix: u8) -> Option<Self> {
     match addr {
-      IpAddr::V6(ip) => Some(Ifv6Net::with_prefix_len_assert(index, ip, prefix)),
+      IpAddr::V6(ip) => Ifv6Net::with_prefix_len(index, ip, prefix).ok(),
       _ => None,
     }
   }
 
+  #[inline]
+  fn with_scope(self, scope: Option<AddrScope>) -> Self {
+    Ifv6Net::with_scope(self, scope)
+  }
+
   #[inline]
   fn addr(&self) -> IpAddr {
     self.addr().into()
@@ -259,9 +370,32 @@ where
   }
 }
 
+/// Returns the IPv4 address embedded in an IPv4-mapped
+/// (`::ffff:a.b.c.d`) or IPv4-compatible (`::a.b.c.d`) IPv6 address,
+/// or `addr` unchanged otherwise, so the classifiers judge such an
+/// address by the IPv4 address it carries. `::` and `::1` keep their
+/// IPv6 meaning.
 #[inline]
-fn local_ip_filter(addr: &IpAddr) -> bool {
+fn embedded_ipv4(addr: &IpAddr) -> IpAddr {
   match addr {
+    IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+      Some(v4) => IpAddr::V4(v4),
+      None => {
+        let bits = u128::from(*v6);
+        if bits >> 32 == 0 && bits > 1 {
+          IpAddr::V4(Ipv4Addr::from(bits as u32))
+        } else {
+          *addr
+        }
+      }
+    },
+    IpAddr::V4(_) => *addr,
+  }
+}
+
+#[inline]
+fn local_ip_filter(addr: &IpAddr) -> bool {
+  match &embedded_ipv4(addr) {
     IpAddr::V4(addr) => !(addr.is_loopback() || addr.is_link_local()),
     IpAddr::V6(addr) => !(addr.is_loopback() || Ipv6AddrExt::is_unicast_link_local(addr)),
   }
@@ -361,6 +495,25 @@ mod address_trait_tests {
     assert!(wrong.is_none());
   }
 
+  // RFC 3021 /31 links and /32 peer addresses are ordinary on Linux
+  // point-to-point setups and must survive the conversion; a prefix
+  // past the family width is dropped instead of panicking.
+  #[test]
+  fn net_point_to_point_prefixes() {
+    for prefix in [31, 32] {
+      let n = <IfNet as Net>::try_from(4, v4([10, 0, 0, 1]), prefix).unwrap();
+      assert_eq!(n.prefix_len(), prefix);
+      let n = <Ifv4Net as Net>::try_from(4, v4([10, 0, 0, 0]), prefix).unwrap();
+      assert_eq!(n.prefix_len(), prefix);
+    }
+    let n = <Ifv6Net as Net>::try_from(4, v6([0xfe; 16]), 128).unwrap();
+    assert_eq!(n.prefix_len(), 128);
+
+    assert!(<IfNet as Net>::try_from(4, v4([10, 0, 0, 1]), 33).is_none());
+    assert!(<Ifv4Net as Net>::try_from(4, v4([10, 0, 0, 1]), 33).is_none());
+    assert!(<Ifv6Net as Net>::try_from(4, v6([0xfe; 16]), 129).is_none());
+  }
+
   #[test]
   fn ipv6addr_ext_classification() {
     // Use UFCS so the calls resolve to the crate's `Ipv6AddrExt`
@@ -1,6 +1,7 @@
 use std::{
   io,
   net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  ops::ControlFlow,
 };
 
 // Only the /proc/net/igmp* parsers use xtoi2, and those are not compiled on
@@ -13,11 +14,17 @@ use smallvec_wrapper::{SmallVec, TinyVec};
 use smol_str::SmolStr;
 
 use super::{
-  IfAddr, IfNet, Ifv4Addr, Ifv4Net, Ifv6Addr, Ifv6Net, Interface, IpRoute, Ipv4Route, Ipv6Route,
-  MacAddr, Net, MAC_ADDRESS_SIZE,
+  Address, EnumerationError, Family, IfAddr, IfNet, Ifv4Addr, Ifv4Net, Ifv6Addr, Ifv6Net,
+  Interface, InterfaceKind, InterfaceStats, IpRoute, Ipv4Route, Ipv6Route, MacAddr, Neighbor,
+  NeighborState, Net, OperStatus, RuntimeHints, MAC_ADDRESS_SIZE,
 };
 
+#[cfg(feature = "configure")]
+pub(super) use configure::*;
+#[cfg(all(target_os = "linux", feature = "ethtool"))]
+pub(super) use ethtool::{driver_info, offload_features};
 pub(super) use local_addr::*;
+pub(super) use netlink::AddrWatcher;
 
 #[path = "linux/netlink.rs"]
 mod netlink;
@@ -29,7 +36,22 @@ mod local_addr;
 #[path = "linux/android.rs"]
 mod android;
 
-use netlink::{netlink_addr, netlink_interface, netlink_walk_routes};
+#[path = "linux/proc_route.rs"]
+mod proc_route;
+
+#[cfg(feature = "configure")]
+#[path = "linux/configure.rs"]
+mod configure;
+
+#[cfg(all(target_os = "linux", feature = "ethtool"))]
+#[path = "linux/ethtool.rs"]
+mod ethtool;
+
+use netlink::{
+  netlink_addr, netlink_addr_walk, netlink_interface, netlink_interface_iter,
+  netlink_interface_lossy, netlink_link_stats, netlink_primary_addr, netlink_probe_routes,
+  netlink_walk_neighbors, netlink_walk_routes, Session,
+};
 
 macro_rules! rt_generic_mod {
   ($($name:ident($rta:expr, $rtn:expr)), +$(,)?) => {
@@ -49,40 +71,40 @@ macro_rules! rt_generic_mod {
 
           use super::{
             super::{IfAddr, Ifv4Addr, Ifv6Addr},
-            netlink::rt_generic_addrs,
+            rt_generic_addrs,
           };
 
           pub(crate) fn [< $name _addrs >]() -> io::Result<SmallVec<IfAddr>> {
-            rt_generic_addrs(AddressFamily::UNSPEC, $rta, $rtn, |_| true)
+            rt_generic_addrs(AddressFamily::UNSPEC, $rta, $rtn, None, |_| true)
           }
 
           pub(crate) fn [< $name _ipv4_addrs >]() -> io::Result<SmallVec<Ifv4Addr>> {
-            rt_generic_addrs(AddressFamily::INET, $rta, $rtn, |_| true)
+            rt_generic_addrs(AddressFamily::INET, $rta, $rtn, None, |_| true)
           }
 
           pub(crate) fn [< $name _ipv6_addrs >]() -> io::Result<SmallVec<Ifv6Addr>> {
-            rt_generic_addrs(AddressFamily::INET6, $rta, $rtn, |_| true)
+            rt_generic_addrs(AddressFamily::INET6, $rta, $rtn, None, |_| true)
           }
 
           pub(crate) fn [< $name _addrs_by_filter >]<F>(f: F) -> io::Result<SmallVec<IfAddr>>
           where
             F: FnMut(&IpAddr) -> bool,
           {
-            rt_generic_addrs(AddressFamily::UNSPEC, $rta, $rtn, f)
+            rt_generic_addrs(AddressFamily::UNSPEC, $rta, $rtn, None, f)
           }
 
           pub(crate) fn [< $name _ipv4_addrs_by_filter >]<F>(f: F) -> io::Result<SmallVec<Ifv4Addr>>
           where
             F: FnMut(&Ipv4Addr) -> bool,
           {
-            rt_generic_addrs(AddressFamily::INET, $rta, $rtn, ipv4_filter_to_ip_filter(f))
+            rt_generic_addrs(AddressFamily::INET, $rta, $rtn, None, ipv4_filter_to_ip_filter(f))
           }
 
           pub(crate) fn [< $name _ipv6_addrs_by_filter >]<F>(f: F) -> io::Result<SmallVec<Ifv6Addr>>
           where
             F: FnMut(&Ipv6Addr) -> bool,
           {
-            rt_generic_addrs(AddressFamily::INET6, $rta, $rtn, ipv6_filter_to_ip_filter(f))
+            rt_generic_addrs(AddressFamily::INET6, $rta, $rtn, None, ipv6_filter_to_ip_filter(f))
           }
         }
       }
@@ -90,11 +112,51 @@ macro_rules! rt_generic_mod {
   };
 }
 
+/// Netlink route-attribute walk, over every routing table or over
+/// `table` alone. If the netlink socket is denied (`PermissionDenied`),
+/// `RTA_GATEWAY` lookups across all tables are answered from
+/// `/proc/net/route` / `/proc/net/ipv6_route` instead; see
+/// `linux/proc_route.rs`. Procfs does not say which table a route is
+/// in, so a lookup in one table has no fallback.
+fn rt_generic_addrs<A, F>(
+  family: AddressFamily,
+  rta: u16,
+  rtn: Option<u8>,
+  table: Option<u32>,
+  mut f: F,
+) -> io::Result<SmallVec<A>>
+where
+  A: Address + Eq,
+  F: FnMut(&IpAddr) -> bool,
+{
+  match netlink::rt_generic_addrs(family, rta, rtn, table, &mut f) {
+    Err(e)
+      if e.kind() == io::ErrorKind::PermissionDenied
+        && rta == linux_raw_sys::netlink::rtattr_type_t::RTA_GATEWAY as u16
+        && table.is_none() =>
+    {
+      proc_route::gateway_addrs(family, f)
+    }
+    other => other,
+  }
+}
+
 rt_generic_mod!(gateway(
   linux_raw_sys::netlink::rtattr_type_t::RTA_GATEWAY as u16,
   None
 ),);
 
+/// `gateway_addrs` over routing table `table` alone.
+pub(super) fn gateway_addrs_in(table: u32) -> io::Result<SmallVec<IfAddr>> {
+  rt_generic_addrs(
+    AddressFamily::UNSPEC,
+    linux_raw_sys::netlink::rtattr_type_t::RTA_GATEWAY as u16,
+    None,
+    Some(table),
+    |_| true,
+  )
+}
+
 #[inline]
 fn route_v4_from_raw(
   oif: u32,
@@ -149,7 +211,45 @@ fn route_v6_from_raw(
   Some(Ipv6Route::new(oif, net, gw))
 }
 
-pub(super) fn route_table_by_filter<F>(mut f: F) -> io::Result<SmallVec<IpRoute>>
+pub(super) fn route_probe() -> io::Result<()> {
+  netlink_probe_routes()
+}
+
+pub(super) fn route_table_by_filter<F>(f: F) -> io::Result<SmallVec<IpRoute>>
+where
+  F: FnMut(&IpRoute) -> bool,
+{
+  route_table_in_by_filter(None, f)
+}
+
+/// Like `route_table_by_filter`, but with `table` set walks only that
+/// routing table instead of the built-in ones.
+pub(super) fn route_table_in_by_filter<F>(table: Option<u32>, f: F) -> io::Result<SmallVec<IpRoute>>
+where
+  F: FnMut(&IpRoute) -> bool,
+{
+  session_routes(&mut Session::new()?, table, false, f)
+}
+
+/// Like `route_table_by_filter`, but keeps the TOS-specific routes the
+/// other walks drop.
+pub(super) fn route_table_with_tos_by_filter<F>(f: F) -> io::Result<SmallVec<IpRoute>>
+where
+  F: FnMut(&IpRoute) -> bool,
+{
+  session_routes(&mut Session::new()?, None, true, f)
+}
+
+/// Routes of both families. A TOS-specific route only carries packets
+/// with a matching ToS byte, so it is left out unless `with_tos` is set
+/// — the default-route and source-address lookups built on these rows
+/// assume a route applies to any traffic.
+fn session_routes<F>(
+  session: &mut Session,
+  table: Option<u32>,
+  with_tos: bool,
+  mut f: F,
+) -> io::Result<SmallVec<IpRoute>>
 where
   F: FnMut(&IpRoute) -> bool,
 {
@@ -165,42 +265,94 @@ where
   // walks per-family for the same reason. Two dumps is the right
   // tradeoff for a consistent answer.
   let mut out: SmallVec<IpRoute> = SmallVec::new();
-  netlink_walk_routes(AddressFamily::INET, |fam, oif, dst_len, dst, gw| {
-    if fam as u16 == AddressFamily::INET.as_raw() {
-      if let Some(r) = route_v4_from_raw(oif, dst_len, dst, gw).map(IpRoute::V4) {
-        if f(&r) {
-          out.push(r);
+  session.walk_routes(
+    AddressFamily::INET,
+    table,
+    |fam, oif, dst_len, dst, gw, q| {
+      if q.tos != 0 && !with_tos {
+        return;
+      }
+      if fam as u16 == AddressFamily::INET.as_raw() {
+        if let Some(r) = route_v4_from_raw(oif, dst_len, dst, gw).map(|r| {
+          IpRoute::V4(
+            r.with_qualifiers(q.tos, q.realm)
+              .with_metric(q.metric)
+              .with_route_metrics(q.metrics),
+          )
+        }) {
+          if f(&r) {
+            out.push(r);
+          }
         }
       }
-    }
-  })?;
-  netlink_walk_routes(AddressFamily::INET6, |fam, oif, dst_len, dst, gw| {
-    if fam as u16 == AddressFamily::INET6.as_raw() {
-      if let Some(r) = route_v6_from_raw(oif, dst_len, dst, gw).map(IpRoute::V6) {
-        if f(&r) {
-          out.push(r);
+    },
+  )?;
+  session.walk_routes(
+    AddressFamily::INET6,
+    table,
+    |fam, oif, dst_len, dst, gw, q| {
+      if q.tos != 0 && !with_tos {
+        return;
+      }
+      if fam as u16 == AddressFamily::INET6.as_raw() {
+        if let Some(r) = route_v6_from_raw(oif, dst_len, dst, gw).map(|r| {
+          IpRoute::V6(
+            r.with_qualifiers(q.tos, q.realm)
+              .with_metric(q.metric)
+              .with_route_metrics(q.metrics),
+          )
+        }) {
+          if f(&r) {
+            out.push(r);
+          }
         }
       }
-    }
-  })?;
+    },
+  )?;
   Ok(out)
 }
 
+/// Interfaces, addresses and routes read with back-to-back dumps over
+/// one netlink socket.
+pub(super) fn capture() -> io::Result<(TinyVec<Interface>, SmallVec<IfNet>, SmallVec<IpRoute>)> {
+  let mut session = Session::new()?;
+  let interfaces = match session.links(AddressFamily::UNSPEC, 0) {
+    Ok(links) => links
+      .collect::<Result<_, _>>()
+      .map_err(EnumerationError::into_error)?,
+    // The same `RTM_GETLINK` denial fallback as `interface_table`.
+    #[cfg(target_os = "android")]
+    Err(e) if e.kind() == io::ErrorKind::PermissionDenied => android::interface_table(0)?,
+    Err(e) => return Err(e),
+  };
+  let addrs = session.addrs(AddressFamily::UNSPEC, |_| true)?;
+  let routes = session_routes(&mut session, None, false, |_| true)?;
+  Ok((interfaces, addrs, routes))
+}
+
 pub(super) fn route_ipv4_table_by_filter<F>(mut f: F) -> io::Result<SmallVec<Ipv4Route>>
 where
   F: FnMut(&Ipv4Route) -> bool,
 {
   let mut out: SmallVec<Ipv4Route> = SmallVec::new();
-  netlink_walk_routes(AddressFamily::INET, |fam, oif, dst_len, dst, gw| {
-    if fam as u16 != AddressFamily::INET.as_raw() {
-      return;
-    }
-    if let Some(r) = route_v4_from_raw(oif, dst_len, dst, gw) {
-      if f(&r) {
-        out.push(r);
+  netlink_walk_routes(
+    AddressFamily::INET,
+    None,
+    |fam, oif, dst_len, dst, gw, q| {
+      if fam as u16 != AddressFamily::INET.as_raw() || q.tos != 0 {
+        return;
       }
-    }
-  })?;
+      if let Some(r) = route_v4_from_raw(oif, dst_len, dst, gw) {
+        let r = r
+          .with_qualifiers(q.tos, q.realm)
+          .with_metric(q.metric)
+          .with_route_metrics(q.metrics);
+        if f(&r) {
+          out.push(r);
+        }
+      }
+    },
+  )?;
   Ok(out)
 }
 
@@ -209,16 +361,24 @@ where
   F: FnMut(&Ipv6Route) -> bool,
 {
   let mut out: SmallVec<Ipv6Route> = SmallVec::new();
-  netlink_walk_routes(AddressFamily::INET6, |fam, oif, dst_len, dst, gw| {
-    if fam as u16 != AddressFamily::INET6.as_raw() {
-      return;
-    }
-    if let Some(r) = route_v6_from_raw(oif, dst_len, dst, gw) {
-      if f(&r) {
-        out.push(r);
+  netlink_walk_routes(
+    AddressFamily::INET6,
+    None,
+    |fam, oif, dst_len, dst, gw, q| {
+      if fam as u16 != AddressFamily::INET6.as_raw() || q.tos != 0 {
+        return;
       }
-    }
-  })?;
+      if let Some(r) = route_v6_from_raw(oif, dst_len, dst, gw) {
+        let r = r
+          .with_qualifiers(q.tos, q.realm)
+          .with_metric(q.metric)
+          .with_route_metrics(q.metrics);
+        if f(&r) {
+          out.push(r);
+        }
+      }
+    },
+  )?;
   Ok(out)
 }
 
@@ -231,6 +391,11 @@ impl Interface {
       name: SmolStr::default(),
       mac_addr: None,
       flags,
+      carrier: None,
+      oper_status: OperStatus::Unknown,
+      media_type: None,
+      kind: InterfaceKind::Other(0),
+      master: None,
     }
   }
 }
@@ -294,6 +459,64 @@ pub(super) fn interface_table(index: u32) -> io::Result<TinyVec<Interface>> {
   }
 }
 
+#[cfg(not(target_os = "android"))]
+pub(super) use netlink::LinkIter as InterfaceIter;
+
+#[cfg(not(target_os = "android"))]
+pub(super) fn interface_iter(index: u32) -> io::Result<InterfaceIter> {
+  netlink_interface_iter(AddressFamily::UNSPEC, index)
+}
+
+/// Interfaces from the netlink dump, or from the ioctl fallback when
+/// `RTM_GETLINK` is denied; see `interface_table`.
+#[cfg(target_os = "android")]
+pub(super) enum InterfaceIter {
+  Netlink(netlink::LinkIter),
+  Ioctl(<TinyVec<Interface> as IntoIterator>::IntoIter),
+}
+
+#[cfg(target_os = "android")]
+impl Iterator for InterfaceIter {
+  type Item = Result<Interface, EnumerationError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    match self {
+      Self::Netlink(links) => links.next(),
+      Self::Ioctl(interfaces) => interfaces.next().map(Ok),
+    }
+  }
+}
+
+#[cfg(target_os = "android")]
+pub(super) fn interface_iter(index: u32) -> io::Result<InterfaceIter> {
+  match netlink_interface_iter(AddressFamily::UNSPEC, index) {
+    Ok(links) => Ok(InterfaceIter::Netlink(links)),
+    Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+      android::interface_table(index).map(|table| InterfaceIter::Ioctl(table.into_iter()))
+    }
+    Err(e) => Err(e),
+  }
+}
+
+#[cfg(not(target_os = "android"))]
+pub(super) fn interface_table_lossy(
+  errors: &mut Vec<EnumerationError>,
+) -> io::Result<TinyVec<Interface>> {
+  netlink_interface_lossy(AddressFamily::UNSPEC, 0, errors)
+}
+
+#[cfg(target_os = "android")]
+pub(super) fn interface_table_lossy(
+  errors: &mut Vec<EnumerationError>,
+) -> io::Result<TinyVec<Interface>> {
+  // Same PermissionDenied fallback as `interface_table`; the ioctl path
+  // has no raw records to skip.
+  match netlink_interface_lossy(AddressFamily::UNSPEC, 0, errors) {
+    Err(e) if e.kind() == io::ErrorKind::PermissionDenied => android::interface_table(0),
+    other => other,
+  }
+}
+
 pub(super) fn interface_ipv4_addresses<F>(index: u32, f: F) -> io::Result<SmallVec<Ifv4Net>>
 where
   F: FnMut(&IpAddr) -> bool,
@@ -315,6 +538,260 @@ where
   netlink_addr(AddressFamily::UNSPEC, index, f)
 }
 
+pub(super) fn for_each_interface_addr<F>(mut f: F) -> io::Result<()>
+where
+  F: FnMut(&IfNet) -> ControlFlow<()>,
+{
+  netlink_addr_walk(AddressFamily::UNSPEC, 0, |_| true, |net: IfNet| f(&net))
+}
+
+pub(super) fn interface_primary_addresses() -> io::Result<SmallVec<IfNet>> {
+  netlink_primary_addr(AddressFamily::UNSPEC, 0, |_| true)
+}
+
+pub(super) fn neighbor_table<F>(family: Option<Family>, mut f: F) -> io::Result<SmallVec<Neighbor>>
+where
+  F: FnMut(&IpAddr) -> bool,
+{
+  let family = match family {
+    None => AddressFamily::UNSPEC,
+    Some(Family::V4) => AddressFamily::INET,
+    Some(Family::V6) => AddressFamily::INET6,
+  };
+  let mut out = SmallVec::new();
+  netlink_walk_neighbors(family, |index, ip, mac, state| {
+    if f(&ip) {
+      out.push(Neighbor::new(index, ip, mac, neighbor_state(state)));
+    }
+  })?;
+  Ok(out)
+}
+
+/// Maps an `ndm_state` to [`NeighborState`]. The kernel keeps exactly
+/// one `NUD_*` bit set.
+fn neighbor_state(nud: u16) -> NeighborState {
+  use linux_raw_sys::netlink::{
+    NUD_DELAY, NUD_FAILED, NUD_INCOMPLETE, NUD_NOARP, NUD_PERMANENT, NUD_PROBE, NUD_REACHABLE,
+    NUD_STALE,
+  };
+
+  match nud as u32 {
+    NUD_INCOMPLETE => NeighborState::Incomplete,
+    NUD_REACHABLE => NeighborState::Reachable,
+    NUD_STALE => NeighborState::Stale,
+    NUD_DELAY => NeighborState::Delay,
+    NUD_PROBE => NeighborState::Probe,
+    NUD_FAILED => NeighborState::Failed,
+    NUD_NOARP => NeighborState::NoArp,
+    NUD_PERMANENT => NeighborState::Permanent,
+    _ => NeighborState::Unknown,
+  }
+}
+
+const IPV6_CONF_PATH: &str = "/proc/sys/net/ipv6/conf";
+
+/// Reads `disable_ipv6` for the named interface. A kernel without the
+/// IPv6 stack (built without it, or booted with `ipv6.disable=1`) has
+/// no `ipv6/conf` tree at all, which means v6 is off everywhere.
+pub(super) fn ipv6_enabled(name: &str) -> io::Result<bool> {
+  let path = std::path::Path::new(IPV6_CONF_PATH)
+    .join(name)
+    .join("disable_ipv6");
+  match std::fs::read_to_string(&path) {
+    Ok(contents) => parse_disable_ipv6(&contents),
+    Err(e)
+      if e.kind() == io::ErrorKind::NotFound && !std::path::Path::new(IPV6_CONF_PATH).exists() =>
+    {
+      Ok(false)
+    }
+    Err(e) => Err(e),
+  }
+}
+
+fn parse_disable_ipv6(contents: &str) -> io::Result<bool> {
+  match contents.trim() {
+    "0" => Ok(true),
+    "1" => Ok(false),
+    other => Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("unexpected disable_ipv6 value: {other:?}"),
+    )),
+  }
+}
+
+pub(super) fn ipv6_hop_limit(name: &str) -> io::Result<Option<u8>> {
+  let path = std::path::Path::new(IPV6_CONF_PATH)
+    .join(name)
+    .join("hop_limit");
+  match std::fs::read_to_string(&path) {
+    Ok(contents) => parse_hop_limit(&contents).map(Some),
+    Err(e)
+      if e.kind() == io::ErrorKind::NotFound && !std::path::Path::new(IPV6_CONF_PATH).exists() =>
+    {
+      Ok(None)
+    }
+    Err(e) => Err(e),
+  }
+}
+
+fn parse_hop_limit(contents: &str) -> io::Result<u8> {
+  contents.trim().parse().map_err(|_| {
+    io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("unexpected hop_limit value: {contents:?}"),
+    )
+  })
+}
+
+/// Reads the IPv6 MTU of the named interface, which may sit below the
+/// link MTU. `None` when the kernel has no IPv6 support, or when IPv6
+/// is off on this link because its MTU is below 1280 and the kernel
+/// dropped the interface's `ipv6/conf` entry.
+pub(super) fn ipv6_mtu(name: &str) -> io::Result<Option<u32>> {
+  let path = std::path::Path::new(IPV6_CONF_PATH).join(name).join("mtu");
+  match std::fs::read_to_string(&path) {
+    Ok(contents) => parse_ipv6_mtu(&contents).map(Some),
+    Err(e)
+      if e.kind() == io::ErrorKind::NotFound
+        && (!std::path::Path::new(IPV6_CONF_PATH).exists()
+          || std::path::Path::new(SYS_CLASS_NET_PATH).join(name).exists()) =>
+    {
+      Ok(None)
+    }
+    Err(e) => Err(e),
+  }
+}
+
+fn parse_ipv6_mtu(contents: &str) -> io::Result<u32> {
+  contents.trim().parse().map_err(|_| {
+    io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("unexpected mtu value: {contents:?}"),
+    )
+  })
+}
+
+/// Reads `accept_ra` for the named interface. `1` accepts router
+/// advertisements unless the interface forwards, `2` accepts them even
+/// then; both count as accepting. Without IPv6 nothing is accepted.
+pub(super) fn accepts_ra(name: &str) -> io::Result<bool> {
+  let path = std::path::Path::new(IPV6_CONF_PATH)
+    .join(name)
+    .join("accept_ra");
+  match std::fs::read_to_string(&path) {
+    Ok(contents) => parse_accept_ra(&contents),
+    Err(e)
+      if e.kind() == io::ErrorKind::NotFound && !std::path::Path::new(IPV6_CONF_PATH).exists() =>
+    {
+      Ok(false)
+    }
+    Err(e) => Err(e),
+  }
+}
+
+fn parse_accept_ra(contents: &str) -> io::Result<bool> {
+  match contents.trim() {
+    "0" => Ok(false),
+    "1" | "2" => Ok(true),
+    other => Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("unexpected accept_ra value: {other:?}"),
+    )),
+  }
+}
+
+const SYS_CLASS_NET_PATH: &str = "/sys/class/net";
+
+/// Reads the traffic counters of interface `idx`, fresh from the
+/// kernel.
+pub(super) fn interface_stats(idx: u32) -> io::Result<InterfaceStats> {
+  netlink_link_stats(idx)
+}
+
+/// Reads the negotiated speed of the named interface from sysfs, in
+/// bits per second. The kernel fails the read with `EINVAL` or
+/// `EOPNOTSUPP` when the driver has no speed to report (loopback,
+/// most virtual devices, a link that is down) and prints `-1` when the
+/// speed is unknown; all of these mean `None`, as does a host without
+/// sysfs mounted.
+pub(super) fn link_speed(name: &str) -> io::Result<Option<u64>> {
+  let path = std::path::Path::new(SYS_CLASS_NET_PATH)
+    .join(name)
+    .join("speed");
+  match std::fs::read_to_string(&path) {
+    Ok(contents) => parse_link_speed(&contents),
+    Err(e)
+      if matches!(
+        e.raw_os_error(),
+        Some(libc::EINVAL) | Some(libc::EOPNOTSUPP)
+      ) =>
+    {
+      Ok(None)
+    }
+    Err(e)
+      if e.kind() == io::ErrorKind::NotFound
+        && !std::path::Path::new(SYS_CLASS_NET_PATH).exists() =>
+    {
+      Ok(None)
+    }
+    Err(e) => Err(e),
+  }
+}
+
+/// Parses a sysfs `speed` value, given in Mbit/s.
+fn parse_link_speed(contents: &str) -> io::Result<Option<u64>> {
+  let mbps: i64 = contents.trim().parse().map_err(|_| {
+    io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("unexpected speed value: {contents:?}"),
+    )
+  })?;
+  Ok(
+    u64::try_from(mbps)
+      .ok()
+      .filter(|&mbps| mbps != 0)
+      .map(|mbps| mbps * 1_000_000),
+  )
+}
+
+pub(super) fn runtime_hints() -> RuntimeHints {
+  RuntimeHints {
+    in_container: in_container(),
+    netlink_available: netlink::netlink_available(),
+    proc_net_available: proc_route::available(),
+  }
+}
+
+/// Marker files container runtimes drop into the root filesystem:
+/// Docker's `/.dockerenv` and Podman's `/run/.containerenv`.
+const CONTAINER_MARKERS: [&str; 2] = ["/.dockerenv", "/run/.containerenv"];
+
+fn in_container() -> bool {
+  if CONTAINER_MARKERS
+    .iter()
+    .any(|path| std::path::Path::new(path).exists())
+  {
+    return true;
+  }
+  // LXC and systemd-nspawn set `container=` in the environment of the
+  // container's init, which is usually inherited.
+  if std::env::var_os("container").is_some() {
+    return true;
+  }
+  std::fs::read_to_string("/proc/1/cgroup").is_ok_and(|contents| cgroup_in_container(&contents))
+}
+
+/// Looks for a container runtime in the cgroup paths of
+/// `/proc/1/cgroup`. Under cgroup v2 with a private cgroup namespace
+/// the only line is `0::/`, so this finds nothing there.
+fn cgroup_in_container(contents: &str) -> bool {
+  const RUNTIMES: [&str; 5] = ["docker", "kubepods", "containerd", "libpod", "lxc"];
+  contents.lines().any(|line| {
+    let path = line.splitn(3, ':').nth(2).unwrap_or_default();
+    RUNTIMES.iter().any(|runtime| path.contains(runtime))
+  })
+}
+
 const IGMP_PATH: &str = "/proc/net/igmp";
 const IGMP6_PATH: &str = "/proc/net/igmp6";
 
@@ -325,7 +802,7 @@ pub(super) fn interface_multicast_ipv4_addresses<F>(
 where
   F: FnMut(&Ipv4Addr) -> bool,
 {
-  parse_proc_net_igmp(IGMP_PATH, ifi, f)
+  missing_family_to_empty(IGMP_PATH, parse_proc_net_igmp(IGMP_PATH, ifi, f))
 }
 
 pub(super) fn interface_multicast_ipv6_addresses<F>(
@@ -335,7 +812,7 @@ pub(super) fn interface_multicast_ipv6_addresses<F>(
 where
   F: FnMut(&Ipv6Addr) -> bool,
 {
-  parse_proc_net_igmp6(IGMP6_PATH, ifi, f)
+  missing_family_to_empty(IGMP6_PATH, parse_proc_net_igmp6(IGMP6_PATH, ifi, f))
 }
 
 pub(super) fn interface_multicast_addresses<F>(ifi: u32, mut f: F) -> io::Result<SmallVec<IfAddr>>
@@ -343,23 +820,49 @@ where
   F: FnMut(&IpAddr) -> bool,
 {
   // Parse IPv4 multicast addrs
-  let ifmat4 = parse_proc_net_igmp("/proc/net/igmp", ifi, |addr| f(&(*addr).into()))?;
+  let ifmat4 = interface_multicast_ipv4_addresses(ifi, |addr| f(&(*addr).into()))?;
 
   // Parse IPv6 multicast addrs
-  let ifmat6 = parse_proc_net_igmp6("/proc/net/igmp6", ifi, |addr| f(&(*addr).into()))?;
+  let ifmat6 = interface_multicast_ipv6_addresses(ifi, |addr| f(&(*addr).into()))?;
+
+  let mut addrs: SmallVec<IfAddr> = ifmat4
+    .into_iter()
+    .map(From::from)
+    .chain(ifmat6.into_iter().map(From::from))
+    .collect();
+  // The /proc files list groups in kernel hash order and may repeat a
+  // membership; hand back one entry per (interface, group), ordered by
+  // interface and then address so repeated calls compare equal.
+  addrs.sort_by_key(|addr| (addr.index(), addr.addr()));
+  addrs.dedup();
+  Ok(addrs)
+}
 
-  Ok(
-    ifmat4
-      .into_iter()
-      .map(From::from)
-      .chain(ifmat6.into_iter().map(From::from))
-      .collect(),
-  )
+/// The kernel only creates `/proc/net/igmp6` (and `igmp`) while the
+/// matching stack runs — a kernel booted with `ipv6.disable=1` has no
+/// `igmp6`. A missing file next to its siblings therefore means the
+/// family has no groups at all. Without the directory itself (`/proc`
+/// not mounted) the answer is unknown and the error stands.
+fn missing_family_to_empty<T>(
+  path: &str,
+  result: io::Result<SmallVec<T>>,
+) -> io::Result<SmallVec<T>> {
+  match result {
+    Err(e)
+      if e.kind() == io::ErrorKind::NotFound
+        && std::path::Path::new(path)
+          .parent()
+          .is_some_and(std::path::Path::exists) =>
+    {
+      Ok(SmallVec::new())
+    }
+    result => result,
+  }
 }
 
 // Android 10+ denies apps access to /proc/net, so the parsers that read
 // /proc/net/igmp* are not compiled there. The Android stubs return
-// `Unsupported` (matching the DragonFly multicast stub in bsd_like.rs): the
+// `UnsupportedPlatform` (matching the DragonFly multicast stub in bsd_like.rs): the
 // public `interface_multicast_*` surface still exists for cross-platform
 // callers, but a real call reports the limitation instead of a misleading
 // empty result or a raw permission error.
@@ -368,10 +871,7 @@ fn parse_proc_net_igmp<F>(_path: &str, _ifi: u32, _f: F) -> std::io::Result<Smal
 where
   F: FnMut(&Ipv4Addr) -> bool,
 {
-  Err(io::Error::new(
-    io::ErrorKind::Unsupported,
-    "multicast group enumeration is unavailable on Android (/proc/net is restricted for apps)",
-  ))
+  Err(crate::Error::UnsupportedPlatform.into())
 }
 
 #[cfg(not(target_os = "android"))]
@@ -416,21 +916,19 @@ where
         Ok(res) => idx = res,
         Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
       }
-    } else if field0.len() == 8 {
-      if ifi == 0 || ifi == idx {
-        // The Linux kernel puts the IP address in /proc/net/igmp in
-        // native endianness.
-        let src = field0.as_bytes();
-        let mut b = [0u8; 4];
-        for i in (0..src.len()).step_by(2) {
-          b[i / 2] = xtoi2(&src[i..i + 2], 0).unwrap_or(0);
-        }
+    } else if field0.len() == 8 && (ifi == 0 || ifi == idx) {
+      // The Linux kernel puts the IP address in /proc/net/igmp in
+      // native endianness.
+      let src = field0.as_bytes();
+      let mut b = [0u8; 4];
+      for i in (0..src.len()).step_by(2) {
+        b[i / 2] = xtoi2(&src[i..i + 2], 0).unwrap_or(0);
+      }
 
-        b.reverse();
-        let ip = b.into();
-        if f(&ip) {
-          ifmat.push(Ifv4Addr::new(idx, ip));
-        }
+      b.reverse();
+      let ip = b.into();
+      if f(&ip) {
+        ifmat.push(Ifv4Addr::new(idx, ip));
       }
     }
   }
@@ -443,10 +941,7 @@ fn parse_proc_net_igmp6<F>(_path: &str, _ifi: u32, _f: F) -> io::Result<SmallVec
 where
   F: FnMut(&Ipv6Addr) -> bool,
 {
-  Err(io::Error::new(
-    io::ErrorKind::Unsupported,
-    "multicast group enumeration is unavailable on Android (/proc/net is restricted for apps)",
-  ))
+  Err(crate::Error::UnsupportedPlatform.into())
 }
 
 #[cfg(not(target_os = "android"))]
@@ -499,7 +994,7 @@ where
 
       let ip = data.into();
       if f(&ip) {
-        ifmat.push(Ifv6Addr::new(idx, ip));
+        ifmat.push(Ifv6Addr::zoned(idx, ip));
       }
     }
   }
@@ -511,6 +1006,24 @@ where
 mod tests {
   use super::*;
 
+  // Runtime names in any cgroup path mark a container; a host's or a
+  // namespaced cgroup v2 root does not.
+  #[test]
+  fn cgroup_container_markers() {
+    assert!(cgroup_in_container(
+      "12:pids:/docker/3f2a9c\n0::/docker/3f2a9c\n"
+    ));
+    assert!(cgroup_in_container(
+      "0::/kubepods.slice/kubepods-besteffort.slice/cri-containerd-ab12.scope\n"
+    ));
+    assert!(cgroup_in_container("0::/machine.slice/libpod-4d1e.scope\n"));
+    assert!(!cgroup_in_container("0::/\n"));
+    assert!(!cgroup_in_container("0::/init.scope\n"));
+    assert!(!cgroup_in_container(
+      "0::/user.slice/user-1000.slice/session-2.scope\n"
+    ));
+  }
+
   // `route_v4_from_raw` / `route_v6_from_raw` cover every branch of
   // the family / length / gateway validation matrix. They live on
   // the hot path between the netlink walker and `IpRoute`, so any
@@ -588,4 +1101,141 @@ mod tests {
     let r = route_v6_from_raw(1, 32, dst, None).unwrap();
     assert!(r.gateway().is_none());
   }
+
+  // The sysctl file holds a single digit and a newline; anything else
+  // must be rejected rather than guessed at.
+  #[test]
+  fn parse_disable_ipv6_values() {
+    assert!(parse_disable_ipv6("0\n").unwrap());
+    assert!(!parse_disable_ipv6("1\n").unwrap());
+    assert_eq!(
+      parse_disable_ipv6("yes").unwrap_err().kind(),
+      io::ErrorKind::InvalidData
+    );
+  }
+
+  // `hop_limit` is a decimal 1..=255; out-of-range or junk is rejected.
+  #[test]
+  fn parse_hop_limit_values() {
+    assert_eq!(parse_hop_limit("64\n").unwrap(), 64);
+    assert_eq!(parse_hop_limit("255").unwrap(), 255);
+    assert!(parse_hop_limit("256\n").is_err());
+    assert!(parse_hop_limit("").is_err());
+  }
+
+  // Loopback's default hop limit is whatever the sysctl says, which is
+  // never zero.
+  #[test]
+  fn loopback_hop_limit() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    if let Some(hop_limit) = ipv6_hop_limit(lo.name()).unwrap() {
+      assert!(hop_limit >= 1, "{hop_limit}");
+    }
+  }
+
+  // Loopback's IPv6 MTU, when it has one, is at least the IPv6
+  // minimum and never above the link MTU.
+  #[test]
+  fn loopback_ipv6_mtu() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    if let Some(mtu) = lo.ipv6_mtu().unwrap() {
+      assert!((1280..=lo.mtu()).contains(&mtu), "{mtu}");
+    }
+    assert_eq!(lo.ipv4_mtu().unwrap(), Some(lo.mtu()));
+  }
+
+  // `accept_ra` is 0, 1 or 2; anything else is rejected.
+  #[test]
+  fn parse_accept_ra_values() {
+    assert!(!parse_accept_ra("0\n").unwrap());
+    assert!(parse_accept_ra("1\n").unwrap());
+    assert!(parse_accept_ra("2").unwrap());
+    assert_eq!(
+      parse_accept_ra("3").unwrap_err().kind(),
+      io::ErrorKind::InvalidData
+    );
+  }
+
+  // Reading loopback's setting must succeed whatever its value.
+  #[test]
+  fn loopback_accepts_ra() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    lo.accepts_ra().unwrap();
+  }
+
+  // `speed` is in Mbit/s; `-1` (`SPEED_UNKNOWN`) and zero mean no speed.
+  #[test]
+  fn parse_link_speed_values() {
+    assert_eq!(parse_link_speed("1000\n").unwrap(), Some(1_000_000_000));
+    assert_eq!(parse_link_speed("100000").unwrap(), Some(100_000_000_000));
+    assert_eq!(parse_link_speed("-1\n").unwrap(), None);
+    assert_eq!(parse_link_speed("0").unwrap(), None);
+    assert_eq!(
+      parse_link_speed("fast").unwrap_err().kind(),
+      io::ErrorKind::InvalidData
+    );
+  }
+
+  // A stack that is not running has no `/proc/net` file: that is an
+  // empty answer. A missing `/proc/net` is not.
+  #[cfg(not(target_os = "android"))]
+  #[test]
+  fn missing_proc_net_file_is_empty_family() {
+    let read = |path| parse_proc_net_igmp6(path, 0, |_| true);
+    let missing = "/proc/net/getifs-no-such-file";
+    assert!(missing_family_to_empty(missing, read(missing))
+      .unwrap()
+      .is_empty());
+    let unmounted = "/getifs-no-such-dir/igmp6";
+    assert_eq!(
+      missing_family_to_empty(unmounted, read(unmounted))
+        .unwrap_err()
+        .kind(),
+      io::ErrorKind::NotFound
+    );
+  }
+
+  // The loopback driver reports no speed; sysfs fails the read.
+  #[test]
+  fn loopback_has_no_link_speed() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    assert_eq!(lo.link_speed().unwrap(), None);
+  }
+
+  // A device backed by hardware has a driver bound to it; loopback,
+  // which has none, is refused.
+  #[cfg(all(target_os = "linux", feature = "ethtool"))]
+  #[test]
+  fn physical_interface_driver_info() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    assert!(lo.driver_info().is_err());
+
+    let physical = crate::interfaces().unwrap().into_iter().find(|ifi| {
+      std::path::Path::new(SYS_CLASS_NET_PATH)
+        .join(ifi.name())
+        .join("device")
+        .exists()
+    });
+    let Some(physical) = physical else {
+      println!("skipping test; no physical interface");
+      return;
+    };
+    let info = physical.driver_info().unwrap();
+    assert!(!info.driver().is_empty(), "{}: {info:?}", physical.name());
+  }
+
+  // Every netdev answers the generic feature requests, loopback
+  // included, and the names decode to the ones `ethtool -k` prints.
+  #[cfg(all(target_os = "linux", feature = "ethtool"))]
+  #[test]
+  fn loopback_offload_features() {
+    let lo = crate::loopback_interface().unwrap().unwrap();
+    let features = lo.offload_features().unwrap();
+    assert!(!features.as_slice().is_empty());
+    assert!(
+      features.get("rx-gro").is_some(),
+      "{:?}",
+      features.as_slice()
+    );
+  }
 }
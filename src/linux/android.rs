@@ -49,7 +49,7 @@ use smallvec_wrapper::TinyVec;
 use smol_str::SmolStr;
 
 use super::{netlink::netlink_addr, Flags};
-use crate::{IfNet, Interface};
+use crate::{IfNet, Interface, InterfaceKind, OperStatus};
 
 const IF_NAMESIZE: usize = 16;
 
@@ -176,6 +176,21 @@ fn build_interface(sock: BorrowedFd<'_>, index: u32) -> io::Result<Option<Interf
     // not attempt SIOCGIFHWADDR.
     mac_addr: None,
     flags,
+    // No ioctl exposes the carrier bit; `IFLA_CARRIER` is only
+    // available through the denied `RTM_GETLINK` dump.
+    carrier: None,
+    // Nor the operational state, which is `IFLA_OPERSTATE`.
+    oper_status: OperStatus::Unknown,
+    // `SIOCGIFHWADDR` would give the `ARPHRD_*` type, but it is not
+    // issued (see above); loopback is the one kind the flags identify.
+    media_type: flags.contains(Flags::LOOPBACK).then_some("loopback"),
+    kind: if flags.contains(Flags::LOOPBACK) {
+      InterfaceKind::Loopback
+    } else {
+      InterfaceKind::Other(0)
+    },
+    // `IFLA_MASTER` is likewise only carried by `RTM_GETLINK`.
+    master: None,
   }))
 }
 
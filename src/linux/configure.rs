@@ -0,0 +1,98 @@
+//! Interface reconfiguration. Link settings go through the `SIOCSIF*`
+//! ioctls, issued on an `AF_INET` datagram socket like the `SIOCGIF*`
+//! reads in `linux/android.rs`; addresses and routes through netlink.
+
+use std::{io, net::IpAddr};
+
+use ipnet::IpNet;
+
+use rustix::{
+  fd::AsFd,
+  ioctl::{self, Opcode, Setter, Updater},
+  net::{socket, AddressFamily, SocketType},
+};
+
+const IF_NAMESIZE: usize = 16;
+
+// include/uapi/linux/sockios.h
+const SIOCGIFFLAGS: Opcode = 0x8913;
+const SIOCSIFFLAGS: Opcode = 0x8914;
+const SIOCSIFMTU: Opcode = 0x8922;
+
+/// `struct ifreq` with the `ifr_ifru` union kept as raw bytes; see
+/// `linux/android.rs` for the same layout.
+#[repr(C)]
+struct Ifreq {
+  ifr_name: [u8; IF_NAMESIZE],
+  ifr_ifru: [u8; 24],
+}
+
+impl Ifreq {
+  /// An `ifreq` naming the interface with `index`. The name is copied
+  /// as raw bytes, so non-UTF8 names work too.
+  fn for_index(index: u32) -> io::Result<Self> {
+    let mut ifr = Ifreq {
+      ifr_name: [0; IF_NAMESIZE],
+      ifr_ifru: [0; 24],
+    };
+    // SAFETY: `ifr_name` has room for the `IF_NAMESIZE` bytes
+    // `if_indextoname` writes at most.
+    if unsafe { libc::if_indextoname(index, ifr.ifr_name.as_mut_ptr().cast()) }.is_null() {
+      return Err(io::Error::last_os_error());
+    }
+    Ok(ifr)
+  }
+}
+
+pub(crate) fn set_mtu(index: u32, mtu: u32) -> io::Result<()> {
+  let mut ifr = Ifreq::for_index(index)?;
+  // `ifr_mtu` is the `int` at the front of the union.
+  ifr.ifr_ifru[..4].copy_from_slice(&(mtu as i32).to_ne_bytes());
+
+  let sock = socket(AddressFamily::INET, SocketType::DGRAM, None)?;
+  // SAFETY: `Ifreq` matches the kernel's `struct ifreq` layout, which
+  // is what SIOCSIFMTU reads.
+  unsafe { ioctl::ioctl(sock.as_fd(), Setter::<SIOCSIFMTU, Ifreq>::new(ifr)) }?;
+  Ok(())
+}
+
+pub(crate) fn set_interface_up(index: u32, up: bool) -> io::Result<()> {
+  let mut ifr = Ifreq::for_index(index)?;
+  let sock = socket(AddressFamily::INET, SocketType::DGRAM, None)?;
+  // SAFETY: as in `set_mtu`; SIOCGIFFLAGS fills in `ifr_flags`.
+  unsafe { ioctl::ioctl(sock.as_fd(), Updater::<SIOCGIFFLAGS, Ifreq>::new(&mut ifr)) }?;
+
+  // `ifr_flags` is the `short` at the front of the union.
+  let mut flags = i16::from_ne_bytes([ifr.ifr_ifru[0], ifr.ifr_ifru[1]]);
+  if up {
+    flags |= libc::IFF_UP as i16;
+  } else {
+    flags &= !(libc::IFF_UP as i16);
+  }
+  ifr.ifr_ifru[..2].copy_from_slice(&flags.to_ne_bytes());
+
+  // SAFETY: as above.
+  unsafe { ioctl::ioctl(sock.as_fd(), Setter::<SIOCSIFFLAGS, Ifreq>::new(ifr)) }?;
+  Ok(())
+}
+
+pub(crate) fn add_addr(index: u32, net: IpNet) -> io::Result<()> {
+  super::netlink::netlink_set_addr(true, index, net)
+}
+
+pub(crate) fn del_addr(index: u32, net: IpNet) -> io::Result<()> {
+  super::netlink::netlink_set_addr(false, index, net)
+}
+
+pub(crate) fn add_route(
+  dest: IpNet,
+  gateway: Option<IpAddr>,
+  ifindex: u32,
+  metric: u32,
+) -> io::Result<()> {
+  super::netlink::netlink_add_route(dest, gateway, ifindex, metric)
+}
+
+pub(crate) fn del_route(dest: IpNet) -> io::Result<()> {
+  super::netlink::netlink_del_route(dest)
+}
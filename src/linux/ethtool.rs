@@ -0,0 +1,204 @@
+//! Driver details and generic offload features via the `SIOCETHTOOL`
+//! ioctl.
+//!
+//! The feature bit positions follow the kernel's internal
+//! `NETIF_F_*_BIT` enum, which changes between releases, so the names
+//! are read from the `ETH_SS_FEATURES` string set alongside the bits
+//! rather than hardcoded.
+
+use std::{io, mem};
+
+use rustix::{
+  fd::{AsFd, BorrowedFd},
+  ioctl::{self, Opcode, Updater},
+  net::{socket, AddressFamily, SocketType},
+};
+use smol_str::SmolStr;
+
+use crate::{DriverInfo, OffloadFeature, OffloadFeatures};
+
+const IF_NAMESIZE: usize = 16;
+
+// Stable Linux UAPI values (include/uapi/linux/sockios.h and
+// include/uapi/linux/ethtool.h); linux-raw-sys does not carry the
+// ethtool header.
+const SIOCETHTOOL: Opcode = 0x8946;
+const ETHTOOL_GDRVINFO: u32 = 0x03;
+const ETHTOOL_GSTRINGS: u32 = 0x1b;
+const ETHTOOL_GSSET_INFO: u32 = 0x37;
+const ETHTOOL_GFEATURES: u32 = 0x3a;
+const ETH_SS_FEATURES: u32 = 4;
+const ETH_GSTRING_LEN: usize = 32;
+
+/// `struct ifreq` with the `ifr_data` member of the union selected;
+/// the trailing padding rounds the union up to its full size.
+#[repr(C)]
+struct Ifreq {
+  ifr_name: [u8; IF_NAMESIZE],
+  ifr_data: *mut u8,
+  _pad: [u8; 24 - mem::size_of::<*mut u8>()],
+}
+
+/// `struct ethtool_sset_info` asking about a single string set, so
+/// `data` has room for exactly one count.
+#[repr(C)]
+struct SsetInfo {
+  cmd: u32,
+  reserved: u32,
+  sset_mask: u64,
+  data: u32,
+}
+
+/// `struct ethtool_drvinfo`; only the strings up to `bus_info` are
+/// read, the rest is kept for the kernel to fill.
+#[repr(C)]
+struct DrvInfo {
+  cmd: u32,
+  driver: [u8; 32],
+  version: [u8; 32],
+  fw_version: [u8; 32],
+  bus_info: [u8; 32],
+  _rest: [u8; 32 + 12 + 5 * 4],
+}
+
+/// Size of the `cmd`, `string_set` and `len` header of
+/// `struct ethtool_gstrings`.
+const GSTRINGS_HEADER: usize = 12;
+
+/// Reads the driver details of the interface called `name`. Devices
+/// without a driver to name, such as loopback, fail with
+/// `EOPNOTSUPP`.
+pub(crate) fn driver_info(name: &str) -> io::Result<DriverInfo> {
+  let sock = socket(AddressFamily::INET, SocketType::DGRAM, None)?;
+  let mut info = DrvInfo {
+    cmd: ETHTOOL_GDRVINFO,
+    driver: [0; 32],
+    version: [0; 32],
+    fw_version: [0; 32],
+    bus_info: [0; 32],
+    _rest: [0; 32 + 12 + 5 * 4],
+  };
+  ethtool(sock.as_fd(), name, (&mut info as *mut DrvInfo).cast())?;
+  Ok(DriverInfo {
+    driver: c_str(&info.driver),
+    version: c_str(&info.version),
+    bus_info: c_str(&info.bus_info),
+  })
+}
+
+/// Reads the generic features of the interface called `name`.
+pub(crate) fn offload_features(name: &str) -> io::Result<OffloadFeatures> {
+  // Datagram socket used purely as an ioctl handle.
+  let sock = socket(AddressFamily::INET, SocketType::DGRAM, None)?;
+  let sock = sock.as_fd();
+
+  let mut info = SsetInfo {
+    cmd: ETHTOOL_GSSET_INFO,
+    reserved: 0,
+    sset_mask: 1 << ETH_SS_FEATURES,
+    data: 0,
+  };
+  ethtool(sock, name, (&mut info as *mut SsetInfo).cast())?;
+  // The kernel clears the bit of a set it does not know.
+  if info.sset_mask & (1 << ETH_SS_FEATURES) == 0 {
+    return Ok(OffloadFeatures::default());
+  }
+  let count = info.data as usize;
+
+  let mut strings = vec![0u8; GSTRINGS_HEADER + count * ETH_GSTRING_LEN];
+  strings[..4].copy_from_slice(&ETHTOOL_GSTRINGS.to_ne_bytes());
+  strings[4..8].copy_from_slice(&ETH_SS_FEATURES.to_ne_bytes());
+  strings[8..12].copy_from_slice(&(count as u32).to_ne_bytes());
+  ethtool(sock, name, strings.as_mut_ptr())?;
+
+  // `struct ethtool_gfeatures` followed by one
+  // `{ available, requested, active, never_changed }` block per 32
+  // features.
+  let blocks = count.div_ceil(32);
+  let mut features = vec![0u32; 2 + blocks * 4];
+  features[0] = ETHTOOL_GFEATURES;
+  features[1] = blocks as u32;
+  ethtool(sock, name, features.as_mut_ptr().cast())?;
+
+  Ok(decode(&strings[GSTRINGS_HEADER..], &features[2..], count))
+}
+
+/// Issues one `SIOCETHTOOL` request for `name` with `data` pointing
+/// at the command buffer, which the kernel reads and fills in place.
+fn ethtool(sock: BorrowedFd<'_>, name: &str, data: *mut u8) -> io::Result<()> {
+  let mut ifr = Ifreq {
+    ifr_name: [0; IF_NAMESIZE],
+    ifr_data: data,
+    _pad: [0; 24 - mem::size_of::<*mut u8>()],
+  };
+  let bytes = name.as_bytes();
+  if bytes.len() >= IF_NAMESIZE {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      "interface name too long",
+    ));
+  }
+  ifr.ifr_name[..bytes.len()].copy_from_slice(bytes);
+  unsafe { ioctl::ioctl(sock, Updater::<SIOCETHTOOL, Ifreq>::new(&mut ifr)) }.map_err(Into::into)
+}
+
+/// Pairs the `ETH_SS_FEATURES` names with the bits of the
+/// `ETHTOOL_GFEATURES` blocks.
+fn decode(names: &[u8], blocks: &[u32], count: usize) -> OffloadFeatures {
+  let features = (0..count)
+    .map(|i| {
+      let name = names
+        .get(i * ETH_GSTRING_LEN..(i + 1) * ETH_GSTRING_LEN)
+        .map(c_str)
+        .unwrap_or_default();
+      let block = &blocks[(i / 32) * 4..];
+      let bit = 1u32 << (i % 32);
+      OffloadFeature {
+        name,
+        available: block[0] & bit != 0,
+        requested: block[1] & bit != 0,
+        active: block[2] & bit != 0,
+        fixed: block[3] & bit != 0,
+      }
+    })
+    .collect();
+  OffloadFeatures { features }
+}
+
+/// Decodes a NUL-padded fixed-size string field.
+fn c_str(raw: &[u8]) -> SmolStr {
+  let len = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+  SmolStr::new(String::from_utf8_lossy(&raw[..len]))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn names(names: &[&str]) -> Vec<u8> {
+    let mut out = vec![0u8; names.len() * ETH_GSTRING_LEN];
+    for (i, name) in names.iter().enumerate() {
+      out[i * ETH_GSTRING_LEN..][..name.len()].copy_from_slice(name.as_bytes());
+    }
+    out
+  }
+
+  // Bit `i` of each block word belongs to the `i`-th name, and the
+  // second block starts again at bit 0.
+  #[test]
+  fn decode_pairs_bits_with_names() {
+    let mut list = vec!["unused"; 33];
+    list[1] = "rx-gro";
+    list[32] = "tx-tcp-segmentation";
+    let blocks = [0b10, 0, 0b10, 0, 0, 0, 1, 1];
+    let features = decode(&names(&list), &blocks, list.len());
+
+    assert_eq!(features.as_slice().len(), 33);
+    let gro = features.get("rx-gro").unwrap();
+    assert!(gro.available() && gro.active() && !gro.requested() && !gro.fixed());
+    let tso = features.get("tx-tcp-segmentation").unwrap();
+    assert!(tso.active() && tso.fixed() && !tso.available());
+    assert!(features.gro() && features.tso());
+    assert!(!features.gso() && !features.tx_checksum());
+  }
+}
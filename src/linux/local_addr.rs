@@ -7,17 +7,73 @@ use rustix::net::AddressFamily;
 use smallvec_wrapper::SmallVec;
 
 use crate::{
-  ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter, local_ip_filter, IfNet, Ifv4Net, Ifv6Net,
+  ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter, local_ip_filter, Family, IfNet, Ifv4Net,
+  Ifv6Net, Net,
 };
 
-use super::netlink::{netlink_addr, netlink_best_local_addrs, netlink_best_local_addrs_into};
+use super::{
+  netlink::{
+    netlink_addr, netlink_best_default_oifs, netlink_best_local_addrs_into, netlink_bindable_addr,
+    netlink_route_get, netlink_source_addr,
+  },
+  proc_route,
+};
+
+/// `netlink_best_local_addrs_into`, falling back to procfs when the
+/// netlink socket is denied (see `linux/proc_route.rs`). Returns
+/// whether a default route was found.
+fn best_local_addrs_into<N>(family: AddressFamily, out: &mut SmallVec<N>) -> io::Result<bool>
+where
+  N: Net,
+{
+  let len = out.len();
+  match netlink_best_local_addrs_into(family, None, out) {
+    Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+      out.truncate(len);
+      proc_route::best_local_addrs_into(family, out)
+    }
+    other => other,
+  }
+}
+
+/// The first interface behind the best IPv4 default route, else the
+/// best IPv6 one. Same netlink-then-procfs fallback as
+/// `best_local_addrs_into`.
+pub(crate) fn default_route_index() -> io::Result<Option<u32>> {
+  for family in [AddressFamily::INET, AddressFamily::INET6] {
+    let indices = match netlink_best_default_oifs(family, None) {
+      Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+        proc_route::best_default_indices(family)
+      }
+      other => other,
+    }?;
+    if let Some(&index) = indices.first() {
+      return Ok(Some(index));
+    }
+  }
+  Ok(None)
+}
 
 pub(crate) fn best_local_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
-  netlink_best_local_addrs(AddressFamily::INET)
+  let mut out = SmallVec::new();
+  best_local_addrs_into(AddressFamily::INET, &mut out)?;
+  Ok(out)
 }
 
 pub(crate) fn best_local_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
-  netlink_best_local_addrs(AddressFamily::INET6)
+  let mut out = SmallVec::new();
+  best_local_addrs_into(AddressFamily::INET6, &mut out)?;
+  Ok(out)
+}
+
+pub(crate) fn best_local_ipv4_addrs_opt() -> io::Result<Option<SmallVec<Ifv4Net>>> {
+  let mut out = SmallVec::new();
+  Ok(best_local_addrs_into(AddressFamily::INET, &mut out)?.then_some(out))
+}
+
+pub(crate) fn best_local_ipv6_addrs_opt() -> io::Result<Option<SmallVec<Ifv6Net>>> {
+  let mut out = SmallVec::new();
+  Ok(best_local_addrs_into(AddressFamily::INET6, &mut out)?.then_some(out))
 }
 
 pub(crate) fn best_local_addrs() -> io::Result<SmallVec<IfNet>> {
@@ -35,8 +91,18 @@ pub(crate) fn best_local_addrs() -> io::Result<SmallVec<IfNet>> {
   // `Net::try_from` accepts both — so one shared buffer is correct
   // and avoids the per-family intermediate allocations.
   let mut out: SmallVec<IfNet> = SmallVec::new();
-  netlink_best_local_addrs_into(AddressFamily::INET, &mut out)?;
-  netlink_best_local_addrs_into(AddressFamily::INET6, &mut out)?;
+  best_local_addrs_into(AddressFamily::INET, &mut out)?;
+  best_local_addrs_into(AddressFamily::INET6, &mut out)?;
+  Ok(out)
+}
+
+/// `best_local_addrs` with the default routes looked up in `table`
+/// alone. Procfs does not say which table a route is in, so there is
+/// no fallback when the netlink socket is denied.
+pub(crate) fn best_local_addrs_in(table: u32) -> io::Result<SmallVec<IfNet>> {
+  let mut out: SmallVec<IfNet> = SmallVec::new();
+  netlink_best_local_addrs_into(AddressFamily::INET, Some(table), &mut out)?;
+  netlink_best_local_addrs_into(AddressFamily::INET6, Some(table), &mut out)?;
   Ok(out)
 }
 
@@ -80,3 +146,29 @@ where
     f(addr) && local_ip_filter(addr)
   })
 }
+
+/// The output interface and preferred source address of the route the
+/// kernel would pick for `dest`.
+pub(crate) fn route_source(dest: IpAddr) -> io::Result<Option<(u32, Option<IpAddr>)>> {
+  netlink_route_get(dest)
+}
+
+/// The address of `family` on interface `index` to use as source when
+/// no route names one; see `crate::scope::pick_source`.
+pub(crate) fn interface_source_addr(index: u32, family: Family) -> io::Result<Option<IfNet>> {
+  let family = match family {
+    Family::V4 => AddressFamily::INET,
+    Family::V6 => AddressFamily::INET6,
+  };
+  netlink_source_addr(family, index)
+}
+
+/// Every address of `family` except IPv6 ones the kernel has not
+/// finished DAD on, found duplicated, or deprecated.
+pub(crate) fn bindable_addrs(family: Family) -> io::Result<SmallVec<IfNet>> {
+  let family = match family {
+    Family::V4 => AddressFamily::INET,
+    Family::V6 => AddressFamily::INET6,
+  };
+  netlink_bindable_addr(family, 0, |_| true)
+}
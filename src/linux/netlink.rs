@@ -3,14 +3,27 @@ use linux_raw_sys::{
   netlink::{self, NLM_F_DUMP, NLM_F_DUMP_INTR, NLM_F_REQUEST},
 };
 use rustix::net::{
-  getsockname, netlink::SocketAddrNetlink, recvfrom, sendto, socket, AddressFamily, RecvFlags,
-  SendFlags, SocketType,
+  bind, getsockname, netlink::SocketAddrNetlink, recv, recvfrom, sendto, socket, AddressFamily,
+  RecvFlags, SendFlags, SocketAddrAny, SocketType,
 };
 
 use smallvec_wrapper::{SmallVec, TinyVec};
-use std::{collections::HashSet, io, mem, net::IpAddr, os::fd::OwnedFd};
+use std::{
+  cell::RefCell,
+  collections::{HashSet, VecDeque},
+  io, mem,
+  net::{IpAddr, Ipv4Addr},
+  ops::ControlFlow,
+  os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd},
+  time::{Duration, Instant, SystemTime},
+};
 
-use crate::local_ip_filter;
+use crate::{
+  local_ip_filter,
+  scope::{pick_source, SourceFlags},
+  AddrScope, EnumerationError, Event, IfNet, InterfaceKind, InterfaceStats, OperStatus,
+  RouteMetrics,
+};
 
 use super::{super::Address, Flags, Interface, MacAddr, Net, MAC_ADDRESS_SIZE};
 
@@ -19,12 +32,22 @@ const NLMSG_ALIGNTO: u32 = netlink::NLMSG_ALIGNTO;
 const NLMSG_DONE: u32 = netlink::NLMSG_DONE;
 const NLMSG_ERROR: u32 = netlink::NLMSG_ERROR;
 
+// Strict header checking for dump requests (Linux 4.20+).
+const SOL_NETLINK: libc::c_int = linux_raw_sys::net::SOL_NETLINK as libc::c_int;
+const NETLINK_GET_STRICT_CHK: libc::c_int = netlink::NETLINK_GET_STRICT_CHK as libc::c_int;
+
 const RTM_GETLINK: u32 = netlink::RTM_GETLINK as u32;
 const RTM_GETADDR: u32 = netlink::RTM_GETADDR as u32;
 const RTM_GETROUTE: u32 = netlink::RTM_GETROUTE as u32;
 const RTM_NEWLINK: u32 = netlink::RTM_NEWLINK as u32;
+const RTM_DELLINK: u32 = netlink::RTM_DELLINK as u32;
 const RTM_NEWADDR: u32 = netlink::RTM_NEWADDR as u32;
+const RTM_DELADDR: u32 = netlink::RTM_DELADDR as u32;
 const RTM_NEWROUTE: u32 = netlink::RTM_NEWROUTE as u32;
+#[cfg(feature = "configure")]
+const RTM_DELROUTE: u32 = netlink::RTM_DELROUTE as u32;
+const RTM_GETNEIGH: u32 = netlink::RTM_GETNEIGH as u32;
+const RTM_NEWNEIGH: u32 = netlink::RTM_NEWNEIGH as u32;
 // Nexthop subsystem (Linux 5.3+). Used to resolve RTA_NH_ID on route
 // entries that reference an `ip nexthop`-managed indirection.
 const RTM_GETNEXTHOP: u32 = netlink::RTM_GETNEXTHOP as u32;
@@ -44,6 +67,7 @@ const RTA_OIF: u16 = netlink::rtattr_type_t::RTA_OIF as u16;
 const RTA_PRIORITY: u16 = netlink::rtattr_type_t::RTA_PRIORITY as u16;
 const RTA_MULTIPATH: u16 = netlink::rtattr_type_t::RTA_MULTIPATH as u16;
 const RTA_SRC: u16 = netlink::rtattr_type_t::RTA_SRC as u16;
+const RTA_PREFSRC: u16 = netlink::rtattr_type_t::RTA_PREFSRC as u16;
 // RTA_TABLE carries the full 32-bit table id when it doesn't fit in the
 // 8-bit `rtm_table` field (table > 255). Without parsing it we'd treat
 // custom policy tables as if they were the main table.
@@ -74,6 +98,18 @@ const RTA_VIA: u16 = netlink::rtattr_type_t::RTA_VIA as u16;
 // attribute; absence falls back to MEDIUM, the documented default,
 // so v4 selection is unchanged.
 const RTA_PREF: u16 = netlink::rtattr_type_t::RTA_PREF as u16;
+// `RTA_FLOW` carries the routing realms (`ip route ... realm`): the
+// destination realm in the low 16 bits, the source realm in the high
+// 16. It may appear on the route and, for ECMP, on each nexthop.
+const RTA_FLOW: u16 = netlink::rtattr_type_t::RTA_FLOW as u16;
+// `RTA_METRICS` nests one `rtattr` per `RTAX_*` metric set on the route;
+// `RTA_CACHEINFO` carries a `struct rta_cacheinfo` whose `rta_expires`
+// is the route's remaining lifetime in `USER_HZ` ticks.
+const RTA_METRICS: u16 = netlink::rtattr_type_t::RTA_METRICS as u16;
+const RTA_CACHEINFO: u16 = netlink::rtattr_type_t::RTA_CACHEINFO as u16;
+const RTAX_MTU: u16 = netlink::RTAX_MTU as u16;
+const RTAX_RTT: u16 = netlink::RTAX_RTT as u16;
+const RTAX_HOPLIMIT: u16 = netlink::RTAX_HOPLIMIT as u16;
 
 // `struct rtnexthop` flag bits from <linux/rtnetlink.h>. Nexthops with
 // any of these set are not currently usable, so the multipath walker
@@ -86,6 +122,17 @@ const RTNH_F_UNRESOLVED: u8 = 32;
 const RTN_UNICAST: u8 = 1;
 const RTN_LOCAL: u8 = 2;
 
+// rtm_protocol and rtm_scope values from <linux/rtnetlink.h>, for the
+// routes `add_route` installs.
+#[cfg(feature = "configure")]
+const RTPROT_STATIC: u8 = 4;
+#[cfg(feature = "configure")]
+const RT_SCOPE_UNIVERSE: u8 = 0;
+#[cfg(feature = "configure")]
+const RT_SCOPE_LINK: u8 = 253;
+#[cfg(feature = "configure")]
+const RT_SCOPE_NOWHERE: u8 = 255;
+
 const RT_TABLE_MAIN: u16 = netlink::rt_class_t::RT_TABLE_MAIN as u16;
 // `route_table` only emits routes from the kernel's standard RPDB
 // tables — `local` (255), `main` (254), and `default` (253). These
@@ -121,10 +168,9 @@ const RT_TABLE_DEFAULT: u32 = netlink::rt_class_t::RT_TABLE_DEFAULT as u32;
 /// holds broadcast / address-owning routes, not transit defaults, so
 /// `dst_len == 0` candidates never come from there.
 ///
-/// Any other table (`local` / `main` / `default` are the only ones
-/// upstream code admits — see the table allow-list at the
-/// `route_table` walker filter) returns `u8::MAX`, which is
-/// unreachable in normal flow but keeps the function total.
+/// Any other table returns `u8::MAX`. Only a walk of one named table
+/// (see [`table_admitted`]) sees such routes, and there they all rank
+/// the same.
 #[inline]
 fn table_rank_for(table_id: u32) -> u8 {
   if table_id == RT_TABLE_LOCAL {
@@ -138,6 +184,24 @@ fn table_rank_for(table_id: u32) -> u8 {
   }
 }
 
+/// Whether a route from `table_id` belongs in a walk of `table`, or,
+/// when no table is named, in a walk of the three standard RPDB tables
+/// the default rule chain consults: `local` (255), `main` (254) and
+/// `default` (253). Together they describe what the kernel would do
+/// for any outbound packet on a host without custom `ip rule` policy.
+/// Anything outside that set is a custom policy table selected by
+/// `ip rule` with fwmark / iif / uid / etc., whose constraints aren't
+/// representable in `IpRoute`.
+#[inline]
+fn table_admitted(table_id: u32, table: Option<u32>) -> bool {
+  match table {
+    Some(table) => table_id == table,
+    None => {
+      table_id == RT_TABLE_MAIN as u32 || table_id == RT_TABLE_LOCAL || table_id == RT_TABLE_DEFAULT
+    }
+  }
+}
+
 /// RFC 4191 router-preference rank for IPv6 default routes, lex-ready
 /// (smaller wins). The wire values from
 /// `include/uapi/linux/icmpv6.h` are non-monotonic
@@ -172,10 +236,92 @@ fn pref_rank_for(pref: u8) -> u8 {
 
 const IFA_LOCAL: u32 = netlink::IFA_LOCAL as u32;
 const IFA_ADDRESS: u32 = netlink::IFA_ADDRESS as u32;
+const IFA_BROADCAST: u32 = netlink::IFA_BROADCAST as u32;
+const IFA_FLAGS: u32 = netlink::IFA_FLAGS as u32;
+
+/// IPv6 address states in which `bind()` fails with `EADDRNOTAVAIL`
+/// (tentative, DAD failed) or new sockets should no longer use the
+/// address (deprecated).
+const IFA_F_NOT_BINDABLE: u32 =
+  netlink::IFA_F_TENTATIVE | netlink::IFA_F_DADFAILED | netlink::IFA_F_DEPRECATED;
+
+/// Which addresses of an `RTM_GETADDR` dump [`decode_addr_message`]
+/// keeps.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AddrSelect {
+  /// Every address.
+  All,
+  /// Drops IPv4 addresses the kernel flagged `IFA_F_SECONDARY`.
+  Primary,
+  /// Drops IPv6 addresses in an [`IFA_F_NOT_BINDABLE`] state.
+  Bindable,
+}
+
+const NDA_DST: u16 = netlink::NDA_DST as u16;
+const NDA_LLADDR: u16 = netlink::NDA_LLADDR as u16;
 
 const IFLA_MTU: u32 = if_arp::IFLA_MTU as u32;
 const IFLA_IFNAME: u32 = if_arp::IFLA_IFNAME as u32;
 const IFLA_ADDRESS: u32 = if_arp::IFLA_ADDRESS as u32;
+const IFLA_CARRIER: u32 = if_arp::IFLA_CARRIER as u32;
+const IFLA_MASTER: u32 = if_arp::IFLA_MASTER as u32;
+const IFLA_OPERSTATE: u32 = if_arp::IFLA_OPERSTATE as u32;
+const IFLA_STATS: u32 = if_arp::IFLA_STATS as u32;
+const IFLA_STATS64: u32 = if_arp::IFLA_STATS64 as u32;
+
+/// Maps an `IFLA_OPERSTATE` value (`IF_OPER_*`, RFC 2863 order) to
+/// [`OperStatus`]. Values the kernel does not define read as unknown.
+fn oper_status_from_operstate(state: u8) -> OperStatus {
+  match state as u32 {
+    s if s == if_arp::IF_OPER_NOTPRESENT as u32 => OperStatus::NotPresent,
+    s if s == if_arp::IF_OPER_DOWN as u32 => OperStatus::Down,
+    s if s == if_arp::IF_OPER_LOWERLAYERDOWN as u32 => OperStatus::LowerLayerDown,
+    s if s == if_arp::IF_OPER_TESTING as u32 => OperStatus::Testing,
+    s if s == if_arp::IF_OPER_DORMANT as u32 => OperStatus::Dormant,
+    s if s == if_arp::IF_OPER_UP as u32 => OperStatus::Up,
+    _ => OperStatus::Unknown,
+  }
+}
+
+/// Maps an `ifi_type` (`ARPHRD_*`) to [`Interface::media_type`]'s
+/// string. Wireless NICs that present themselves as Ethernet (nearly
+/// all of them, under mac80211) read as `"ethernet"`.
+fn media_type_from_arphrd(ty: u16) -> Option<&'static str> {
+  Some(match ty as u32 {
+    if_arp::ARPHRD_ETHER => "ethernet",
+    if_arp::ARPHRD_LOOPBACK => "loopback",
+    if_arp::ARPHRD_IEEE80211
+    | if_arp::ARPHRD_IEEE80211_PRISM
+    | if_arp::ARPHRD_IEEE80211_RADIOTAP => "wireless",
+    if_arp::ARPHRD_IPGRE | if_arp::ARPHRD_IP6GRE => "gre",
+    if_arp::ARPHRD_TUNNEL | if_arp::ARPHRD_TUNNEL6 | if_arp::ARPHRD_SIT => "tunnel",
+    if_arp::ARPHRD_PPP => "ppp",
+    if_arp::ARPHRD_INFINIBAND => "infiniband",
+    if_arp::ARPHRD_CAN => "can",
+    if_arp::ARPHRD_IEEE802154 => "ieee802154",
+    _ => return None,
+  })
+}
+
+/// Maps an `ifi_type` (`ARPHRD_*`) to [`Interface::kind`].
+fn kind_from_arphrd(ty: u16) -> InterfaceKind {
+  match ty as u32 {
+    if_arp::ARPHRD_ETHER => InterfaceKind::Ethernet,
+    if_arp::ARPHRD_LOOPBACK => InterfaceKind::Loopback,
+    if_arp::ARPHRD_IEEE80211
+    | if_arp::ARPHRD_IEEE80211_PRISM
+    | if_arp::ARPHRD_IEEE80211_RADIOTAP => InterfaceKind::Wifi,
+    if_arp::ARPHRD_IPGRE
+    | if_arp::ARPHRD_IP6GRE
+    | if_arp::ARPHRD_TUNNEL
+    | if_arp::ARPHRD_TUNNEL6
+    | if_arp::ARPHRD_SIT => InterfaceKind::Tunnel,
+    if_arp::ARPHRD_PPP => InterfaceKind::Ppp,
+    if_arp::ARPHRD_ATM => InterfaceKind::Atm,
+    if_arp::ARPHRD_IEEE802_TR => InterfaceKind::TokenRing,
+    _ => InterfaceKind::Other(ty),
+  }
+}
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
@@ -192,6 +338,37 @@ struct Handle {
   sa: SocketAddrNetlink,
 }
 
+/// Whether a netlink route socket can be created at all; seccomp
+/// profiles that forbid `AF_NETLINK` fail here.
+pub(super) fn netlink_available() -> bool {
+  // SAFETY: `Handle::new` only creates a socket, which is closed again
+  // when the handle drops.
+  unsafe { Handle::new() }.is_ok()
+}
+
+/// One netlink socket reused for several dumps in a row, each sent
+/// under its own sequence number so a stray reply to an earlier dump
+/// is rejected instead of mixed in.
+pub(super) struct Session {
+  handle: Handle,
+  seq: u32,
+}
+
+impl Session {
+  pub(super) fn new() -> io::Result<Self> {
+    Ok(Self {
+      // SAFETY: see `netlink_available`.
+      handle: unsafe { Handle::new() }?,
+      seq: 0,
+    })
+  }
+
+  fn next_seq(&mut self) -> u32 {
+    self.seq += 1;
+    self.seq
+  }
+}
+
 impl Handle {
   unsafe fn new() -> io::Result<Self> {
     // Create the netlink socket. We deliberately do NOT bind() it.
@@ -212,6 +389,28 @@ impl Handle {
     Ok(Self { fd: sock, sa })
   }
 
+  /// Opts the socket into strict header checking (Linux 4.20+), under
+  /// which `RTM_GETADDR` dumps honour `ifa_index` and `RTM_GETROUTE`
+  /// dumps honour `RTA_TABLE`. rustix has no wrapper for this option.
+  ///
+  /// Older kernels and some sandboxes refuse it. Callers then send the
+  /// plain dump request instead, whose replies the receive loops filter
+  /// client-side.
+  unsafe fn enable_strict_check(&self) -> io::Result<()> {
+    let on: libc::c_int = 1;
+    let ret = libc::setsockopt(
+      self.fd.as_raw_fd(),
+      SOL_NETLINK,
+      NETLINK_GET_STRICT_CHK,
+      (&on as *const libc::c_int).cast(),
+      mem::size_of::<libc::c_int>() as libc::socklen_t,
+    );
+    if ret != 0 {
+      return Err(io::Error::last_os_error());
+    }
+    Ok(())
+  }
+
   unsafe fn send(&self, req: &NetlinkRouteRequest) -> io::Result<usize> {
     self.send_bytes(req.as_bytes())
   }
@@ -220,203 +419,491 @@ impl Handle {
     sendto(&self.fd, bytes, SendFlags::empty(), &self.sa).map_err(Into::into)
   }
 
+  /// Requests a dump of `family`'s routes, of routing table `table`
+  /// alone when one is named.
+  ///
+  /// Under strict checking the kernel dumps only the table named by
+  /// `RTA_TABLE`. Where strict checking is refused the plain dump of
+  /// every table is requested, and the walkers' [`table_admitted`]
+  /// check narrows it down instead. Table 0 (`RT_TABLE_UNSPEC`) names
+  /// no table; the kernel would read it as "every table", so it is
+  /// refused.
+  unsafe fn send_route_dump(
+    &self,
+    family: AddressFamily,
+    seq: u32,
+    table: Option<u32>,
+  ) -> io::Result<()> {
+    match table {
+      Some(0) => {
+        return Err(io::Error::new(
+          io::ErrorKind::InvalidInput,
+          "routing table 0 (RT_TABLE_UNSPEC) names no table",
+        ))
+      }
+      Some(table) if self.enable_strict_check().is_ok() => {
+        let mut body = [0u8; 12];
+        body[0] = family.as_raw() as u8;
+        let msg = NetlinkMessage::new(RTM_GETROUTE as u16, NLM_F_DUMP as u16, &body)
+          .seq(seq)
+          .attr(RTA_TABLE, &table.to_ne_bytes())
+          .finish();
+        self.send_bytes(&msg)?;
+      }
+      _ => {
+        let req = NetlinkRouteRequest::new(RTM_GETROUTE as u16, seq, family.as_raw() as u8);
+        self.send(&req)?;
+      }
+    }
+    Ok(())
+  }
+
   unsafe fn sock(&self) -> io::Result<SocketAddrNetlink> {
     getsockname(&self.fd)
-      .and_then(|addr| addr.try_into())
-      .map_err(Into::into)
+      .map_err(io::Error::from)
+      .and_then(netlink_sockaddr)
+  }
+
+  /// Returns the portid the kernel stamps into `nlmsg_pid` on replies
+  /// to this socket. Must be called after the first send.
+  ///
+  /// Some sandboxed netlink implementations autobind on send but keep
+  /// reporting portid 0 from `getsockname`. In that case fall back to
+  /// the process id, which is the portid the kernel hands the first
+  /// autobound netlink socket of a process.
+  unsafe fn portid(&self) -> io::Result<u32> {
+    Ok(reply_portid(self.sock()?.pid()))
   }
 
-  unsafe fn recv(&self, dst: &mut [u8]) -> io::Result<usize> {
-    let (nr, _, _) = recvfrom(&self.fd, dst, RecvFlags::empty())?;
+  /// Receives one datagram into `dst`, growing it to fit; see
+  /// [`recv_datagram`].
+  unsafe fn recv(&self, dst: &mut Vec<u8>) -> io::Result<usize> {
+    let nr = loop {
+      if let Some(nr) = recv_datagram(self.fd.as_fd(), dst)? {
+        break nr;
+      }
+    };
 
     if nr < NLMSG_HDRLEN {
-      return Err(rustix::io::Errno::INVAL.into());
+      return Err(malformed());
     }
 
     Ok(nr)
   }
 }
 
+/// The portid replies carry, given the one `getsockname` reported after
+/// the first send; see [`Handle::portid`].
+fn reply_portid(reported: u32) -> u32 {
+  match reported {
+    0 => std::process::id(),
+    pid => pid,
+  }
+}
+
+/// Reads one whole netlink datagram into `buf`, growing `buf` first
+/// when the datagram does not fit.
+///
+/// Netlink never splits a message across datagrams, so there is no
+/// partial tail to carry between reads. What does happen is that a
+/// datagram larger than the buffer is truncated and its remainder
+/// dropped; the parsers then see a message running past the end of the
+/// read and fail with `EINVAL`. Peeking with `MSG_TRUNC` first reports
+/// the full size without consuming anything, as iproute2's
+/// `rtnl_recvmsg` does.
+///
+/// Returns `None` for a datagram some other process unicast to our
+/// portid: any local process may do that without privileges, so only
+/// messages from the kernel (portid 0) are trusted, as iproute2 and
+/// systemd-networkd check.
+fn recv_datagram(fd: BorrowedFd<'_>, buf: &mut Vec<u8>) -> io::Result<Option<usize>> {
+  let (_, len) = recv(fd, &mut [0u8; 0], RecvFlags::PEEK | RecvFlags::TRUNC)?;
+  if len > buf.len() {
+    buf.resize(len, 0);
+  }
+  let (nr, _, from) = recvfrom(fd, &mut buf[..], RecvFlags::empty())?;
+  match from.map(SocketAddrNetlink::try_from) {
+    Some(Ok(from)) if from.pid() == 0 => Ok(Some(nr)),
+    _ => Ok(None),
+  }
+}
+
+/// Converts the address `getsockname` reported for a [`Handle`] into a
+/// netlink address. The socket is always `AF_NETLINK`, so a failure
+/// here means something underneath us is badly wrong; say so instead
+/// of surfacing a bare `EINVAL`.
+fn netlink_sockaddr(addr: SocketAddrAny) -> io::Result<SocketAddrNetlink> {
+  let family = addr.address_family();
+  SocketAddrNetlink::try_from(addr).map_err(|_| {
+    io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!(
+        "getsockname on the netlink route socket returned a non-netlink address (family {})",
+        family.as_raw()
+      ),
+    )
+  })
+}
+
 /// Receive-buffer size for route / nexthop dumps.
 ///
 /// A single `RTM_NEWROUTE` message can comfortably exceed 4 KiB on
 /// hosts with large ECMP `RTA_MULTIPATH` lists or `RTM_NEWNEXTHOP`
 /// dumps with deep `NHA_GROUP` payloads (8 bytes per member). The
-/// per-interface and per-address walks stay on a page (their messages
-/// are small and bounded), but route walks must accept any single
-/// message the kernel produces — the OS truncates oversize messages
-/// silently, surfacing later as `nlmsg_len > nr` → spurious
-/// `EINVAL` aborting the whole walk.
-///
-/// `iproute2` uses 32 KiB for the same dumps; matching that gives
-/// plenty of headroom for ECMP across dozens of nexthops without
-/// resorting to the more invasive `recvmsg` + `MSG_TRUNC` retry
-/// pattern.
+/// per-interface and per-address walks start on a page (their messages
+/// are small and bounded); [`recv_datagram`] grows any buffer that
+/// turns out too small, but starting route walks at the 32 KiB
+/// `iproute2` uses saves the extra resize on ECMP-heavy hosts.
 const ROUTE_RECV_BUF_SIZE: usize = 32 * 1024;
 
 pub(super) fn netlink_interface(family: AddressFamily, ifi: u32) -> io::Result<TinyVec<Interface>> {
-  unsafe {
-    let handle = Handle::new()?;
+  netlink_interface_into(family, ifi, None)
+}
 
-    // Create and send netlink request
-    let req = NetlinkRouteRequest::new(RTM_GETLINK as u16, 1, family.as_raw() as u8, ifi);
-    handle.send(&req)?;
+/// Like [`netlink_interface`], but an `RTM_NEWLINK` message whose body
+/// fails to parse is recorded in `errors` and skipped instead of
+/// aborting the whole dump. Framing errors (a bad `nlmsg_len`, a
+/// foreign sequence number) still fail the call: past those there is
+/// no reliable way to find the next message.
+pub(super) fn netlink_interface_lossy(
+  family: AddressFamily,
+  ifi: u32,
+  errors: &mut Vec<EnumerationError>,
+) -> io::Result<TinyVec<Interface>> {
+  netlink_interface_into(family, ifi, Some(errors))
+}
 
-    // Get socket name
-    let lsa = handle.sock()?;
+fn netlink_interface_into(
+  family: AddressFamily,
+  ifi: u32,
+  mut errors: Option<&mut Vec<EnumerationError>>,
+) -> io::Result<TinyVec<Interface>> {
+  let mut interfaces = TinyVec::new();
+  for link in netlink_interface_iter(family, ifi)? {
+    match (link, errors.as_deref_mut()) {
+      (Ok(interface), _) => interfaces.push(interface),
+      (Err(e), Some(errors)) => errors.push(e),
+      (Err(e), None) => return Err(e.into_error().into()),
+    }
+  }
+  Ok(interfaces)
+}
 
-    // Receive and process messages
-    let page_size = rustix::param::page_size();
-    let mut rb = vec![0u8; page_size];
+/// Receives a whole `RTM_GETLINK` dump, or just link `ifi` when it is
+/// nonzero, and returns an iterator that decodes its `RTM_NEWLINK`
+/// messages one at a time.
+///
+/// The framing of every reply is checked while receiving, so the
+/// iterator itself only fails per interface, when a message body does
+/// not parse.
+pub(super) fn netlink_interface_iter(family: AddressFamily, ifi: u32) -> io::Result<LinkIter> {
+  // `ifi_index` is signed on the wire, so no link has an index past
+  // `i32::MAX`; the kernel would reject the request with EINVAL.
+  if ifi > i32::MAX as u32 {
+    return Ok(LinkIter {
+      buf: Vec::new(),
+      pos: 0,
+      ifi,
+    });
+  }
 
-    let mut interfaces = TinyVec::new();
+  Session::new()?.links(family, ifi)
+}
 
-    'outer: loop {
-      let nr = handle.recv(&mut rb)?;
+impl Session {
+  /// Receives a link dump; see [`netlink_interface_iter`].
+  pub(super) fn links(&mut self, family: AddressFamily, ifi: u32) -> io::Result<LinkIter> {
+    let seq = self.next_seq();
+    let handle = &self.handle;
 
-      let mut received = &rb[..nr];
+    unsafe {
+      // Create and send netlink request. For a single interface, ask for
+      // just that link instead of dumping them all and filtering here.
+      if ifi == 0 {
+        let req = NetlinkRouteRequest::new(RTM_GETLINK as u16, seq, family.as_raw() as u8);
+        handle.send(&req)?;
+      } else {
+        handle.send_bytes(&build_link_get_request(seq, family.as_raw() as u8, ifi))?;
+      }
 
-      while received.len() >= NLMSG_HDRLEN {
-        let h = decode_nlmsghdr(received);
-        let hlen = h.nlmsg_len as usize;
-        let l = nlm_align_of(hlen);
-        if hlen < NLMSG_HDRLEN || l > received.len() {
-          return Err(rustix::io::Errno::INVAL.into());
+      // Get socket portid
+      let pid = handle.portid()?;
+
+      // Receive and process messages
+      let page_size = rustix::param::page_size();
+      let mut rb = vec![0u8; page_size];
+
+      let mut links = Vec::new();
+
+      loop {
+        let nr = handle.recv(&mut rb)?;
+        match link_batch_into(&rb[..nr], seq, pid, &mut links) {
+          // A dump ends with NLMSG_DONE; a single-link reply is one
+          // unterminated message.
+          Ok(done) if done || ifi != 0 => break,
+          Ok(_) => {}
+          // No such link: the same empty result a filtered dump gives.
+          Err(e) if ifi != 0 && e.raw_os_error() == Some(libc::ENODEV) => break,
+          Err(e) => return Err(e),
         }
+      }
 
-        if h.nlmsg_seq != 1 || h.nlmsg_pid != lsa.pid() {
-          return Err(rustix::io::Errno::INVAL.into());
+      Ok(LinkIter {
+        buf: links,
+        pos: 0,
+        ifi,
+      })
+    }
+  }
+}
+
+/// Checks the framing of one `recv()` batch of `RTM_GETLINK` dump
+/// replies to request `seq` and appends its `RTM_NEWLINK` messages,
+/// header included, to `links`. Returns `Ok(true)` once the dump is
+/// complete.
+fn link_batch_into(
+  mut received: &[u8],
+  seq: u32,
+  pid: u32,
+  links: &mut Vec<u8>,
+) -> io::Result<bool> {
+  while received.len() >= NLMSG_HDRLEN {
+    let h = decode_nlmsghdr(received);
+    let hlen = h.nlmsg_len as usize;
+    let l = nlm_align_of(hlen);
+    if hlen < NLMSG_HDRLEN || l > received.len() {
+      return Err(malformed());
+    }
+
+    if h.nlmsg_seq != seq || h.nlmsg_pid != pid {
+      return Err(malformed());
+    }
+
+    match h.nlmsg_type as u32 {
+      NLMSG_DONE => {
+        // A dump the kernel marked interrupted (NLM_F_DUMP_INTR) may be
+        // missing entries because the link table changed mid-walk;
+        // returning a partial snapshot as success would be a silent
+        // wrong answer. Surface EINTR so the caller can retry, matching
+        // the route walkers.
+        if h.nlmsg_flags as u32 & NLM_F_DUMP_INTR != 0 {
+          return Err(rustix::io::Errno::INTR.into());
         }
+        return Ok(true);
+      }
+      // Decode the errno instead of flattening every NLMSG_ERROR to
+      // EINVAL: a denial delivered in-band (e.g. RTM_GETLINK ->
+      // -EACCES/-EPERM for Android's untrusted_app) must surface as
+      // PermissionDenied so the ioctl fallback in
+      // `super::interface_table` can engage. Mirrors the route walkers.
+      NLMSG_ERROR => match decode_nlmsgerr(received, hlen)? {
+        NlmsgErrOutcome::Ack => {}
+        NlmsgErrOutcome::FamilyUnavailable => return Ok(true),
+      },
+      val if val == RTM_NEWLINK => links.extend_from_slice(&received[..l]),
+      _ => {}
+    }
 
-        // Bound the per-message slice to `hlen` rather than the rest
-        // of the recv buffer. Netlink dumps routinely pack multiple
-        // messages into one recv() and an unbounded slice would let
-        // the attribute walker run past the current message into the
-        // next message's header — corrupting fields or returning
-        // EINVAL on healthy kernel output.
-        let msg_buf = &received[NLMSG_HDRLEN..hlen];
+    received = &received[l..];
+  }
 
-        match h.nlmsg_type as u32 {
-          NLMSG_DONE => {
-            // A dump the kernel marked interrupted (NLM_F_DUMP_INTR) may be
-            // missing entries because the link table changed mid-walk;
-            // returning a partial snapshot as success would be a silent
-            // wrong answer. Surface EINTR so the caller can retry, matching
-            // the route walkers.
-            if h.nlmsg_flags as u32 & NLM_F_DUMP_INTR != 0 {
-              return Err(rustix::io::Errno::INTR.into());
-            }
-            break 'outer;
-          }
-          // Decode the errno instead of flattening every NLMSG_ERROR to
-          // EINVAL: a denial delivered in-band (e.g. RTM_GETLINK ->
-          // -EACCES/-EPERM for Android's untrusted_app) must surface as
-          // PermissionDenied so the ioctl fallback in
-          // `super::interface_table` can engage. Mirrors the route walkers.
-          NLMSG_ERROR => match decode_nlmsgerr(received, hlen)? {
-            NlmsgErrOutcome::Ack => {
-              received = &received[l..];
-              continue;
-            }
-            NlmsgErrOutcome::FamilyUnavailable => break 'outer,
-          },
-          val if val == RTM_NEWLINK => {
-            let info_hdr = IfInfoMessageHeader::parse(msg_buf)?;
-            let mut info_data = &msg_buf[IfInfoMessageHeader::SIZE..];
-            if ifi != 0 && ifi != info_hdr.index as u32 {
-              // move forward
-              received = &received[l..];
-              continue;
-            }
+  Ok(false)
+}
 
-            let mut interface = Interface::new(
-              info_hdr.index as u32,
-              Flags::from_bits_truncate(info_hdr.flags),
-            );
-            while info_data.len() >= RtAttr::SIZE {
-              let attr = RtAttr {
-                len: u16::from_ne_bytes(info_data[..2].try_into().unwrap()),
-                ty: u16::from_ne_bytes(info_data[2..4].try_into().unwrap()),
-              };
-              let attrlen = attr.len as usize;
-              if attrlen < RtAttr::SIZE || attrlen > info_data.len() {
-                return Err(rustix::io::Errno::INVAL.into());
-              }
+/// Iterator over the interfaces of a received `RTM_GETLINK` dump. It
+/// owns the messages and decodes each `RTM_NEWLINK` only when asked
+/// for the next interface, so stopping early skips the rest.
+pub(crate) struct LinkIter {
+  /// `RTM_NEWLINK` messages whose framing [`link_batch_into`] already
+  /// checked.
+  buf: Vec<u8>,
+  pos: usize,
+  ifi: u32,
+}
 
-              // Payload excludes the header and excludes any trailing
-              // padding (the padding is counted by `alen` for iterator
-              // advance but is not part of the attribute value).
-              let data = &info_data[RtAttr::SIZE..attrlen];
-              // Aligned length is used to walk to the next attribute,
-              // but must not be allowed to exceed the buffer — a
-              // malformed last attribute could otherwise make the
-              // slice below panic.
-              let alen = rta_align_of(attrlen).min(info_data.len());
-
-              match attr.ty as u32 {
-                IFLA_MTU if data.len() >= 4 => {
-                  interface.mtu = u32::from_ne_bytes(data[..4].try_into().unwrap());
-                }
-                IFLA_IFNAME => {
-                  // Kernel-emitted IFLA_IFNAME is null-terminated, but
-                  // we still bound the read to `data` in case of a
-                  // malformed message (avoids UB from `CStr::from_ptr`
-                  // scanning past the attribute). Use the lossy UTF-8
-                  // conversion — matching the pre-refactor
-                  // `CStr::to_string_lossy` behaviour — so an interface
-                  // with non-UTF8 bytes surfaces as a replacement-char
-                  // string rather than silently becoming empty and
-                  // colliding with other nameless interfaces.
-                  let nul = data.iter().position(|&b| b == 0).unwrap_or(data.len());
-                  interface.name = String::from_utf8_lossy(&data[..nul]).as_ref().into();
-                }
-                IFLA_ADDRESS => match data.len() {
-                  // We never return any /32 or /128 IP address
-                  // prefix on any IP tunnel interface as the
-                  // hardware address.
-                  // ipv4
-                  4 if info_hdr.ty == ARPHRD_IPGRE as u16
-                    || info_hdr.ty == ARPHRD_TUNNEL as u16 =>
-                  {
-                    info_data = &info_data[alen..];
-                    continue;
-                  }
-                  // ipv6
-                  16 if info_hdr.ty == ARPHRD_TUNNEL6 as u16 || info_hdr.ty == 823 => {
-                    info_data = &info_data[alen..];
-                    continue;
-                  } // 823 is any over GRE over IPv6 tunneling
-                  _ => {
-                    let mut nonzero = false;
-                    for b in data {
-                      if *b != 0 {
-                        nonzero = true;
-                        break;
-                      }
-                    }
-                    if nonzero {
-                      let mut buf = [0; MAC_ADDRESS_SIZE];
-                      let len = data.len().min(MAC_ADDRESS_SIZE);
-                      buf[..len].copy_from_slice(&data[..len]);
-                      interface.mac_addr = Some(MacAddr::from_raw(buf));
-                    }
-                  }
-                },
-                _ => {}
-              }
+impl LinkIter {
+  /// Returns the next `RTM_NEWLINK` of a requested link as its header
+  /// and undecoded attribute block.
+  fn next_link(&mut self) -> Option<Result<(IfInfoMessageHeader, &[u8]), EnumerationError>> {
+    while self.buf.len() - self.pos >= NLMSG_HDRLEN {
+      let received = &self.buf[self.pos..];
+      let hlen = decode_nlmsghdr(received).nlmsg_len as usize;
+      self.pos += nlm_align_of(hlen);
+
+      // Bound the per-message slice to `hlen` rather than the rest
+      // of the buffer. Netlink dumps routinely pack multiple
+      // messages into one recv() and an unbounded slice would let
+      // the attribute walker run past the current message into the
+      // next message's header — corrupting fields or rejecting
+      // healthy kernel output as malformed.
+      let msg_buf = &received[NLMSG_HDRLEN..hlen];
+      match IfInfoMessageHeader::parse(msg_buf) {
+        Ok(info_hdr) if self.ifi != 0 && self.ifi != info_hdr.index as u32 => {}
+        Ok(info_hdr) => return Some(Ok((info_hdr, &msg_buf[IfInfoMessageHeader::SIZE..]))),
+        Err(e) => return Some(Err(EnumerationError::new(None, e))),
+      }
+    }
+    None
+  }
+}
+
+impl Iterator for LinkIter {
+  type Item = Result<Interface, EnumerationError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next_link().map(|link| {
+      link.and_then(|(info_hdr, info_data)| {
+        parse_link(&info_hdr, info_data)
+          .map_err(|e| EnumerationError::new(Some(info_hdr.index as u32), e))
+      })
+    })
+  }
+}
 
-              info_data = &info_data[alen..];
+/// Builds an [`Interface`] from an `RTM_NEWLINK` header and its
+/// attribute block.
+fn parse_link(info_hdr: &IfInfoMessageHeader, mut info_data: &[u8]) -> io::Result<Interface> {
+  let mut interface = Interface::new(
+    info_hdr.index as u32,
+    Flags::from_bits_truncate(info_hdr.flags),
+  );
+  interface.media_type = media_type_from_arphrd(info_hdr.ty);
+  interface.kind = kind_from_arphrd(info_hdr.ty);
+  while info_data.len() >= RtAttr::SIZE {
+    let attr = RtAttr {
+      len: u16::from_ne_bytes(info_data[..2].try_into().unwrap()),
+      ty: u16::from_ne_bytes(info_data[2..4].try_into().unwrap()),
+    };
+    let attrlen = attr.len as usize;
+    if attrlen < RtAttr::SIZE || attrlen > info_data.len() {
+      return Err(malformed());
+    }
+
+    // Payload excludes the header and excludes any trailing
+    // padding (the padding is counted by `alen` for iterator
+    // advance but is not part of the attribute value).
+    let data = &info_data[RtAttr::SIZE..attrlen];
+    // Aligned length is used to walk to the next attribute,
+    // but must not be allowed to exceed the buffer — a
+    // malformed last attribute could otherwise make the
+    // slice below panic.
+    let alen = rta_align_of(attrlen).min(info_data.len());
+
+    match attr.ty as u32 {
+      IFLA_MTU if data.len() >= 4 => {
+        interface.mtu = u32::from_ne_bytes(data[..4].try_into().unwrap());
+      }
+      IFLA_CARRIER if !data.is_empty() => {
+        interface.carrier = Some(data[0] != 0);
+      }
+      IFLA_OPERSTATE if !data.is_empty() => {
+        interface.oper_status = oper_status_from_operstate(data[0]);
+      }
+      IFLA_MASTER if data.len() >= 4 => {
+        let master = u32::from_ne_bytes(data[..4].try_into().unwrap());
+        interface.master = (master != 0).then_some(master);
+      }
+      IFLA_IFNAME => {
+        // Kernel-emitted IFLA_IFNAME is null-terminated, but
+        // we still bound the read to `data` in case of a
+        // malformed message (avoids UB from `CStr::from_ptr`
+        // scanning past the attribute). Use the lossy UTF-8
+        // conversion — matching the pre-refactor
+        // `CStr::to_string_lossy` behaviour — so an interface
+        // with non-UTF8 bytes surfaces as a replacement-char
+        // string rather than silently becoming empty and
+        // colliding with other nameless interfaces.
+        let nul = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+        interface.name = String::from_utf8_lossy(&data[..nul]).as_ref().into();
+      }
+      IFLA_ADDRESS => match data.len() {
+        // We never return any /32 or /128 IP address
+        // prefix on any IP tunnel interface as the
+        // hardware address.
+        // ipv4
+        4 if info_hdr.ty == ARPHRD_IPGRE as u16 || info_hdr.ty == ARPHRD_TUNNEL as u16 => {
+          info_data = &info_data[alen..];
+          continue;
+        }
+        // ipv6
+        16 if info_hdr.ty == ARPHRD_TUNNEL6 as u16 || info_hdr.ty == 823 => {
+          info_data = &info_data[alen..];
+          continue;
+        } // 823 is any over GRE over IPv6 tunneling
+        _ => {
+          let mut nonzero = false;
+          for b in data {
+            if *b != 0 {
+              nonzero = true;
+              break;
             }
-            interfaces.push(interface);
           }
-          _ => {}
+          if nonzero {
+            let mut buf = [0; MAC_ADDRESS_SIZE];
+            let len = data.len().min(MAC_ADDRESS_SIZE);
+            buf[..len].copy_from_slice(&data[..len]);
+            interface.mac_addr = Some(MacAddr::from_raw(buf));
+          }
         }
+      },
+      _ => {}
+    }
 
-        received = &received[l..];
+    info_data = &info_data[alen..];
+  }
+  Ok(interface)
+}
+
+/// Reads the traffic counters of link `ifi` from the `IFLA_STATS64`
+/// attribute of its `RTM_NEWLINK`.
+pub(super) fn netlink_link_stats(ifi: u32) -> io::Result<InterfaceStats> {
+  // Index 0 would turn the request into a dump of every link.
+  if ifi != 0 {
+    if let Some(link) = netlink_interface_iter(AddressFamily::UNSPEC, ifi)?.next_link() {
+      let (_, info_data) = link.map_err(EnumerationError::into_error)?;
+      return parse_link_stats(info_data);
+    }
+  }
+  Err(crate::Error::InterfaceNotFound.into())
+}
+
+/// Picks the counters out of an `RTM_NEWLINK` attribute block. Both
+/// `rtnl_link_stats64` and the legacy 32-bit `rtnl_link_stats`, which
+/// only kernels older than 2.6.35 send alone, start with the same six
+/// counters: packets, bytes and errors, receive before transmit.
+fn parse_link_stats(mut info_data: &[u8]) -> io::Result<InterfaceStats> {
+  let mut legacy = None;
+  while info_data.len() >= RtAttr::SIZE {
+    let attrlen = u16::from_ne_bytes(info_data[..2].try_into().unwrap()) as usize;
+    let ty = u16::from_ne_bytes(info_data[2..4].try_into().unwrap()) as u32;
+    if attrlen < RtAttr::SIZE || attrlen > info_data.len() {
+      return Err(malformed());
+    }
+    let data = &info_data[RtAttr::SIZE..attrlen];
+    match ty {
+      IFLA_STATS64 if data.len() >= 6 * 8 => {
+        let c = |i: usize| u64::from_ne_bytes(data[i * 8..i * 8 + 8].try_into().unwrap());
+        return Ok(link_stats_from(c));
+      }
+      IFLA_STATS if data.len() >= 6 * 4 => {
+        let c = |i: usize| u32::from_ne_bytes(data[i * 4..i * 4 + 4].try_into().unwrap()) as u64;
+        legacy = Some(link_stats_from(c));
       }
+      _ => {}
     }
+    info_data = &info_data[rta_align_of(attrlen).min(info_data.len())..];
+  }
+  legacy.ok_or_else(|| crate::Error::parse("RTM_NEWLINK link statistics").into())
+}
 
-    Ok(interfaces)
+fn link_stats_from(counter: impl Fn(usize) -> u64) -> InterfaceStats {
+  InterfaceStats {
+    rx_packets: counter(0),
+    tx_packets: counter(1),
+    rx_bytes: counter(2),
+    tx_bytes: counter(3),
+    rx_errors: counter(4),
+    tx_errors: counter(5),
   }
 }
 
@@ -436,24 +923,387 @@ where
 pub(super) fn netlink_addr_into<N, F>(
   family: AddressFamily,
   ifi: u32,
-  mut f: F,
+  f: F,
+  addrs: &mut SmallVec<N>,
+) -> io::Result<()>
+where
+  N: Net,
+  F: FnMut(&IpAddr) -> bool,
+{
+  netlink_addr_dump(family, ifi, AddrSelect::All, f, addrs)
+}
+
+/// Same as `netlink_addr` but drops IPv4 addresses the kernel flagged
+/// `IFA_F_SECONDARY`, i.e. every address after the first one it added
+/// in the same subnet.
+pub(super) fn netlink_primary_addr<N, F>(
+  family: AddressFamily,
+  ifi: u32,
+  f: F,
+) -> io::Result<SmallVec<N>>
+where
+  N: Net,
+  F: FnMut(&IpAddr) -> bool,
+{
+  let mut out = SmallVec::new();
+  netlink_addr_dump(family, ifi, AddrSelect::Primary, f, &mut out)?;
+  Ok(out)
+}
+
+/// Same as `netlink_addr` but drops IPv6 addresses that cannot be
+/// bound: tentative, DAD-failed and deprecated ones.
+pub(super) fn netlink_bindable_addr<N, F>(
+  family: AddressFamily,
+  ifi: u32,
+  f: F,
+) -> io::Result<SmallVec<N>>
+where
+  N: Net,
+  F: FnMut(&IpAddr) -> bool,
+{
+  let mut out = SmallVec::new();
+  netlink_addr_dump(family, ifi, AddrSelect::Bindable, f, &mut out)?;
+  Ok(out)
+}
+
+fn netlink_addr_dump<N, F>(
+  family: AddressFamily,
+  ifi: u32,
+  select: AddrSelect,
+  f: F,
   addrs: &mut SmallVec<N>,
 ) -> io::Result<()>
 where
   N: Net,
   F: FnMut(&IpAddr) -> bool,
+{
+  Session::new()?.addr_dump(family, ifi, select, f, push_into(addrs))
+}
+
+/// Like `netlink_addr`, but hands each address to `emit` as soon as it
+/// is decoded instead of collecting them. Once `emit` breaks, the rest
+/// of the dump is left unread.
+pub(super) fn netlink_addr_walk<N, F, E>(
+  family: AddressFamily,
+  ifi: u32,
+  f: F,
+  mut emit: E,
+) -> io::Result<()>
+where
+  N: Net,
+  F: FnMut(&IpAddr) -> bool,
+  E: FnMut(N) -> ControlFlow<()>,
+{
+  Session::new()?.addr_dump(family, ifi, AddrSelect::All, f, |addr, _| emit(addr))
+}
+
+/// Picks the address of `family` on interface `ifi` to use as source,
+/// weighing the `IFA_F_DEPRECATED` and (IPv6) `IFA_F_TEMPORARY` flags
+/// as `pick_source` describes.
+pub(super) fn netlink_source_addr(family: AddressFamily, ifi: u32) -> io::Result<Option<IfNet>> {
+  let mut candidates = SmallVec::new();
+  Session::new()?.addr_dump(
+    family,
+    ifi,
+    AddrSelect::All,
+    |_| true,
+    |net: IfNet, flags| {
+      // `IFA_F_TEMPORARY` is `IFA_F_SECONDARY` on IPv4.
+      let temporary = net.addr().is_ipv6() && flags & netlink::IFA_F_TEMPORARY != 0;
+      candidates.push((
+        net,
+        SourceFlags {
+          deprecated: flags & netlink::IFA_F_DEPRECATED != 0,
+          temporary,
+        },
+      ));
+      ControlFlow::Continue(())
+    },
+  )?;
+  Ok(pick_source(candidates))
+}
+
+/// An address sink that appends to `addrs` and never stops the walk.
+fn push_into<N>(addrs: &mut SmallVec<N>) -> impl FnMut(N, u32) -> ControlFlow<()> + '_ {
+  move |addr, _| {
+    addrs.push(addr);
+    ControlFlow::Continue(())
+  }
+}
+
+impl Session {
+  /// Receives a whole `RTM_GETADDR` dump of `family`; see
+  /// [`netlink_addr`].
+  pub(super) fn addrs<N, F>(&mut self, family: AddressFamily, f: F) -> io::Result<SmallVec<N>>
+  where
+    N: Net,
+    F: FnMut(&IpAddr) -> bool,
+  {
+    let mut out = SmallVec::new();
+    self.addr_dump(family, 0, AddrSelect::All, f, push_into(&mut out))?;
+    Ok(out)
+  }
+
+  /// Runs an `RTM_GETADDR` dump, passing each selected address to
+  /// `emit` until it breaks. A broken-off dump leaves replies unread,
+  /// so the session must not be used for another dump afterwards.
+  fn addr_dump<N, F, E>(
+    &mut self,
+    family: AddressFamily,
+    ifi: u32,
+    select: AddrSelect,
+    mut f: F,
+    mut emit: E,
+  ) -> io::Result<()>
+  where
+    N: Net,
+    F: FnMut(&IpAddr) -> bool,
+    E: FnMut(N, u32) -> ControlFlow<()>,
+  {
+    let seq = self.next_seq();
+    let handle = &self.handle;
+
+    unsafe {
+      // Create and send netlink request. For a single interface, ask the
+      // kernel to do the filtering so hosts with many addresses elsewhere
+      // don't pay for shipping and decoding all of them; without strict
+      // checking it would not, so dump everything and filter here.
+      if ifi != 0 && handle.enable_strict_check().is_ok() {
+        handle.send_bytes(&build_addr_dump_request(seq, family.as_raw() as u8, ifi))?;
+      } else {
+        let req = NetlinkRouteRequest::new(RTM_GETADDR as u16, seq, family.as_raw() as u8);
+        handle.send(&req)?;
+      }
+
+      // Get socket portid
+      let pid = handle.portid()?;
+
+      // Receive and process messages
+      let page_size = rustix::param::page_size();
+      let mut rb = vec![0u8; page_size];
+
+      'outer: loop {
+        let nr = handle.recv(&mut rb)?;
+        let mut received = &rb[..nr];
+
+        // means auto choose interface for addr fetching
+        while received.len() >= NLMSG_HDRLEN {
+          let h = decode_nlmsghdr(received);
+          let hlen = h.nlmsg_len as usize;
+          let l = nlm_align_of(hlen);
+          if hlen < NLMSG_HDRLEN || l > received.len() {
+            return Err(malformed());
+          }
+
+          if h.nlmsg_seq != seq || h.nlmsg_pid != pid {
+            return Err(malformed());
+          }
+
+          // See `netlink_interface` for why this is bounded to `hlen`.
+          let msg_buf = &received[NLMSG_HDRLEN..hlen];
+
+          match h.nlmsg_type as u32 {
+            NLMSG_DONE => {
+              // A dump the kernel marked interrupted (NLM_F_DUMP_INTR) may be
+              // missing addresses because the table changed mid-walk (DHCP /
+              // VPN / interface flap). The Android interface fallback derives
+              // its interface list from this dump, so a partial-but-`Ok`
+              // result would silently drop interfaces. Surface EINTR so the
+              // caller can retry, matching the route walkers.
+              if h.nlmsg_flags as u32 & NLM_F_DUMP_INTR != 0 {
+                return Err(rustix::io::Errno::INTR.into());
+              }
+              break 'outer;
+            }
+            // Decode the errno rather than flattening to EINVAL, mirroring the
+            // route walkers — a real error (e.g. EACCES/EPERM) propagates with
+            // its `ErrorKind` intact instead of becoming InvalidInput.
+            NLMSG_ERROR => match decode_nlmsgerr(received, hlen) {
+              Ok(NlmsgErrOutcome::Ack) => {
+                received = &received[l..];
+                continue;
+              }
+              Ok(NlmsgErrOutcome::FamilyUnavailable) => break 'outer,
+              // A strict-checked dump for an index that doesn't exist
+              // fails with ENODEV; the unfiltered dump would simply have
+              // matched nothing, so report it the same way.
+              Err(e)
+                if ifi != 0
+                  && e.raw_os_error() == Some(rustix::io::Errno::NODEV.raw_os_error()) =>
+              {
+                break 'outer
+              }
+              Err(e) => return Err(e),
+            },
+            val if val == RTM_NEWADDR => {
+              let flow = decode_addr_message(msg_buf, ifi, select, &mut f, &mut emit)?;
+              if flow.is_break() {
+                break 'outer;
+              }
+            }
+            _ => {}
+          }
+
+          received = &received[l..];
+        }
+      }
+
+      Ok(())
+    }
+  }
+}
+
+/// Maps an `ifa_scope` onto [`AddrScope`]. User-defined scopes between
+/// the named ones have no counterpart and are left to inference.
+fn addr_scope(ifa_scope: u8) -> Option<AddrScope> {
+  use netlink::rt_scope_t::*;
+
+  match ifa_scope {
+    s if s == RT_SCOPE_UNIVERSE as u8 => Some(AddrScope::Global),
+    s if s == RT_SCOPE_SITE as u8 => Some(AddrScope::Site),
+    s if s == RT_SCOPE_LINK as u8 => Some(AddrScope::Link),
+    s if s == RT_SCOPE_HOST as u8 => Some(AddrScope::Host),
+    _ => None,
+  }
+}
+
+/// Decodes one `RTM_NEWADDR` / `RTM_DELADDR` body (`ifaddrmsg` plus
+/// attributes) and hands its address, with its full `IFA_F_*` flags,
+/// to `emit` if it belongs to `ifi` (0 for any interface), passes `f`
+/// and is kept by `select`.
+/// Shared by the address dump and the address watcher. Returns
+/// `emit`'s verdict on the last address it was given.
+fn decode_addr_message<N, F, E>(
+  msg_buf: &[u8],
+  ifi: u32,
+  select: AddrSelect,
+  f: &mut F,
+  emit: &mut E,
+) -> io::Result<ControlFlow<()>>
+where
+  N: Net,
+  F: FnMut(&IpAddr) -> bool,
+  E: FnMut(N, u32) -> ControlFlow<()>,
+{
+  let ifam = IfNetMessageHeader::parse(msg_buf)?;
+  // `IFA_F_SECONDARY` shares its bit with IPv6's `IFA_F_TEMPORARY`, so
+  // it only means "secondary" for IPv4.
+  if select == AddrSelect::Primary
+    && ifam.family as u16 == AddressFamily::INET.as_raw()
+    && ifam.flags as u32 & netlink::IFA_F_SECONDARY != 0
+  {
+    return Ok(ControlFlow::Continue(()));
+  }
+  let mut ifa_msg_data = &msg_buf[IfNetMessageHeader::SIZE..];
+  let mut point_to_point = false;
+  let mut attrs = SmallVec::new();
+  while ifa_msg_data.len() >= RtAttr::SIZE {
+    let attr = RtAttr {
+      len: u16::from_ne_bytes(ifa_msg_data[..2].try_into().unwrap()),
+      ty: u16::from_ne_bytes(ifa_msg_data[2..4].try_into().unwrap()),
+    };
+    let attrlen = attr.len as usize;
+    if attrlen < RtAttr::SIZE || attrlen > ifa_msg_data.len() {
+      return Err(malformed());
+    }
+    // `data` excludes trailing padding; `alen` (aligned) is used only
+    // to advance to the next attribute, and is clamped so a malformed
+    // last attribute cannot panic.
+    let data = &ifa_msg_data[RtAttr::SIZE..attrlen];
+    let alen = rta_align_of(attrlen).min(ifa_msg_data.len());
+
+    if ifi == 0 || ifi == ifam.index {
+      attrs.push((attr, data));
+    }
+    ifa_msg_data = &ifa_msg_data[alen..];
+  }
+
+  // `ifa_flags` only has room for the low 8 bits; kernels since 3.14
+  // send the full set in `IFA_FLAGS` as well.
+  let flags = attrs
+    .iter()
+    .find(|(attr, data)| attr.ty == IFA_FLAGS as u16 && data.len() >= 4)
+    .map_or(ifam.flags as u32, |(_, data)| {
+      u32::from_ne_bytes(data[..4].try_into().unwrap())
+    });
+  if select == AddrSelect::Bindable
+    && ifam.family as u16 == AddressFamily::INET6.as_raw()
+    && flags & IFA_F_NOT_BINDABLE != 0
+  {
+    return Ok(ControlFlow::Continue(()));
+  }
+
+  // When `IFA_LOCAL` is present it is the interface's own address and
+  // `IFA_ADDRESS` is either the same value (IPv4 always sends both) or
+  // the peer, so only `IFA_LOCAL` is emitted. Otherwise `IFA_ADDRESS`
+  // is the sole source. Either way each message yields its address
+  // once — `::1` on loopback arrives as a lone `IFA_ADDRESS`.
+  let mut broadcast = None;
+  for (attr, data) in attrs.iter() {
+    if attr.ty == IFA_LOCAL as u16 {
+      point_to_point = true;
+    } else if attr.ty == IFA_BROADCAST as u16 && data.len() >= 4 {
+      let ip: [u8; 4] = data[..4].try_into().unwrap();
+      broadcast = Some(Ipv4Addr::from(ip));
+    }
+  }
+
+  let scope = addr_scope(ifam.scope);
+  for (attr, data) in attrs.iter() {
+    if point_to_point && attr.ty == IFA_ADDRESS as u16 {
+      continue;
+    }
+
+    match AddressFamily::from_raw(ifam.family as u16) {
+      AddressFamily::INET if data.len() >= 4 => {
+        let ip: [u8; 4] = data[..4].try_into().unwrap();
+        if attr.ty == IFA_ADDRESS as u16 || attr.ty == IFA_LOCAL as u16 {
+          if let Some(addr) =
+            N::try_from_with_filter(ifam.index, ip.into(), ifam.prefix_len, |addr| f(addr))
+          {
+            if emit(addr.with_broadcast(broadcast).with_scope(scope), flags).is_break() {
+              return Ok(ControlFlow::Break(()));
+            }
+          }
+        }
+      }
+      AddressFamily::INET6 if data.len() >= 16 => {
+        let ip: [u8; 16] = data[..16].try_into().unwrap();
+        if attr.ty == IFA_ADDRESS as u16 || attr.ty == IFA_LOCAL as u16 {
+          if let Some(addr) =
+            N::try_from_with_filter(ifam.index, ip.into(), ifam.prefix_len, |addr| f(addr))
+          {
+            if emit(addr.with_scope(scope), flags).is_break() {
+              return Ok(ControlFlow::Break(()));
+            }
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  Ok(ControlFlow::Continue(()))
+}
+
+/// Walks the kernel neighbour (ARP / NDP) table with an `RTM_GETNEIGH`
+/// dump, calling `on_neigh(ifindex, ip, lladdr, nud_state)` for each
+/// entry. `lladdr` is `None` when the entry has no 6-byte link-layer
+/// address yet (`NUD_INCOMPLETE` / `NUD_FAILED`) or the link does not
+/// use MAC addresses.
+pub(super) fn netlink_walk_neighbors<F>(family: AddressFamily, mut on_neigh: F) -> io::Result<()>
+where
+  F: FnMut(u32, IpAddr, Option<MacAddr>, u16),
 {
   unsafe {
     let handle = Handle::new()?;
 
-    // Create and send netlink request
-    let req = NetlinkRouteRequest::new(RTM_GETADDR as u16, 1, family.as_raw() as u8, ifi);
+    // The `rtgenmsg` family byte sits where `ndmsg.ndm_family` does, so
+    // the generic dump request selects the family here too.
+    let req = NetlinkRouteRequest::new(RTM_GETNEIGH as u16, 1, family.as_raw() as u8);
     handle.send(&req)?;
 
-    // Get socket name
-    let lsa = handle.sock()?;
+    let pid = handle.portid()?;
 
-    // Receive and process messages
     let page_size = rustix::param::page_size();
     let mut rb = vec![0u8; page_size];
 
@@ -461,17 +1311,16 @@ where
       let nr = handle.recv(&mut rb)?;
       let mut received = &rb[..nr];
 
-      // means auto choose interface for addr fetching
       while received.len() >= NLMSG_HDRLEN {
         let h = decode_nlmsghdr(received);
         let hlen = h.nlmsg_len as usize;
         let l = nlm_align_of(hlen);
         if hlen < NLMSG_HDRLEN || l > received.len() {
-          return Err(rustix::io::Errno::INVAL.into());
+          return Err(malformed());
         }
 
-        if h.nlmsg_seq != 1 || h.nlmsg_pid != lsa.pid() {
-          return Err(rustix::io::Errno::INVAL.into());
+        if h.nlmsg_seq != 1 || h.nlmsg_pid != pid {
+          return Err(malformed());
         }
 
         // See `netlink_interface` for why this is bounded to `hlen`.
@@ -479,20 +1328,13 @@ where
 
         match h.nlmsg_type as u32 {
           NLMSG_DONE => {
-            // A dump the kernel marked interrupted (NLM_F_DUMP_INTR) may be
-            // missing addresses because the table changed mid-walk (DHCP /
-            // VPN / interface flap). The Android interface fallback derives
-            // its interface list from this dump, so a partial-but-`Ok`
-            // result would silently drop interfaces. Surface EINTR so the
-            // caller can retry, matching the route walkers.
+            // Same as the address dump: an interrupted snapshot may be
+            // missing entries, so let the caller retry.
             if h.nlmsg_flags as u32 & NLM_F_DUMP_INTR != 0 {
               return Err(rustix::io::Errno::INTR.into());
             }
             break 'outer;
           }
-          // Decode the errno rather than flattening to EINVAL, mirroring the
-          // route walkers — a real error (e.g. EACCES/EPERM) propagates with
-          // its `ErrorKind` intact instead of becoming InvalidInput.
           NLMSG_ERROR => match decode_nlmsgerr(received, hlen)? {
             NlmsgErrOutcome::Ack => {
               received = &received[l..];
@@ -500,103 +1342,494 @@ where
             }
             NlmsgErrOutcome::FamilyUnavailable => break 'outer,
           },
-          val if val == RTM_NEWADDR => {
-            let ifam = IfNetMessageHeader::parse(msg_buf)?;
-            let mut ifa_msg_data = &msg_buf[IfNetMessageHeader::SIZE..];
-            let mut point_to_point = false;
-            let mut attrs = SmallVec::new();
-            while ifa_msg_data.len() >= RtAttr::SIZE {
-              let attr = RtAttr {
-                len: u16::from_ne_bytes(ifa_msg_data[..2].try_into().unwrap()),
-                ty: u16::from_ne_bytes(ifa_msg_data[2..4].try_into().unwrap()),
-              };
-              let attrlen = attr.len as usize;
-              if attrlen < RtAttr::SIZE || attrlen > ifa_msg_data.len() {
-                return Err(rustix::io::Errno::INVAL.into());
-              }
-              // `data` excludes trailing padding; `alen` (aligned) is
-              // used only to advance to the next attribute, and is
-              // clamped so a malformed last attribute cannot panic.
-              let data = &ifa_msg_data[RtAttr::SIZE..attrlen];
-              let alen = rta_align_of(attrlen).min(ifa_msg_data.len());
-
-              if ifi == 0 || ifi == ifam.index {
-                attrs.push((attr, data));
-              }
-              ifa_msg_data = &ifa_msg_data[alen..];
-            }
+          val if val == RTM_NEWNEIGH => decode_neigh_message(msg_buf, &mut on_neigh)?,
+          _ => {}
+        }
 
-            for (attr, _) in attrs.iter() {
-              if attr.ty == IFA_LOCAL as u16 {
-                point_to_point = true;
-                break;
+        received = &received[l..];
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// A netlink request built up field by field: `nlmsghdr`, a fixed
+/// body, then `rtattr`s, each padded to `NLMSG_ALIGNTO`. The header
+/// length is filled in by [`finish`](Self::finish).
+struct NetlinkMessage {
+  bytes: Vec<u8>,
+}
+
+impl NetlinkMessage {
+  fn new(kind: u16, flags: u16, body: &[u8]) -> Self {
+    let mut bytes = Vec::with_capacity(64);
+    bytes.extend_from_slice(&0u32.to_ne_bytes());
+    bytes.extend_from_slice(&kind.to_ne_bytes());
+    bytes.extend_from_slice(&(flags | NLM_F_REQUEST as u16).to_ne_bytes());
+    bytes.extend_from_slice(&1u32.to_ne_bytes());
+    bytes.extend_from_slice(&std::process::id().to_ne_bytes());
+    let mut msg = Self { bytes };
+    msg.push_padded(body);
+    msg
+  }
+
+  /// Replaces the default sequence number `1`.
+  fn seq(mut self, seq: u32) -> Self {
+    self.bytes[8..12].copy_from_slice(&seq.to_ne_bytes());
+    self
+  }
+
+  fn attr(mut self, kind: u16, data: &[u8]) -> Self {
+    let len = (mem::size_of::<RtAttr>() + data.len()) as u16;
+    self.bytes.extend_from_slice(&len.to_ne_bytes());
+    self.bytes.extend_from_slice(&kind.to_ne_bytes());
+    self.push_padded(data);
+    self
+  }
+
+  fn push_padded(&mut self, data: &[u8]) {
+    self.bytes.extend_from_slice(data);
+    self.bytes.resize(nlm_align_of(self.bytes.len()), 0);
+  }
+
+  fn finish(mut self) -> Vec<u8> {
+    let len = self.bytes.len() as u32;
+    self.bytes[0..4].copy_from_slice(&len.to_ne_bytes());
+    self.bytes
+  }
+}
+
+/// Sends one `NLM_F_ACK` request and waits for the kernel's verdict,
+/// returning its errno as the error.
+#[cfg(feature = "configure")]
+fn netlink_transact(msg: &[u8]) -> io::Result<()> {
+  unsafe {
+    let handle = Handle::new()?;
+    handle.send_bytes(msg)?;
+    let pid = handle.portid()?;
+
+    let mut rb = vec![0u8; rustix::param::page_size()];
+    loop {
+      let nr = handle.recv(&mut rb)?;
+      let mut received = &rb[..nr];
+
+      while received.len() >= NLMSG_HDRLEN {
+        let h = decode_nlmsghdr(received);
+        let hlen = h.nlmsg_len as usize;
+        let l = nlm_align_of(hlen);
+        if hlen < NLMSG_HDRLEN || l > received.len() {
+          return Err(malformed());
+        }
+
+        // Only the reply to our request ends the exchange; anything
+        // else on the socket is skipped.
+        if h.nlmsg_seq == 1 && h.nlmsg_pid == pid && h.nlmsg_type as u32 == NLMSG_ERROR {
+          if hlen < NLMSG_HDRLEN + 4 {
+            return Err(malformed());
+          }
+          let errno =
+            i32::from_ne_bytes(received[NLMSG_HDRLEN..NLMSG_HDRLEN + 4].try_into().unwrap());
+          return match errno {
+            0 => Ok(()),
+            errno => Err(io::Error::from_raw_os_error(errno.unsigned_abs() as i32)),
+          };
+        }
+
+        received = &received[l..];
+      }
+    }
+  }
+}
+
+/// Adds (`RTM_NEWADDR`) or removes (`RTM_DELADDR`) `net` on interface
+/// `index`. An IPv4 address gets `IFA_LOCAL` and `IFA_ADDRESS` set to
+/// the same value, as `ip address add` does for non-peer addresses.
+#[cfg(feature = "configure")]
+pub(super) fn netlink_set_addr(add: bool, index: u32, net: ipnet::IpNet) -> io::Result<()> {
+  let (family, addr) = match net.addr() {
+    IpAddr::V4(addr) => (AddressFamily::INET, addr.octets().to_vec()),
+    IpAddr::V6(addr) => (AddressFamily::INET6, addr.octets().to_vec()),
+  };
+  // `ifaddrmsg`: family, prefix length, flags, scope, index.
+  let mut body = [0u8; 8];
+  body[0] = family.as_raw() as u8;
+  body[1] = net.prefix_len();
+  body[4..8].copy_from_slice(&index.to_ne_bytes());
+
+  let (kind, flags) = if add {
+    (
+      RTM_NEWADDR,
+      netlink::NLM_F_ACK | netlink::NLM_F_CREATE | netlink::NLM_F_EXCL,
+    )
+  } else {
+    (RTM_DELADDR, netlink::NLM_F_ACK)
+  };
+  let msg = NetlinkMessage::new(kind as u16, flags as u16, &body)
+    .attr(IFA_LOCAL as u16, &addr)
+    .attr(IFA_ADDRESS as u16, &addr)
+    .finish();
+  netlink_transact(&msg)
+}
+
+/// The 12-byte `rtmsg` for a route to `dest` in the main table: family,
+/// destination and source prefix lengths, tos, table, protocol, scope,
+/// type and flags.
+#[cfg(feature = "configure")]
+fn route_rtmsg(dest: ipnet::IpNet, protocol: u8, scope: u8, kind: u8) -> [u8; 12] {
+  let family = match dest {
+    ipnet::IpNet::V4(_) => AddressFamily::INET,
+    ipnet::IpNet::V6(_) => AddressFamily::INET6,
+  };
+  let mut body = [0u8; 12];
+  body[0] = family.as_raw() as u8;
+  body[1] = dest.prefix_len();
+  body[4] = RT_TABLE_MAIN as u8;
+  body[5] = protocol;
+  body[6] = scope;
+  body[7] = kind;
+  body
+}
+
+fn ip_octets(addr: IpAddr) -> Vec<u8> {
+  match addr {
+    IpAddr::V4(addr) => addr.octets().to_vec(),
+    IpAddr::V6(addr) => addr.octets().to_vec(),
+  }
+}
+
+/// Adds (`RTM_NEWROUTE`) a static unicast route to `dest` in the main
+/// table, as `ip route add` does. A route without a gateway is on-link
+/// and gets link scope. `RTA_DST` is left out for a default route, and
+/// `RTA_OIF` when `ifindex` is `0`.
+#[cfg(feature = "configure")]
+pub(super) fn netlink_add_route(
+  dest: ipnet::IpNet,
+  gateway: Option<IpAddr>,
+  ifindex: u32,
+  metric: u32,
+) -> io::Result<()> {
+  let scope = if gateway.is_some() {
+    RT_SCOPE_UNIVERSE
+  } else {
+    RT_SCOPE_LINK
+  };
+  let body = route_rtmsg(dest, RTPROT_STATIC, scope, RTN_UNICAST);
+  let flags = netlink::NLM_F_ACK | netlink::NLM_F_CREATE | netlink::NLM_F_EXCL;
+  let mut msg = NetlinkMessage::new(RTM_NEWROUTE as u16, flags as u16, &body);
+  if dest.prefix_len() != 0 {
+    msg = msg.attr(RTA_DST, &ip_octets(dest.addr()));
+  }
+  if let Some(gateway) = gateway {
+    msg = msg.attr(RTA_GATEWAY, &ip_octets(gateway));
+  }
+  if ifindex != 0 {
+    msg = msg.attr(RTA_OIF, &ifindex.to_ne_bytes());
+  }
+  let msg = msg.attr(RTA_PRIORITY, &metric.to_ne_bytes()).finish();
+  netlink_transact(&msg)
+}
+
+/// Removes (`RTM_DELROUTE`) a route to `dest` from the main table.
+/// Protocol and type are left unspecified and the scope set to
+/// `RT_SCOPE_NOWHERE`, so the kernel matches the first route to `dest`
+/// whatever they are, as `ip route del` does.
+#[cfg(feature = "configure")]
+pub(super) fn netlink_del_route(dest: ipnet::IpNet) -> io::Result<()> {
+  let body = route_rtmsg(dest, 0, RT_SCOPE_NOWHERE, 0);
+  let mut msg = NetlinkMessage::new(RTM_DELROUTE as u16, netlink::NLM_F_ACK as u16, &body);
+  if dest.prefix_len() != 0 {
+    msg = msg.attr(RTA_DST, &ip_octets(dest.addr()));
+  }
+  netlink_transact(&msg.finish())
+}
+
+/// Asks the kernel which route it would use for `dest` with a single
+/// `RTM_GETROUTE` request (no `NLM_F_DUMP`), as `ip route get` does.
+/// Returns the output interface (`RTA_OIF`) and preferred source
+/// (`RTA_PREFSRC`) of the answer, or `None` when `dest` is unreachable.
+pub(super) fn netlink_route_get(dest: IpAddr) -> io::Result<Option<(u32, Option<IpAddr>)>> {
+  use rustix::io::Errno;
+
+  let family = match dest {
+    IpAddr::V4(_) => AddressFamily::INET,
+    IpAddr::V6(_) => AddressFamily::INET6,
+  };
+  // `rtmsg`: family, destination prefix length, then zeroes.
+  let mut body = [0u8; 12];
+  body[0] = family.as_raw() as u8;
+  body[1] = match dest {
+    IpAddr::V4(_) => 32,
+    IpAddr::V6(_) => 128,
+  };
+  let msg = NetlinkMessage::new(RTM_GETROUTE as u16, 0, &body)
+    .attr(RTA_DST, &ip_octets(dest))
+    .finish();
+
+  unsafe {
+    let handle = Handle::new()?;
+    handle.send_bytes(&msg)?;
+    let pid = handle.portid()?;
+
+    let mut rb = vec![0u8; rustix::param::page_size()];
+    loop {
+      let nr = handle.recv(&mut rb)?;
+      let mut received = &rb[..nr];
+
+      while received.len() >= NLMSG_HDRLEN {
+        let h = decode_nlmsghdr(received);
+        let hlen = h.nlmsg_len as usize;
+        let l = nlm_align_of(hlen);
+        if hlen < NLMSG_HDRLEN || hlen > received.len() {
+          return Err(malformed());
+        }
+        if h.nlmsg_seq != 1 || h.nlmsg_pid != pid {
+          received = &received[l.min(received.len())..];
+          continue;
+        }
+
+        match h.nlmsg_type as u32 {
+          NLMSG_ERROR => {
+            return match decode_nlmsgerr(received, hlen) {
+              Ok(_) => Ok(None),
+              Err(e)
+                if e.raw_os_error() == Some(Errno::NETUNREACH.raw_os_error())
+                  || e.raw_os_error() == Some(Errno::HOSTUNREACH.raw_os_error()) =>
+              {
+                Ok(None)
               }
+              Err(e) => Err(e),
+            };
+          }
+          RTM_NEWROUTE => {
+            let msg_buf = &received[NLMSG_HDRLEN..hlen];
+            if msg_buf.len() < body.len() {
+              return Err(malformed());
             }
-
-            for (attr, data) in attrs.iter() {
-              if point_to_point && attr.ty == IFA_ADDRESS as u16 {
-                continue;
+            // `rtm_type`: anything but unicast and local (unreachable,
+            // prohibit, blackhole) means no packet would be sent.
+            if !matches!(msg_buf[7], RTN_UNICAST | RTN_LOCAL) {
+              return Ok(None);
+            }
+            let (mut oif, mut src) = (0, None);
+            let mut attrs = &msg_buf[body.len()..];
+            while attrs.len() >= RtAttr::SIZE {
+              let alen = u16::from_ne_bytes(attrs[..2].try_into().unwrap()) as usize;
+              let ty = u16::from_ne_bytes(attrs[2..4].try_into().unwrap());
+              if alen < RtAttr::SIZE || alen > attrs.len() {
+                return Err(malformed());
               }
-
-              match AddressFamily::from_raw(ifam.family as u16) {
-                AddressFamily::INET if data.len() >= 4 => {
-                  let ip: [u8; 4] = data[..4].try_into().unwrap();
-                  if attr.ty == IFA_ADDRESS as u16 || attr.ty == IFA_LOCAL as u16 {
-                    if let Some(addr) =
-                      N::try_from_with_filter(ifam.index, ip.into(), ifam.prefix_len, |addr| {
-                        f(addr)
-                      })
-                    {
-                      addrs.push(addr);
-                    }
-                  }
-                }
-                AddressFamily::INET6 if data.len() >= 16 => {
-                  let ip: [u8; 16] = data[..16].try_into().unwrap();
-                  if attr.ty == IFA_ADDRESS as u16 || attr.ty == IFA_LOCAL as u16 {
-                    if let Some(addr) =
-                      N::try_from_with_filter(ifam.index, ip.into(), ifam.prefix_len, |addr| {
-                        f(addr)
-                      })
-                    {
-                      addrs.push(addr);
-                    }
-                  }
+              let data = &attrs[RtAttr::SIZE..alen];
+              match ty {
+                RTA_OIF if data.len() >= 4 => {
+                  oif = u32::from_ne_bytes(data[..4].try_into().unwrap());
                 }
+                RTA_PREFSRC => src = parse_rta_ipaddr(msg_buf[0], data),
                 _ => {}
               }
+              attrs = &attrs[rta_align_of(alen).min(attrs.len())..];
             }
+            return Ok((oif != 0).then_some((oif, src)));
           }
           _ => {}
         }
 
-        received = &received[l..];
+        received = &received[l.min(received.len())..];
       }
     }
-
-    Ok(())
   }
 }
 
-pub fn netlink_best_local_addrs<N>(family: AddressFamily) -> io::Result<SmallVec<N>>
+/// Decodes one `RTM_NEWNEIGH` body: a 12-byte `ndmsg` (family, pad,
+/// pad, ifindex, state, flags, type) followed by attributes.
+fn decode_neigh_message<F>(msg_buf: &[u8], on_neigh: &mut F) -> io::Result<()>
 where
-  N: Net,
+  F: FnMut(u32, IpAddr, Option<MacAddr>, u16),
 {
-  let mut out = SmallVec::new();
-  netlink_best_local_addrs_into(family, &mut out)?;
-  Ok(out)
+  const NDMSG_SIZE: usize = 12;
+  if msg_buf.len() < NDMSG_SIZE {
+    return Err(malformed());
+  }
+  let family = msg_buf[0];
+  let index = u32::from_ne_bytes(msg_buf[4..8].try_into().unwrap());
+  let state = u16::from_ne_bytes(msg_buf[8..10].try_into().unwrap());
+
+  let mut dst = None;
+  let mut lladdr = None;
+  let mut data = &msg_buf[NDMSG_SIZE..];
+  while data.len() >= RtAttr::SIZE {
+    let alen = u16::from_ne_bytes(data[..2].try_into().unwrap()) as usize;
+    let ty = u16::from_ne_bytes(data[2..4].try_into().unwrap());
+    if alen < RtAttr::SIZE || alen > data.len() {
+      return Err(malformed());
+    }
+    let payload = &data[RtAttr::SIZE..alen];
+    match ty {
+      NDA_DST => dst = parse_rta_ipaddr(family, payload),
+      NDA_LLADDR if payload.len() == MAC_ADDRESS_SIZE => {
+        lladdr = Some(MacAddr::from_raw(payload.try_into().unwrap()));
+      }
+      _ => {}
+    }
+    data = &data[rta_align_of(alen).min(data.len())..];
+  }
+
+  if let Some(ip) = dst {
+    on_neigh(index, ip, lladdr, state);
+  }
+  Ok(())
+}
+
+/// A netlink socket subscribed to the IPv4 and IPv6 address multicast
+/// groups and, when built with [`AddrWatcher::with_links`], the link
+/// group. Route and neighbour churn never wakes the reader.
+///
+/// Unlike [`Handle`], this one has to `bind()`: group membership is
+/// part of the bind address. Android's `untrusted_app` SELinux domain
+/// denies that, so apps there get `PermissionDenied` from `new`.
+pub(crate) struct AddrWatcher {
+  fd: OwnedFd,
+  /// Events decoded from the last datagram but not handed out yet.
+  pending: RefCell<VecDeque<Event>>,
+}
+
+impl AddrWatcher {
+  const ADDR_GROUPS: u32 = netlink::RTMGRP_IPV4_IFADDR | netlink::RTMGRP_IPV6_IFADDR;
+
+  pub(crate) fn new() -> io::Result<Self> {
+    Self::bind_groups(Self::ADDR_GROUPS)
+  }
+
+  /// Like [`AddrWatcher::new`], but also joined to `RTMGRP_LINK`, so
+  /// `RTM_NEWLINK` / `RTM_DELLINK` surface as link events.
+  pub(crate) fn with_links() -> io::Result<Self> {
+    Self::bind_groups(Self::ADDR_GROUPS | netlink::RTMGRP_LINK)
+  }
+
+  fn bind_groups(groups: u32) -> io::Result<Self> {
+    let fd = socket(AddressFamily::NETLINK, SocketType::RAW, None)?;
+    bind(&fd, &SocketAddrNetlink::new(0, groups))?;
+    Ok(Self {
+      fd,
+      pending: RefCell::new(VecDeque::new()),
+    })
+  }
+
+  /// Switches `recv` between blocking and returning `WouldBlock` when
+  /// no datagram is queued.
+  pub(crate) fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+    rustix::io::ioctl_fionbio(&self.fd, nonblocking).map_err(Into::into)
+  }
+
+  #[inline]
+  pub(crate) fn as_fd(&self) -> BorrowedFd<'_> {
+    self.fd.as_fd()
+  }
+
+  /// Blocks until the next event, or until `deadline` passes. An
+  /// `ENOBUFS` error means the kernel dropped notifications because
+  /// the reader fell behind; callers should re-dump the state they
+  /// track.
+  pub(crate) fn recv(&self, deadline: Option<Instant>) -> io::Result<Event> {
+    let mut buf = vec![0u8; rustix::param::page_size()];
+    loop {
+      if let Some(event) = self.pending.borrow_mut().pop_front() {
+        return Ok(event);
+      }
+
+      if let Some(deadline) = deadline {
+        crate::utils::poll_readable(self.fd.as_fd(), deadline)?;
+      }
+      let Some(nr) = recv_datagram(self.fd.as_fd(), &mut buf)? else {
+        continue;
+      };
+      let mut received = &buf[..nr];
+      while received.len() >= NLMSG_HDRLEN {
+        let h = decode_nlmsghdr(received);
+        let hlen = h.nlmsg_len as usize;
+        let l = nlm_align_of(hlen);
+        if hlen < NLMSG_HDRLEN || hlen > received.len() {
+          return Err(malformed());
+        }
+
+        let msg_buf = &received[NLMSG_HDRLEN..hlen];
+        let added = match h.nlmsg_type as u32 {
+          RTM_NEWADDR => Some(true),
+          RTM_DELADDR => Some(false),
+          ty @ (RTM_NEWLINK | RTM_DELLINK) => {
+            let index = IfInfoMessageHeader::parse(msg_buf)?.index as u32;
+            self.pending.borrow_mut().push_back(if ty == RTM_NEWLINK {
+              Event::LinkAdded(index)
+            } else {
+              Event::LinkRemoved(index)
+            });
+            None
+          }
+          _ => None,
+        };
+        if let Some(added) = added {
+          let mut addrs: SmallVec<IfNet> = SmallVec::new();
+          // `push_into` never breaks, so there is no verdict to act on.
+          let _ = decode_addr_message(
+            msg_buf,
+            0,
+            AddrSelect::All,
+            &mut |_: &IpAddr| true,
+            &mut push_into(&mut addrs),
+          )?;
+          self
+            .pending
+            .borrow_mut()
+            .extend(addrs.into_iter().map(|addr| {
+              if added {
+                Event::AddrAdded(addr)
+              } else {
+                Event::AddrRemoved(addr)
+              }
+            }));
+        }
+
+        received = &received[l.min(received.len())..];
+      }
+    }
+  }
 }
 
-/// Variant of [`netlink_best_local_addrs`] that pushes into the
-/// caller's buffer. Lets the union `best_local_addrs()` walk both
-/// families without allocating intermediate per-family `SmallVec`s.
+/// Appends the local addresses of the interface(s) behind the best
+/// default route for `family` to the caller's buffer. Lets the union
+/// `best_local_addrs()` walk both families without allocating
+/// intermediate per-family `SmallVec`s. Returns whether a default
+/// route was found at all, so callers can tell "no route" from "no
+/// addresses on the routed interface". With `table` set the default
+/// route is looked up in that routing table alone.
 pub fn netlink_best_local_addrs_into<N>(
   family: AddressFamily,
+  table: Option<u32>,
   out: &mut SmallVec<N>,
-) -> io::Result<()>
+) -> io::Result<bool>
 where
   N: Net,
 {
+  // Fetch addresses for every selected interface, appending into the
+  // caller-provided buffer. Returns immediately on the first syscall
+  // failure; partial results stay in `out` (consistent with every
+  // other walker that pushes into a sink).
+  let oifs = netlink_best_default_oifs(family, table)?;
+  for &idx in oifs.iter() {
+    netlink_addr_into(family, idx, local_ip_filter, out)?;
+  }
+  Ok(!oifs.is_empty())
+}
+
+/// Returns the sorted, deduplicated interface indices behind the best
+/// default route(s) for `family` — every oif tied at the best
+/// `(table, metric, preference)` key. Empty when the family has no
+/// usable default route or no stack at all. With `table` set only that
+/// routing table's defaults compete; see [`table_admitted`].
+pub(crate) fn netlink_best_default_oifs(
+  family: AddressFamily,
+  table: Option<u32>,
+) -> io::Result<SmallVec<u32>> {
   unsafe {
     // Lazy nexthop-dump: don't pay the `RTM_GETNEXTHOP` round-trip
     // unless the route walk actually encounters an `RTA_NH_ID`
@@ -618,13 +1851,12 @@ where
 
     let handle = Handle::new()?;
 
-    let req = NetlinkRouteRequest::new(RTM_GETROUTE as u16, 1, family.as_raw() as u8, 0);
-    handle.send(&req)?;
+    handle.send_route_dump(family, 1, table)?;
 
     // Snapshot the kernel-assigned address so we can reject any reply
     // that doesn't belong to this socket — same defence the other
     // netlink walkers use.
-    let lsa = handle.sock()?;
+    let pid = handle.portid()?;
 
     // Route walks must accept any single message the kernel emits —
     // see `ROUTE_RECV_BUF_SIZE` for why a page is too small here.
@@ -668,10 +1900,10 @@ where
         // `RTM_NEWROUTE` would either panic the slice below or — if
         // `l == 0` — keep the inner loop from advancing forever.
         if hlen < NLMSG_HDRLEN || l > received.len() {
-          return Err(rustix::io::Errno::INVAL.into());
+          return Err(malformed());
         }
-        if h.nlmsg_seq != 1 || h.nlmsg_pid != lsa.pid() {
-          return Err(rustix::io::Errno::INVAL.into());
+        if h.nlmsg_seq != 1 || h.nlmsg_pid != pid {
+          return Err(malformed());
         }
 
         match h.nlmsg_type as u32 {
@@ -695,7 +1927,7 @@ where
             // instead of `Err`. Lets `best_local_addrs()` keep the
             // populated v4 result on a v6-disabled host (and vice
             // versa).
-            NlmsgErrOutcome::FamilyUnavailable => return Ok(()),
+            NlmsgErrOutcome::FamilyUnavailable => return Ok(SmallVec::new()),
           },
           val if val == RTM_NEWROUTE => {
             // See `netlink_interface` for why this is bounded to `hlen`.
@@ -812,7 +2044,7 @@ where
                 // corrupted kernel output could steer us to the wrong
                 // interface. Bail out in the same way the interface
                 // and address parsers above do.
-                return Err(rustix::io::Errno::INVAL.into());
+                return Err(malformed());
               }
               let data = &rtattr_buf[RtAttr::SIZE..attrlen];
               let alen = rta_align_of(attrlen).min(rtattr_buf.len());
@@ -869,9 +2101,7 @@ where
             if has_src_constraint
               || dst_specific
               || dst_malformed
-              || (table_id != RT_TABLE_MAIN as u32
-                && table_id != RT_TABLE_LOCAL
-                && table_id != RT_TABLE_DEFAULT)
+              || !table_admitted(table_id, table)
             {
               received = &received[l..];
               continue;
@@ -1002,15 +2232,7 @@ where
     // the same ifindex.
     best_oifs.sort_unstable();
     best_oifs.dedup();
-
-    // Fetch addresses for every selected interface, appending into
-    // the caller-provided buffer. Returns immediately on the first
-    // syscall failure; partial results stay in `out` (consistent with
-    // every other walker that pushes into a sink).
-    for idx in best_oifs {
-      netlink_addr_into(family, idx, local_ip_filter, out)?;
-    }
-    Ok(())
+    Ok(best_oifs)
   }
 }
 
@@ -1059,6 +2281,47 @@ fn build_nh_dump_request(seq: u32) -> [u8; 24] {
   bytes
 }
 
+/// Build the wire bytes for a non-dump `RTM_GETLINK` asking for the
+/// single link `ifi`. The kernel looks the link up by `ifi_index` in
+/// the `struct ifinfomsg` body and answers with one `RTM_NEWLINK`, or
+/// `ENODEV` when there is no such link.
+fn build_link_get_request(seq: u32, family: u8, ifi: u32) -> [u8; 32] {
+  let mut bytes = [0u8; 32];
+  // nlmsghdr (16 bytes)
+  bytes[0..4].copy_from_slice(&32u32.to_ne_bytes());
+  bytes[4..6].copy_from_slice(&(RTM_GETLINK as u16).to_ne_bytes());
+  bytes[6..8].copy_from_slice(&(NLM_F_REQUEST as u16).to_ne_bytes());
+  bytes[8..12].copy_from_slice(&seq.to_ne_bytes());
+  bytes[12..16].copy_from_slice(&std::process::id().to_ne_bytes());
+  // ifinfomsg body (16 bytes): family, pad, type=0, then the 32-bit
+  // index; flags and change stay zero.
+  bytes[16] = family;
+  bytes[20..24].copy_from_slice(&ifi.to_ne_bytes());
+  bytes
+}
+
+/// Build the wire bytes for an `RTM_GETADDR` dump restricted to one
+/// interface. Unlike the `rtgenmsg` body `NetlinkRouteRequest` sends,
+/// this carries a full `struct ifaddrmsg`, which is what a socket with
+/// `NETLINK_GET_STRICT_CHK` requires and where it reads `ifa_index`
+/// from. Kernels without strict checking accept the same bytes and
+/// ignore everything past the family.
+fn build_addr_dump_request(seq: u32, family: u8, ifi: u32) -> [u8; 24] {
+  let mut bytes = [0u8; 24];
+  // nlmsghdr (16 bytes)
+  bytes[0..4].copy_from_slice(&24u32.to_ne_bytes());
+  bytes[4..6].copy_from_slice(&(RTM_GETADDR as u16).to_ne_bytes());
+  bytes[6..8].copy_from_slice(&((NLM_F_DUMP | NLM_F_REQUEST) as u16).to_ne_bytes());
+  bytes[8..12].copy_from_slice(&seq.to_ne_bytes());
+  bytes[12..16].copy_from_slice(&std::process::id().to_ne_bytes());
+  // ifaddrmsg body (8 bytes): family, prefixlen=0, flags=0, scope=0,
+  // then the 32-bit interface index. Strict checking rejects any other
+  // nonzero selector.
+  bytes[16] = family;
+  bytes[20..24].copy_from_slice(&ifi.to_ne_bytes());
+  bytes
+}
+
 /// Dump every `RTM_NEWNEXTHOP` entry the kernel knows about and return
 /// them as a map keyed by nexthop id. Always dumps with
 /// `nh_family = AF_UNSPEC` — see `build_nh_dump_request` for why
@@ -1073,7 +2336,7 @@ fn dump_nexthops() -> io::Result<std::collections::HashMap<u32, NexthopInfo>> {
     let req = build_nh_dump_request(1);
     handle.send_bytes(&req)?;
 
-    let lsa = handle.sock()?;
+    let pid = handle.portid()?;
     // Nexthop dumps can carry deep `NHA_GROUP` payloads (8 bytes per
     // member); use the route-walk buffer size for the same reason
     // detailed at `ROUTE_RECV_BUF_SIZE`.
@@ -1090,10 +2353,10 @@ fn dump_nexthops() -> io::Result<std::collections::HashMap<u32, NexthopInfo>> {
         let hlen = h.nlmsg_len as usize;
         let l = nlm_align_of(hlen);
         if hlen < NLMSG_HDRLEN || l > received.len() {
-          return Err(rustix::io::Errno::INVAL.into());
+          return Err(malformed());
         }
-        if h.nlmsg_seq != 1 || h.nlmsg_pid != lsa.pid() {
-          return Err(rustix::io::Errno::INVAL.into());
+        if h.nlmsg_seq != 1 || h.nlmsg_pid != pid {
+          return Err(malformed());
         }
 
         match h.nlmsg_type as u32 {
@@ -1312,385 +2575,469 @@ fn resolve_nh_id(
   Some(out)
 }
 
-/// Yields one entry per `RTM_NEWROUTE` message: `(family, oif, dst_len, dst,
-/// gateway)`. `dst` is `None` when the kernel omits `RTA_DST` (default
-/// route). `gateway` is `None` when there is no `RTA_GATEWAY` (a directly
-/// attached / link-scope route). All other parsing is the caller's
-/// responsibility — this lets `route_table` / `route_ipv4_table` /
-/// `route_ipv6_table` build different concrete types from the same walk.
-pub(super) fn netlink_walk_routes<F>(family: AddressFamily, mut on_route: F) -> io::Result<()>
-where
-  F: FnMut(u8, u32, u8, Option<IpAddr>, Option<IpAddr>),
-{
+/// Issues a single `RTM_GETROUTE` dump request and inspects only the
+/// first reply. An in-band `NLMSG_ERROR` (e.g. `EACCES` from an SELinux
+/// policy) or a socket-level failure surfaces as `Err`; anything else
+/// means the kernel accepted the request. The rest of the dump is
+/// discarded when the socket is closed.
+pub(super) fn netlink_probe_routes() -> io::Result<()> {
   unsafe {
-    // Lazy nexthop-dump: we collect every `RTA_NH_ID` route we see
-    // during the route walk and resolve them in a single post-walk
-    // dump. This avoids paying the `RTM_GETNEXTHOP` round-trip when
-    // no route uses nexthop objects (the typical Linux host today,
-    // since `ip nexthop`-managed routes are a 5.3+ opt-in feature).
-    // It also decouples ordinary route enumeration from nexthop-
-    // subsystem availability — a transient `NLM_F_DUMP_INTR` or
-    // unrelated nexthop churn during the upfront dump used to fail
-    // `route_table()` even on hosts whose route table contains no
-    // `RTA_NH_ID` references.
-    //
-    // Same pattern `rt_generic_addrs` (the gateway walker) already
-    // uses; matching it here keeps the two paths consistent.
-    let mut deferred_nh: Vec<(u8, u8, Option<IpAddr>, u32)> = Vec::new();
-
     let handle = Handle::new()?;
 
-    let req = NetlinkRouteRequest::new(RTM_GETROUTE as u16, 1, family.as_raw() as u8, 0);
+    let req = NetlinkRouteRequest::new(RTM_GETROUTE as u16, 1, AddressFamily::INET.as_raw() as u8);
     handle.send(&req)?;
 
-    let lsa = handle.sock()?;
-    // See `ROUTE_RECV_BUF_SIZE`: a page is too small for routes that
-    // carry large `RTA_MULTIPATH` ECMP payloads.
     let mut rb = vec![0u8; ROUTE_RECV_BUF_SIZE];
+    let nr = handle.recv(&mut rb)?;
+    let received = &rb[..nr];
+    let h = decode_nlmsghdr(received);
+    let hlen = h.nlmsg_len as usize;
+    if hlen < NLMSG_HDRLEN || hlen > received.len() {
+      return Err(malformed());
+    }
+    if h.nlmsg_type as u32 == NLMSG_ERROR {
+      decode_nlmsgerr(received, hlen)?;
+    }
+    Ok(())
+  }
+}
 
-    'outer: loop {
-      let nr = handle.recv(&mut rb)?;
-      let mut received = &rb[..nr];
-
-      while received.len() >= NLMSG_HDRLEN {
-        let h = decode_nlmsghdr(received);
-        let hlen = h.nlmsg_len as usize;
-        let l = nlm_align_of(hlen);
-        if hlen < NLMSG_HDRLEN || l > received.len() {
-          return Err(rustix::io::Errno::INVAL.into());
-        }
-        if h.nlmsg_seq != 1 || h.nlmsg_pid != lsa.pid() {
-          return Err(rustix::io::Errno::INVAL.into());
-        }
+/// The Linux-only qualifiers the route walker reports alongside each
+/// `(oif, dst, gateway)` tuple.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct RouteQualifiers {
+  /// `rtm_tos`; `0` for a route that matches any ToS.
+  pub(super) tos: u8,
+  /// `RTA_FLOW`, if present.
+  pub(super) realm: Option<u32>,
+  /// `RTA_PRIORITY`; a missing attribute means metric `0`.
+  pub(super) metric: u32,
+  /// `RTA_METRICS` and the expiry from `RTA_CACHEINFO`.
+  pub(super) metrics: RouteMetrics,
+}
 
-        match h.nlmsg_type as u32 {
-          NLMSG_DONE => {
-            // The kernel marks the closing NLMSG_DONE with
-            // `NLM_F_DUMP_INTR` if the routing table changed during
-            // the dump (DHCP renewal, VPN connect/disconnect, an
-            // interface flap, container start, etc.). The snapshot
-            // we accumulated is silently incomplete in that case —
-            // surface as EINTR rather than treat it as success and
-            // hand back a half-walked table.
-            if h.nlmsg_flags as u32 & NLM_F_DUMP_INTR != 0 {
-              return Err(rustix::io::Errno::INTR.into());
-            }
-            break 'outer;
-          }
-          NLMSG_ERROR => match decode_nlmsgerr(received, hlen)? {
-            NlmsgErrOutcome::Ack => {
-              received = &received[l..];
-              continue;
-            }
-            // The requested family has no stack — surface as "no
-            // routes" so callers of `route_ipv6_table()` on a
-            // v4-only host get `Ok([])` instead of `Err`, and the
-            // union `route_table()` keeps whichever family is
-            // populated.
-            NlmsgErrOutcome::FamilyUnavailable => return Ok(()),
-          },
-          val if val == RTM_NEWROUTE => {
-            // Bound the per-message slice to `hlen` rather than the
-            // rest of the recv buffer. Netlink dumps routinely pack
-            // multiple `RTM_NEWROUTE` messages into one recv(); an
-            // unbounded slice would let the attribute walker below
-            // run into the next message's header, mixing fields
-            // across routes (or returning EINVAL on healthy kernel
-            // output).
-            let rtm = &received[NLMSG_HDRLEN..hlen];
-            let rtm_header = RtmMessageHeader::parse(rtm)?;
+/// Yields one entry per `RTM_NEWROUTE` message: `(family, oif, dst_len, dst,
+/// gateway, qualifiers)`. `dst` is `None` when the kernel omits `RTA_DST`
+/// (default route). `gateway` is `None` when there is no `RTA_GATEWAY` (a
+/// directly attached / link-scope route). All other parsing is the caller's
+/// responsibility — this lets `route_table` / `route_ipv4_table` /
+/// `route_ipv6_table` build different concrete types from the same walk.
+///
+/// With `table` set only that routing table is walked; otherwise the
+/// three built-in tables the default rule chain consults are.
+pub(super) fn netlink_walk_routes<F>(
+  family: AddressFamily,
+  table: Option<u32>,
+  on_route: F,
+) -> io::Result<()>
+where
+  F: FnMut(u8, u32, u8, Option<IpAddr>, Option<IpAddr>, RouteQualifiers),
+{
+  Session::new()?.walk_routes(family, table, on_route)
+}
 
-            // The `IpRoute` model (destination + single gateway + single
-            // output interface) only meaningfully represents
-            // RTN_UNICAST and RTN_LOCAL routes. Skip everything else
-            // — broadcast, multicast, blackhole, unreachable, prohibit,
-            // nat, etc. don't have a usable single (oif, gw) tuple,
-            // and emitting them as if they did would mislead callers.
-            if rtm_header.rtm_type != RTN_UNICAST && rtm_header.rtm_type != RTN_LOCAL {
-              received = &received[l..];
-              continue;
-            }
+impl Session {
+  /// Receives a route dump; see [`netlink_walk_routes`].
+  pub(super) fn walk_routes<F>(
+    &mut self,
+    family: AddressFamily,
+    table: Option<u32>,
+    mut on_route: F,
+  ) -> io::Result<()>
+  where
+    F: FnMut(u8, u32, u8, Option<IpAddr>, Option<IpAddr>, RouteQualifiers),
+  {
+    let seq = self.next_seq();
+    let handle = &self.handle;
 
-            // Source-constrained policy routes (`rtm_src_len != 0` or
-            // an `RTA_SRC` attribute present) only apply when the
-            // packet's source matches that prefix. The current
-            // `IpRoute` model has no field for the source constraint,
-            // so emitting these rows would make a constrained route
-            // look generally usable. Skip until the model carries
-            // source prefixes (the `RTA_SRC` check happens during the
-            // attribute walk below — flagged via `has_src_constraint`
-            // and applied before the final on_route call).
-            if rtm_header.rtm_src_len != 0 {
-              received = &received[l..];
-              continue;
-            }
+    unsafe {
+      // Lazy nexthop-dump: we collect every `RTA_NH_ID` route we see
+      // during the route walk and resolve them in a single post-walk
+      // dump. This avoids paying the `RTM_GETNEXTHOP` round-trip when
+      // no route uses nexthop objects (the typical Linux host today,
+      // since `ip nexthop`-managed routes are a 5.3+ opt-in feature).
+      // It also decouples ordinary route enumeration from nexthop-
+      // subsystem availability — a transient `NLM_F_DUMP_INTR` or
+      // unrelated nexthop churn during the upfront dump used to fail
+      // `route_table()` even on hosts whose route table contains no
+      // `RTA_NH_ID` references.
+      //
+      // Same pattern `rt_generic_addrs` (the gateway walker) already
+      // uses; matching it here keeps the two paths consistent.
+      let mut deferred_nh: Vec<(u8, u8, Option<IpAddr>, u32, RouteQualifiers)> = Vec::new();
+
+      handle.send_route_dump(family, seq, table)?;
+
+      let pid = handle.portid()?;
+      // See `ROUTE_RECV_BUF_SIZE`: a page is too small for routes that
+      // carry large `RTA_MULTIPATH` ECMP payloads.
+      let mut rb = vec![0u8; ROUTE_RECV_BUF_SIZE];
+
+      'outer: loop {
+        let nr = handle.recv(&mut rb)?;
+        let mut received = &rb[..nr];
+
+        while received.len() >= NLMSG_HDRLEN {
+          let h = decode_nlmsghdr(received);
+          let hlen = h.nlmsg_len as usize;
+          let l = nlm_align_of(hlen);
+          if hlen < NLMSG_HDRLEN || l > received.len() {
+            return Err(malformed());
+          }
+          if h.nlmsg_seq != seq || h.nlmsg_pid != pid {
+            return Err(malformed());
+          }
 
-            // TOS-specific routes only apply to packets whose IP ToS
-            // byte matches `rtm_tos`. Emitting them as ordinary routes
-            // would make a TOS-conditional route look usable for any
-            // traffic. `IpRoute` has no TOS field, so skip.
-            if rtm_header.rtm_tos != 0 {
-              received = &received[l..];
-              continue;
+          match h.nlmsg_type as u32 {
+            NLMSG_DONE => {
+              // The kernel marks the closing NLMSG_DONE with
+              // `NLM_F_DUMP_INTR` if the routing table changed during
+              // the dump (DHCP renewal, VPN connect/disconnect, an
+              // interface flap, container start, etc.). The snapshot
+              // we accumulated is silently incomplete in that case —
+              // surface as EINTR rather than treat it as success and
+              // hand back a half-walked table.
+              if h.nlmsg_flags as u32 & NLM_F_DUMP_INTR != 0 {
+                return Err(rustix::io::Errno::INTR.into());
+              }
+              break 'outer;
             }
+            NLMSG_ERROR => match decode_nlmsgerr(received, hlen)? {
+              NlmsgErrOutcome::Ack => {
+                received = &received[l..];
+                continue;
+              }
+              // The requested family has no stack — surface as "no
+              // routes" so callers of `route_ipv6_table()` on a
+              // v4-only host get `Ok([])` instead of `Err`, and the
+              // union `route_table()` keeps whichever family is
+              // populated.
+              NlmsgErrOutcome::FamilyUnavailable => return Ok(()),
+            },
+            val if val == RTM_NEWROUTE => {
+              // Bound the per-message slice to `hlen` rather than the
+              // rest of the recv buffer. Netlink dumps routinely pack
+              // multiple `RTM_NEWROUTE` messages into one recv(); an
+              // unbounded slice would let the attribute walker below
+              // run into the next message's header, mixing fields
+              // across routes (or rejecting healthy kernel output as
+              // malformed).
+              let rtm = &received[NLMSG_HDRLEN..hlen];
+              let rtm_header = RtmMessageHeader::parse(rtm)?;
+
+              // The `IpRoute` model (destination + single gateway + single
+              // output interface) only meaningfully represents
+              // RTN_UNICAST and RTN_LOCAL routes. Skip everything else
+              // — broadcast, multicast, blackhole, unreachable, prohibit,
+              // nat, etc. don't have a usable single (oif, gw) tuple,
+              // and emitting them as if they did would mislead callers.
+              if rtm_header.rtm_type != RTN_UNICAST && rtm_header.rtm_type != RTN_LOCAL {
+                received = &received[l..];
+                continue;
+              }
 
-            let mut rtattr_buf = &rtm[RtmMessageHeader::SIZE..];
-            let mut oif: u32 = 0;
-            let mut dst: Option<IpAddr> = None;
-            let mut gw: Option<IpAddr> = None;
-            let mut has_src_constraint = false;
-            // Track present-but-malformed for RTA_DST / RTA_GATEWAY.
-            // `parse_rta_ipaddr` returns `None` for either "the
-            // attribute had a wrong-family / too-short payload" *or*
-            // "the attribute wasn't there." Keeping a separate
-            // present-flag lets us reject a malformed attribute
-            // outright without conflating it with the legitimate
-            // "default-route" / "on-link" encodings (`dst` absent
-            // with `rtm_dst_len == 0`, `gw` absent for direct
-            // routes). Without this, a kernel emitting a wrong-sized
-            // RTA_DST alongside `rtm_dst_len = 24` would surface as
-            // `0.0.0.0/24`.
-            let mut dst_present = false;
-            let mut dst_malformed = false;
-            let mut gw_malformed = false;
-            // Set true when the route carries a cross-family
-            // `RTA_VIA`. See the constant's doc comment for why we
-            // skip these — the route walker can't represent a
-            // mismatched-family gateway with `IpRoute`.
-            let mut has_via = false;
-            // Linux returns the full table id either inline in
-            // `rtm_table` (values 0..=255) or via an RTA_TABLE
-            // attribute when the id exceeds 255 — the kernel sets
-            // `rtm_table = RT_TABLE_UNSPEC (0)` in that case. Track
-            // the effective id so we can drop custom policy tables.
-            let mut table_id: u32 = rtm_header.rtm_table as u32;
-            // Routes installed via `ip nexthop` carry only an
-            // RTA_NH_ID and no top-level RTA_OIF / RTA_MULTIPATH. We
-            // capture the id and resolve it against the up-front
-            // RTM_GETNEXTHOP dump (`nh_map`) — this lets `route_table`
-            // surface default routes installed through nexthop objects
-            // (Linux 5.3+) that would otherwise be silently dropped by
-            // the `oif == 0` guard.
-            let mut nh_id: Option<u32> = None;
-            // ECMP routes carry their nexthops inside RTA_MULTIPATH
-            // (one or more `struct rtnexthop` each with sub-attrs).
-            // We accumulate them and emit them after walking the
-            // top-level attribute list, so we know `dst` / `dst_len`
-            // before fanning out per-nexthop.
-            let mut multipath: Option<&[u8]> = None;
-
-            while rtattr_buf.len() >= RtAttr::SIZE {
-              let attr = RtAttr {
-                len: u16::from_ne_bytes(rtattr_buf[..2].try_into().unwrap()),
-                ty: u16::from_ne_bytes(rtattr_buf[2..4].try_into().unwrap()),
-              };
-              let attrlen = attr.len as usize;
-              if attrlen < RtAttr::SIZE || attrlen > rtattr_buf.len() {
-                return Err(rustix::io::Errno::INVAL.into());
+              // Source-constrained policy routes (`rtm_src_len != 0` or
+              // an `RTA_SRC` attribute present) only apply when the
+              // packet's source matches that prefix. The current
+              // `IpRoute` model has no field for the source constraint,
+              // so emitting these rows would make a constrained route
+              // look generally usable. Skip until the model carries
+              // source prefixes (the `RTA_SRC` check happens during the
+              // attribute walk below — flagged via `has_src_constraint`
+              // and applied before the final on_route call).
+              if rtm_header.rtm_src_len != 0 {
+                received = &received[l..];
+                continue;
               }
-              let data = &rtattr_buf[RtAttr::SIZE..attrlen];
-              let alen = rta_align_of(attrlen).min(rtattr_buf.len());
 
-              match attr.ty {
-                RTA_OIF if data.len() >= 4 => {
-                  oif = u32::from_ne_bytes(data[..4].try_into().unwrap());
+              // TOS-specific routes only apply to packets whose IP ToS
+              // byte matches `rtm_tos`. The ToS travels in `qualifiers`;
+              // the route-table walks drop such rows unless the caller
+              // asked for them (`route_table_with_tos`).
+              let mut qualifiers = RouteQualifiers {
+                tos: rtm_header.rtm_tos,
+                realm: None,
+                metric: 0,
+                metrics: RouteMetrics::default(),
+              };
+              let mut route_metrics = None;
+              let mut expires = None;
+
+              let mut rtattr_buf = &rtm[RtmMessageHeader::SIZE..];
+              let mut oif: u32 = 0;
+              let mut dst: Option<IpAddr> = None;
+              let mut gw: Option<IpAddr> = None;
+              let mut has_src_constraint = false;
+              // Track present-but-malformed for RTA_DST / RTA_GATEWAY.
+              // `parse_rta_ipaddr` returns `None` for either "the
+              // attribute had a wrong-family / too-short payload" *or*
+              // "the attribute wasn't there." Keeping a separate
+              // present-flag lets us reject a malformed attribute
+              // outright without conflating it with the legitimate
+              // "default-route" / "on-link" encodings (`dst` absent
+              // with `rtm_dst_len == 0`, `gw` absent for direct
+              // routes). Without this, a kernel emitting a wrong-sized
+              // RTA_DST alongside `rtm_dst_len = 24` would surface as
+              // `0.0.0.0/24`.
+              let mut dst_present = false;
+              let mut dst_malformed = false;
+              let mut gw_malformed = false;
+              // Set true when the route carries a cross-family
+              // `RTA_VIA`. See the constant's doc comment for why we
+              // skip these — the route walker can't represent a
+              // mismatched-family gateway with `IpRoute`.
+              let mut has_via = false;
+              // Linux returns the full table id either inline in
+              // `rtm_table` (values 0..=255) or via an RTA_TABLE
+              // attribute when the id exceeds 255 — the kernel sets
+              // `rtm_table = RT_TABLE_UNSPEC (0)` in that case. Track
+              // the effective id so we can drop custom policy tables.
+              let mut table_id: u32 = rtm_header.rtm_table as u32;
+              // Routes installed via `ip nexthop` carry only an
+              // RTA_NH_ID and no top-level RTA_OIF / RTA_MULTIPATH. We
+              // capture the id and resolve it against the up-front
+              // RTM_GETNEXTHOP dump (`nh_map`) — this lets `route_table`
+              // surface default routes installed through nexthop objects
+              // (Linux 5.3+) that would otherwise be silently dropped by
+              // the `oif == 0` guard.
+              let mut nh_id: Option<u32> = None;
+              // ECMP routes carry their nexthops inside RTA_MULTIPATH
+              // (one or more `struct rtnexthop` each with sub-attrs).
+              // We accumulate them and emit them after walking the
+              // top-level attribute list, so we know `dst` / `dst_len`
+              // before fanning out per-nexthop.
+              let mut multipath: Option<&[u8]> = None;
+
+              while rtattr_buf.len() >= RtAttr::SIZE {
+                let attr = RtAttr {
+                  len: u16::from_ne_bytes(rtattr_buf[..2].try_into().unwrap()),
+                  ty: u16::from_ne_bytes(rtattr_buf[2..4].try_into().unwrap()),
+                };
+                let attrlen = attr.len as usize;
+                if attrlen < RtAttr::SIZE || attrlen > rtattr_buf.len() {
+                  return Err(malformed());
                 }
-                RTA_DST => {
-                  dst_present = true;
-                  dst = parse_rta_ipaddr(rtm_header.rtm_family, data);
-                  if dst.is_none() {
-                    dst_malformed = true;
+                let data = &rtattr_buf[RtAttr::SIZE..attrlen];
+                let alen = rta_align_of(attrlen).min(rtattr_buf.len());
+
+                match attr.ty {
+                  RTA_OIF if data.len() >= 4 => {
+                    oif = u32::from_ne_bytes(data[..4].try_into().unwrap());
                   }
-                }
-                RTA_GATEWAY => {
-                  gw = parse_rta_ipaddr(rtm_header.rtm_family, data);
-                  if gw.is_none() {
-                    gw_malformed = true;
+                  RTA_DST => {
+                    dst_present = true;
+                    dst = parse_rta_ipaddr(rtm_header.rtm_family, data);
+                    if dst.is_none() {
+                      dst_malformed = true;
+                    }
                   }
+                  RTA_GATEWAY => {
+                    gw = parse_rta_ipaddr(rtm_header.rtm_family, data);
+                    if gw.is_none() {
+                      gw_malformed = true;
+                    }
+                  }
+                  RTA_VIA => {
+                    // Cross-family gateway. `IpRoute` can't represent
+                    // an IPv4 route with an IPv6 next-hop or vice
+                    // versa, and treating the route as on-link
+                    // (`gw = None`) would silently misroute. Mark and
+                    // skip after the walk.
+                    has_via = true;
+                  }
+                  RTA_MULTIPATH => {
+                    multipath = Some(data);
+                  }
+                  RTA_SRC => {
+                    // Source constraint present even though `rtm_src_len`
+                    // was zero — defence-in-depth flag.
+                    has_src_constraint = true;
+                  }
+                  RTA_TABLE if data.len() >= 4 => {
+                    table_id = u32::from_ne_bytes(data[..4].try_into().unwrap());
+                  }
+                  RTA_NH_ID if data.len() >= 4 => {
+                    nh_id = Some(u32::from_ne_bytes(data[..4].try_into().unwrap()));
+                  }
+                  RTA_PRIORITY if data.len() >= 4 => {
+                    qualifiers.metric = u32::from_ne_bytes(data[..4].try_into().unwrap());
+                  }
+                  RTA_FLOW if data.len() >= 4 => {
+                    qualifiers.realm = Some(u32::from_ne_bytes(data[..4].try_into().unwrap()));
+                  }
+                  RTA_METRICS => route_metrics = Some(data),
+                  RTA_CACHEINFO => expires = parse_rta_cacheinfo_expires(data),
+                  _ => {}
                 }
-                RTA_VIA => {
-                  // Cross-family gateway. `IpRoute` can't represent
-                  // an IPv4 route with an IPv6 next-hop or vice
-                  // versa, and treating the route as on-link
-                  // (`gw = None`) would silently misroute. Mark and
-                  // skip after the walk.
-                  has_via = true;
-                }
-                RTA_MULTIPATH => {
-                  multipath = Some(data);
-                }
-                RTA_SRC => {
-                  // Source constraint present even though `rtm_src_len`
-                  // was zero — defence-in-depth flag.
-                  has_src_constraint = true;
-                }
-                RTA_TABLE if data.len() >= 4 => {
-                  table_id = u32::from_ne_bytes(data[..4].try_into().unwrap());
-                }
-                RTA_NH_ID if data.len() >= 4 => {
-                  nh_id = Some(u32::from_ne_bytes(data[..4].try_into().unwrap()));
-                }
-                _ => {}
+
+                rtattr_buf = &rtattr_buf[alen..];
               }
 
-              rtattr_buf = &rtattr_buf[alen..];
-            }
+              qualifiers.metrics =
+                route_metrics_from_attrs(route_metrics.unwrap_or_default(), expires);
+
+              // Reject malformed routes before any further processing:
+              //   - RTA_DST present but unparseable (wrong family / too
+              //     short).
+              //   - RTA_DST absent but `rtm_dst_len != 0`. The "default
+              //     route" encoding is `dst absent + dst_len == 0`;
+              //     anything else means the kernel claimed a non-zero
+              //     prefix length without supplying the address, which
+              //     would synthesize a fake `0.0.0.0/N` / `::/N`.
+              //   - RTA_GATEWAY present but unparseable. Treating a
+              //     malformed gateway as `None` would silently
+              //     downgrade the route to "on-link", which is a
+              //     different routing decision.
+              if dst_malformed
+                || gw_malformed
+                || has_via
+                || (dst.is_none() && rtm_header.rtm_dst_len != 0)
+              {
+                received = &received[l..];
+                continue;
+              }
+              // Suppress the "unused" warning for the present flag —
+              // `dst_malformed` already encodes the real branch we care
+              // about.
+              let _ = dst_present;
 
-            // Reject malformed routes before any further processing:
-            //   - RTA_DST present but unparseable (wrong family / too
-            //     short).
-            //   - RTA_DST absent but `rtm_dst_len != 0`. The "default
-            //     route" encoding is `dst absent + dst_len == 0`;
-            //     anything else means the kernel claimed a non-zero
-            //     prefix length without supplying the address, which
-            //     would synthesize a fake `0.0.0.0/N` / `::/N`.
-            //   - RTA_GATEWAY present but unparseable. Treating a
-            //     malformed gateway as `None` would silently
-            //     downgrade the route to "on-link", which is a
-            //     different routing decision.
-            if dst_malformed
-              || gw_malformed
-              || has_via
-              || (dst.is_none() && rtm_header.rtm_dst_len != 0)
-            {
-              received = &received[l..];
-              continue;
-            }
-            // Suppress the "unused" warning for the present flag —
-            // `dst_malformed` already encodes the real branch we care
-            // about.
-            let _ = dst_present;
+              // Skip if a source constraint snuck in via RTA_SRC.
+              if has_src_constraint {
+                received = &received[l..];
+                continue;
+              }
 
-            // Skip if a source constraint snuck in via RTA_SRC.
-            if has_src_constraint {
-              received = &received[l..];
-              continue;
-            }
+              // Unless the caller named a table, drop routes from custom
+              // policy tables; see `table_admitted`.
+              if !table_admitted(table_id, table) {
+                received = &received[l..];
+                continue;
+              }
 
-            // Drop routes from custom policy tables. The three
-            // standard RPDB tables consulted by the default rule
-            // chain are `local` (255), `main` (254), and `default`
-            // (253); together they describe what the kernel would
-            // actually do for any outbound packet on a host without
-            // custom `ip rule` policy. Anything outside that set is
-            // a custom policy table selected by `ip rule` with
-            // fwmark / iif / uid / etc., whose constraints aren't
-            // representable in `IpRoute`.
-            if table_id != RT_TABLE_MAIN as u32
-              && table_id != RT_TABLE_LOCAL
-              && table_id != RT_TABLE_DEFAULT
-            {
-              received = &received[l..];
-              continue;
-            }
+              // Resolve nexthop-object references. The route had only an
+              // RTA_NH_ID — look up the nexthop in the dump map. Single
+              // leaves emit one route; groups fan out to one route per
+              // member (similar to RTA_MULTIPATH).
+              //
+              // Lazy resolution: defer every `RTA_NH_ID` route to the
+              // post-walk pass. The pass dumps `RTM_GETNEXTHOP` once
+              // and resolves the entire batch — same correctness as
+              // dump-up-front, lower cost when no route uses nexthop
+              // objects.
+              //
+              // `resolve_nh_id` outcomes (handled by the post-walk
+              // block):
+              //   - `None`: id absent from the dump. Surface as
+              //     `EINTR` so the caller can retry — kernel state
+              //     was changing during enumeration.
+              //   - `Some(empty)`: id present but unusable
+              //     (blackhole / dead / linkdown / unresolved, or a
+              //     group whose members are all filtered). Skip the
+              //     route silently.
+              //   - `Some(non-empty)`: emit one route per resolved
+              //     `(oif, gw)`.
+              if let Some(id) = nh_id {
+                deferred_nh.push((
+                  rtm_header.rtm_family,
+                  rtm_header.rtm_dst_len,
+                  dst,
+                  id,
+                  qualifiers,
+                ));
+                received = &received[l..];
+                continue;
+              }
 
-            // Resolve nexthop-object references. The route had only an
-            // RTA_NH_ID — look up the nexthop in the dump map. Single
-            // leaves emit one route; groups fan out to one route per
-            // member (similar to RTA_MULTIPATH).
-            //
-            // Lazy resolution: defer every `RTA_NH_ID` route to the
-            // post-walk pass. The pass dumps `RTM_GETNEXTHOP` once
-            // and resolves the entire batch — same correctness as
-            // dump-up-front, lower cost when no route uses nexthop
-            // objects.
-            //
-            // `resolve_nh_id` outcomes (handled by the post-walk
-            // block):
-            //   - `None`: id absent from the dump. Surface as
-            //     `EINTR` so the caller can retry — kernel state
-            //     was changing during enumeration.
-            //   - `Some(empty)`: id present but unusable
-            //     (blackhole / dead / linkdown / unresolved, or a
-            //     group whose members are all filtered). Skip the
-            //     route silently.
-            //   - `Some(non-empty)`: emit one route per resolved
-            //     `(oif, gw)`.
-            if let Some(id) = nh_id {
-              deferred_nh.push((rtm_header.rtm_family, rtm_header.rtm_dst_len, dst, id));
-              received = &received[l..];
-              continue;
-            }
+              // For ECMP routes, decode `RTA_MULTIPATH` and emit one
+              // route per nexthop. The wire format of each nexthop is
+              // `struct rtnexthop { u16 rtnh_len; u8 rtnh_flags; u8
+              // rtnh_hops; s32 rtnh_ifindex; }` followed by RTA-encoded
+              // sub-attributes (typically RTA_GATEWAY). On a multi-WAN
+              // host where the kernel installs only `default nexthop
+              // via A dev e0 nexthop via B dev e1`, the previous "skip
+              // ECMP" behaviour caused `route_table_by_filter(|r|
+              // r.is_default())` to return *no* default route.
+              if let Some(mp) = multipath {
+                walk_multipath(
+                  rtm_header.rtm_family,
+                  rtm_header.rtm_dst_len,
+                  dst,
+                  mp,
+                  qualifiers,
+                  &mut on_route,
+                );
+                received = &received[l..];
+                continue;
+              }
+
+              // Skip routes that arrived without RTA_OIF and weren't
+              // ECMP — emitting `oif=0` would mislead callers into
+              // thinking the route was usable on interface 0.
+              if oif == 0 {
+                received = &received[l..];
+                continue;
+              }
 
-            // For ECMP routes, decode `RTA_MULTIPATH` and emit one
-            // route per nexthop. The wire format of each nexthop is
-            // `struct rtnexthop { u16 rtnh_len; u8 rtnh_flags; u8
-            // rtnh_hops; s32 rtnh_ifindex; }` followed by RTA-encoded
-            // sub-attributes (typically RTA_GATEWAY). On a multi-WAN
-            // host where the kernel installs only `default nexthop
-            // via A dev e0 nexthop via B dev e1`, the previous "skip
-            // ECMP" behaviour caused `route_table_by_filter(|r|
-            // r.is_default())` to return *no* default route.
-            if let Some(mp) = multipath {
-              walk_multipath(
+              on_route(
                 rtm_header.rtm_family,
+                oif,
                 rtm_header.rtm_dst_len,
                 dst,
-                mp,
-                &mut on_route,
+                gw,
+                qualifiers,
               );
-              received = &received[l..];
-              continue;
-            }
-
-            // Skip routes that arrived without RTA_OIF and weren't
-            // ECMP — emitting `oif=0` would mislead callers into
-            // thinking the route was usable on interface 0.
-            if oif == 0 {
-              received = &received[l..];
-              continue;
             }
-
-            on_route(rtm_header.rtm_family, oif, rtm_header.rtm_dst_len, dst, gw);
+            _ => {}
           }
-          _ => {}
-        }
 
-        received = &received[l..];
+          received = &received[l..];
+        }
       }
-    }
 
-    // Resolve any deferred `RTA_NH_ID` references in a single batch.
-    // Skipping this block when nothing was deferred is the whole
-    // point of the lazy-dump optimization — a host with no
-    // nexthop-object routes never pays the `RTM_GETNEXTHOP`
-    // round-trip. `None` from `resolve_nh_id` means the id wasn't
-    // in the dump (kernel state changed during enumeration); we
-    // surface that as `EINTR` so the caller can retry rather than
-    // silently lose the route. `Some(empty)` means the nexthop is
-    // present but unusable (blackhole / down) — skip silently.
-    // `Some(non-empty)` emits one route per resolved leaf.
-    if !deferred_nh.is_empty() {
-      let nh_map = dump_nexthops()?;
-      for (rfamily, dst_len, dst, id) in deferred_nh {
-        match resolve_nh_id(&nh_map, id) {
-          None => return Err(rustix::io::Errno::INTR.into()),
-          Some(resolved) => {
-            for (nh_oif, nh_gw) in resolved {
-              on_route(rfamily, nh_oif, dst_len, dst, nh_gw);
+      // Resolve any deferred `RTA_NH_ID` references in a single batch.
+      // Skipping this block when nothing was deferred is the whole
+      // point of the lazy-dump optimization — a host with no
+      // nexthop-object routes never pays the `RTM_GETNEXTHOP`
+      // round-trip. `None` from `resolve_nh_id` means the id wasn't
+      // in the dump (kernel state changed during enumeration); we
+      // surface that as `EINTR` so the caller can retry rather than
+      // silently lose the route. `Some(empty)` means the nexthop is
+      // present but unusable (blackhole / down) — skip silently.
+      // `Some(non-empty)` emits one route per resolved leaf.
+      if !deferred_nh.is_empty() {
+        let nh_map = dump_nexthops()?;
+        for (rfamily, dst_len, dst, id, qualifiers) in deferred_nh {
+          match resolve_nh_id(&nh_map, id) {
+            None => return Err(rustix::io::Errno::INTR.into()),
+            Some(resolved) => {
+              for (nh_oif, nh_gw) in resolved {
+                on_route(rfamily, nh_oif, dst_len, dst, nh_gw, qualifiers);
+              }
             }
           }
         }
       }
-    }
 
-    Ok(())
+      Ok(())
+    }
   }
 }
 
 /// Walk the contents of an `RTA_MULTIPATH` attribute payload and call
-/// `on_route(family, oif, dst_len, dst, gw)` for each nexthop. Each
-/// nexthop is a `struct rtnexthop` followed by RTA-encoded sub-attrs
-/// (typically `RTA_GATEWAY`). Aligns advance pointers like the kernel
-/// (4-byte `RTA_ALIGNTO`).
+/// `on_route(family, oif, dst_len, dst, gw, qualifiers)` for each
+/// nexthop. Each nexthop is a `struct rtnexthop` followed by RTA-encoded
+/// sub-attrs (typically `RTA_GATEWAY`). A per-nexthop `RTA_FLOW`
+/// overrides the route-level realm. Aligns advance pointers like the
+/// kernel (4-byte `RTA_ALIGNTO`).
 fn walk_multipath<F>(
   rtm_family: u8,
   dst_len: u8,
   dst: Option<IpAddr>,
   mut buf: &[u8],
+  qualifiers: RouteQualifiers,
   on_route: &mut F,
 ) where
-  F: FnMut(u8, u32, u8, Option<IpAddr>, Option<IpAddr>),
+  F: FnMut(u8, u32, u8, Option<IpAddr>, Option<IpAddr>, RouteQualifiers),
 {
   // sizeof(struct rtnexthop) = 8 (u16 + u8 + u8 + i32).
   const RTNH_SIZE: usize = 8;
@@ -1744,6 +3091,7 @@ fn walk_multipath<F>(
     let mut nh_gw_malformed = false;
     let mut nh_has_via = false;
     let mut nh_truncated = false;
+    let mut nh_qualifiers = qualifiers;
     let mut sub = &buf[RTNH_SIZE..nh_len];
     while sub.len() >= RtAttr::SIZE {
       let attr_len = u16::from_ne_bytes(sub[..2].try_into().unwrap()) as usize;
@@ -1759,13 +3107,17 @@ fn walk_multipath<F>(
         }
       } else if attr_ty == RTA_VIA {
         nh_has_via = true;
+      } else if attr_ty == RTA_FLOW && attr_len >= RtAttr::SIZE + 4 {
+        nh_qualifiers.realm = Some(u32::from_ne_bytes(
+          sub[RtAttr::SIZE..RtAttr::SIZE + 4].try_into().unwrap(),
+        ));
       }
       let alen = rta_align_of(attr_len).min(sub.len());
       sub = &sub[alen..];
     }
 
     if nh_ifindex != 0 && !nh_gw_malformed && !nh_has_via && !nh_truncated {
-      on_route(rtm_family, nh_ifindex, dst_len, dst, nh_gw);
+      on_route(rtm_family, nh_ifindex, dst_len, dst, nh_gw, nh_qualifiers);
     }
 
     // Advance to the next nexthop, RTA-aligned.
@@ -1809,7 +3161,7 @@ fn decode_nlmsgerr(received: &[u8], hlen: usize) -> io::Result<NlmsgErrOutcome>
   use rustix::io::Errno;
 
   if hlen < NLMSG_HDRLEN + 4 {
-    return Err(Errno::INVAL.into());
+    return Err(malformed());
   }
   let errno = i32::from_ne_bytes(received[NLMSG_HDRLEN..NLMSG_HDRLEN + 4].try_into().unwrap());
   if errno == 0 {
@@ -1856,6 +3208,50 @@ fn multipath_oifs_into(mut buf: &[u8], out: &mut SmallVec<u32>) {
   }
 }
 
+/// Decodes the nested `RTAX_*` attributes of an `RTA_METRICS` payload.
+/// `RTAX_RTT` is stored in units of 1/8 ms, as `ip route ... rtt`
+/// writes it; zero values are reported as absent.
+fn route_metrics_from_attrs(mut data: &[u8], expire: Option<SystemTime>) -> RouteMetrics {
+  let (mut mtu, mut rtt, mut hopcount) = (None, None, None);
+  while data.len() >= RtAttr::SIZE {
+    let alen = u16::from_ne_bytes(data[..2].try_into().unwrap()) as usize;
+    let ty = u16::from_ne_bytes(data[2..4].try_into().unwrap());
+    if alen < RtAttr::SIZE || alen > data.len() {
+      break;
+    }
+    let payload = &data[RtAttr::SIZE..alen];
+    if payload.len() >= 4 {
+      let val = u32::from_ne_bytes(payload[..4].try_into().unwrap());
+      match ty {
+        RTAX_MTU if val != 0 => mtu = Some(val),
+        RTAX_RTT if val != 0 => rtt = Some(Duration::from_micros(val as u64 * 125)),
+        RTAX_HOPLIMIT if val != 0 => hopcount = Some(val),
+        _ => {}
+      }
+    }
+    data = &data[rta_align_of(alen).min(data.len())..];
+  }
+  RouteMetrics::new(mtu, rtt, hopcount, expire)
+}
+
+/// Reads `rta_expires` from an `RTA_CACHEINFO` payload and turns the
+/// remaining `USER_HZ` ticks into wall-clock time. Zero means the route
+/// does not expire.
+fn parse_rta_cacheinfo_expires(data: &[u8]) -> Option<SystemTime> {
+  const OFFSET: usize = mem::offset_of!(netlink::rta_cacheinfo, rta_expires);
+  let ticks = i32::from_ne_bytes(data.get(OFFSET..OFFSET + 4)?.try_into().unwrap());
+  if ticks <= 0 {
+    return None;
+  }
+  // SAFETY: no preconditions.
+  let hz = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+  if hz <= 0 {
+    return None;
+  }
+  let remaining = Duration::from_millis(ticks as u64 * 1000 / hz as u64);
+  SystemTime::now().checked_add(remaining)
+}
+
 /// Decode an `RTA_DST` / `RTA_GATEWAY` attribute payload as the IP family
 /// declared by `rtm_family`. Netlink RTA address payloads are in network
 /// byte order regardless of host endianness.
@@ -1878,6 +3274,7 @@ pub(super) fn rt_generic_addrs<A, F>(
   family: AddressFamily,
   rta: u16,
   rtn: Option<u8>,
+  table: Option<u32>,
   mut f: F,
 ) -> io::Result<SmallVec<A>>
 where
@@ -1897,11 +3294,10 @@ where
     let handle = Handle::new()?;
 
     // Create and send netlink request for routes
-    let req = NetlinkRouteRequest::new(RTM_GETROUTE as u16, 1, family.as_raw() as u8, 0);
-    handle.send(&req)?;
+    handle.send_route_dump(family, 1, table)?;
 
-    // Get socket name
-    let lsa = handle.sock()?;
+    // Get socket portid
+    let pid = handle.portid()?;
 
     // Receive and process messages. `rt_generic_addrs` walks routes
     // with `RTA_MULTIPATH` payloads — see `ROUTE_RECV_BUF_SIZE`.
@@ -1924,11 +3320,11 @@ where
         let l = nlm_align_of(hlen);
 
         if hlen < NLMSG_HDRLEN || l > received.len() {
-          return Err(rustix::io::Errno::INVAL.into());
+          return Err(malformed());
         }
 
-        if h.nlmsg_seq != 1 || h.nlmsg_pid != lsa.pid() {
-          return Err(rustix::io::Errno::INVAL.into());
+        if h.nlmsg_seq != 1 || h.nlmsg_pid != pid {
+          return Err(malformed());
         }
 
         match h.nlmsg_type as u32 {
@@ -1982,6 +3378,8 @@ where
             let mut current_ifi = 0;
             let mut multipath: Option<&[u8]> = None;
             let mut nh_id: Option<u32> = None;
+            // Effective table id (RTA_TABLE override for > 255).
+            let mut table_id: u32 = rtm_header.rtm_table as u32;
             while rtattr_buf.len() >= RtAttr::SIZE {
               let attr = RtAttr {
                 len: u16::from_ne_bytes(rtattr_buf[..2].try_into().unwrap()),
@@ -1990,10 +3388,10 @@ where
 
               let attrlen = attr.len as usize;
               if attrlen < RtAttr::SIZE || attrlen > rtattr_buf.len() {
-                // Same rationale as in `netlink_best_local_addrs`:
+                // Same rationale as in `netlink_best_local_addrs_into`:
                 // a partially-parsed route could emit a bogus address
                 // into `gateways`. Fail the whole call instead.
-                return Err(rustix::io::Errno::INVAL.into());
+                return Err(malformed());
               }
 
               let data = &rtattr_buf[RtAttr::SIZE..attrlen];
@@ -2045,11 +3443,8 @@ where
                   }
                   _ => {}
                 },
-                RTA_OIF => {
-                  if data.len() >= 4 {
-                    let idx = u32::from_ne_bytes(data[..4].try_into().unwrap());
-                    current_ifi = idx;
-                  }
+                RTA_OIF if data.len() >= 4 => {
+                  current_ifi = u32::from_ne_bytes(data[..4].try_into().unwrap());
                 }
                 RTA_MULTIPATH => {
                   multipath = Some(data);
@@ -2057,12 +3452,22 @@ where
                 RTA_NH_ID if data.len() >= 4 => {
                   nh_id = Some(u32::from_ne_bytes(data[..4].try_into().unwrap()));
                 }
+                RTA_TABLE if data.len() >= 4 => {
+                  table_id = u32::from_ne_bytes(data[..4].try_into().unwrap());
+                }
                 _ => {}
               }
 
               rtattr_buf = &rtattr_buf[alen..];
             }
 
+            // Without a named table every table's gateways count, as
+            // they always have.
+            if table.is_some_and(|table| table_id != table) {
+              received = &received[l..];
+              continue;
+            }
+
             // Inline closure for the dedup + try_from + push step.
             // Avoids three duplicate copies across the top-level /
             // multipath / nh_id paths and keeps the per-path code
@@ -2215,7 +3620,7 @@ impl RtmMessageHeader {
   #[inline]
   fn parse(src: &[u8]) -> io::Result<Self> {
     if src.len() < Self::SIZE {
-      return Err(rustix::io::Errno::INVAL.into());
+      return Err(malformed());
     }
 
     Ok(Self {
@@ -2232,6 +3637,12 @@ impl RtmMessageHeader {
   }
 }
 
+// A reply that does not frame or decode the way the kernel lays it out.
+#[inline]
+fn malformed() -> io::Error {
+  crate::Error::parse("netlink message").into()
+}
+
 // Round the length of a netlink message up to align it properly.
 #[inline]
 const fn nlm_align_of(msg_len: usize) -> usize {
@@ -2270,13 +3681,7 @@ impl NetlinkRouteRequest {
     & !(NLMSG_ALIGNTO as usize - 1);
 
   #[inline]
-  fn new(proto: u16, seq: u32, family: u8, _ifi: u32) -> Self {
-    // TODO(al8n): do not dump when ifi is not 0
-    // let flags = if ifi == 0 {
-    //   (libc::NLM_F_DUMP | libc::NLM_F_REQUEST) as u16
-    // } else {
-    //   libc::NLM_F_REQUEST as u16
-    // };
+  fn new(proto: u16, seq: u32, family: u8) -> Self {
     let mut bytes = [0u8; Self::SIZE];
     // `nlmsghdr` (offsets per the C layout):
     //   bytes 0..4   nlmsg_len  : u32
@@ -2320,7 +3725,7 @@ impl IfInfoMessageHeader {
   #[inline]
   fn parse(src: &[u8]) -> io::Result<Self> {
     if src.len() < Self::SIZE {
-      return Err(rustix::io::Errno::INVAL.into());
+      return Err(malformed());
     }
 
     Ok(Self {
@@ -2358,14 +3763,14 @@ impl IfNetMessageHeader {
   const SIZE: usize = mem::size_of::<Self>();
 
   /// Decode an `ifaddrmsg` header from the start of the netlink
-  /// message body. Returns `EINVAL` for short buffers — a malformed
+  /// message body. Fails with a parse error for short buffers — a malformed
   /// `RTM_NEWADDR` with `nlmsg_len == NLMSG_HDRLEN` would otherwise
   /// reach the field reads with an empty `msg_buf` and panic on the
   /// raw index.
   #[inline]
   fn parse(src: &[u8]) -> io::Result<Self> {
     if src.len() < Self::SIZE {
-      return Err(rustix::io::Errno::INVAL.into());
+      return Err(malformed());
     }
     Ok(Self {
       family: src[0],
@@ -2415,12 +3820,8 @@ mod netlink_tests {
       let before = handle.sock().expect("getsockname before send");
       assert_eq!(before.pid(), 0, "socket must be unbound before first send");
 
-      let req = NetlinkRouteRequest::new(
-        RTM_GETLINK as u16,
-        1,
-        AddressFamily::UNSPEC.as_raw() as u8,
-        0,
-      );
+      let req =
+        NetlinkRouteRequest::new(RTM_GETLINK as u16, 1, AddressFamily::UNSPEC.as_raw() as u8);
       handle.send(&req).expect("send RTM_GETLINK");
 
       let after = handle.sock().expect("getsockname after send");
@@ -2432,6 +3833,114 @@ mod netlink_tests {
     }
   }
 
+  // The strict-check opt-in reports whether the kernel took it: either
+  // it is on, or the refusal comes back as the `ENOPROTOOPT` kernels
+  // before 4.20 answer with, never as a silently ignored failure.
+  #[test]
+  fn strict_check_reports_refusal() {
+    unsafe {
+      let handle = Handle::new().expect("create netlink handle");
+      if let Err(e) = handle.enable_strict_check() {
+        assert_eq!(
+          e.raw_os_error(),
+          Some(rustix::io::Errno::NOPROTOOPT.raw_os_error()),
+          "{e}"
+        );
+      }
+    }
+  }
+
+  // The sandbox case `portid` guards against: a socket that reports
+  // portid 0 after its first send is answered as the process id, while
+  // a real autobound portid passes through.
+  #[test]
+  fn zero_portid_falls_back_to_process_id() {
+    assert_eq!(reply_portid(0), std::process::id());
+    assert_eq!(reply_portid(0x8000_1234), 0x8000_1234);
+  }
+
+  // A receive buffer far smaller than the first reply datagram must be
+  // grown to hold it, rather than the datagram being truncated into a
+  // message that runs past the end of the read.
+  #[test]
+  fn recv_grows_buffer_to_whole_datagram() {
+    unsafe {
+      let handle = Handle::new().expect("create netlink handle");
+      let req =
+        NetlinkRouteRequest::new(RTM_GETLINK as u16, 1, AddressFamily::UNSPEC.as_raw() as u8);
+      handle.send(&req).expect("send RTM_GETLINK");
+
+      let mut rb = vec![0u8; NLMSG_HDRLEN];
+      let nr = handle.recv(&mut rb).expect("recv RTM_GETLINK reply");
+      assert!(nr > NLMSG_HDRLEN, "{nr}");
+      assert!(rb.len() >= nr);
+      let pid = handle.portid().unwrap();
+      let mut links = Vec::new();
+      link_batch_into(&rb[..nr], 1, pid, &mut links).expect("whole messages");
+      assert!(!links.is_empty());
+    }
+  }
+
+  // A datagram another socket unicasts to the watcher's portid must be
+  // dropped: only the kernel (portid 0) speaks for the link table.
+  #[test]
+  fn watcher_drops_datagrams_not_from_kernel() {
+    const SPOOFED: u32 = 0x7fff_fff0;
+
+    let watcher = match AddrWatcher::with_links() {
+      Ok(watcher) => watcher,
+      Err(e) if e.kind() == io::ErrorKind::PermissionDenied => return,
+      Err(e) => panic!("{e}"),
+    };
+    let portid = getsockname(watcher.as_fd())
+      .map_err(io::Error::from)
+      .and_then(netlink_sockaddr)
+      .unwrap()
+      .pid();
+
+    let spoofer = socket(AddressFamily::NETLINK, SocketType::RAW, None).unwrap();
+    let msg = newlink_message(0, SPOOFED as i32, &[]);
+    match sendto(
+      &spoofer,
+      &msg,
+      SendFlags::empty(),
+      &SocketAddrNetlink::new(portid, 0),
+    ) {
+      Ok(_) => {}
+      // Unicasting to another route socket takes CAP_NET_ADMIN.
+      Err(rustix::io::Errno::PERM | rustix::io::Errno::ACCESS) => return,
+      Err(e) => panic!("{e}"),
+    }
+
+    // Other tests may churn links meanwhile; drain whatever is queued
+    // and only insist the forged link never shows up.
+    watcher.set_nonblocking(true).unwrap();
+    loop {
+      match watcher.recv(None) {
+        Ok(Event::LinkAdded(index)) => assert_ne!(index, SPOOFED),
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+        Err(e) => panic!("{e}"),
+      }
+    }
+  }
+
+  // A non-netlink address from getsockname must surface as a
+  // descriptive InvalidData error, while a real netlink address passes
+  // through untouched.
+  #[test]
+  fn non_netlink_sockname_is_reported() {
+    use std::net::{Ipv4Addr, SocketAddrV4};
+
+    let inet = SocketAddrAny::from(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0));
+    let err = netlink_sockaddr(inet).expect_err("AF_INET is not a netlink address");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("non-netlink"), "{err}");
+
+    let nl = SocketAddrAny::from(SocketAddrNetlink::new(42, 0));
+    assert_eq!(netlink_sockaddr(nl).unwrap().pid(), 42);
+  }
+
   // Codex round 3: an in-band RTM_GETLINK denial arrives as
   // NLMSG_ERROR(-EACCES/-EPERM). `decode_nlmsgerr` must surface the real
   // errno as PermissionDenied (not flatten it to EINVAL) so the Android
@@ -2450,4 +3959,292 @@ mod netlink_tests {
       decode_nlmsgerr(&buf, NLMSG_HDRLEN + 4).expect_err("a negative errno must be an error");
     assert_eq!(err.kind(), ErrorKind::PermissionDenied);
   }
+
+  /// Serializes one `RTM_NEWLINK` reply carrying `attrs` (already
+  /// encoded `rtattr`s) for interface `index`.
+  fn newlink_message(pid: u32, index: i32, attrs: &[u8]) -> Vec<u8> {
+    let len = NLMSG_HDRLEN + IfInfoMessageHeader::SIZE + attrs.len();
+    let mut buf = vec![0u8; nlm_align_of(len)];
+    buf[0..4].copy_from_slice(&(len as u32).to_ne_bytes());
+    buf[4..6].copy_from_slice(&(RTM_NEWLINK as u16).to_ne_bytes());
+    buf[8..12].copy_from_slice(&1u32.to_ne_bytes());
+    buf[12..16].copy_from_slice(&pid.to_ne_bytes());
+    buf[NLMSG_HDRLEN + 4..NLMSG_HDRLEN + 8].copy_from_slice(&index.to_ne_bytes());
+    buf[NLMSG_HDRLEN + IfInfoMessageHeader::SIZE..len].copy_from_slice(attrs);
+    buf
+  }
+
+  /// Runs one batch through [`link_batch_into`] and [`LinkIter`] the
+  /// way `netlink_interface_into` does, returning whether the dump is
+  /// complete.
+  fn link_messages_into(
+    received: &[u8],
+    pid: u32,
+    ifi: u32,
+    interfaces: &mut TinyVec<Interface>,
+    mut errors: Option<&mut Vec<EnumerationError>>,
+  ) -> io::Result<bool> {
+    let mut buf = Vec::new();
+    let done = link_batch_into(received, 1, pid, &mut buf)?;
+    for link in (LinkIter { buf, pos: 0, ifi }) {
+      match (link, errors.as_deref_mut()) {
+        (Ok(interface), _) => interfaces.push(interface),
+        (Err(e), Some(errors)) => errors.push(e),
+        (Err(e), None) => return Err(e.into_error().into()),
+      }
+    }
+    Ok(done)
+  }
+
+  fn ifname_attr(name: impl AsRef<[u8]>) -> Vec<u8> {
+    let name = name.as_ref();
+    let len = RtAttr::SIZE + name.len() + 1;
+    let mut attr = vec![0u8; rta_align_of(len)];
+    attr[0..2].copy_from_slice(&(len as u16).to_ne_bytes());
+    attr[2..4].copy_from_slice(&(IFLA_IFNAME as u16).to_ne_bytes());
+    attr[RtAttr::SIZE..RtAttr::SIZE + name.len()].copy_from_slice(name);
+    attr
+  }
+
+  // One corrupt `RTM_NEWLINK` (its only attribute claims more bytes
+  // than the message holds) sits between two healthy ones. The lossy
+  // walk must keep both neighbours and report the bad record by index;
+  // the strict walk must still fail.
+  #[test]
+  fn lossy_link_walk_skips_malformed_record() {
+    const PID: u32 = 4242;
+
+    let mut batch = newlink_message(PID, 1, &ifname_attr("good0"));
+    let mut bad_attr = ifname_attr("bad");
+    bad_attr[0..2].copy_from_slice(&200u16.to_ne_bytes());
+    batch.extend(newlink_message(PID, 2, &bad_attr));
+    batch.extend(newlink_message(PID, 3, &ifname_attr("good1")));
+
+    let mut interfaces = TinyVec::new();
+    let mut errors = Vec::new();
+    let done = link_messages_into(&batch, PID, 0, &mut interfaces, Some(&mut errors)).unwrap();
+    assert!(!done);
+    let names: Vec<_> = interfaces.iter().map(|ifi| ifi.name.as_str()).collect();
+    assert_eq!(names, ["good0", "good1"]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].index(), Some(2));
+
+    let mut interfaces = TinyVec::new();
+    let err = link_messages_into(&batch, PID, 0, &mut interfaces, None).unwrap_err();
+    assert!(matches!(
+      crate::Error::from(err),
+      crate::Error::Parse { .. }
+    ));
+  }
+
+  // The kernel accepts any bytes in an interface name. A non-UTF8 name
+  // decodes with replacement characters instead of failing the walk.
+  #[test]
+  fn non_utf8_link_name_is_lossy() {
+    const PID: u32 = 4242;
+
+    let batch = newlink_message(PID, 7, &ifname_attr(b"eth\xff0"));
+    let mut interfaces = TinyVec::new();
+    link_messages_into(&batch, PID, 0, &mut interfaces, None).unwrap();
+    assert_eq!(interfaces.len(), 1);
+    assert_eq!(interfaces[0].name.as_str(), "eth\u{fffd}0");
+  }
+
+  // A per-nexthop `RTA_FLOW` inside `RTA_MULTIPATH` overrides the
+  // route-level realm; a nexthop without one inherits it, and the ToS
+  // always carries through.
+  #[test]
+  fn multipath_nexthop_realm_overrides_route_realm() {
+    fn nexthop(ifindex: i32, attrs: &[u8]) -> Vec<u8> {
+      let len = 8 + attrs.len();
+      let mut nh = vec![0u8; rta_align_of(len)];
+      nh[0..2].copy_from_slice(&(len as u16).to_ne_bytes());
+      nh[4..8].copy_from_slice(&ifindex.to_ne_bytes());
+      nh[8..len].copy_from_slice(attrs);
+      nh
+    }
+    let mut flow = vec![0u8; RtAttr::SIZE + 4];
+    flow[0..2].copy_from_slice(&((RtAttr::SIZE + 4) as u16).to_ne_bytes());
+    flow[2..4].copy_from_slice(&RTA_FLOW.to_ne_bytes());
+    flow[4..8].copy_from_slice(&9u32.to_ne_bytes());
+
+    let mut mp = nexthop(2, &flow);
+    mp.extend(nexthop(3, &[]));
+
+    let route = RouteQualifiers {
+      tos: 0x10,
+      realm: Some(5),
+      metric: 100,
+      metrics: RouteMetrics::default(),
+    };
+    let mut seen = Vec::new();
+    walk_multipath(
+      AddressFamily::INET.as_raw() as u8,
+      24,
+      None,
+      &mp,
+      route,
+      &mut |_, oif, _, _, _, q: RouteQualifiers| seen.push((oif, q)),
+    );
+    assert_eq!(
+      seen,
+      [
+        (
+          2,
+          RouteQualifiers {
+            tos: 0x10,
+            realm: Some(9),
+            metric: 100,
+            metrics: RouteMetrics::default(),
+          }
+        ),
+        (3, route),
+      ]
+    );
+  }
+
+  // The kernel-filtered per-interface dump must return exactly what
+  // filtering the full dump client-side does.
+  #[test]
+  fn indexed_addr_dump_matches_full_dump() {
+    let all: SmallVec<crate::IfNet> = netlink_addr(AddressFamily::UNSPEC, 0, |_| true).unwrap();
+    let mut indices: SmallVec<u32> = all.iter().map(|a| a.index()).collect();
+    indices.dedup();
+    for idx in indices {
+      let only: SmallVec<crate::IfNet> =
+        netlink_addr(AddressFamily::UNSPEC, idx, |_| true).unwrap();
+      let expected: SmallVec<crate::IfNet> =
+        all.iter().filter(|a| a.index() == idx).cloned().collect();
+      assert_eq!(only, expected, "index {idx}");
+    }
+  }
+
+  // Strict checking turns an unknown index into ENODEV; callers still
+  // see the empty result the unfiltered dump used to give.
+  #[test]
+  fn indexed_addr_dump_unknown_index_is_empty() {
+    let addrs: SmallVec<crate::IfNet> =
+      netlink_addr(AddressFamily::UNSPEC, 0x7fff_fff0, |_| true).unwrap();
+    assert!(addrs.is_empty());
+  }
+
+  // `IFLA_OPERSTATE` carries the `IF_OPER_*` numbering; anything past
+  // `IF_OPER_UP` is not defined and must not read as up.
+  #[test]
+  fn operstate_maps_to_oper_status() {
+    let expected = [
+      OperStatus::Unknown,
+      OperStatus::NotPresent,
+      OperStatus::Down,
+      OperStatus::LowerLayerDown,
+      OperStatus::Testing,
+      OperStatus::Dormant,
+      OperStatus::Up,
+      OperStatus::Unknown,
+    ];
+    for (state, status) in expected.into_iter().enumerate() {
+      assert_eq!(oper_status_from_operstate(state as u8), status, "{state}");
+    }
+  }
+
+  // Tunnel and GRE devices share `ARPHRD_*` families across IPv4 and
+  // IPv6; unlisted types such as `ARPHRD_NONE` (tun, WireGuard) are
+  // unknown rather than guessed.
+  #[test]
+  fn arphrd_maps_to_media_type() {
+    for (ty, expected) in [
+      (if_arp::ARPHRD_ETHER, Some("ethernet")),
+      (if_arp::ARPHRD_LOOPBACK, Some("loopback")),
+      (if_arp::ARPHRD_IEEE80211_RADIOTAP, Some("wireless")),
+      (if_arp::ARPHRD_IPGRE, Some("gre")),
+      (if_arp::ARPHRD_IP6GRE, Some("gre")),
+      (if_arp::ARPHRD_SIT, Some("tunnel")),
+      (if_arp::ARPHRD_NONE, None),
+    ] {
+      assert_eq!(media_type_from_arphrd(ty as u16), expected, "{ty}");
+    }
+  }
+
+  // `IFLA_STATS64` wins over the 32-bit `IFLA_STATS` sent next to it,
+  // in whichever order they come; the legacy block alone still counts.
+  #[test]
+  fn link_stats_prefer_stats64() {
+    fn stats_attr(ty: u32, counters: &[u8]) -> Vec<u8> {
+      let len = RtAttr::SIZE + counters.len();
+      let mut attr = vec![0u8; rta_align_of(len)];
+      attr[0..2].copy_from_slice(&(len as u16).to_ne_bytes());
+      attr[2..4].copy_from_slice(&(ty as u16).to_ne_bytes());
+      attr[RtAttr::SIZE..len].copy_from_slice(counters);
+      attr
+    }
+    let legacy: Vec<u8> = (1..=23u32).flat_map(u32::to_ne_bytes).collect();
+    let full: Vec<u8> = (1..=23u64).flat_map(|c| (c << 32).to_ne_bytes()).collect();
+    let legacy = stats_attr(IFLA_STATS, &legacy);
+    let full = stats_attr(IFLA_STATS64, &full);
+
+    let stats = parse_link_stats(&[legacy.clone(), full].concat()).unwrap();
+    assert_eq!(stats.rx_packets(), 1 << 32);
+    assert_eq!(stats.rx_bytes(), 3 << 32);
+    assert_eq!(stats.tx_errors(), 6 << 32);
+
+    let stats = parse_link_stats(&legacy).unwrap();
+    assert_eq!(
+      [
+        stats.rx_packets(),
+        stats.tx_packets(),
+        stats.rx_bytes(),
+        stats.tx_bytes(),
+        stats.rx_errors(),
+        stats.tx_errors()
+      ],
+      [1, 2, 3, 4, 5, 6]
+    );
+
+    assert_eq!(
+      parse_link_stats(&ifname_attr("eth0")).unwrap_err().kind(),
+      io::ErrorKind::InvalidData
+    );
+  }
+
+  // GRE and the other tunnel types all read as one kind; a type with
+  // no kind of its own keeps its raw code.
+  #[test]
+  fn arphrd_maps_to_kind() {
+    for (ty, expected) in [
+      (if_arp::ARPHRD_ETHER, InterfaceKind::Ethernet),
+      (if_arp::ARPHRD_LOOPBACK, InterfaceKind::Loopback),
+      (if_arp::ARPHRD_IEEE80211_RADIOTAP, InterfaceKind::Wifi),
+      (if_arp::ARPHRD_IP6GRE, InterfaceKind::Tunnel),
+      (if_arp::ARPHRD_SIT, InterfaceKind::Tunnel),
+      (if_arp::ARPHRD_ATM, InterfaceKind::Atm),
+      (
+        if_arp::ARPHRD_NONE,
+        InterfaceKind::Other(if_arp::ARPHRD_NONE as u16),
+      ),
+    ] {
+      assert_eq!(kind_from_arphrd(ty as u16), expected, "{ty}");
+    }
+  }
+
+  // `RTA_METRICS` nests `RTAX_*` attributes; RTT is in 1/8 ms units and
+  // unknown or zero metrics are left out.
+  #[test]
+  fn route_metrics_decode_rtax_attrs() {
+    let mut data = Vec::new();
+    for (ty, val) in [
+      (RTAX_MTU, 1400u32),
+      (RTAX_RTT, 80),
+      (RTAX_HOPLIMIT, 0),
+      (1, 7),
+    ] {
+      data.extend_from_slice(&8u16.to_ne_bytes());
+      data.extend_from_slice(&ty.to_ne_bytes());
+      data.extend_from_slice(&val.to_ne_bytes());
+    }
+    let metrics = route_metrics_from_attrs(&data, None);
+    assert_eq!(metrics.mtu(), Some(1400));
+    assert_eq!(metrics.rtt(), Some(Duration::from_millis(10)));
+    assert_eq!(metrics.hopcount(), None);
+    assert_eq!(metrics.expire(), None);
+  }
 }
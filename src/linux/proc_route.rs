@@ -0,0 +1,424 @@
+//! procfs route fallback.
+//!
+//! Some sandboxes (seccomp profiles, hardened container runtimes) refuse
+//! to create `AF_NETLINK` sockets while leaving `/proc/net` readable.
+//! When a netlink route walk fails with `PermissionDenied`, the gateway
+//! and best-local-address lookups are answered from here instead:
+//!
+//! - routes come from `/proc/net/route` and `/proc/net/ipv6_route`;
+//! - IPv6 addresses come from `/proc/net/if_inet6`;
+//! - IPv4 addresses come from `SIOCGIFADDR` / `SIOCGIFNETMASK`, since
+//!   procfs has no per-interface IPv4 address list. Only the primary
+//!   address of each interface is visible this way.
+//!
+//! The proc files name interfaces rather than numbering them, so each
+//! name is resolved with `if_nametoindex`; a name that no longer
+//! resolves (the interface went away mid-read) is skipped.
+
+use std::{
+  collections::HashSet,
+  io,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use rustix::{
+  fd::AsFd,
+  ioctl::{self, Opcode, Updater},
+  net::{netdevice::name_to_index, socket, AddressFamily, SocketType},
+};
+use smallvec_wrapper::SmallVec;
+
+use crate::{local_ip_filter, Address, Net};
+
+const ROUTE_PATH: &str = "/proc/net/route";
+const IPV6_ROUTE_PATH: &str = "/proc/net/ipv6_route";
+const IF_INET6_PATH: &str = "/proc/net/if_inet6";
+
+// `include/uapi/linux/route.h`
+const RTF_UP: u32 = 0x0001;
+const RTF_GATEWAY: u32 = 0x0002;
+
+// `include/uapi/linux/sockios.h`
+const SIOCGIFADDR: Opcode = 0x8915;
+const SIOCGIFNETMASK: Opcode = 0x891b;
+
+const IF_NAMESIZE: usize = 16;
+
+/// One usable (`RTF_UP`) row of a procfs route table.
+#[derive(Debug, PartialEq, Eq)]
+struct ProcRoute<'a> {
+  iface: &'a str,
+  dst: IpAddr,
+  dst_len: u8,
+  gateway: Option<IpAddr>,
+  metric: u32,
+}
+
+/// Parses `/proc/net/route`.
+///
+/// Addresses and masks are the kernel's `__be32` printed with `%08X`
+/// as a native integer, so the native byte order of the parsed value is
+/// the network byte order of the address.
+fn parse_ipv4_routes(content: &str) -> impl Iterator<Item = ProcRoute<'_>> {
+  content.lines().skip(1).filter_map(|line| {
+    let fields: SmallVec<&str> = line.split_whitespace().collect();
+    if fields.len() < 8 {
+      return None;
+    }
+    let hex = |s: &str| u32::from_str_radix(s, 16).ok();
+    let flags = hex(fields[3])?;
+    if flags & RTF_UP == 0 {
+      return None;
+    }
+    let gateway = Ipv4Addr::from(hex(fields[2])?.to_ne_bytes());
+    let mask = u32::from_be_bytes(hex(fields[7])?.to_ne_bytes());
+    Some(ProcRoute {
+      iface: fields[0],
+      dst: IpAddr::V4(Ipv4Addr::from(hex(fields[1])?.to_ne_bytes())),
+      dst_len: mask.leading_ones() as u8,
+      gateway: (flags & RTF_GATEWAY != 0).then_some(IpAddr::V4(gateway)),
+      metric: fields[6].parse().ok()?,
+    })
+  })
+}
+
+/// Parses `/proc/net/ipv6_route`: destination, prefix length, source,
+/// source prefix length, next hop, metric, refcount, use count, flags
+/// and device, with addresses as 32 hex digits in network byte order.
+fn parse_ipv6_routes(content: &str) -> impl Iterator<Item = ProcRoute<'_>> {
+  content.lines().filter_map(|line| {
+    let fields: SmallVec<&str> = line.split_whitespace().collect();
+    if fields.len() < 10 {
+      return None;
+    }
+    let hex = |s: &str| u32::from_str_radix(s, 16).ok();
+    let flags = hex(fields[8])?;
+    if flags & RTF_UP == 0 {
+      return None;
+    }
+    let gateway = parse_hex_ipv6(fields[4])?;
+    Some(ProcRoute {
+      iface: fields[9],
+      dst: IpAddr::V6(parse_hex_ipv6(fields[0])?),
+      dst_len: u8::from_str_radix(fields[1], 16).ok()?,
+      gateway: (flags & RTF_GATEWAY != 0 && !gateway.is_unspecified())
+        .then_some(IpAddr::V6(gateway)),
+      metric: hex(fields[5])?,
+    })
+  })
+}
+
+fn parse_hex_ipv6(s: &str) -> Option<Ipv6Addr> {
+  if s.len() != 32 {
+    return None;
+  }
+  u128::from_str_radix(s, 16).ok().map(Ipv6Addr::from)
+}
+
+/// Whether the procfs route table can be read.
+pub(super) fn available() -> bool {
+  std::fs::File::open(ROUTE_PATH).is_ok()
+}
+
+/// Reads a proc file, treating a missing one as empty: `ipv6_route`
+/// and `if_inet6` do not exist when IPv6 is disabled.
+fn read_proc(path: &str) -> io::Result<String> {
+  match std::fs::read_to_string(path) {
+    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+    other => other,
+  }
+}
+
+fn route_tables(family: AddressFamily) -> io::Result<(String, String)> {
+  let v4 = if family != AddressFamily::INET6 {
+    read_proc(ROUTE_PATH)?
+  } else {
+    String::new()
+  };
+  let v6 = if family != AddressFamily::INET {
+    read_proc(IPV6_ROUTE_PATH)?
+  } else {
+    String::new()
+  };
+  Ok((v4, v6))
+}
+
+/// Procfs counterpart of the netlink `RTA_GATEWAY` walk.
+pub(super) fn gateway_addrs<A, F>(family: AddressFamily, mut f: F) -> io::Result<SmallVec<A>>
+where
+  A: Address + Eq,
+  F: FnMut(&IpAddr) -> bool,
+{
+  let (v4, v6) = route_tables(family)?;
+  let sock = socket(AddressFamily::INET, SocketType::DGRAM, None)?;
+
+  let mut out = SmallVec::new();
+  let mut seen: HashSet<(u32, IpAddr)> = HashSet::new();
+  for route in parse_ipv4_routes(&v4).chain(parse_ipv6_routes(&v6)) {
+    let Some(gw) = route.gateway else {
+      continue;
+    };
+    let Ok(index) = name_to_index(sock.as_fd(), route.iface) else {
+      continue;
+    };
+    if seen.insert((index, gw)) {
+      if let Some(addr) = A::try_from_with_filter(index, gw, &mut f) {
+        out.push(addr);
+      }
+    }
+  }
+  Ok(out)
+}
+
+/// Procfs counterpart of `netlink_best_local_addrs_into`: picks the
+/// lowest-metric default route(s) for `family` and appends the local
+/// addresses of their interfaces. Returns whether a default route was
+/// found.
+pub(super) fn best_local_addrs_into<N>(
+  family: AddressFamily,
+  out: &mut SmallVec<N>,
+) -> io::Result<bool>
+where
+  N: Net,
+{
+  let (v4, v6) = route_tables(family)?;
+  let routes = family_routes(family, &v4, &v6);
+  let sock = socket(AddressFamily::INET, SocketType::DGRAM, None)?;
+  let best = best_default_routes(&sock, &routes);
+  if best.is_empty() {
+    return Ok(false);
+  }
+
+  if family == AddressFamily::INET6 {
+    let if_inet6 = read_proc(IF_INET6_PATH)?;
+    for (index, addr, prefix) in parse_if_inet6(&if_inet6) {
+      if best.iter().any(|(i, _)| *i == index) {
+        if let Some(net) = N::try_from_with_filter(index, IpAddr::V6(addr), prefix, local_ip_filter)
+        {
+          out.push(net);
+        }
+      }
+    }
+  } else {
+    for (index, name) in best {
+      if let Some((addr, prefix)) = primary_ipv4(&sock, name)? {
+        if let Some(net) = N::try_from_with_filter(index, IpAddr::V4(addr), prefix, local_ip_filter)
+        {
+          out.push(net);
+        }
+      }
+    }
+  }
+  Ok(true)
+}
+
+/// Procfs counterpart of `netlink_best_default_oifs`: the sorted
+/// interface indices behind the lowest-metric default route(s) for
+/// `family`.
+pub(super) fn best_default_indices(family: AddressFamily) -> io::Result<SmallVec<u32>> {
+  let (v4, v6) = route_tables(family)?;
+  let routes = family_routes(family, &v4, &v6);
+  let sock = socket(AddressFamily::INET, SocketType::DGRAM, None)?;
+  let mut indices: SmallVec<u32> = best_default_routes(&sock, &routes)
+    .into_iter()
+    .map(|(index, _)| index)
+    .collect();
+  indices.sort_unstable();
+  Ok(indices)
+}
+
+fn family_routes<'a>(family: AddressFamily, v4: &'a str, v6: &'a str) -> SmallVec<ProcRoute<'a>> {
+  if family == AddressFamily::INET6 {
+    parse_ipv6_routes(v6).collect()
+  } else {
+    parse_ipv4_routes(v4).collect()
+  }
+}
+
+/// The distinct `(index, name)` pairs of the default routes tied at the
+/// lowest metric; empty when there is no default route.
+fn best_default_routes<'a>(sock: &impl AsFd, routes: &[ProcRoute<'a>]) -> SmallVec<(u32, &'a str)> {
+  let mut best = SmallVec::new();
+  let Some(best_metric) = routes
+    .iter()
+    .filter(|r| r.dst_len == 0)
+    .map(|r| r.metric)
+    .min()
+  else {
+    return best;
+  };
+
+  for route in routes
+    .iter()
+    .filter(|r| r.dst_len == 0 && r.metric == best_metric)
+  {
+    if let Ok(index) = name_to_index(sock.as_fd(), route.iface) {
+      if !best.iter().any(|(i, _)| *i == index) {
+        best.push((index, route.iface));
+      }
+    }
+  }
+  best
+}
+
+/// Parses `/proc/net/if_inet6`: address, interface index, prefix
+/// length, scope, flags and device, numbers in hex.
+fn parse_if_inet6(content: &str) -> impl Iterator<Item = (u32, Ipv6Addr, u8)> + '_ {
+  content.lines().filter_map(|line| {
+    let fields: SmallVec<&str> = line.split_whitespace().collect();
+    if fields.len() < 6 {
+      return None;
+    }
+    Some((
+      u32::from_str_radix(fields[1], 16).ok()?,
+      parse_hex_ipv6(fields[0])?,
+      u8::from_str_radix(fields[2], 16).ok()?,
+    ))
+  })
+}
+
+/// `struct ifreq` with the `ifr_ifru` union kept as raw bytes; see
+/// `linux/android.rs` for the same layout.
+#[repr(C)]
+struct Ifreq {
+  ifr_name: [u8; IF_NAMESIZE],
+  ifr_ifru: [u8; 24],
+}
+
+/// Reads the primary IPv4 address and prefix length of `name`, or
+/// `None` if it has no IPv4 address.
+fn primary_ipv4(sock: &impl AsFd, name: &str) -> io::Result<Option<(Ipv4Addr, u8)>> {
+  let mut ifr = Ifreq {
+    ifr_name: [0; IF_NAMESIZE],
+    ifr_ifru: [0; 24],
+  };
+  let n = name.len().min(IF_NAMESIZE - 1);
+  ifr.ifr_name[..n].copy_from_slice(&name.as_bytes()[..n]);
+
+  // Both ioctls fill a `sockaddr_in` at the front of the union; the
+  // address sits after the 2-byte family and 2-byte port.
+  let sin_addr = |ifr: &Ifreq| {
+    Ipv4Addr::new(
+      ifr.ifr_ifru[4],
+      ifr.ifr_ifru[5],
+      ifr.ifr_ifru[6],
+      ifr.ifr_ifru[7],
+    )
+  };
+
+  // SAFETY: `Ifreq` matches the kernel's `struct ifreq` layout and the
+  // opcode reads and writes exactly one of them.
+  match unsafe { ioctl::ioctl(sock.as_fd(), Updater::<SIOCGIFADDR, Ifreq>::new(&mut ifr)) } {
+    Ok(()) => {}
+    // No IPv4 address assigned, or the interface went away.
+    Err(e)
+      if e == rustix::io::Errno::ADDRNOTAVAIL
+        || e == rustix::io::Errno::NODEV
+        || e == rustix::io::Errno::NXIO =>
+    {
+      return Ok(None)
+    }
+    Err(e) => return Err(e.into()),
+  }
+  let addr = sin_addr(&ifr);
+
+  // SAFETY: as above.
+  unsafe {
+    ioctl::ioctl(
+      sock.as_fd(),
+      Updater::<SIOCGIFNETMASK, Ifreq>::new(&mut ifr),
+    )
+  }?;
+  let prefix = u32::from(sin_addr(&ifr)).leading_ones() as u8;
+
+  Ok(Some((addr, prefix)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // A `/proc/net/route` capture from a host with one DHCP uplink. The
+  // hex fields are little-endian renderings of network-order values,
+  // so the expectations only hold on little-endian targets.
+  #[cfg(target_endian = "little")]
+  #[test]
+  fn ipv4_default_gateway() {
+    const SAMPLE: &str = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t00000000\t0102A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+eth0\t0002A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0
+docker0\t000011AC\t00000000\t0000\t0\t0\t0\t0000FFFF\t0\t0\t0
+";
+    let routes: Vec<_> = parse_ipv4_routes(SAMPLE).collect();
+    // The docker0 row lacks RTF_UP and is dropped.
+    assert_eq!(routes.len(), 2);
+
+    let default = routes.iter().find(|r| r.dst_len == 0).unwrap();
+    assert_eq!(default.iface, "eth0");
+    assert_eq!(default.metric, 100);
+    assert_eq!(
+      default.gateway,
+      Some(IpAddr::V4(Ipv4Addr::new(192, 168, 2, 1)))
+    );
+
+    let connected = &routes[1];
+    assert_eq!(connected.dst, IpAddr::V4(Ipv4Addr::new(192, 168, 2, 0)));
+    assert_eq!(connected.dst_len, 24);
+    assert_eq!(connected.gateway, None);
+  }
+
+  #[test]
+  fn ipv6_default_gateway() {
+    const SAMPLE: &str = "\
+fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001 eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00450003 eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000000 ffffffff 00000001 00000000 00200200 lo
+";
+    let routes: Vec<_> = parse_ipv6_routes(SAMPLE).collect();
+    // The `lo` reject route lacks RTF_UP and is dropped.
+    assert_eq!(routes.len(), 2);
+
+    assert_eq!(routes[0].dst_len, 64);
+    assert_eq!(routes[0].gateway, None);
+
+    let default = &routes[1];
+    assert_eq!(default.dst_len, 0);
+    assert_eq!(default.metric, 0x400);
+    assert_eq!(
+      default.gateway,
+      Some(IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)))
+    );
+  }
+
+  #[test]
+  fn if_inet6_entries() {
+    const SAMPLE: &str = "\
+00000000000000000000000000000001 01 80 10 80       lo
+20010db8000000000000000000000001 02 40 00 00     eth0
+";
+    let entries: Vec<_> = parse_if_inet6(SAMPLE).collect();
+    assert_eq!(
+      entries,
+      [
+        (1, Ipv6Addr::LOCALHOST, 128),
+        (2, Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), 64),
+      ]
+    );
+  }
+
+  // On a host where procfs is mounted the live tables must parse and
+  // the lookups must not error.
+  #[test]
+  fn live_tables() {
+    if std::fs::metadata(ROUTE_PATH).is_err() {
+      return;
+    }
+    gateway_addrs::<crate::IfAddr, _>(AddressFamily::UNSPEC, |_| true).unwrap();
+    let mut out = SmallVec::<crate::IfNet>::new();
+    best_local_addrs_into(AddressFamily::INET, &mut out).unwrap();
+    best_local_addrs_into(AddressFamily::INET6, &mut out).unwrap();
+    best_default_indices(AddressFamily::INET).unwrap();
+    best_default_indices(AddressFamily::INET6).unwrap();
+  }
+}
@@ -5,7 +5,7 @@ use std::{
 
 use smallvec_wrapper::SmallVec;
 
-use super::{os, IfNet, Ifv4Net, Ifv6Net};
+use super::{os, Family, IfNet, Ifv4Net, Ifv6Net, Result};
 
 /// Returns all non-loopback IPv4 addresses configured on every
 /// interface on the system.
@@ -28,8 +28,8 @@ use super::{os, IfNet, Ifv4Net, Ifv6Net};
 ///   println!("{addr}");
 /// }
 /// ```
-pub fn local_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
-  os::local_ipv4_addrs()
+pub fn local_ipv4_addrs() -> Result<SmallVec<Ifv4Net>> {
+  os::local_ipv4_addrs().map_err(Into::into)
 }
 
 /// Returns all non-loopback IPv6 addresses configured on every
@@ -53,8 +53,8 @@ pub fn local_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
 ///   println!("{addr}");
 /// }
 /// ```
-pub fn local_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
-  os::local_ipv6_addrs()
+pub fn local_ipv6_addrs() -> Result<SmallVec<Ifv6Net>> {
+  os::local_ipv6_addrs().map_err(Into::into)
 }
 
 /// Returns all non-loopback IP addresses (both IPv4 and IPv6)
@@ -78,8 +78,8 @@ pub fn local_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
 ///   println!("{addr}");
 /// }
 /// ```
-pub fn local_addrs() -> io::Result<SmallVec<IfNet>> {
-  os::local_addrs()
+pub fn local_addrs() -> Result<SmallVec<IfNet>> {
+  os::local_addrs().map_err(Into::into)
 }
 
 /// Returns all non-loopback IPv4 addresses configured on every
@@ -99,11 +99,11 @@ pub fn local_addrs() -> io::Result<SmallVec<IfNet>> {
 ///   println!("{addr}");
 /// }
 /// ```
-pub fn local_ipv4_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<Ifv4Net>>
+pub fn local_ipv4_addrs_by_filter<F>(f: F) -> Result<SmallVec<Ifv4Net>>
 where
   F: FnMut(&Ipv4Addr) -> bool,
 {
-  os::local_ipv4_addrs_by_filter(f)
+  os::local_ipv4_addrs_by_filter(f).map_err(Into::into)
 }
 
 /// Returns all non-loopback IPv6 addresses configured on every
@@ -123,11 +123,11 @@ where
 ///   println!("{addr}");
 /// }
 /// ```
-pub fn local_ipv6_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<Ifv6Net>>
+pub fn local_ipv6_addrs_by_filter<F>(f: F) -> Result<SmallVec<Ifv6Net>>
 where
   F: FnMut(&Ipv6Addr) -> bool,
 {
-  os::local_ipv6_addrs_by_filter(f)
+  os::local_ipv6_addrs_by_filter(f).map_err(Into::into)
 }
 
 /// Returns all non-loopback IP addresses (both IPv4 and IPv6)
@@ -149,11 +149,105 @@ where
 ///   println!("{addr}");
 /// }
 /// ```
-pub fn local_addrs_by_filter<F>(f: F) -> io::Result<SmallVec<IfNet>>
+pub fn local_addrs_by_filter<F>(f: F) -> Result<SmallVec<IfNet>>
 where
   F: FnMut(&IpAddr) -> bool,
 {
-  os::local_addrs_by_filter(f)
+  os::local_addrs_by_filter(f).map_err(Into::into)
+}
+
+/// Like [`local_addrs_by_filter`], but the filter sees the whole
+/// [`IfNet`], so it can also select by prefix length or interface
+/// index.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{local_addrs_by_net_filter, loopback_interface};
+///
+/// let lo = loopback_interface().unwrap().map(|ifi| ifi.index());
+/// let addrs =
+///   local_addrs_by_net_filter(|net| net.prefix_len() >= 24 && Some(net.index()) != lo).unwrap();
+/// for addr in addrs {
+///   println!("{addr}");
+/// }
+/// ```
+pub fn local_addrs_by_net_filter<F>(mut f: F) -> Result<SmallVec<IfNet>>
+where
+  F: FnMut(&IfNet) -> bool,
+{
+  let mut addrs = os::local_addrs()?;
+  addrs.retain(|net| f(net));
+  Ok(addrs)
+}
+
+/// Like [`local_addrs`], but drops addresses on Apple's peer-to-peer
+/// `awdl` and `llw` interfaces.
+///
+/// Those links carry an IPv6 link-local address (filtered anyway) and,
+/// while AirDrop or similar is active, sometimes more; none of it is
+/// reachable beyond the nearby peer. Costs one extra interface dump to
+/// learn which indices to drop.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::local_addrs_excluding_awdl;
+///
+/// for addr in local_addrs_excluding_awdl().unwrap() {
+///   println!("{addr}");
+/// }
+/// ```
+#[cfg(apple)]
+#[cfg_attr(docsrs, doc(cfg(target_vendor = "apple")))]
+pub fn local_addrs_excluding_awdl() -> Result<SmallVec<IfNet>> {
+  let peer_to_peer: SmallVec<u32> = crate::interfaces()?
+    .into_iter()
+    .filter(|ifi| ifi.apple_hint().is_some_and(|hint| hint.is_peer_to_peer()))
+    .map(|ifi| ifi.index())
+    .collect();
+  let mut addrs = local_addrs()?;
+  addrs.retain(|addr| !peer_to_peer.contains(&addr.index()));
+  Ok(addrs)
+}
+
+/// Returns [`local_addrs`], each paired with the metric of the
+/// address's connected (on-link) route.
+///
+/// The connected route is the gateway-less route on the address's
+/// interface whose destination is the address's own prefix; when two
+/// interfaces hold addresses in the same subnet, the lower metric marks
+/// the one the kernel prefers. The metric is `None` when there is no
+/// such route — a host-prefix address, or one added with
+/// `noprefixroute`. See [`Ipv4Route::metric`](crate::Ipv4Route::metric)
+/// for what each platform reports.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::local_addrs_with_metric;
+///
+/// for (addr, metric) in local_addrs_with_metric().unwrap() {
+///   println!("{addr}: {metric:?}");
+/// }
+/// ```
+pub fn local_addrs_with_metric() -> Result<SmallVec<(IfNet, Option<u32>)>> {
+  let addrs = local_addrs()?;
+  let connected = crate::route_table_by_filter(|route| route.gateway().is_none())?;
+  Ok(
+    addrs
+      .into_iter()
+      .map(|addr| {
+        let prefix = addr.net().trunc();
+        let metric = connected
+          .iter()
+          .filter(|route| route.index() == addr.index() && route.destination() == prefix)
+          .map(|route| route.metric())
+          .min();
+        (addr, metric)
+      })
+      .collect(),
+  )
 }
 
 /// Returns the IPv4 addresses from the interface(s) with the best default route.
@@ -185,8 +279,8 @@ where
 ///   println!("{addr}");
 /// }
 /// ```
-pub fn best_local_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
-  os::best_local_ipv4_addrs()
+pub fn best_local_ipv4_addrs() -> Result<SmallVec<Ifv4Net>> {
+  os::best_local_ipv4_addrs().map_err(Into::into)
 }
 
 /// Returns the IPv6 addresses from the interface(s) with the best default route.
@@ -218,8 +312,77 @@ pub fn best_local_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
 ///   println!("{addr}");
 /// }
 /// ```
-pub fn best_local_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
-  os::best_local_ipv6_addrs()
+pub fn best_local_ipv6_addrs() -> Result<SmallVec<Ifv6Net>> {
+  os::best_local_ipv6_addrs().map_err(Into::into)
+}
+
+/// Like [`best_local_ipv4_addrs`], but distinguishes "no default route"
+/// from "the default-route interface has no usable address".
+///
+/// Returns `Ok(None)` when there is no usable IPv4 default route (no
+/// connectivity), and `Ok(Some(addrs))` — possibly empty — when there
+/// is one. Errors, such as a denied routing socket, are still `Err`.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::best_local_ipv4_addrs_opt;
+///
+/// match best_local_ipv4_addrs_opt().unwrap() {
+///   None => println!("no IPv4 default route"),
+///   Some(addrs) if addrs.is_empty() => println!("default route, but no address"),
+///   Some(addrs) => println!("{addrs:?}"),
+/// }
+/// ```
+pub fn best_local_ipv4_addrs_opt() -> Result<Option<SmallVec<Ifv4Net>>> {
+  os::best_local_ipv4_addrs_opt().map_err(Into::into)
+}
+
+/// Like [`best_local_ipv6_addrs`], but distinguishes "no default route"
+/// from "the default-route interface has no usable address".
+///
+/// Returns `Ok(None)` when there is no usable IPv6 default route, and
+/// `Ok(Some(addrs))` — possibly empty — when there is one.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::best_local_ipv6_addrs_opt;
+///
+/// if best_local_ipv6_addrs_opt().unwrap().is_none() {
+///   println!("no IPv6 default route");
+/// }
+/// ```
+pub fn best_local_ipv6_addrs_opt() -> Result<Option<SmallVec<Ifv6Net>>> {
+  os::best_local_ipv6_addrs_opt().map_err(Into::into)
+}
+
+/// Returns the single IPv6 address from the best-default-route
+/// interface that macOS would pick as the source for outbound traffic,
+/// or `None` if that interface has no usable global IPv6 address.
+///
+/// With `net.inet6.ip6.prefer_tempaddr` enabled (the default), the
+/// kernel prefers a non-deprecated address over a deprecated one
+/// (RFC 6724 rule 3) and, among those, a privacy (temporary) address
+/// over the stable EUI-64 / opaque one (rule 7). This reads each
+/// candidate's `ia6_flags` via `SIOCGIFAFLAG_IN6` and applies the same
+/// order; tentative, duplicated and detached addresses are skipped.
+///
+/// See also [`best_local_ipv6_addrs`].
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::best_local_ipv6_addr_for_outbound;
+///
+/// if let Some(addr) = best_local_ipv6_addr_for_outbound().unwrap() {
+///   println!("{addr}");
+/// }
+/// ```
+#[cfg(target_os = "macos")]
+#[cfg_attr(docsrs, doc(cfg(target_os = "macos")))]
+pub fn best_local_ipv6_addr_for_outbound() -> Result<Option<Ifv6Net>> {
+  os::best_local_ipv6_addr_for_outbound().map_err(Into::into)
 }
 
 /// Returns both IPv4 and IPv6 addresses from the interfaces with the best default routes.
@@ -238,6 +401,10 @@ pub fn best_local_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
 /// kernel would actually pick; callers needing kernel-equivalent
 /// selection should issue `RTM_GET` over `PF_ROUTE` themselves.
 ///
+/// Returns an empty list when there is no default route; use
+/// [`best_local_ipv4_addrs_opt`] / [`best_local_ipv6_addrs_opt`] to tell
+/// that apart from a default-route interface without addresses.
+///
 /// See also [`local_addrs`].
 ///
 /// ## Example
@@ -251,6 +418,127 @@ pub fn best_local_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
 ///   println!("{addr}");
 /// }
 /// ```
-pub fn best_local_addrs() -> io::Result<SmallVec<IfNet>> {
-  os::best_local_addrs()
+pub fn best_local_addrs() -> Result<SmallVec<IfNet>> {
+  os::best_local_addrs().map_err(Into::into)
+}
+
+/// Like [`best_local_addrs`], but picks the default routes from one
+/// Linux routing table, such as a table a VPN or `ip rule` policy
+/// installs its default in.
+///
+/// [`best_local_addrs`] weighs the defaults in the built-in `local`,
+/// `main` and `default` tables; this looks at `table` alone. Table `0`
+/// (`RT_TABLE_UNSPEC`) names no table and fails with
+/// [`io::ErrorKind::InvalidInput`]. Unlike [`best_local_addrs`] there is
+/// no `/proc/net` fallback when the netlink socket is denied, since
+/// procfs does not say which table a route is in.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::best_local_addrs_in;
+///
+/// // The `main` table.
+/// for addr in best_local_addrs_in(254).unwrap() {
+///   println!("{addr}");
+/// }
+/// ```
+#[cfg(linux_like)]
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn best_local_addrs_in(table: u32) -> Result<SmallVec<IfNet>> {
+  os::best_local_addrs_in(table).map_err(Into::into)
+}
+
+/// Returns the addresses of `family` a server can `bind()` to and
+/// offer as listen addresses, loopback included.
+///
+/// Unlike [`interface_addrs`](crate::interface_addrs), addresses the
+/// kernel would refuse to bind or steers new connections away from are
+/// left out:
+///
+/// - **Linux / Android**: IPv6 addresses flagged tentative (DAD still
+///   running), DAD-failed or deprecated.
+/// - **macOS**: IPv6 addresses flagged anycast, tentative, duplicated,
+///   detached or deprecated in their `ia6_flags`.
+/// - **Other BSDs**: every address; their per-address IPv6 flags are
+///   not read.
+/// - **Windows**: addresses whose DAD state is not preferred, and every
+///   address of an adapter that is not operationally up.
+///
+/// Anycast addresses are never part of the unicast lists this walks.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{bindable_addrs, Family};
+///
+/// for addr in bindable_addrs(Family::V6).unwrap() {
+///   println!("listen on [{}]", addr.addr());
+/// }
+/// ```
+pub fn bindable_addrs(family: Family) -> Result<SmallVec<IfNet>> {
+  os::bindable_addrs(family).map_err(Into::into)
+}
+
+/// Returns the local address the system would use as the source when
+/// sending to `dest`, with the interface it is configured on, or `None`
+/// when no route covers `dest`.
+///
+/// Unlike [`best_local_addrs`], which looks only at the default route,
+/// this asks the kernel for the route to `dest` itself, so a more
+/// specific route (a VPN subnet, a directly connected network) is
+/// honoured.
+///
+/// - **Linux / Android**: a single `RTM_GETROUTE` for `dest`, as
+///   `ip route get` sends, reading `RTA_PREFSRC` and `RTA_OIF`.
+/// - **BSD-like / macOS**: an `RTM_GET` on a routing socket, as
+///   `route get` sends, reading `RTAX_IFA` and `rtm_index`.
+/// - **Windows**: `GetBestRoute2`.
+///
+/// Best-effort: policy routing keyed on anything but the destination
+/// (source address, mark, uid) is not taken into account, and when the
+/// kernel names no source, the outgoing interface's address is chosen
+/// the way [`Interface::source_addr`](crate::Interface::source_addr)
+/// does.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::source_addr_for;
+///
+/// if let Some(src) = source_addr_for("127.0.0.1".parse().unwrap()).unwrap() {
+///   println!("{src}");
+/// }
+/// ```
+pub fn source_addr_for(dest: IpAddr) -> Result<Option<IfNet>> {
+  let Some((index, src)) = os::route_source(dest)? else {
+    return Ok(None);
+  };
+  let Some(src) = src else {
+    return os::interface_source_addr(index, Family::of(&dest)).map_err(Into::into);
+  };
+  // A route to one of the host's own addresses goes out through
+  // loopback while the address lives elsewhere, so the source is
+  // looked up on every interface, preferring the route's.
+  let candidates = interface_addrs_matching(0, |ip| *ip == src)?;
+  Ok(
+    candidates
+      .iter()
+      .find(|net| net.index() == index)
+      .or_else(|| candidates.first())
+      .copied(),
+  )
+}
+
+fn interface_addrs_matching<F>(index: u32, f: F) -> io::Result<SmallVec<IfNet>>
+where
+  F: FnMut(&IpAddr) -> bool,
+{
+  cfg_if::cfg_if! {
+    if #[cfg(windows)] {
+      os::interface_addresses((index != 0).then_some(index), f)
+    } else {
+      os::interface_addresses(index, f)
+    }
+  }
 }
@@ -0,0 +1,86 @@
+use std::io;
+
+use super::Result;
+
+use windows_sys::Win32::{
+  Foundation::NO_ERROR,
+  NetworkManagement::{
+    IpHelper::{ConvertInterfaceIndexToLuid, ConvertInterfaceLuidToIndex},
+    Ndis::NET_LUID_LH,
+  },
+};
+
+/// Returns the interface index of the interface with the given LUID.
+///
+/// Unlike interface indices, which Windows may reassign across reboots
+/// or driver reloads, an interface LUID (`NET_LUID`) stays stable for
+/// the lifetime of the adapter. The LUID is passed as its raw 64-bit
+/// `Value`.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{ifindex_to_luid, ifluid_to_index, interfaces};
+///
+/// let interface = interfaces().unwrap().into_iter().next().unwrap();
+/// let luid = ifindex_to_luid(interface.index()).unwrap();
+///
+/// assert_eq!(ifluid_to_index(luid).unwrap(), interface.index());
+/// ```
+pub fn ifluid_to_index(luid: u64) -> Result<u32> {
+  let luid = NET_LUID_LH { Value: luid };
+  let mut index = 0u32;
+  // SAFETY: `luid` is a valid `NET_LUID_LH` and `index` is a writable
+  // local `u32`.
+  let res = unsafe { ConvertInterfaceLuidToIndex(&luid, &mut index) };
+  if res != NO_ERROR {
+    return Err(io::Error::from_raw_os_error(res as i32).into());
+  }
+  Ok(index)
+}
+
+/// Returns the LUID of the interface with the given index.
+///
+/// The LUID is returned as its raw 64-bit `Value`; see
+/// [`ifluid_to_index`] for the reverse conversion.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{ifindex_to_luid, interfaces};
+///
+/// let interface = interfaces().unwrap().into_iter().next().unwrap();
+/// let luid = ifindex_to_luid(interface.index()).unwrap();
+///
+/// println!("{}: {luid:#x}", interface.name());
+/// ```
+pub fn ifindex_to_luid(index: u32) -> Result<u64> {
+  let mut luid = NET_LUID_LH { Value: 0 };
+  // SAFETY: `luid` is a writable local `NET_LUID_LH`.
+  let res = unsafe { ConvertInterfaceIndexToLuid(index, &mut luid) };
+  if res != NO_ERROR {
+    return Err(io::Error::from_raw_os_error(res as i32).into());
+  }
+  // SAFETY: every bit pattern of the union is a valid `u64`.
+  Ok(unsafe { luid.Value })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn out_of_range_index_returns_err() {
+    assert!(ifindex_to_luid(0xFFFE_FFFE).is_err());
+  }
+
+  // Every enumerated interface must survive an index → LUID → index
+  // round trip.
+  #[test]
+  fn round_trip_all_interfaces() {
+    for ifi in crate::interfaces().unwrap() {
+      let luid = ifindex_to_luid(ifi.index()).unwrap();
+      assert_eq!(ifluid_to_index(luid).unwrap(), ifi.index(), "{ifi:?}");
+    }
+  }
+}
@@ -40,7 +40,7 @@ macro_rules! cfg_bsd_multicast {
       // `NET_RT_MAXID = 4` confirms there are only 4 selectors,
       // none of them multicast). The DragonFly impl in
       // `bsd_like.rs::interface_multiaddr_table` returns
-      // `Err(ErrorKind::Unsupported)` — see the doc comment there.
+      // `Err(Error::UnsupportedPlatform)` — see the doc comment there.
       #[cfg(any(
         target_vendor = "apple",
         target_os = "freebsd",
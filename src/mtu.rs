@@ -1,15 +1,9 @@
 use core::net::IpAddr;
-use std::{
-  io,
-  net::{Ipv4Addr, Ipv6Addr},
-};
-
-use super::{interface_addrs, interface_ipv4_addrs, interface_ipv6_addrs, interfaces};
+use std::net::{Ipv4Addr, Ipv6Addr};
 
-#[inline]
-fn interface_not_found_for_ip() -> io::Error {
-  io::Error::other("interface not found")
-}
+use super::{
+  interface_addrs, interface_ipv4_addrs, interface_ipv6_addrs, interfaces, Error, Result,
+};
 
 /// Get the MTU of the given [`IpAddr`].
 ///
@@ -21,7 +15,7 @@ fn interface_not_found_for_ip() -> io::Error {
 /// let mtu = get_ip_mtu("127.0.0.1".parse().unwrap()).unwrap();
 /// println!("MTU: {}", mtu);
 /// ```
-pub fn get_ip_mtu(ip: IpAddr) -> io::Result<u32> {
+pub fn get_ip_mtu(ip: IpAddr) -> Result<u32> {
   // Fast path: enumerate interfaces (with their MTUs) and all
   // interface addresses exactly once each, then match by
   // `IpAddr` → index → MTU in memory. O(1) dumps regardless of
@@ -51,7 +45,7 @@ pub fn get_ip_mtu(ip: IpAddr) -> io::Result<u32> {
       }
     }
   }
-  Err(interface_not_found_for_ip())
+  Err(Error::InterfaceNotFound)
 }
 
 /// Get the MTU of the given [`Ipv4Addr`].
@@ -65,7 +59,7 @@ pub fn get_ip_mtu(ip: IpAddr) -> io::Result<u32> {
 /// let mtu = get_ipv4_mtu(Ipv4Addr::LOCALHOST).unwrap();
 /// println!("MTU: {}", mtu);
 /// ```
-pub fn get_ipv4_mtu(ip: Ipv4Addr) -> io::Result<u32> {
+pub fn get_ipv4_mtu(ip: Ipv4Addr) -> Result<u32> {
   // Same fast-path / per-interface fallback shape as `get_ip_mtu`
   // — see the comment there for rationale.
   let ifis = interfaces()?;
@@ -86,7 +80,7 @@ pub fn get_ipv4_mtu(ip: Ipv4Addr) -> io::Result<u32> {
       }
     }
   }
-  Err(interface_not_found_for_ip())
+  Err(Error::InterfaceNotFound)
 }
 
 /// Get the MTU of the given [`Ipv6Addr`].
@@ -100,7 +94,7 @@ pub fn get_ipv4_mtu(ip: Ipv4Addr) -> io::Result<u32> {
 /// let mtu = get_ipv6_mtu(Ipv6Addr::LOCALHOST).unwrap();
 /// println!("MTU: {}", mtu);
 /// ```
-pub fn get_ipv6_mtu(ip: Ipv6Addr) -> io::Result<u32> {
+pub fn get_ipv6_mtu(ip: Ipv6Addr) -> Result<u32> {
   // Same fast-path / per-interface fallback shape as `get_ip_mtu`
   // — see the comment there for rationale.
   let ifis = interfaces()?;
@@ -121,27 +115,24 @@ pub fn get_ipv6_mtu(ip: Ipv6Addr) -> io::Result<u32> {
       }
     }
   }
-  Err(interface_not_found_for_ip())
+  Err(Error::InterfaceNotFound)
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
-  // Hits the `interface_not_found_for_ip()` constructor and the
-  // trailing `Err(...)` returns in each of the three lookup
+  // Hits the trailing `Err(...)` returns in each of the three lookup
   // functions. Uses a documentation-reserved IP that's guaranteed
   // not to be assigned to any local interface (RFC 5737 TEST-NET-3
   // for IPv4; RFC 3849 documentation prefix for IPv6).
   //
-  // Asserts on the specific "interface not found" message so a
-  // failure of `interfaces()` or `interface_addrs()` (which would
-  // also surface as `Err`, but for an unrelated reason) doesn't
-  // accidentally make the test pass.
-  fn assert_not_found(err: io::Error) {
-    assert_eq!(err.kind(), io::ErrorKind::Other);
+  // Asserts on the specific variant so a failure of `interfaces()` or
+  // `interface_addrs()` (which would also surface as `Err`, but for an
+  // unrelated reason) doesn't accidentally make the test pass.
+  fn assert_not_found(err: Error) {
     assert!(
-      err.to_string().contains("interface not found"),
+      matches!(err, Error::InterfaceNotFound),
       "expected interface-not-found error, got: {err}"
     );
   }
@@ -1,7 +1,14 @@
 use std::io;
 
+use super::Result;
+
 /// Returns the index of the interface by the given name.
 ///
+/// A name that matches no interface fails with
+/// [`Error::InterfaceNotFound`](crate::Error::InterfaceNotFound). On
+/// Windows the name may be either the friendly name (alias) or the NDIS
+/// interface name.
+///
 /// ## Example
 ///
 /// ```rust
@@ -12,8 +19,118 @@ use std::io;
 ///
 /// assert_eq!(interface.index(), index);
 /// ```
-pub fn ifname_to_index(name: &str) -> io::Result<u32> {
-  ifname_to_index_in(name)
+pub fn ifname_to_index(name: &str) -> Result<u32> {
+  lookup(name)?.ok_or(crate::Error::InterfaceNotFound)
+}
+
+/// Returns whether an interface with the given name exists.
+///
+/// A name that matches no interface yields `Ok(false)`; `Err` is
+/// reserved for failures that say nothing about the name (e.g. the
+/// lookup socket cannot be opened). On Windows the name may be either
+/// the friendly name (alias) or the NDIS interface name, matching
+/// [`ifname_to_index`].
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::{interface_exists, interfaces};
+///
+/// let interface = interfaces().unwrap().into_iter().next().unwrap();
+/// assert!(interface_exists(interface.name()).unwrap());
+/// assert!(!interface_exists("nonexistent_iface_xyz_12345").unwrap());
+/// ```
+pub fn interface_exists(name: &str) -> Result<bool> {
+  Ok(lookup(name)?.is_some())
+}
+
+/// Resolves `name` to its index, `None` when no interface has it.
+#[cfg(not(windows))]
+fn lookup(name: &str) -> io::Result<Option<u32>> {
+  match ifname_to_index_in(name) {
+    Ok(index) => Ok(Some(index)),
+    Err(e) if is_no_such_interface(&e) => Ok(None),
+    Err(e) => Err(e),
+  }
+}
+
+/// `if_nametoindex(3)` reports an unknown name as `ENXIO` on the BSDs
+/// and `ENODEV` on Linux. A name the kernel could never accept (interior
+/// NUL) cannot name an existing interface either.
+#[cfg(not(windows))]
+pub(crate) fn is_no_such_interface(err: &io::Error) -> bool {
+  #[cfg(bsd_like)]
+  const NOT_FOUND: [i32; 2] = [libc::ENXIO, libc::ENODEV];
+  #[cfg(linux_like)]
+  const NOT_FOUND: [i32; 2] = [
+    rustix::io::Errno::NXIO.raw_os_error(),
+    rustix::io::Errno::NODEV.raw_os_error(),
+  ];
+
+  err.kind() == io::ErrorKind::InvalidInput
+    || err
+      .raw_os_error()
+      .is_some_and(|code| NOT_FOUND.contains(&code))
+}
+
+/// Tries `name` as the friendly name (alias) first, then as the NDIS
+/// interface name; `None` when neither names an interface.
+#[cfg(windows)]
+fn lookup(name: &str) -> io::Result<Option<u32>> {
+  use std::ffi::CString;
+
+  use widestring::U16CString;
+  use windows_sys::Win32::{
+    Foundation::{ERROR_INVALID_NAME, ERROR_INVALID_PARAMETER, ERROR_NOT_FOUND, NO_ERROR},
+    NetworkManagement::{
+      IpHelper::{
+        ConvertInterfaceAliasToLuid, ConvertInterfaceLuidToIndex, ConvertInterfaceNameToLuidA,
+      },
+      Ndis::NET_LUID_LH,
+    },
+  };
+
+  // Both conversions report an unknown name as one of these rather
+  // than a dedicated "no such interface" code.
+  let not_found = |code: u32| {
+    matches!(
+      code,
+      ERROR_NOT_FOUND | ERROR_INVALID_PARAMETER | ERROR_INVALID_NAME
+    )
+  };
+
+  let index = |luid: &NET_LUID_LH| {
+    let mut index = 0u32;
+    // SAFETY: `luid` came from a successful conversion and `index` is
+    // writable.
+    match unsafe { ConvertInterfaceLuidToIndex(luid, &mut index) } {
+      NO_ERROR => Ok(Some(index)),
+      res => Err(io::Error::from_raw_os_error(res as i32)),
+    }
+  };
+
+  let mut luid = NET_LUID_LH { Value: 0 };
+  if let Ok(wide_name) = U16CString::from_str(name) {
+    // SAFETY: `wide_name` is NUL-terminated and `luid` is writable.
+    let res = unsafe { ConvertInterfaceAliasToLuid(wide_name.as_ptr(), &mut luid) };
+    if res == NO_ERROR {
+      return index(&luid);
+    }
+    if !not_found(res) {
+      return Err(io::Error::from_raw_os_error(res as i32));
+    }
+  }
+
+  let Ok(name) = CString::new(name) else {
+    return Ok(None);
+  };
+  // SAFETY: `name` is NUL-terminated and `luid` is writable.
+  let res = unsafe { ConvertInterfaceNameToLuidA(name.as_ptr() as _, &mut luid) };
+  match res {
+    NO_ERROR => index(&luid),
+    res if not_found(res) => Ok(None),
+    res => Err(io::Error::from_raw_os_error(res as i32)),
+  }
 }
 
 #[cfg(bsd_like)]
@@ -41,65 +158,37 @@ fn ifname_to_index_in(name: &str) -> io::Result<u32> {
   name_to_index(socket_fd, name).map_err(Into::into)
 }
 
-#[cfg(windows)]
-fn ifname_to_index_in(name: &str) -> io::Result<u32> {
-  use std::ffi::CString;
-
-  use widestring::U16CString;
-  use windows_sys::Win32::NetworkManagement::{
-    IpHelper::{if_nametoindex, ConvertInterfaceAliasToLuid, ConvertInterfaceLuidToIndex},
-    Ndis::NET_LUID_LH,
-  };
-
-  fn try_friendly_name(name: &str) -> io::Result<u32> {
-    let wide_name =
-      U16CString::from_str(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-    let mut luid = NET_LUID_LH { Value: 0 };
-
-    // Convert friendly name to LUID
-    let result = unsafe { ConvertInterfaceAliasToLuid(wide_name.as_ptr(), &mut luid) };
-    if result != 0 {
-      return Err(io::Error::last_os_error());
-    }
-
-    // Convert LUID to index
-    let mut idx = 0u32;
-    let result = unsafe { ConvertInterfaceLuidToIndex(&luid, &mut idx) };
-    if result != 0 {
-      return Err(io::Error::last_os_error());
-    }
-
-    Ok(idx)
-  }
-
-  // Try friendly name first
-  try_friendly_name(name).or_else(|_| {
-    // fallback to if_nametoindex
-    let name_cstr =
-      CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
-
-    let res = unsafe { if_nametoindex(name_cstr.as_ptr() as _) };
-    if res == 0 {
-      Err(io::Error::last_os_error())
-    } else {
-      Ok(res)
-    }
-  })
-}
-
 #[cfg(test)]
 mod tests {
   use super::*;
 
   // Covers the `Err(...)` arm on every platform: a name that
   // doesn't correspond to any interface should surface as
-  // `Err(io::Error)`. Uses an obviously-fake string with characters
+  // `InterfaceNotFound`. Uses an obviously-fake string with characters
   // most kernels reject for ifnames.
   #[test]
   fn nonexistent_name_returns_err() {
     let r = ifname_to_index("nonexistent_iface_xyz_12345");
-    assert!(r.is_err());
+    assert!(matches!(r, Err(crate::Error::InterfaceNotFound)), "{r:?}");
+  }
+
+  #[test]
+  fn exists_reports_bogus_name_as_false() {
+    assert!(!interface_exists("nonexistent_iface_xyz_12345").unwrap());
+    assert!(!interface_exists("bad\0name").unwrap());
+  }
+
+  // Skipped on DragonFly for the same interface-churn reason as
+  // `round_trip_first_interface` below.
+  #[cfg(not(target_os = "dragonfly"))]
+  #[test]
+  fn exists_reports_loopback_as_true() {
+    let lo = crate::interfaces()
+      .unwrap()
+      .into_iter()
+      .find(|ifi| ifi.flags().contains(crate::Flags::LOOPBACK))
+      .unwrap();
+    assert!(interface_exists(lo.name()).unwrap());
   }
 
   // Covers the success arm by round-tripping a real interface
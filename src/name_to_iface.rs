@@ -1,4 +1,6 @@
-use std::{io, net::Ipv4Addr};
+use std::net::Ipv4Addr;
+
+use super::Result;
 
 /// Returns the IPv4 interface of by the given name.
 ///
@@ -15,7 +17,7 @@ use std::{io, net::Ipv4Addr};
 /// let addrs = interface.ipv4_addrs().unwrap().into_iter().map(|net| net.addr()).collect::<Vec<_>>();
 /// assert!(addrs.contains(&iface));
 /// ```
-pub fn ifname_to_v4_iface(name: &str) -> io::Result<Option<Ipv4Addr>> {
+pub fn ifname_to_v4_iface(name: &str) -> Result<Option<Ipv4Addr>> {
   let idx = super::name_to_idx::ifname_to_index(name)?;
   let iface = super::interface_by_index(idx)?;
 
@@ -43,7 +45,7 @@ pub fn ifname_to_v4_iface(name: &str) -> io::Result<Option<Ipv4Addr>> {
 ///
 /// assert_eq!(interface.index(), iface.unwrap());
 /// ```
-pub fn ifname_to_v6_iface(name: &str) -> io::Result<Option<u32>> {
+pub fn ifname_to_v6_iface(name: &str) -> Result<Option<u32>> {
   super::name_to_idx::ifname_to_index(name).map(|idx| (idx != 0).then_some(idx))
 }
 
@@ -64,7 +66,7 @@ pub fn ifname_to_v6_iface(name: &str) -> io::Result<Option<u32>> {
 /// let addrs = interface.ipv4_addrs().unwrap().into_iter().map(|net| net.addr()).collect::<Vec<_>>();
 /// assert!(addrs.contains(&v4_iface.unwrap()));
 /// ```
-pub fn ifname_to_iface(name: &str) -> io::Result<(Option<Ipv4Addr>, Option<u32>)> {
+pub fn ifname_to_iface(name: &str) -> Result<(Option<Ipv4Addr>, Option<u32>)> {
   let idx = super::name_to_idx::ifname_to_index(name)?;
   let v6_iface = (idx != 0).then_some(idx);
   let iface = super::interface_by_index(idx)?;
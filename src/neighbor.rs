@@ -0,0 +1,224 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use smallvec_wrapper::SmallVec;
+use smol_str::SmolStr;
+
+use super::{ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter, os, Family, MacAddr, Result};
+
+/// The reachability state of a [`Neighbor`] entry.
+///
+/// The names follow the Linux `NUD_*` and Windows `NL_NEIGHBOR_STATE`
+/// values, which describe the same RFC 4861 state machine. The BSD
+/// sysctl dump carries no state, so entries there are reported as
+/// [`Permanent`](Self::Permanent) when static, [`Reachable`](Self::Reachable)
+/// when resolved and [`Incomplete`](Self::Incomplete) otherwise.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NeighborState {
+  /// Address resolution is in progress.
+  Incomplete,
+  /// The link-layer address was recently confirmed.
+  Reachable,
+  /// The link-layer address is known but has not been confirmed
+  /// recently.
+  Stale,
+  /// Waiting before probing a stale entry.
+  Delay,
+  /// Probing a stale entry.
+  Probe,
+  /// Address resolution failed.
+  Failed,
+  /// The link needs no resolution (loopback, point-to-point).
+  NoArp,
+  /// A static entry that never expires.
+  Permanent,
+  /// A state the platform reported that none of the above covers.
+  Unknown,
+}
+
+impl NeighborState {
+  /// Returns `true` if the entry's link-layer address can be used to
+  /// send traffic.
+  #[inline]
+  pub const fn is_valid(&self) -> bool {
+    matches!(
+      self,
+      Self::Reachable | Self::Stale | Self::Delay | Self::Probe | Self::Permanent | Self::NoArp
+    )
+  }
+}
+
+/// An entry from the kernel neighbour (ARP / NDP) table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Neighbor {
+  index: u32,
+  ip: IpAddr,
+  mac: Option<MacAddr>,
+  state: NeighborState,
+}
+
+impl core::fmt::Display for Neighbor {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self.mac {
+      Some(mac) => write!(
+        f,
+        "{} lladdr {} ({}) {:?}",
+        self.ip, mac, self.index, self.state
+      ),
+      None => write!(f, "{} ({}) {:?}", self.ip, self.index, self.state),
+    }
+  }
+}
+
+impl Neighbor {
+  #[inline]
+  pub(crate) const fn new(
+    index: u32,
+    ip: IpAddr,
+    mac: Option<MacAddr>,
+    state: NeighborState,
+  ) -> Self {
+    Self {
+      index,
+      ip,
+      mac,
+      state,
+    }
+  }
+
+  /// Returns the index of the interface the neighbour was seen on.
+  #[inline]
+  pub const fn index(&self) -> u32 {
+    self.index
+  }
+
+  /// Returns the name of the interface the neighbour was seen on.
+  ///
+  /// This method invokes `if_indextoname` internally.
+  pub fn name(&self) -> Result<SmolStr> {
+    crate::idx_to_name::ifindex_to_name(self.index)
+  }
+
+  /// Returns the neighbour's IP address.
+  #[inline]
+  pub const fn ip(&self) -> IpAddr {
+    self.ip
+  }
+
+  /// Returns the neighbour's link-layer address, or `None` while it is
+  /// unresolved or when the link does not use MAC addresses.
+  #[inline]
+  pub const fn mac_addr(&self) -> Option<MacAddr> {
+    self.mac
+  }
+
+  /// Returns the entry's reachability state.
+  #[inline]
+  pub const fn state(&self) -> NeighborState {
+    self.state
+  }
+}
+
+/// Returns every entry in the kernel neighbour table, both IPv4 (ARP)
+/// and IPv6 (NDP).
+///
+/// Reads `RTM_GETNEIGH` on Linux, the `NET_RT_FLAGS` / `RTF_LLINFO`
+/// sysctl on BSD and macOS, and `GetIpNetTable2` on Windows.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::neighbors;
+///
+/// for neighbor in neighbors().unwrap() {
+///   println!("{neighbor}");
+/// }
+/// ```
+pub fn neighbors() -> Result<SmallVec<Neighbor>> {
+  os::neighbor_table(None, |_| true).map_err(Into::into)
+}
+
+/// Returns the IPv4 (ARP) entries of the kernel neighbour table.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::neighbors_ipv4;
+///
+/// for neighbor in neighbors_ipv4().unwrap() {
+///   println!("{neighbor}");
+/// }
+/// ```
+pub fn neighbors_ipv4() -> Result<SmallVec<Neighbor>> {
+  os::neighbor_table(Some(Family::V4), |_| true).map_err(Into::into)
+}
+
+/// Returns the IPv6 (NDP) entries of the kernel neighbour table.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::neighbors_ipv6;
+///
+/// for neighbor in neighbors_ipv6().unwrap() {
+///   println!("{neighbor}");
+/// }
+/// ```
+pub fn neighbors_ipv6() -> Result<SmallVec<Neighbor>> {
+  os::neighbor_table(Some(Family::V6), |_| true).map_err(Into::into)
+}
+
+/// Returns the neighbour table entries whose IP address matches the
+/// given filter.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::neighbors_by_filter;
+///
+/// for neighbor in neighbors_by_filter(|ip| !ip.is_loopback()).unwrap() {
+///   println!("{neighbor}");
+/// }
+/// ```
+pub fn neighbors_by_filter<F>(f: F) -> Result<SmallVec<Neighbor>>
+where
+  F: FnMut(&IpAddr) -> bool,
+{
+  os::neighbor_table(None, f).map_err(Into::into)
+}
+
+/// Returns the IPv4 neighbour table entries matching the given filter.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::neighbors_ipv4_by_filter;
+///
+/// for neighbor in neighbors_ipv4_by_filter(|ip| ip.is_private()).unwrap() {
+///   println!("{neighbor}");
+/// }
+/// ```
+pub fn neighbors_ipv4_by_filter<F>(f: F) -> Result<SmallVec<Neighbor>>
+where
+  F: FnMut(&Ipv4Addr) -> bool,
+{
+  os::neighbor_table(Some(Family::V4), ipv4_filter_to_ip_filter(f)).map_err(Into::into)
+}
+
+/// Returns the IPv6 neighbour table entries matching the given filter.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::neighbors_ipv6_by_filter;
+///
+/// // Link-local (`fe80::/10`) neighbours only.
+/// for neighbor in neighbors_ipv6_by_filter(|ip| ip.segments()[0] & 0xffc0 == 0xfe80).unwrap() {
+///   println!("{neighbor}");
+/// }
+/// ```
+pub fn neighbors_ipv6_by_filter<F>(f: F) -> Result<SmallVec<Neighbor>>
+where
+  F: FnMut(&Ipv6Addr) -> bool,
+{
+  os::neighbor_table(Some(Family::V6), ipv6_filter_to_ip_filter(f)).map_err(Into::into)
+}
@@ -1,14 +1,11 @@
-use std::{
-  io,
-  net::{IpAddr, Ipv4Addr, Ipv6Addr},
-};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use iprfc::RFC6890;
 use smallvec_wrapper::SmallVec;
 
-use crate::{ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter};
+use crate::{embedded_ipv4, ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter};
 
-use super::{os, IfNet, Ifv4Net, Ifv6Net};
+use super::{os, IfNet, Ifv4Net, Ifv6Net, Result};
 
 /// Returns all IPv4 addresses that are NOT part of [RFC
 /// 6890] (regardless of whether or not there is a default route, unlike
@@ -28,12 +25,12 @@ use super::{os, IfNet, Ifv4Net, Ifv6Net};
 /// ```
 ///
 /// [RFC 6890]: https://tools.ietf.org/html/rfc6890
-pub fn public_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
+pub fn public_ipv4_addrs() -> Result<SmallVec<Ifv4Net>> {
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_ipv4_addresses(None, public_ip_filter)
+      os::interface_ipv4_addresses(None, public_ip_filter).map_err(Into::into)
     } else {
-      os::interface_ipv4_addresses(0, public_ip_filter)
+      os::interface_ipv4_addresses(0, public_ip_filter).map_err(Into::into)
     }
   }
 }
@@ -56,12 +53,12 @@ pub fn public_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
 /// ```
 ///
 /// [RFC 6890]: https://tools.ietf.org/html/rfc6890
-pub fn public_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
+pub fn public_ipv6_addrs() -> Result<SmallVec<Ifv6Net>> {
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_ipv6_addresses(None, public_ip_filter)
+      os::interface_ipv6_addresses(None, public_ip_filter).map_err(Into::into)
     } else {
-      os::interface_ipv6_addresses(0, public_ip_filter)
+      os::interface_ipv6_addresses(0, public_ip_filter).map_err(Into::into)
     }
   }
 }
@@ -84,12 +81,12 @@ pub fn public_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
 /// ```
 ///
 /// [RFC 6890]: https://tools.ietf.org/html/rfc6890
-pub fn public_addrs() -> io::Result<SmallVec<IfNet>> {
+pub fn public_addrs() -> Result<SmallVec<IfNet>> {
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_addresses(None, public_ip_filter)
+      os::interface_addresses(None, public_ip_filter).map_err(Into::into)
     } else {
-      os::interface_addresses(0, public_ip_filter)
+      os::interface_addresses(0, public_ip_filter).map_err(Into::into)
     }
   }
 }
@@ -112,7 +109,7 @@ pub fn public_addrs() -> io::Result<SmallVec<IfNet>> {
 /// ```
 ///
 /// [RFC 6890]: https://tools.ietf.org/html/rfc6890
-pub fn public_ipv4_addrs_by_filter<F>(mut f: F) -> io::Result<SmallVec<Ifv4Net>>
+pub fn public_ipv4_addrs_by_filter<F>(mut f: F) -> Result<SmallVec<Ifv4Net>>
 where
   F: FnMut(&Ipv4Addr) -> bool,
 {
@@ -120,11 +117,11 @@ where
     if #[cfg(windows)] {
       os::interface_ipv4_addresses(None, |ip| {
         public_ip_filter(ip) && ipv4_filter_to_ip_filter(&mut f)(ip)
-      })
+      }).map_err(Into::into)
     } else {
       os::interface_ipv4_addresses(0, |ip| {
         public_ip_filter(ip) && ipv4_filter_to_ip_filter(&mut f)(ip)
-      })
+      }).map_err(Into::into)
     }
   }
 }
@@ -147,7 +144,7 @@ where
 /// ```
 ///
 /// [RFC 6890]: https://tools.ietf.org/html/rfc6890
-pub fn public_ipv6_addrs_by_filter<F>(mut f: F) -> io::Result<SmallVec<Ifv6Net>>
+pub fn public_ipv6_addrs_by_filter<F>(mut f: F) -> Result<SmallVec<Ifv6Net>>
 where
   F: FnMut(&Ipv6Addr) -> bool,
 {
@@ -155,11 +152,11 @@ where
     if #[cfg(windows)] {
       os::interface_ipv6_addresses(None, |ip| {
         public_ip_filter(ip) && ipv6_filter_to_ip_filter(&mut f)(ip)
-      })
+      }).map_err(Into::into)
     } else {
       os::interface_ipv6_addresses(0, |ip| {
         public_ip_filter(ip) && ipv6_filter_to_ip_filter(&mut f)(ip)
-      })
+      }).map_err(Into::into)
     }
   }
 }
@@ -183,20 +180,34 @@ where
 /// ```
 ///
 /// [RFC 6890]: https://tools.ietf.org/html/rfc6890
-pub fn public_addrs_by_filter<F>(mut f: F) -> io::Result<SmallVec<IfNet>>
+pub fn public_addrs_by_filter<F>(mut f: F) -> Result<SmallVec<IfNet>>
 where
   F: FnMut(&IpAddr) -> bool,
 {
   cfg_if::cfg_if! {
     if #[cfg(windows)] {
-      os::interface_addresses(None, |ip| public_ip_filter(ip) && f(ip))
+      os::interface_addresses(None, |ip| public_ip_filter(ip) && f(ip)).map_err(Into::into)
     } else {
-      os::interface_addresses(0, |ip| public_ip_filter(ip) && f(ip))
+      os::interface_addresses(0, |ip| public_ip_filter(ip) && f(ip)).map_err(Into::into)
     }
   }
 }
 
 #[inline]
 fn public_ip_filter(ip: &IpAddr) -> bool {
-  !RFC6890.contains(ip)
+  let ip = embedded_ipv4(ip);
+  !RFC6890.contains(&ip)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn mapped_addresses_follow_embedded_ipv4() {
+    let mapped_private: IpAddr = "::ffff:192.168.1.1".parse().unwrap();
+    let mapped_public: IpAddr = "::ffff:8.8.8.8".parse().unwrap();
+    assert!(!public_ip_filter(&mapped_private));
+    assert!(public_ip_filter(&mapped_public));
+  }
 }
@@ -1,11 +1,71 @@
-use std::io;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, SystemTime};
 
 use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 use smallvec_wrapper::SmallVec;
 use smol_str::SmolStr;
 
 use super::os;
+use super::Result;
+
+/// Path metrics the kernel keeps on a route: `rtm_rmx` on BSD and
+/// macOS, `RTA_METRICS` / `RTA_CACHEINFO` on Linux.
+///
+/// Each value is `None` when the kernel has none for the route, which
+/// is the common case for routes nobody configured metrics on. Windows
+/// keeps no such metrics on its forwarding rows and always reports
+/// [`RouteMetrics::default`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct RouteMetrics {
+  mtu: Option<u32>,
+  rtt: Option<Duration>,
+  hopcount: Option<u32>,
+  expire: Option<SystemTime>,
+}
+
+impl RouteMetrics {
+  #[inline]
+  pub(crate) const fn new(
+    mtu: Option<u32>,
+    rtt: Option<Duration>,
+    hopcount: Option<u32>,
+    expire: Option<SystemTime>,
+  ) -> Self {
+    Self {
+      mtu,
+      rtt,
+      hopcount,
+      expire,
+    }
+  }
+
+  /// Returns the path MTU cached on the route (`rmx_mtu` / `RTAX_MTU`).
+  #[inline]
+  pub const fn mtu(&self) -> Option<u32> {
+    self.mtu
+  }
+
+  /// Returns the estimated round-trip time (`rmx_rtt` / `RTAX_RTT`).
+  #[inline]
+  pub const fn rtt(&self) -> Option<Duration> {
+    self.rtt
+  }
+
+  /// Returns the hop count: the expected number of hops to the
+  /// destination (`rmx_hopcount`) on BSD, the hop limit for packets
+  /// sent along the route (`RTAX_HOPLIMIT`) on Linux.
+  #[inline]
+  pub const fn hopcount(&self) -> Option<u32> {
+    self.hopcount
+  }
+
+  /// Returns when the route expires (`rmx_expire` / the expiry in
+  /// `RTA_CACHEINFO`), or `None` for a route that does not.
+  #[inline]
+  pub const fn expire(&self) -> Option<SystemTime> {
+    self.expire
+  }
+}
 
 macro_rules! routev_impl {
   ($kind:literal) => {
@@ -16,6 +76,12 @@ macro_rules! routev_impl {
         index: u32,
         destination: [<Ip $kind Net>],
         gateway: Option<[<Ip $kind Addr>]>,
+        metric: u32,
+        metrics: RouteMetrics,
+        #[cfg(linux_like)]
+        tos: u8,
+        #[cfg(linux_like)]
+        realm: Option<u32>,
       }
 
       impl core::fmt::Display for [<Ip $kind Route>] {
@@ -35,7 +101,38 @@ macro_rules! routev_impl {
           destination: [<Ip $kind Net>],
           gateway: Option<[<Ip $kind Addr>]>,
         ) -> Self {
-          Self { index, destination, gateway }
+          Self {
+            index,
+            destination,
+            gateway,
+            metric: 0,
+            metrics: RouteMetrics::new(None, None, None, None),
+            #[cfg(linux_like)]
+            tos: 0,
+            #[cfg(linux_like)]
+            realm: None,
+          }
+        }
+
+        #[inline]
+        pub(crate) const fn with_metric(mut self, metric: u32) -> Self {
+          self.metric = metric;
+          self
+        }
+
+        #[cfg(not(windows))]
+        #[inline]
+        pub(crate) const fn with_route_metrics(mut self, metrics: RouteMetrics) -> Self {
+          self.metrics = metrics;
+          self
+        }
+
+        #[cfg(linux_like)]
+        #[inline]
+        pub(crate) const fn with_qualifiers(mut self, tos: u8, realm: Option<u32>) -> Self {
+          self.tos = tos;
+          self.realm = realm;
+          self
         }
 
         /// Returns the output interface index for this route.
@@ -47,7 +144,7 @@ macro_rules! routev_impl {
         /// Returns the output interface name.
         ///
         /// This method invokes `if_indextoname` internally.
-        pub fn name(&self) -> io::Result<SmolStr> {
+        pub fn name(&self) -> Result<SmolStr> {
           crate::idx_to_name::ifindex_to_name(self.index)
         }
 
@@ -71,6 +168,49 @@ macro_rules! routev_impl {
         pub const fn is_default(&self) -> bool {
           self.destination.prefix_len() == 0
         }
+
+        /// Returns the route metric; among otherwise equal routes the
+        /// lower one is preferred.
+        ///
+        /// This is `RTA_PRIORITY` on Linux, `rtm_priority` on OpenBSD
+        /// and the route's own `Metric` on Windows (without the
+        /// interface metric the kernel adds to it). macOS and the
+        /// other BSDs keep no per-route priority and report `0`.
+        #[inline]
+        pub const fn metric(&self) -> u32 {
+          self.metric
+        }
+
+        /// Returns the path metrics the kernel keeps on this route.
+        #[inline]
+        pub const fn metrics(&self) -> &RouteMetrics {
+          &self.metrics
+        }
+
+        /// Returns the ToS / traffic-class value this route is
+        /// restricted to (`rtm_tos`), or `None` for a route that
+        /// applies to packets with any ToS.
+        #[cfg(linux_like)]
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[inline]
+        pub const fn tos(&self) -> Option<u8> {
+          match self.tos {
+            0 => None,
+            tos => Some(tos),
+          }
+        }
+
+        /// Returns the routing realms (`RTA_FLOW`, set with
+        /// `ip route ... realm`), or `None` if the route has none.
+        ///
+        /// The destination realm is in the low 16 bits and the source
+        /// realm in the high 16 bits, as the kernel stores them.
+        #[cfg(linux_like)]
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[inline]
+        pub const fn realm(&self) -> Option<u32> {
+          self.realm
+        }
       }
     }
   };
@@ -124,7 +264,7 @@ impl IpRoute {
   /// Returns the output interface name.
   ///
   /// This method invokes `if_indextoname` internally.
-  pub fn name(&self) -> io::Result<SmolStr> {
+  pub fn name(&self) -> Result<SmolStr> {
     crate::idx_to_name::ifindex_to_name(self.index())
   }
 
@@ -160,6 +300,49 @@ impl IpRoute {
       Self::V6(r) => r.is_default(),
     }
   }
+
+  /// Returns the route metric. See [`Ipv4Route::metric`].
+  #[inline]
+  pub const fn metric(&self) -> u32 {
+    match self {
+      Self::V4(r) => r.metric(),
+      Self::V6(r) => r.metric(),
+    }
+  }
+
+  /// Returns the path metrics the kernel keeps on this route. See
+  /// [`RouteMetrics`].
+  #[inline]
+  pub const fn metrics(&self) -> &RouteMetrics {
+    match self {
+      Self::V4(r) => r.metrics(),
+      Self::V6(r) => r.metrics(),
+    }
+  }
+
+  /// Returns the ToS / traffic-class value this route is restricted
+  /// to, or `None` for a route that applies to any ToS.
+  #[cfg(linux_like)]
+  #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+  #[inline]
+  pub const fn tos(&self) -> Option<u8> {
+    match self {
+      Self::V4(r) => r.tos(),
+      Self::V6(r) => r.tos(),
+    }
+  }
+
+  /// Returns the routing realms (`RTA_FLOW`), or `None` if the route
+  /// has none.
+  #[cfg(linux_like)]
+  #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+  #[inline]
+  pub const fn realm(&self) -> Option<u32> {
+    match self {
+      Self::V4(r) => r.realm(),
+      Self::V6(r) => r.realm(),
+    }
+  }
 }
 
 /// Returns the **unicast and local** entries from the kernel routing
@@ -184,7 +367,8 @@ impl IpRoute {
 ///   added in Linux 5.3) are resolved against an up-front
 ///   `RTM_GETNEXTHOP` dump: leaves emit one route, groups fan out to
 ///   one route per member (group-of-groups is rare and skipped).
-///   Blackhole nexthops are filtered.
+///   Blackhole nexthops are filtered. [`route_table_with_tos`] lists
+///   the TOS-specific rows as well.
 ///
 ///   Cross-family next-hop routes (the kernel's `RTA_VIA` encoding —
 ///   e.g. an IPv4 route via an IPv6 link-local gateway) are dropped:
@@ -225,8 +409,8 @@ impl IpRoute {
 ///   println!("{route}");
 /// }
 /// ```
-pub fn route_table() -> io::Result<SmallVec<IpRoute>> {
-  os::route_table_by_filter(|_| true)
+pub fn route_table() -> Result<SmallVec<IpRoute>> {
+  os::route_table_by_filter(|_| true).map_err(Into::into)
 }
 
 /// Returns the IPv4 unicast/local entries from the kernel routing
@@ -242,8 +426,8 @@ pub fn route_table() -> io::Result<SmallVec<IpRoute>> {
 ///   println!("{route}");
 /// }
 /// ```
-pub fn route_ipv4_table() -> io::Result<SmallVec<Ipv4Route>> {
-  os::route_ipv4_table_by_filter(|_| true)
+pub fn route_ipv4_table() -> Result<SmallVec<Ipv4Route>> {
+  os::route_ipv4_table_by_filter(|_| true).map_err(Into::into)
 }
 
 /// Returns the IPv6 unicast/local entries from the kernel routing
@@ -259,8 +443,8 @@ pub fn route_ipv4_table() -> io::Result<SmallVec<Ipv4Route>> {
 ///   println!("{route}");
 /// }
 /// ```
-pub fn route_ipv6_table() -> io::Result<SmallVec<Ipv6Route>> {
-  os::route_ipv6_table_by_filter(|_| true)
+pub fn route_ipv6_table() -> Result<SmallVec<Ipv6Route>> {
+  os::route_ipv6_table_by_filter(|_| true).map_err(Into::into)
 }
 
 /// Returns routing-table entries that match the given filter. Only
@@ -279,11 +463,11 @@ pub fn route_ipv6_table() -> io::Result<SmallVec<Ipv6Route>> {
 ///   println!("{route}");
 /// }
 /// ```
-pub fn route_table_by_filter<F>(f: F) -> io::Result<SmallVec<IpRoute>>
+pub fn route_table_by_filter<F>(f: F) -> Result<SmallVec<IpRoute>>
 where
   F: FnMut(&IpRoute) -> bool,
 {
-  os::route_table_by_filter(f)
+  os::route_table_by_filter(f).map_err(Into::into)
 }
 
 /// Returns IPv4 routing-table entries that match the given filter.
@@ -299,11 +483,11 @@ where
 ///   println!("{route}");
 /// }
 /// ```
-pub fn route_ipv4_table_by_filter<F>(f: F) -> io::Result<SmallVec<Ipv4Route>>
+pub fn route_ipv4_table_by_filter<F>(f: F) -> Result<SmallVec<Ipv4Route>>
 where
   F: FnMut(&Ipv4Route) -> bool,
 {
-  os::route_ipv4_table_by_filter(f)
+  os::route_ipv4_table_by_filter(f).map_err(Into::into)
 }
 
 /// Returns IPv6 routing-table entries that match the given filter.
@@ -318,11 +502,138 @@ where
 ///   println!("{route}");
 /// }
 /// ```
-pub fn route_ipv6_table_by_filter<F>(f: F) -> io::Result<SmallVec<Ipv6Route>>
+pub fn route_ipv6_table_by_filter<F>(f: F) -> Result<SmallVec<Ipv6Route>>
 where
   F: FnMut(&Ipv6Route) -> bool,
 {
-  os::route_ipv6_table_by_filter(f)
+  os::route_ipv6_table_by_filter(f).map_err(Into::into)
+}
+
+/// Returns the unicast/local entries of one Linux routing table, such
+/// as a table a VPN or `ip rule` policy installs its routes in.
+///
+/// [`route_table`] only walks the built-in `local`, `main` and `default`
+/// tables; this walks `table` alone, with the same route kinds and
+/// decoding. A table id that has no routes (or does not exist) yields
+/// an empty list. Table `0` (`RT_TABLE_UNSPEC`) names no table and
+/// fails with [`io::ErrorKind::InvalidInput`](std::io::ErrorKind::InvalidInput).
+///
+/// [`gateway_addrs_in`](crate::gateway_addrs_in) and
+/// [`best_local_addrs_in`](crate::best_local_addrs_in) answer the
+/// gateway and best-local-address queries for one table the same way.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::route_table_in;
+///
+/// // The `main` table.
+/// for route in route_table_in(254).unwrap() {
+///   println!("{route}");
+/// }
+/// ```
+#[cfg(linux_like)]
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn route_table_in(table: u32) -> Result<SmallVec<IpRoute>> {
+  os::route_table_in_by_filter(Some(table), |_| true).map_err(Into::into)
+}
+
+/// Returns the entries of routing table `table` that match the given
+/// filter. See [`route_table_in`].
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::route_table_in_by_filter;
+///
+/// let defaults = route_table_in_by_filter(254, |r| r.is_default()).unwrap();
+/// for route in defaults {
+///   println!("{route}");
+/// }
+/// ```
+#[cfg(linux_like)]
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn route_table_in_by_filter<F>(table: u32, f: F) -> Result<SmallVec<IpRoute>>
+where
+  F: FnMut(&IpRoute) -> bool,
+{
+  os::route_table_in_by_filter(Some(table), f).map_err(Into::into)
+}
+
+/// Like [`route_table`], but also lists the TOS-specific routes
+/// (`ip route add ... tos N`) that it drops.
+///
+/// Such a route only carries packets whose ToS byte matches
+/// [`IpRoute::tos`], so it must not be mistaken for a route that
+/// applies to arbitrary traffic; routes without a ToS report `None`.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::route_table_with_tos;
+///
+/// for route in route_table_with_tos().unwrap() {
+///   if let Some(tos) = route.tos() {
+///     println!("{route} (tos {tos:#x})");
+///   }
+/// }
+/// ```
+#[cfg(linux_like)]
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn route_table_with_tos() -> Result<SmallVec<IpRoute>> {
+  os::route_table_with_tos_by_filter(|_| true).map_err(Into::into)
+}
+
+/// Returns the entries of [`route_table_with_tos`] that match the
+/// given filter.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::route_table_with_tos_by_filter;
+///
+/// let tos_only = route_table_with_tos_by_filter(|r| r.tos().is_some()).unwrap();
+/// for route in tos_only {
+///   println!("{route}");
+/// }
+/// ```
+#[cfg(linux_like)]
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn route_table_with_tos_by_filter<F>(f: F) -> Result<SmallVec<IpRoute>>
+where
+  F: FnMut(&IpRoute) -> bool,
+{
+  os::route_table_with_tos_by_filter(f).map_err(Into::into)
+}
+
+/// Reports whether this process can query the kernel routing table.
+///
+/// Performs a minimal probe — a single netlink `RTM_GETROUTE` request on
+/// Linux / Android (only the first reply is read), a size-only
+/// `NET_RT_DUMP` `sysctl` on BSD-like systems, and one
+/// `GetIpForwardTable2` call on Windows — rather than a full
+/// enumeration. A `false` result usually means the process is
+/// sandboxed (seccomp, SELinux, a restricted container) or lacks the
+/// privileges the platform requires, so callers can surface a clear
+/// "run as root / grant `CAP_NET_ADMIN`" message instead of an opaque
+/// I/O error or an empty result from [`route_table`] and
+/// [`gateway_addrs`](crate::gateway_addrs).
+///
+/// A `true` result is a strong hint, not a guarantee: the full dump
+/// can still fail later for unrelated reasons (e.g. an interrupted
+/// dump surfacing as `EINTR`).
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::can_query_routes;
+///
+/// if !can_query_routes() {
+///   eprintln!("routing table is not accessible; try running with elevated privileges");
+/// }
+/// ```
+pub fn can_query_routes() -> bool {
+  os::route_probe().is_ok()
 }
 
 #[cfg(test)]
@@ -421,6 +732,11 @@ mod tests {
     }
   }
 
+  #[test]
+  fn can_query_routes_in_ci() {
+    assert!(can_query_routes());
+  }
+
   #[test]
   fn route_v4_table_returns() {
     let routes = route_ipv4_table().unwrap();
@@ -447,4 +763,26 @@ mod tests {
       let _ = r.gateway();
     }
   }
+
+  // Whatever metrics the default routes carry must be plausible: a
+  // cached MTU is never below the IPv4 minimum, and a route that
+  // expires does so in the future.
+  #[test]
+  fn default_route_metrics_are_plausible() {
+    for route in route_table().unwrap() {
+      if !route.is_default() {
+        continue;
+      }
+      let metrics = route.metrics();
+      if let Some(mtu) = metrics.mtu() {
+        assert!(mtu >= 68, "{route}: {metrics:?}");
+      }
+      if let Some(expire) = metrics.expire() {
+        assert!(
+          expire > SystemTime::now() - Duration::from_secs(5),
+          "{route}: {metrics:?}"
+        );
+      }
+    }
+  }
 }
@@ -0,0 +1,69 @@
+/// What the current process can see of the host's networking stack, as
+/// returned by [`runtime_hints`].
+///
+/// Sandboxes and containers routinely hide part of the stack: a
+/// network namespace shows only its own interfaces, seccomp profiles
+/// may refuse `AF_NETLINK` sockets, and `/proc/net` may be masked.
+/// These hints let callers explain an empty or partial result instead
+/// of guessing.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RuntimeHints {
+  pub(crate) in_container: bool,
+  pub(crate) netlink_available: bool,
+  pub(crate) proc_net_available: bool,
+}
+
+impl RuntimeHints {
+  /// Returns `true` if the process appears to run inside a container
+  /// (Docker, Podman, containerd / Kubernetes, LXC, systemd-nspawn).
+  ///
+  /// Detection is heuristic: a container that hides every marker is
+  /// reported as `false`.
+  #[inline]
+  pub const fn in_container(&self) -> bool {
+    self.in_container
+  }
+
+  /// Returns `true` if an `AF_NETLINK` route socket can be created.
+  ///
+  /// When it cannot, gateway and best-local-address lookups fall back
+  /// to `/proc/net`, and everything else fails.
+  #[inline]
+  pub const fn netlink_available(&self) -> bool {
+    self.netlink_available
+  }
+
+  /// Returns `true` if `/proc/net` is readable, which the netlink
+  /// fallback and the multicast address lookups depend on.
+  #[inline]
+  pub const fn proc_net_available(&self) -> bool {
+    self.proc_net_available
+  }
+}
+
+/// Probes the environment for conditions that make results empty or
+/// partial.
+///
+/// Only Linux and Android are probed. On other platforms every hint is
+/// `false`: there is no netlink or `/proc/net` to rely on, and no
+/// container detection is attempted.
+///
+/// ## Example
+///
+/// ```rust
+/// use getifs::runtime_hints;
+///
+/// let hints = runtime_hints();
+/// if hints.in_container() {
+///   println!("only this container's network namespace is visible");
+/// }
+/// ```
+pub fn runtime_hints() -> RuntimeHints {
+  cfg_if::cfg_if! {
+    if #[cfg(linux_like)] {
+      crate::os::runtime_hints()
+    } else {
+      RuntimeHints::default()
+    }
+  }
+}
@@ -0,0 +1,169 @@
+use std::net::IpAddr;
+
+use super::{IfNet, Ipv6AddrExt};
+
+/// The reach of an interface address, ordered from widest to
+/// narrowest so that sorting by it puts globally usable addresses
+/// first.
+///
+/// [`IfNet::scope`] takes the scope the OS reports for the address
+/// where there is one: `ifa_scope` on Linux / Android, and the
+/// `SCOPE_ID` level of an IPv6 address on Windows, when set. Elsewhere —
+/// the BSDs, macOS, Windows IPv4, and values built by hand — it is
+/// inferred with [`AddrScope::of`], using the same rules the Linux
+/// kernel applies when it assigns `ifa_scope`:
+///
+/// - loopback (`127.0.0.0/8`, `::1`) is [`Host`](AddrScope::Host);
+/// - `169.254.0.0/16` and `fe80::/10` are [`Link`](AddrScope::Link);
+/// - the deprecated `fec0::/10` site-local block is [`Site`](AddrScope::Site);
+/// - everything else, including RFC 1918 and ULA (`fc00::/7`)
+///   addresses, is [`Global`](AddrScope::Global).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AddrScope {
+  /// Usable beyond the local link.
+  Global,
+  /// Usable within a site (`fec0::/10` only).
+  Site,
+  /// Usable only on the attached link.
+  Link,
+  /// Usable only within this host.
+  Host,
+}
+
+impl AddrScope {
+  /// Returns the scope of `addr`.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use getifs::AddrScope;
+  ///
+  /// assert_eq!(AddrScope::of(&"fe80::1".parse().unwrap()), AddrScope::Link);
+  /// assert_eq!(AddrScope::of(&"10.0.0.1".parse().unwrap()), AddrScope::Global);
+  /// ```
+  pub fn of(addr: &IpAddr) -> Self {
+    match addr {
+      IpAddr::V4(ip) if ip.is_loopback() => Self::Host,
+      IpAddr::V4(ip) if ip.is_link_local() => Self::Link,
+      IpAddr::V4(_) => Self::Global,
+      IpAddr::V6(ip) if ip.is_loopback() => Self::Host,
+      IpAddr::V6(ip) if Ipv6AddrExt::is_unicast_link_local(ip) => Self::Link,
+      IpAddr::V6(ip) if (ip.segments()[0] & 0xffc0) == 0xfec0 => Self::Site,
+      IpAddr::V6(_) => Self::Global,
+    }
+  }
+}
+
+impl IfNet {
+  /// Returns the [`AddrScope`] of the interface address: the one the OS
+  /// reports where it reports one, else inferred with [`AddrScope::of`].
+  #[inline]
+  pub fn scope(&self) -> AddrScope {
+    match self {
+      Self::V4(net) => net.scope(),
+      Self::V6(net) => net.scope(),
+    }
+  }
+}
+
+/// The per-address state beyond its scope that RFC 6724 source
+/// selection weighs, as far as the platform reports it.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct SourceFlags {
+  /// The address's preferred lifetime has run out.
+  pub(crate) deprecated: bool,
+  /// A privacy (temporary) address.
+  pub(crate) temporary: bool,
+}
+
+/// Picks the source among one interface's addresses the way RFC 6724
+/// orders them: the widest scope (rule 2), then a preferred address
+/// over a deprecated one (rule 3), then a public address over a
+/// temporary one. Ties keep the order the candidates come in.
+pub(crate) fn pick_source<I>(candidates: I) -> Option<IfNet>
+where
+  I: IntoIterator<Item = (IfNet, SourceFlags)>,
+{
+  candidates
+    .into_iter()
+    .min_by_key(|(net, flags)| (net.scope(), flags.deprecated, flags.temporary))
+    .map(|(net, _)| net)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn classification() {
+    let cases = [
+      ("127.0.0.1", AddrScope::Host),
+      ("169.254.1.1", AddrScope::Link),
+      ("192.168.1.1", AddrScope::Global),
+      ("8.8.8.8", AddrScope::Global),
+      ("::1", AddrScope::Host),
+      ("fe80::1", AddrScope::Link),
+      ("fec0::1", AddrScope::Site),
+      ("fd00::1", AddrScope::Global),
+      ("2001:db8::1", AddrScope::Global),
+    ];
+    for (addr, scope) in cases {
+      assert_eq!(AddrScope::of(&addr.parse().unwrap()), scope, "{addr}");
+    }
+  }
+
+  // Sorting by scope must yield global → site → link → host.
+  #[test]
+  fn ordering() {
+    let mut scopes = [
+      AddrScope::Host,
+      AddrScope::Link,
+      AddrScope::Global,
+      AddrScope::Site,
+    ];
+    scopes.sort();
+    assert_eq!(
+      scopes,
+      [
+        AddrScope::Global,
+        AddrScope::Site,
+        AddrScope::Link,
+        AddrScope::Host
+      ]
+    );
+  }
+
+  // Scope outranks the lifetime flags, a deprecated address loses to
+  // a preferred one, and a public one wins among the rest; equal
+  // candidates keep their order.
+  #[test]
+  fn source_preference() {
+    let net = |addr: &str| IfNet::with_prefix_len_assert(1, addr.parse().unwrap(), 64);
+    let flags = |deprecated, temporary| SourceFlags {
+      deprecated,
+      temporary,
+    };
+
+    let picked = pick_source([
+      (net("fe80::1"), flags(false, true)),
+      (net("2001:db8::1"), flags(true, false)),
+    ]);
+    assert_eq!(
+      picked.unwrap().addr(),
+      "2001:db8::1".parse::<IpAddr>().unwrap()
+    );
+
+    let picked = pick_source([
+      (net("2001:db8::1"), flags(true, true)),
+      (net("2001:db8::2"), flags(false, false)),
+      (net("2001:db8::3"), flags(false, true)),
+      (net("2001:db8::4"), flags(false, false)),
+    ]);
+    assert_eq!(
+      picked.unwrap().addr(),
+      "2001:db8::2".parse::<IpAddr>().unwrap()
+    );
+
+    assert_eq!(pick_source([]), None);
+  }
+}
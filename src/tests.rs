@@ -12,6 +12,8 @@ mod linux;
 
 struct TestInterface {
   name: String,
+  /// The other end when the device is a veth pair.
+  peer: Option<std::ffi::OsString>,
   local: IpAddr,
   remote: IpAddr,
   setup_cmds: Vec<Command>,
@@ -22,6 +24,7 @@ impl TestInterface {
   fn new(local: IpAddr, remote: IpAddr) -> Self {
     Self {
       name: String::new(),
+      peer: None,
       local,
       remote,
       setup_cmds: Vec::new(),
@@ -49,6 +52,7 @@ impl TestInterface {
     for cmd in &mut self.teardown_cmds {
       run_once(cmd)?;
     }
+    self.teardown_cmds.clear();
     Ok(())
   }
 
@@ -59,6 +63,15 @@ impl TestInterface {
     for cmd in &mut self.teardown_cmds {
       let _ = cmd.output();
     }
+    self.teardown_cmds.clear();
+  }
+}
+
+/// A test that panics or returns early still removes its device; an
+/// explicit `teardown` leaves nothing for this to run.
+impl Drop for TestInterface {
+  fn drop(&mut self) {
+    self.try_teardown();
   }
 }
 
@@ -92,6 +105,8 @@ fn is_environmental_skip(msg: &str) -> bool {
     || msg.contains("not supported")
     || msg.contains("Operation not supported")
     || msg.contains("module")
+    // iproute2's wording when the `dummy` / `bonding` driver is absent.
+    || msg.contains("Unknown device type")
 }
 
 #[test]
@@ -62,3 +62,34 @@ impl TestInterface {
     Err(io::Error::other("not yet implemented for BSD"))
   }
 }
+
+// An IPv6 address added to loopback with `SIOCAIFADDR_IN6` is listed,
+// and gone again after `SIOCDIFADDR_IN6`. Loopback keeps the test from
+// needing a cloned interface.
+#[cfg(all(
+  feature = "configure",
+  any(target_os = "macos", freebsdlike, netbsdlike)
+))]
+#[test]
+fn add_and_del_ipv6_addr_round_trip() {
+  if unsafe { libc::getuid() } != 0 {
+    return;
+  }
+
+  let index = crate::loopback_interface().unwrap().unwrap().index();
+  let net: ipnet::IpNet = "fd62:29::1/128".parse().unwrap();
+  let listed = || {
+    crate::interface_by_index(index)
+      .unwrap()
+      .unwrap()
+      .addrs()
+      .unwrap()
+      .iter()
+      .any(|addr| addr.net() == net)
+  };
+
+  crate::add_addr(index, net).unwrap();
+  assert!(listed(), "{net} not listed after add");
+  crate::del_addr(index, net).unwrap();
+  assert!(!listed(), "{net} still listed after del");
+}
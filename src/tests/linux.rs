@@ -1,6 +1,11 @@
 use super::TestInterface;
 
-use std::{io, process::Command};
+use std::{
+  ffi::{OsStr, OsString},
+  io,
+  net::{IpAddr, Ipv4Addr},
+  process::Command,
+};
 
 impl TestInterface {
   pub fn set_broadcast(&mut self, suffix: i32) -> io::Result<()> {
@@ -147,3 +152,1232 @@ impl TestInterface {
     Ok(())
   }
 }
+
+/// The kind of device [`TestInterface::link`] adds.
+pub enum LinkKind {
+  Dummy,
+  Bond,
+  /// A veth pair whose other end gets the given name. Deleting either
+  /// end removes both.
+  Veth(OsString),
+}
+
+impl LinkKind {
+  pub fn veth(peer: impl AsRef<OsStr>) -> Self {
+    Self::Veth(peer.as_ref().to_owned())
+  }
+}
+
+impl TestInterface {
+  /// Describes a `kind` device called `name`. Nothing runs until
+  /// [`create`](Self::create); the device is deleted on drop.
+  pub fn link(name: &str, kind: LinkKind) -> Self {
+    let unspecified = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+    let mut ti = Self::new(unspecified, unspecified);
+    ti.name = name.to_string();
+
+    let mut add_cmd = Command::new("ip");
+    add_cmd.args(["link", "add", name, "type"]);
+    match kind {
+      LinkKind::Dummy => add_cmd.arg("dummy"),
+      LinkKind::Bond => add_cmd.arg("bond"),
+      LinkKind::Veth(peer) => {
+        add_cmd.args(["veth", "peer", "name"]).arg(&peer);
+        ti.peer = Some(peer);
+        &mut add_cmd
+      }
+    };
+    ti.setup_cmds.push(add_cmd);
+
+    let mut del_cmd = Command::new("ip");
+    del_cmd.args(["link", "delete", name]);
+    ti.teardown_cmds.push(del_cmd);
+    ti
+  }
+
+  /// Assigns an address to the device. `spec` is what follows
+  /// `ip address add`, e.g. `"10.0.0.1/24 broadcast 10.0.0.127"`.
+  pub fn addr(mut self, spec: &str) -> Self {
+    let mut cmd = Command::new("ip");
+    cmd
+      .args(["address", "add"])
+      .args(spec.split_whitespace())
+      .args(["dev", &self.name]);
+    self.setup_cmds.push(cmd);
+    self
+  }
+
+  /// Like [`addr`](Self::addr), for the other end of a veth pair.
+  pub fn peer_addr(mut self, spec: &str) -> Self {
+    let peer = self.peer.clone().expect("not a veth pair");
+    let mut cmd = Command::new("ip");
+    cmd
+      .args(["address", "add"])
+      .args(spec.split_whitespace())
+      .arg("dev")
+      .arg(peer);
+    self.setup_cmds.push(cmd);
+    self
+  }
+
+  /// Brings the device up, and the other end too for a veth pair.
+  pub fn up(mut self) -> Self {
+    for name in std::iter::once(OsStr::new(&self.name)).chain(self.peer.as_deref()) {
+      let mut cmd = Command::new("ip");
+      cmd.args(["link", "set"]).arg(name).arg("up");
+      self.setup_cmds.push(cmd);
+    }
+    self
+  }
+
+  /// Runs the setup. `None` means the test should be skipped: not
+  /// root, no `ip(8)`, or the host lacks the driver.
+  pub fn create(mut self) -> Option<Self> {
+    if rustix::process::getuid().as_raw() != 0 || which::which("ip").is_err() {
+      return None;
+    }
+    if let Err(e) = self.setup() {
+      let err_msg = e.to_string();
+      if super::is_environmental_skip(&err_msg) {
+        println!("skipping test; {} setup failed: {err_msg}", self.name);
+        return None;
+      }
+      panic!("{}", e);
+    }
+    Some(self)
+  }
+
+  pub fn index(&self) -> u32 {
+    crate::ifname_to_index(&self.name).unwrap()
+  }
+}
+
+/// Runs `ip(8)` with `args`, panicking if it fails.
+fn ip(args: &[&str]) {
+  super::run_once(Command::new("ip").args(args)).unwrap();
+}
+
+// A dummy device has no physical link, so the kernel reports
+// `IFLA_CARRIER = 1` as soon as it is brought up. That makes it a
+// deterministic fixture for `Interface::has_carrier` without needing
+// real hardware.
+#[test]
+fn dummy_interface_reports_carrier() {
+  let Some(ti) = TestInterface::link("gotest6120", LinkKind::Dummy)
+    .up()
+    .create()
+  else {
+    return;
+  };
+
+  let ifi = crate::interface_by_name(&ti.name).unwrap().unwrap();
+  assert_eq!(ifi.has_carrier(), Some(true), "{ifi:?}");
+}
+
+// Linux keeps addresses configured on a link that is administratively
+// down: `interface_addrs` must report them, `up_interface_addrs` must
+// not.
+#[test]
+fn down_interface_addrs() {
+  let Some(_ti) = TestInterface::link("gotest6121", LinkKind::Dummy)
+    .addr("169.254.61.21/16")
+    .create()
+  else {
+    return;
+  };
+  let addr: IpAddr = "169.254.61.21".parse().unwrap();
+
+  let all = crate::interface_addrs().unwrap();
+  assert!(all.iter().any(|a| a.addr() == addr), "{all:?}");
+  let up = crate::up_interface_addrs().unwrap();
+  assert!(!up.iter().any(|a| a.addr() == addr), "{up:?}");
+}
+
+// A bond with two dummy members: each member must report the bond as
+// its master, and the bond must list exactly those two as slaves.
+#[test]
+fn bond_slaves() {
+  let Some(bond) = TestInterface::link("gotest6130", LinkKind::Bond).create() else {
+    return;
+  };
+  let mut members = Vec::new();
+  for name in ["gotest6131", "gotest6132"] {
+    let Some(member) = TestInterface::link(name, LinkKind::Dummy).create() else {
+      return;
+    };
+    ip(&["link", "set", name, "master", &bond.name]);
+    members.push(member);
+  }
+
+  let bond_ifi = crate::interface_by_name(&bond.name).unwrap().unwrap();
+  let mut expected: Vec<u32> = members
+    .iter()
+    .map(|member| {
+      let ifi = crate::interface_by_name(&member.name).unwrap().unwrap();
+      assert_eq!(ifi.master_index(), Some(bond_ifi.index()), "{ifi:?}");
+      ifi.index()
+    })
+    .collect();
+  expected.sort_unstable();
+
+  let mut slaves = bond_ifi.slaves().unwrap().into_iter().collect::<Vec<_>>();
+  slaves.sort_unstable();
+  assert_eq!(slaves, expected);
+  assert_eq!(bond_ifi.master_index(), None);
+}
+
+// An RFC 3021 /31 link: each veth end holds one of the two addresses,
+// and both must come back with prefix 31 rather than being dropped or
+// widened.
+#[test]
+fn slash31_peer_link() {
+  let ends = ["gotest6140", "gotest6141"];
+  let addrs = ["10.61.40.0", "10.61.40.1"];
+  let Some(_ti) = TestInterface::link(ends[0], LinkKind::veth(ends[1]))
+    .addr("10.61.40.0/31")
+    .peer_addr("10.61.40.1/31")
+    .create()
+  else {
+    return;
+  };
+
+  for (end, addr) in ends.iter().zip(addrs) {
+    let addr: IpAddr = addr.parse().unwrap();
+    let ifi = crate::interface_by_name(end).unwrap().unwrap();
+    let ifat = ifi.addrs().unwrap();
+    let ifa = ifat
+      .iter()
+      .find(|ifa| ifa.addr() == addr)
+      .unwrap_or_else(|| panic!("{addr} missing from {ifat:?}"));
+    assert_eq!(ifa.prefix_len(), 31, "{ifa:?}");
+  }
+}
+
+// A ToS-qualified route stays out of the default tables, which the
+// gateway and source-address lookups build on, and comes back from the
+// opt-in walk with its ToS and realm intact.
+#[test]
+fn route_tos_and_realm() {
+  let Some(ti) = TestInterface::link("gotest6150", LinkKind::Dummy)
+    .up()
+    .create()
+  else {
+    return;
+  };
+  ip(&[
+    "route",
+    "add",
+    "10.61.50.0/24",
+    "dev",
+    &ti.name,
+    "tos",
+    "0x10",
+    "realm",
+    "7",
+  ]);
+
+  let dst: ipnet::IpNet = "10.61.50.0/24".parse().unwrap();
+  let v4 = crate::route_ipv4_table().unwrap();
+  assert!(
+    v4.iter().all(|r| ipnet::IpNet::V4(*r.destination()) != dst),
+    "{v4:?}"
+  );
+  let all = crate::route_table_by_filter(|r| r.destination() == dst).unwrap();
+  assert!(all.is_empty(), "{all:?}");
+
+  let routes = crate::route_table_with_tos_by_filter(|r| r.destination() == dst).unwrap();
+  assert_eq!(routes.len(), 1, "{routes:?}");
+  assert_eq!(routes[0].tos(), Some(0x10));
+  assert_eq!(routes[0].realm(), Some(7));
+}
+
+// Two IPv6 defaults on a veth, one via a link-local router and one via
+// a global address: the global-only variant must drop the former while
+// the plain call still reports both, each tagged with the veth index.
+#[test]
+fn gateway_ipv6_global_excludes_link_local() {
+  let Some(ti) = TestInterface::link("gotest6160", LinkKind::veth("gotest6161"))
+    .up()
+    .create()
+  else {
+    return;
+  };
+
+  let link_local: std::net::Ipv6Addr = "fe80::6160".parse().unwrap();
+  let global: std::net::Ipv6Addr = "2001:db8:6160::1".parse().unwrap();
+  for (gw, metric) in [(link_local, "4242"), (global, "4243")] {
+    if let Err(e) = super::run_once(Command::new("ip").args([
+      "-6",
+      "route",
+      "add",
+      "default",
+      "via",
+      &gw.to_string(),
+      "dev",
+      &ti.name,
+      "onlink",
+      "metric",
+      metric,
+    ])) {
+      println!("skipping test; IPv6 route setup failed: {e}");
+      return;
+    }
+  }
+
+  let index = ti.index();
+  let all = crate::gateway_ipv6_addrs().unwrap();
+  let global_only = crate::gateway_ipv6_addrs_global().unwrap();
+  for gw in [link_local, global] {
+    assert!(
+      all.iter().any(|a| a.addr() == gw && a.index() == index),
+      "{gw} missing from {all:?}"
+    );
+  }
+  assert!(
+    global_only.iter().all(|a| a.addr() != link_local),
+    "{global_only:?}"
+  );
+  assert!(
+    global_only
+      .iter()
+      .any(|a| a.addr() == global && a.index() == index),
+    "{global_only:?}"
+  );
+}
+
+// Adding one address must produce exactly one event on the address
+// watcher. A second address serves as an end marker so the test never
+// has to wait for an event that should not come.
+#[test]
+fn watch_addrs_single_event() {
+  let Some(ti) = TestInterface::link("gotest6170", LinkKind::veth("gotest6171")).create() else {
+    return;
+  };
+  let index = ti.index();
+
+  let watcher = match crate::watch_addrs() {
+    Ok(watcher) => watcher,
+    Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return,
+    Err(e) => panic!("{e}"),
+  };
+  for addr in ["10.61.70.1/24", "10.61.70.2/24"] {
+    ip(&["address", "add", addr, "dev", &ti.name]);
+  }
+
+  let expected: crate::IfNet = crate::IfNet::from_net(index, "10.61.70.1/24".parse().unwrap());
+  let marker: crate::IfNet = crate::IfNet::from_net(index, "10.61.70.2/24".parse().unwrap());
+  let mut seen = Vec::new();
+  loop {
+    let event = watcher.recv().unwrap();
+    let (crate::Event::AddrAdded(net) | crate::Event::AddrRemoved(net)) = &event else {
+      continue;
+    };
+    if net.index() != index {
+      continue;
+    }
+    if *net == marker {
+      break;
+    }
+    seen.push(event);
+  }
+  assert_eq!(seen, [crate::Event::AddrAdded(expected)]);
+}
+
+// A veth pair is `ARPHRD_ETHER` on both ends.
+#[test]
+fn veth_media_type_is_ethernet() {
+  let ends = ["gotest6209", "gotest6210"];
+  let Some(_ti) = TestInterface::link(ends[0], LinkKind::veth(ends[1])).create() else {
+    return;
+  };
+
+  for name in ends {
+    let ifi = crate::interface_by_name(name).unwrap().unwrap();
+    assert_eq!(ifi.media_type().as_deref(), Some("ethernet"), "{ifi:?}");
+    assert_eq!(ifi.kind(), crate::InterfaceKind::Ethernet, "{ifi:?}");
+  }
+}
+
+// `wait_for_up` times out on a link that stays down, and returns the
+// link once another thread brings it up part-way through the wait.
+#[test]
+fn wait_for_up_sees_delayed_link() {
+  let name = "gotest6211";
+  let Some(ti) = TestInterface::link(name, LinkKind::veth("gotest6212")).create() else {
+    return;
+  };
+  let index = ti.index();
+
+  let err = crate::wait_for_up(index, std::time::Duration::from_millis(100)).unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::TimedOut, "{err}");
+
+  let raise = std::thread::spawn(move || {
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    ip(&["link", "set", name, "up"]);
+  });
+  let ifi = crate::wait_for_up(name, std::time::Duration::from_secs(10)).unwrap();
+  raise.join().unwrap();
+  assert_eq!(ifi.index(), index);
+  assert!(ifi.flags().contains(crate::Flags::UP), "{ifi:?}");
+}
+
+// A destination inside a connected subnet is sourced from that
+// subnet's address, even though a default route points elsewhere.
+#[test]
+fn source_addr_for_connected_subnet() {
+  let Some(ti) = TestInterface::link("gotest6213", LinkKind::veth("gotest6214"))
+    .addr("10.62.13.1/24")
+    .up()
+    .create()
+  else {
+    return;
+  };
+  let index = ti.index();
+
+  let src = crate::source_addr_for("10.62.13.77".parse().unwrap())
+    .unwrap()
+    .expect("no route into the veth subnet");
+  assert_eq!(src.index(), index, "{src}");
+  assert_eq!(src.addr(), "10.62.13.1".parse::<IpAddr>().unwrap());
+  assert_eq!(src.prefix_len(), 24);
+
+  // The host's own address routes through `lo` but is still reported
+  // on the interface that carries it.
+  let own = crate::source_addr_for("10.62.13.1".parse().unwrap())
+    .unwrap()
+    .expect("no route to a local address");
+  assert_eq!(own.index(), index, "{own}");
+}
+
+// `watch()` reports a veth appearing and disappearing by index. The
+// `ip` commands return only after the kernel has queued the
+// notifications, so a non-blocking drain up to `WouldBlock` sees them
+// all without waiting on a timeout.
+#[test]
+fn watch_links_added_and_removed() {
+  let watcher = match crate::watch() {
+    Ok(watcher) => watcher,
+    Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return,
+    Err(e) => panic!("{e}"),
+  };
+  watcher.set_nonblocking(true).unwrap();
+  let drain = || {
+    let mut events = Vec::new();
+    loop {
+      match watcher.recv() {
+        Ok(event) => events.push(event),
+        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return events,
+        Err(e) => panic!("{e}"),
+      }
+    }
+  };
+  drain();
+
+  let Some(ti) = TestInterface::link("gotest6207", LinkKind::veth("gotest6208")).create() else {
+    return;
+  };
+  let index = ti.index();
+
+  let events = drain();
+  assert!(
+    events.contains(&crate::Event::LinkAdded(index)),
+    "{events:?}"
+  );
+
+  ip(&["link", "delete", &ti.name]);
+  let events = drain();
+  assert!(
+    events.contains(&crate::Event::LinkRemoved(index)),
+    "{events:?}"
+  );
+}
+
+// In a fresh network namespace there is no default route, which must
+// read as `None`; once a default route points at an address-less link
+// the answer becomes `Some(empty)`, and an address then shows up in it.
+// Namespaces are per-thread, so the checks run on a thread that has
+// joined the test namespace while `ip -n` configures it from outside.
+#[test]
+fn best_local_opt_distinguishes_no_route() {
+  let ns = "gotest6180";
+  let ends = ["gotest6181", "gotest6182"];
+  let Some(_ti) = TestInterface::link(ends[0], LinkKind::veth(ends[1])).create() else {
+    return;
+  };
+  if let Err(e) = super::run_once(Command::new("ip").args(["netns", "add", ns])) {
+    let err_msg = e.to_string();
+    if super::is_environmental_skip(&err_msg) {
+      println!("skipping test; netns creation failed: {err_msg}");
+      return;
+    }
+    panic!("{}", e);
+  }
+  let _ns_guard = scopeguard::guard((), |_| {
+    let _ = Command::new("ip").args(["netns", "delete", ns]).output();
+  });
+  ip(&["link", "set", ends[1], "netns", ns]);
+  ip(&["link", "set", ends[0], "up"]);
+  ip(&["-n", ns, "link", "set", ends[1], "up"]);
+
+  let ns_path = format!("/var/run/netns/{ns}");
+  std::thread::scope(|s| {
+    s.spawn(|| {
+      let file = std::fs::File::open(&ns_path).unwrap();
+      // SAFETY: `file` is an open netns handle; setns only switches the
+      // calling thread's network namespace.
+      let rc = unsafe { libc::setns(std::os::fd::AsRawFd::as_raw_fd(&file), libc::CLONE_NEWNET) };
+      assert_eq!(rc, 0, "{}", io::Error::last_os_error());
+
+      assert_eq!(crate::best_local_ipv4_addrs_opt().unwrap(), None);
+
+      ip(&["-n", ns, "route", "add", "default", "dev", ends[1]]);
+      assert_eq!(
+        crate::best_local_ipv4_addrs_opt().unwrap(),
+        Some(Default::default())
+      );
+
+      ip(&["-n", ns, "address", "add", "10.61.80.2/24", "dev", ends[1]]);
+      let addrs = crate::best_local_ipv4_addrs_opt().unwrap().unwrap();
+      assert!(
+        addrs
+          .iter()
+          .any(|a| a.addr() == Ipv4Addr::new(10, 61, 80, 2)),
+        "{addrs:?}"
+      );
+    });
+  });
+}
+
+// An explicitly configured broadcast address, deliberately not the
+// prefix's all-ones address, must come back on the `Ifv4Net`; an
+// address added without one reports none.
+#[test]
+fn ipv4_broadcast_attribute() {
+  let Some(ti) = TestInterface::link("gotest6185", LinkKind::veth("gotest6186"))
+    .addr("10.61.85.1/24 broadcast 10.61.85.127")
+    .addr("10.61.86.1/24")
+    .create()
+  else {
+    return;
+  };
+
+  let index = ti.index();
+  let addrs = crate::interface_by_index(index)
+    .unwrap()
+    .unwrap()
+    .ipv4_addrs()
+    .unwrap();
+  let broadcast_of = |addr: Ipv4Addr| {
+    addrs
+      .iter()
+      .find(|net| net.addr() == addr)
+      .unwrap_or_else(|| panic!("{addr} missing from {addrs:?}"))
+      .broadcast()
+  };
+  assert_eq!(
+    broadcast_of(Ipv4Addr::new(10, 61, 85, 1)),
+    Some(Ipv4Addr::new(10, 61, 85, 127))
+  );
+  assert_eq!(broadcast_of(Ipv4Addr::new(10, 61, 86, 1)), None);
+
+  let expected = crate::Ifv4Addr::new(index, Ipv4Addr::new(10, 61, 85, 127));
+  let interface = crate::interface_by_index(index).unwrap().unwrap();
+  assert_eq!(
+    interface.ipv4_broadcast_addrs().unwrap().as_slice(),
+    &[expected]
+  );
+  assert!(crate::interface_broadcast_addrs()
+    .unwrap()
+    .contains(&expected));
+}
+
+// A second address in the same subnet becomes an `IFA_F_SECONDARY`
+// alias; only the first one added may be reported as the primary.
+#[test]
+fn primary_addrs_skip_secondary() {
+  let Some(ti) = TestInterface::link("gotest6187", LinkKind::veth("gotest6188"))
+    .addr("10.61.87.1/24")
+    .addr("10.61.87.2/24")
+    .create()
+  else {
+    return;
+  };
+
+  let index = ti.index();
+  let all = crate::interface_addrs().unwrap();
+  assert_eq!(
+    all
+      .iter()
+      .filter(|net| net.index() == index && net.addr().is_ipv4())
+      .count(),
+    2,
+    "{all:?}"
+  );
+
+  // The per-interface dedup alone would also keep `.1`, so check the
+  // kernel flag is honoured before it runs.
+  let dumped = crate::os::interface_primary_addresses().unwrap();
+  assert!(
+    !dumped
+      .iter()
+      .any(|net| net.addr() == IpAddr::V4(Ipv4Addr::new(10, 61, 87, 2))),
+    "{dumped:?}"
+  );
+
+  let primary: Vec<_> = crate::primary_addrs_per_family()
+    .unwrap()
+    .into_iter()
+    .filter(|net| net.index() == index && net.addr().is_ipv4())
+    .collect();
+  assert_eq!(primary.len(), 1, "{primary:?}");
+  assert_eq!(primary[0].addr(), IpAddr::V4(Ipv4Addr::new(10, 61, 87, 1)));
+}
+
+// The kernel accepts interface names that are not valid UTF-8. Both
+// the enumeration and `ifindex_to_name` must report them lossily
+// instead of failing.
+#[test]
+fn non_utf8_interface_name() {
+  use std::os::unix::ffi::OsStrExt;
+
+  let raw = OsStr::from_bytes(b"gotest6189\xff");
+  let Some(_ti) = TestInterface::link("gotest6190", LinkKind::veth(raw)).create() else {
+    return;
+  };
+
+  let lossy = "gotest6189\u{fffd}";
+  let ifi = crate::interfaces()
+    .unwrap()
+    .into_iter()
+    .find(|ifi| ifi.name() == lossy)
+    .unwrap_or_else(|| panic!("{lossy} not enumerated"));
+  assert_eq!(crate::ifindex_to_name(ifi.index()).unwrap(), lossy);
+}
+
+// A permanent neighbour entry resolves to its configured MAC, and an
+// address with no entry reports none.
+#[test]
+fn gateway_mac_from_neighbor_table() {
+  let Some(ti) = TestInterface::link("gotest6191", LinkKind::veth("gotest6192"))
+    .addr("10.61.91.1/24")
+    .create()
+  else {
+    return;
+  };
+  ip(&[
+    "neigh",
+    "add",
+    "10.61.91.2",
+    "lladdr",
+    "02:00:5e:61:91:02",
+    "dev",
+    &ti.name,
+    "nud",
+    "permanent",
+  ]);
+
+  assert_eq!(
+    crate::gateway_mac("10.61.91.2".parse().unwrap()).unwrap(),
+    Some("02:00:5e:61:91:02".parse().unwrap())
+  );
+  assert_eq!(
+    crate::gateway_mac("10.61.91.3".parse().unwrap()).unwrap(),
+    None
+  );
+
+  let index = ti.index();
+  let entry = crate::neighbors_ipv4()
+    .unwrap()
+    .into_iter()
+    .find(|n| n.index() == index)
+    .expect("permanent entry listed");
+  assert_eq!(entry.ip(), "10.61.91.2".parse::<IpAddr>().unwrap());
+  assert_eq!(entry.state(), crate::NeighborState::Permanent);
+}
+
+// Several routes through the same next hop surface it once in
+// `gateway_addrs()`, with IPv4 gateways ahead of IPv6 ones.
+#[test]
+fn gateway_addrs_coalesce_redundant_routes() {
+  let Some(ti) = TestInterface::link("gotest6193", LinkKind::veth("gotest6194"))
+    .up()
+    .addr("10.61.93.1/24")
+    .create()
+  else {
+    return;
+  };
+  for dst in ["198.51.100.0/24", "203.0.113.0/24"] {
+    ip(&["route", "add", dst, "via", "10.61.93.254"]);
+  }
+  ip(&[
+    "route",
+    "add",
+    "default",
+    "via",
+    "10.61.93.254",
+    "table",
+    "6193",
+  ]);
+
+  let index = ti.index();
+  let gateways = crate::gateway_addrs().unwrap();
+  let gw: IpAddr = "10.61.93.254".parse().unwrap();
+  assert_eq!(
+    gateways
+      .iter()
+      .filter(|g| g.index() == index && g.addr() == gw)
+      .count(),
+    1,
+    "{gateways:?}"
+  );
+  let first_v6 = gateways
+    .iter()
+    .position(|g| g.addr().is_ipv6())
+    .unwrap_or(gateways.len());
+  assert!(
+    gateways[first_v6..].iter().all(|g| g.addr().is_ipv6()),
+    "{gateways:?}"
+  );
+}
+
+// The same subnet configured on both ends of a veth pair is reported
+// for each address, naming the other end.
+#[test]
+fn overlapping_subnets_reports_duplicated_subnet() {
+  let Some(ti) = TestInterface::link("gotest6195", LinkKind::veth("gotest6196"))
+    .addr("10.61.95.1/24")
+    .peer_addr("10.61.95.2/16")
+    .create()
+  else {
+    return;
+  };
+
+  let a = ti.index();
+  let b = crate::ifname_to_index("gotest6196").unwrap();
+  let report = crate::overlapping_subnets().unwrap();
+  let others_of = |index: u32| {
+    report
+      .iter()
+      .find(|(addr, _)| addr.index() == index && addr.addr().is_ipv4())
+      .map(|(_, others)| others.clone())
+  };
+  assert!(
+    others_of(a).is_some_and(|others| others.contains(&b)),
+    "{report:?}"
+  );
+  assert!(
+    others_of(b).is_some_and(|others| others.contains(&a)),
+    "{report:?}"
+  );
+}
+
+// `set_mtu` changes what the interface reports and can put the old
+// value back; out-of-range arguments never reach the kernel.
+#[cfg(feature = "configure")]
+#[test]
+fn set_mtu_round_trip() {
+  use std::io::ErrorKind;
+
+  assert_eq!(
+    crate::set_mtu(0, 1400).unwrap_err().kind(),
+    ErrorKind::InvalidInput
+  );
+  assert_eq!(
+    crate::set_mtu(1, 0).unwrap_err().kind(),
+    ErrorKind::InvalidInput
+  );
+
+  let Some(ti) = TestInterface::link("gotest6197", LinkKind::veth("gotest6198")).create() else {
+    return;
+  };
+
+  let index = ti.index();
+  let mtu_of = |index| crate::interface_by_index(index).unwrap().unwrap().mtu();
+  let original = mtu_of(index);
+  assert_ne!(original, 1280);
+
+  crate::set_mtu(index, 1280).unwrap();
+  assert_eq!(mtu_of(index), 1280);
+
+  crate::set_mtu(index, original).unwrap();
+  assert_eq!(mtu_of(index), original);
+}
+
+// Toggling the administrative state shows up in the reported flags.
+#[cfg(feature = "configure")]
+#[test]
+fn set_interface_up_toggles_flag() {
+  let Some(ti) = TestInterface::link("gotest6199", LinkKind::veth("gotest6200")).create() else {
+    return;
+  };
+
+  let index = ti.index();
+  let flags_of = |index| crate::interface_by_index(index).unwrap().unwrap().flags();
+  assert!(!flags_of(index).contains(crate::Flags::UP));
+
+  crate::set_interface_up(index, true).unwrap();
+  let flags = flags_of(index);
+  assert!(flags.contains(crate::Flags::UP), "{flags:?}");
+  assert!(flags.contains(crate::Flags::BROADCAST), "{flags:?}");
+
+  crate::set_interface_up(index, false).unwrap();
+  assert!(!flags_of(index).contains(crate::Flags::UP));
+}
+
+// An added address is listed on its interface with the requested
+// prefix, a second add is refused, and removal takes it away again.
+#[cfg(feature = "configure")]
+#[test]
+fn add_and_del_addr_round_trip() {
+  let Some(ti) = TestInterface::link("gotest6201", LinkKind::veth("gotest6202")).create() else {
+    return;
+  };
+
+  let index = ti.index();
+  let mut nets: Vec<ipnet::IpNet> = vec!["10.62.1.1/24".parse().unwrap()];
+  if crate::os::ipv6_enabled(&ti.name).unwrap() {
+    nets.push("2001:db8:6201::1/64".parse().unwrap());
+  }
+  let listed = |net: ipnet::IpNet| {
+    crate::interface_by_index(index)
+      .unwrap()
+      .unwrap()
+      .addrs()
+      .unwrap()
+      .iter()
+      .any(|addr| addr.net() == net)
+  };
+
+  for net in nets {
+    crate::add_addr(index, net).unwrap();
+    assert!(listed(net), "{net} not listed after add");
+    assert_eq!(
+      crate::add_addr(index, net).unwrap_err().kind(),
+      std::io::ErrorKind::AlreadyExists
+    );
+
+    crate::del_addr(index, net).unwrap();
+    assert!(!listed(net), "{net} still listed after del");
+  }
+}
+
+// A route added through a veth end shows up in `route_table()` with
+// the requested gateway and metric, and is gone after `del_route`.
+#[cfg(feature = "configure")]
+#[test]
+fn add_and_del_route_round_trip() {
+  let Some(ti) = TestInterface::link("gotest6203", LinkKind::veth("gotest6204"))
+    .addr("10.62.3.1/24")
+    .up()
+    .create()
+  else {
+    return;
+  };
+
+  let index = ti.index();
+  let dest: ipnet::IpNet = "198.51.100.0/24".parse().unwrap();
+  let gateway: IpAddr = "10.62.3.254".parse().unwrap();
+  let find = || {
+    crate::route_table()
+      .unwrap()
+      .into_iter()
+      .find(|route| route.destination() == dest && route.index() == index)
+  };
+
+  crate::add_route(dest, Some(gateway), index, 777).unwrap();
+  let route = find().expect("route not listed after add");
+  assert_eq!(route.gateway(), Some(gateway));
+  assert_eq!(route.metric(), 777);
+  assert_eq!(
+    crate::add_route(dest, Some(gateway), index, 777)
+      .unwrap_err()
+      .kind(),
+    std::io::ErrorKind::AlreadyExists
+  );
+
+  crate::del_route(dest).unwrap();
+  assert!(find().is_none(), "route still listed after del");
+}
+
+// Metrics set with `ip route ... mtu rtt hoplimit` come back on the
+// route, and an IPv6 route added with `expires` reports when.
+#[test]
+fn route_metrics_round_trip() {
+  let Some(ti) = TestInterface::link("gotest6205", LinkKind::veth("gotest6206"))
+    .up()
+    .create()
+  else {
+    return;
+  };
+  ip(&[
+    "route",
+    "add",
+    "198.51.100.0/24",
+    "dev",
+    &ti.name,
+    "mtu",
+    "1400",
+    "rtt",
+    "10ms",
+    "hoplimit",
+    "9",
+  ]);
+
+  let index = ti.index();
+  let routes = crate::route_table().unwrap();
+  let route = routes
+    .iter()
+    .find(|r| r.index() == index && r.destination() == "198.51.100.0/24".parse().unwrap())
+    .expect("route not listed");
+  let metrics = route.metrics();
+  assert_eq!(metrics.mtu(), Some(1400), "{metrics:?}");
+  assert_eq!(
+    metrics.rtt(),
+    Some(std::time::Duration::from_millis(10)),
+    "{metrics:?}"
+  );
+  assert_eq!(metrics.hopcount(), Some(9), "{metrics:?}");
+  assert_eq!(metrics.expire(), None, "{metrics:?}");
+
+  if crate::os::ipv6_enabled(&ti.name).unwrap() {
+    ip(&[
+      "-6",
+      "route",
+      "add",
+      "2001:db8:6205::/64",
+      "dev",
+      &ti.name,
+      "expires",
+      "300",
+    ]);
+    let routes = crate::route_table().unwrap();
+    let route = routes
+      .iter()
+      .find(|r| r.index() == index && r.destination() == "2001:db8:6205::/64".parse().unwrap())
+      .expect("IPv6 route not listed");
+    let expire = route.metrics().expire().expect("no expiry reported");
+    let now = std::time::SystemTime::now();
+    assert!(expire > now, "{expire:?}");
+    assert!(
+      expire <= now + std::time::Duration::from_secs(301),
+      "{expire:?}"
+    );
+  }
+}
+
+// An address assigned while waiting is reported with its prefix and
+// interface, and one that never shows up times out.
+#[test]
+fn wait_for_addr_sees_delayed_address() {
+  let name = "gotest6215";
+  let Some(ti) = TestInterface::link(name, LinkKind::veth("gotest6216")).create() else {
+    return;
+  };
+  let index = ti.index();
+  let addr: IpAddr = "10.62.15.1".parse().unwrap();
+
+  let err = crate::wait_for_addr(addr, std::time::Duration::from_millis(100)).unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::TimedOut, "{err}");
+
+  let assign = std::thread::spawn(move || {
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    ip(&["address", "add", "10.62.15.1/24", "dev", name]);
+  });
+  let net = crate::wait_for_addr(addr, std::time::Duration::from_secs(10)).unwrap();
+  assign.join().unwrap();
+  assert_eq!(net.addr(), addr);
+  assert_eq!(net.index(), index);
+  assert_eq!(net.prefix_len(), 24);
+}
+
+// Enough addresses on one link that the address dump spans many
+// datagrams; every one of them must come back.
+#[test]
+fn many_addresses_all_reported() {
+  let Some(ti) = TestInterface::link("gotest6217", LinkKind::veth("gotest6218")).create() else {
+    return;
+  };
+  let batch = std::env::temp_dir().join(format!("getifs-{}.batch", ti.name));
+  let _guard = scopeguard::guard((), |_| {
+    let _ = std::fs::remove_file(&batch);
+  });
+  let index = ti.index();
+
+  let want: std::collections::BTreeSet<IpAddr> = (0..300u32)
+    .map(|i| Ipv4Addr::new(10, 62, 17 + (i / 200) as u8, 1 + (i % 200) as u8).into())
+    .collect();
+  let script: String = want
+    .iter()
+    .map(|addr| format!("address add {addr}/32 dev {}\n", ti.name))
+    .collect();
+  std::fs::write(&batch, script).unwrap();
+  super::run_once(Command::new("ip").arg("-batch").arg(&batch)).unwrap();
+
+  let got: std::collections::BTreeSet<IpAddr> = crate::interface_addrs_by_filter(|_| true)
+    .unwrap()
+    .into_iter()
+    .filter(|net| net.index() == index)
+    .map(|net| net.addr())
+    .collect();
+  assert_eq!(got, want);
+}
+
+// A route installed in a policy table is listed by that table alone,
+// and stays out of the built-in tables `route_table` walks.
+#[test]
+fn route_table_in_lists_policy_table() {
+  let Some(ti) = TestInterface::link("gotest6219", LinkKind::veth("gotest6220"))
+    .up()
+    .create()
+  else {
+    return;
+  };
+  ip(&[
+    "route",
+    "add",
+    "198.51.100.0/24",
+    "dev",
+    &ti.name,
+    "table",
+    "6219",
+  ]);
+  let index = ti.index();
+  let dest: ipnet::IpNet = "198.51.100.0/24".parse().unwrap();
+
+  let routes = crate::route_table_in(6219).unwrap();
+  assert!(
+    routes
+      .iter()
+      .any(|r| r.destination() == dest && r.index() == index),
+    "{routes:?}"
+  );
+  assert!(routes.iter().all(|r| r.index() == index), "{routes:?}");
+  assert!(
+    !crate::route_table()
+      .unwrap()
+      .iter()
+      .any(|r| r.destination() == dest),
+    "policy route leaked into route_table"
+  );
+}
+
+// A default route installed in a policy table answers the gateway and
+// best-local queries for that table, and not for the built-in ones.
+#[test]
+fn gateway_and_best_local_in_policy_table() {
+  if rustix::process::getuid().as_raw() != 0 {
+    return;
+  }
+  let Ok(ip) = which::which("ip") else {
+    return;
+  };
+
+  let [name, peer] = ["gotest6229", "gotest6230"];
+  if let Err(e) = super::run_once(
+    Command::new(&ip).args(["link", "add", name, "type", "veth", "peer", "name", peer]),
+  ) {
+    let err_msg = e.to_string();
+    if super::is_environmental_skip(&err_msg) {
+      println!("skipping test; veth creation failed: {err_msg}");
+      return;
+    }
+    panic!("{}", e);
+  }
+  let _guard = scopeguard::guard((), |_| {
+    let _ = Command::new(&ip).args(["link", "delete", name]).output();
+  });
+  for args in [
+    &["address", "add", "10.62.29.1/24", "dev", name][..],
+    &["link", "set", name, "up"],
+    &["link", "set", peer, "up"],
+    &[
+      "route",
+      "add",
+      "default",
+      "via",
+      "10.62.29.254",
+      "dev",
+      name,
+      "table",
+      "6229",
+    ],
+  ] {
+    super::run_once(Command::new(&ip).args(args)).unwrap();
+  }
+  let index = crate::ifname_to_index(name).unwrap();
+  let gateway: IpAddr = "10.62.29.254".parse().unwrap();
+  let local: IpAddr = "10.62.29.1".parse().unwrap();
+
+  let gateways = crate::gateway_addrs_in(6229).unwrap();
+  assert!(
+    gateways
+      .iter()
+      .any(|gw| gw.addr() == gateway && gw.index() == index),
+    "{gateways:?}"
+  );
+  assert!(
+    !crate::gateway_addrs_in(254)
+      .unwrap()
+      .iter()
+      .any(|gw| gw.addr() == gateway),
+    "policy gateway leaked into the main table"
+  );
+
+  let addrs = crate::best_local_addrs_in(6229).unwrap();
+  assert!(addrs.iter().any(|a| a.addr() == local), "{addrs:?}");
+  assert!(
+    !crate::best_local_addrs()
+      .unwrap()
+      .iter()
+      .any(|a| a.addr() == local),
+    "policy default leaked into best_local_addrs"
+  );
+}
+
+// An IPv6 address still in DAD and one marked deprecated are both
+// configured but not offered for binding, while a ready one is.
+#[test]
+fn bindable_addrs_skips_tentative_and_deprecated() {
+  let Some(ti) = TestInterface::link("gotest6221", LinkKind::veth("gotest6222")).create() else {
+    return;
+  };
+  // A down link keeps DAD from finishing, so the plain address stays
+  // tentative; `nodad` skips DAD for the other two.
+  for spec in [
+    "fd62:21::1/64",
+    "fd62:21::2/64 nodad preferred_lft 0",
+    "fd62:21::3/64 nodad",
+  ] {
+    if let Err(e) = super::run_once(
+      Command::new("ip")
+        .args(["-6", "address", "add"])
+        .args(spec.split_whitespace())
+        .args(["dev", &ti.name]),
+    ) {
+      // IPv6 disabled in this namespace.
+      println!("skipping test; {e}");
+      return;
+    }
+  }
+
+  let index = ti.index();
+  let ours = |addrs: &[crate::IfNet]| -> Vec<IpAddr> {
+    let mut ours: Vec<_> = addrs
+      .iter()
+      .filter(|net| net.index() == index)
+      .map(|net| net.addr())
+      .collect();
+    ours.sort();
+    ours
+  };
+  let configured = ours(&crate::interface_addrs().unwrap());
+  for addr in ["fd62:21::1", "fd62:21::2", "fd62:21::3"] {
+    assert!(
+      configured.contains(&addr.parse().unwrap()),
+      "{addr} not configured: {configured:?}"
+    );
+  }
+  assert_eq!(
+    ours(&crate::bindable_addrs(crate::Family::V6).unwrap()),
+    vec!["fd62:21::3".parse::<IpAddr>().unwrap()]
+  );
+}
+
+// A tunnel-style setup where IPv6 runs with a smaller MTU than the
+// link: the per-family values tell them apart while `mtu()` keeps the
+// link's.
+#[test]
+fn ipv6_mtu_below_link_mtu() {
+  let Some(ti) = TestInterface::link("gotest6223", LinkKind::veth("gotest6224")).create() else {
+    return;
+  };
+  let name = ti.name.as_str();
+  ip(&["link", "set", name, "mtu", "1500"]);
+  let conf = format!("/proc/sys/net/ipv6/conf/{name}/mtu");
+  if let Err(e) = std::fs::write(&conf, "1400") {
+    // IPv6 disabled in this namespace.
+    println!("skipping test; {conf}: {e}");
+    return;
+  }
+
+  let ifi = crate::interface_by_name(name).unwrap().unwrap();
+  assert_eq!(ifi.mtu(), 1500);
+  assert_eq!(ifi.ipv4_mtu().unwrap(), Some(1500));
+  assert_eq!(ifi.ipv6_mtu().unwrap(), Some(1400));
+
+  // Below the IPv6 minimum the kernel turns IPv6 off on the link.
+  ip(&["link", "set", name, "mtu", "1000"]);
+  let ifi = crate::interface_by_name(name).unwrap().unwrap();
+  assert_eq!(ifi.ipv4_mtu().unwrap(), Some(1000));
+  assert_eq!(ifi.ipv6_mtu().unwrap(), None);
+}
+
+// `scope host` on a global-looking address: the kernel's `ifa_scope`
+// wins over what the address bits alone would say.
+#[test]
+fn addr_scope_is_kernel_reported() {
+  let Some(ti) = TestInterface::link("gotest6225", LinkKind::veth("gotest6226"))
+    .addr("10.62.25.1/24 scope host")
+    .addr("10.62.26.1/24")
+    .create()
+  else {
+    return;
+  };
+
+  let addrs = crate::interface_by_index(ti.index())
+    .unwrap()
+    .unwrap()
+    .addrs()
+    .unwrap();
+  let scope_of = |addr: Ipv4Addr| {
+    addrs
+      .iter()
+      .find(|net| net.addr() == IpAddr::V4(addr))
+      .unwrap_or_else(|| panic!("{addr} missing from {addrs:?}"))
+      .scope()
+  };
+  let host = Ipv4Addr::new(10, 62, 25, 1);
+  assert_eq!(crate::AddrScope::of(&host.into()), crate::AddrScope::Global);
+  assert_eq!(scope_of(host), crate::AddrScope::Host);
+  assert_eq!(
+    scope_of(Ipv4Addr::new(10, 62, 26, 1)),
+    crate::AddrScope::Global
+  );
+}
+
+// `preferred_lft 0` deprecates an address; with a preferred one of the
+// same scope next to it, the preferred one is the source (RFC 6724
+// rule 3), whichever order the kernel lists them in.
+#[test]
+fn source_addr_skips_deprecated() {
+  let Some(ti) = TestInterface::link("gotest6227", LinkKind::veth("gotest6228")).create() else {
+    return;
+  };
+  // The kernel lists the newest address of a scope first, so the
+  // deprecated one is added last.
+  for spec in ["fd62:26::2/64 nodad", "fd62:26::1/64 nodad preferred_lft 0"] {
+    if let Err(e) = super::run_once(
+      Command::new("ip")
+        .args(["-6", "address", "add"])
+        .args(spec.split_whitespace())
+        .args(["dev", &ti.name]),
+    ) {
+      // IPv6 disabled in this namespace.
+      println!("skipping test; {e}");
+      return;
+    }
+  }
+
+  let ifi = crate::interface_by_index(ti.index()).unwrap().unwrap();
+  assert_eq!(
+    ifi.source_addr(crate::Family::V6).unwrap(),
+    Some("fd62:26::2".parse().unwrap())
+  );
+
+  // A deprecated address is still used when it is the only one.
+  ip(&["-6", "address", "del", "fd62:26::2/64", "dev", &ti.name]);
+  assert_eq!(
+    ifi.source_addr(crate::Family::V6).unwrap(),
+    Some("fd62:26::1".parse().unwrap())
+  );
+}
@@ -23,3 +23,81 @@ unsafe fn wide_str_len(ptr: *mut u16) -> usize {
   }
   len
 }
+
+/// Maps an IANA `ifType` (Windows' `IfType`, the BSDs' `ifi_type`) to
+/// [`Interface::media_type`](crate::Interface::media_type)'s string.
+#[cfg(any(windows, bsd_like))]
+pub(crate) fn iana_media_type(if_type: u32) -> Option<&'static str> {
+  Some(match if_type {
+    6 => "ethernet",
+    9 => "token ring",
+    23 => "ppp",
+    24 => "loopback",
+    37 => "atm",
+    // `gif` and `stf` on the BSDs.
+    55 | 57 | 131 => "tunnel",
+    71 => "wireless",
+    135 => "vlan",
+    144 => "firewire",
+    161 => "lag",
+    199 => "infiniband",
+    209 => "bridge",
+    243 | 244 => "wwan",
+    _ => return None,
+  })
+}
+
+/// Maps an IANA `ifType` (Windows' `IfType`, the BSDs' `ifi_type`) to
+/// [`Interface::kind`](crate::Interface::kind).
+#[cfg(any(windows, bsd_like))]
+pub(crate) fn iana_kind(if_type: u32) -> crate::InterfaceKind {
+  use crate::InterfaceKind;
+
+  match if_type {
+    6 => InterfaceKind::Ethernet,
+    9 => InterfaceKind::TokenRing,
+    23 => InterfaceKind::Ppp,
+    24 => InterfaceKind::Loopback,
+    37 => InterfaceKind::Atm,
+    // `gif` and `stf` on the BSDs.
+    55 | 57 | 131 => InterfaceKind::Tunnel,
+    71 => InterfaceKind::Wifi,
+    other => InterfaceKind::Other(other as u16),
+  }
+}
+
+/// Waits until `fd` is readable, failing with `TimedOut` once
+/// `deadline` passes. `EINTR` restarts the wait with the time left.
+#[cfg(not(windows))]
+pub(crate) fn poll_readable(
+  fd: std::os::fd::BorrowedFd<'_>,
+  deadline: std::time::Instant,
+) -> std::io::Result<()> {
+  use std::os::fd::AsRawFd;
+
+  loop {
+    let left = deadline.saturating_duration_since(std::time::Instant::now());
+    // Round up so a sub-millisecond remainder still waits rather than
+    // spinning on a zero timeout.
+    let ms = left
+      .as_micros()
+      .div_ceil(1000)
+      .min(libc::c_int::MAX as u128) as libc::c_int;
+    let mut pfd = libc::pollfd {
+      fd: fd.as_raw_fd(),
+      events: libc::POLLIN,
+      revents: 0,
+    };
+    // SAFETY: `pfd` is a single valid, writable `pollfd`.
+    match unsafe { libc::poll(&mut pfd, 1, ms) } {
+      0 => return Err(std::io::ErrorKind::TimedOut.into()),
+      n if n > 0 => return Ok(()),
+      _ => {
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+          return Err(err);
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,183 @@
+use std::{
+  io,
+  net::IpAddr,
+  time::{Duration, Instant},
+};
+
+use super::{
+  interface_addrs_by_filter, interface_by_index, interface_by_name, watch_addrs, Error, Event,
+  Flags, IfNet, Interface, Result, Watcher,
+};
+
+/// How often the BSD-like fallback re-reads the interface, since its
+/// routing socket watcher does not report link changes.
+#[cfg(not(any(linux_like, windows)))]
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An interface named either by index or by name, as accepted by
+/// [`wait_for_up`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InterfaceRef<'a> {
+  /// The interface with this index.
+  Index(u32),
+  /// The interface with this name.
+  Name(&'a str),
+}
+
+impl From<u32> for InterfaceRef<'_> {
+  #[inline]
+  fn from(index: u32) -> Self {
+    Self::Index(index)
+  }
+}
+
+impl<'a> From<&'a str> for InterfaceRef<'a> {
+  #[inline]
+  fn from(name: &'a str) -> Self {
+    Self::Name(name)
+  }
+}
+
+impl InterfaceRef<'_> {
+  fn lookup(self) -> Result<Option<Interface>> {
+    match self {
+      Self::Index(index) => interface_by_index(index),
+      Self::Name(name) => interface_by_name(name),
+    }
+  }
+}
+
+/// Waits until the interface is administratively up ([`Flags::UP`])
+/// and returns it as read at that moment.
+///
+/// Returns immediately if the interface is already up. Otherwise the
+/// interface is re-read whenever a change is reported, so one that does
+/// not exist yet is waited for as well. Fails with
+/// [`io::ErrorKind::TimedOut`] once `timeout` passes.
+///
+/// - **Linux / Android / Windows**: woken by [`watch`](crate::watch).
+/// - **BSD-like / macOS**: re-reads the interface every 100 ms.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::wait_for_up;
+/// use std::time::Duration;
+///
+/// let eth0 = wait_for_up("eth0", Duration::from_secs(30)).unwrap();
+/// println!("{} is up", eth0.name());
+/// ```
+// `'a` is only named because `impl Trait` cannot elide it on 1.85.
+#[allow(single_use_lifetimes)]
+pub fn wait_for_up<'a>(
+  interface: impl Into<InterfaceRef<'a>>,
+  timeout: Duration,
+) -> Result<Interface> {
+  let interface = interface.into();
+  let deadline = Instant::now().checked_add(timeout);
+  // Subscribe before the first read, so a transition in between still
+  // wakes the loop.
+  let watcher = change_watcher()?;
+  loop {
+    if let Some(ifi) = interface.lookup()? {
+      if ifi.flags().contains(Flags::UP) {
+        return Ok(ifi);
+      }
+    }
+    wait_for_change(watcher.as_ref(), deadline).map_err(|e| {
+      timed_out(e, || {
+        format!("interface {interface:?} did not come up in time")
+      })
+    })?;
+  }
+}
+
+/// Waits until `ip` is configured on some interface and returns it
+/// with its prefix and interface index.
+///
+/// Returns immediately if the address is already present; otherwise
+/// returns as soon as an [`Event::AddrAdded`] for it is reported, as
+/// when a DHCP lease is applied. Fails with [`io::ErrorKind::TimedOut`]
+/// once `timeout` passes. If several interfaces carry `ip`, whichever
+/// is seen first is returned.
+///
+/// Woken by [`watch_addrs`] on every platform.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::wait_for_addr;
+/// use std::time::Duration;
+///
+/// let net = wait_for_addr("192.0.2.10".parse().unwrap(), Duration::from_secs(30)).unwrap();
+/// println!("{net} is configured");
+/// ```
+pub fn wait_for_addr(ip: IpAddr, timeout: Duration) -> Result<IfNet> {
+  let deadline = Instant::now().checked_add(timeout);
+  let watcher = watch_addrs()?;
+  let mut reread = true;
+  loop {
+    if reread {
+      if let Some(net) = interface_addrs_by_filter(|addr| *addr == ip)?.first() {
+        return Ok(*net);
+      }
+    }
+    let event = wait_for_change(Some(&watcher), deadline)
+      .map_err(|e| timed_out(e, || format!("address {ip} did not appear in time")))?;
+    // Only a poll tick or lost notifications call for a re-read; any
+    // other event either is the address or says nothing about it.
+    reread = match event {
+      Some(Event::AddrAdded(net)) if net.addr() == ip => return Ok(net),
+      Some(_) => false,
+      None => true,
+    };
+  }
+}
+
+fn change_watcher() -> Result<Option<Watcher>> {
+  cfg_if::cfg_if! {
+    if #[cfg(any(linux_like, windows))] {
+      crate::watch().map(Some)
+    } else {
+      Ok(None)
+    }
+  }
+}
+
+/// Returns the next event, or `None` once state should be re-read
+/// without one: after a poll interval, or when the watcher dropped
+/// notifications. Fails with `TimedOut` once `deadline` passes; without
+/// a deadline it may wait forever.
+fn wait_for_change(watcher: Option<&Watcher>, deadline: Option<Instant>) -> Result<Option<Event>> {
+  if let Some(watcher) = watcher {
+    return match watcher.recv_until(deadline) {
+      Ok(event) => Ok(Some(event)),
+      #[cfg(not(windows))]
+      Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => Ok(None),
+      Err(e) => Err(e.into()),
+    };
+  }
+
+  #[cfg(not(any(linux_like, windows)))]
+  {
+    let now = Instant::now();
+    let nap = match deadline {
+      Some(deadline) if deadline <= now => {
+        return Err(io::Error::from(io::ErrorKind::TimedOut).into())
+      }
+      Some(deadline) => POLL_INTERVAL.min(deadline - now),
+      None => POLL_INTERVAL,
+    };
+    std::thread::sleep(nap);
+  }
+  Ok(None)
+}
+
+/// Replaces a bare `TimedOut` with one saying what was awaited.
+fn timed_out(e: Error, what: impl FnOnce() -> String) -> Error {
+  if e.kind() == io::ErrorKind::TimedOut {
+    io::Error::new(io::ErrorKind::TimedOut, what()).into()
+  } else {
+    e
+  }
+}
@@ -0,0 +1,186 @@
+use std::{
+  io,
+  time::{Duration, Instant},
+};
+
+use super::{os, IfNet, Result};
+
+/// A change reported by a [`Watcher`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Event {
+  /// An address was assigned to an interface.
+  AddrAdded(IfNet),
+  /// An address was removed from an interface.
+  AddrRemoved(IfNet),
+  /// The interface with this index appeared or changed. The kernel
+  /// reports flag and state changes (up/down, carrier, rename) the same
+  /// way as a new link, so this is also the cue to re-read it.
+  LinkAdded(u32),
+  /// The interface with this index was removed.
+  LinkRemoved(u32),
+}
+
+/// A subscription to the kernel's change notifications.
+///
+/// Created by [`watch_addrs`] or, on Linux and Windows, [`watch`].
+/// Events can be pulled one at a time with [`Watcher::recv`], or by
+/// iterating the watcher, which never ends on its own.
+pub struct Watcher {
+  inner: os::AddrWatcher,
+}
+
+impl core::fmt::Debug for Watcher {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("Watcher").finish_non_exhaustive()
+  }
+}
+
+impl Watcher {
+  /// Blocks until the next event arrives.
+  ///
+  /// On Linux and the BSDs an `ENOBUFS` error means the kernel dropped
+  /// notifications because the reader fell behind; the watcher stays
+  /// usable, but anything tracked from earlier events should be re-read
+  /// with [`interface_addrs`](crate::interface_addrs).
+  #[inline]
+  pub fn recv(&self) -> Result<Event> {
+    self.inner.recv(None).map_err(Into::into)
+  }
+
+  /// Like [`Watcher::recv`], but gives up with an
+  /// [`io::ErrorKind::TimedOut`] error once `timeout` has passed
+  /// without an event.
+  pub fn recv_timeout(&self, timeout: Duration) -> Result<Event> {
+    // A timeout too large to add is as good as none.
+    self
+      .recv_until(Instant::now().checked_add(timeout))
+      .map_err(Into::into)
+  }
+
+  #[inline]
+  pub(crate) fn recv_until(&self, deadline: Option<Instant>) -> io::Result<Event> {
+    self.inner.recv(deadline)
+  }
+
+  /// Switches the watcher into or out of non-blocking mode.
+  ///
+  /// While non-blocking, [`Watcher::recv`] returns an
+  /// [`io::ErrorKind::WouldBlock`] error instead of waiting. On Linux
+  /// this sets the socket itself non-blocking, so the watcher can sit
+  /// in a caller-owned `epoll` / `poll` loop via its `AsFd` impl; on
+  /// Windows it only changes how the internal channel is read.
+  #[cfg(any(linux_like, windows))]
+  #[cfg_attr(
+    docsrs,
+    doc(cfg(any(target_os = "linux", target_os = "android", windows)))
+  )]
+  #[inline]
+  pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+    self.inner.set_nonblocking(nonblocking).map_err(Into::into)
+  }
+}
+
+#[cfg(linux_like)]
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+impl std::os::fd::AsFd for Watcher {
+  #[inline]
+  fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+    self.inner.as_fd()
+  }
+}
+
+#[cfg(linux_like)]
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+impl std::os::fd::AsRawFd for Watcher {
+  #[inline]
+  fn as_raw_fd(&self) -> std::os::fd::RawFd {
+    std::os::fd::AsRawFd::as_raw_fd(&self.inner.as_fd())
+  }
+}
+
+impl Iterator for Watcher {
+  type Item = Result<Event>;
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    Some(self.recv())
+  }
+}
+
+/// Subscribes to address changes only: the returned [`Watcher`] yields
+/// [`Event::AddrAdded`] and [`Event::AddrRemoved`] and nothing else.
+///
+/// Meant for callers that only need to react to IP changes, such as
+/// rebinding sockets, without being woken for link or route churn.
+///
+/// - **Linux / Android**: a netlink socket joined to just
+///   `RTMGRP_IPV4_IFADDR | RTMGRP_IPV6_IFADDR`.
+/// - **BSD-like / macOS**: a `PF_ROUTE` socket whose `RTM_NEWADDR` /
+///   `RTM_DELADDR` messages are kept and everything else is skipped.
+/// - **Windows**: `NotifyUnicastIpAddressChange`, cancelled when the
+///   watcher is dropped.
+///
+/// Only changes made after this call are reported.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::{watch_addrs, Event};
+///
+/// for event in watch_addrs().unwrap() {
+///   match event.unwrap() {
+///     Event::AddrAdded(addr) => println!("+ {addr}"),
+///     Event::AddrRemoved(addr) => println!("- {addr}"),
+///     _ => {}
+///   }
+/// }
+/// ```
+pub fn watch_addrs() -> Result<Watcher> {
+  os::AddrWatcher::new()
+    .map(|inner| Watcher { inner })
+    .map_err(Into::into)
+}
+
+/// Subscribes to link and address changes: the returned [`Watcher`]
+/// yields [`Event::LinkAdded`], [`Event::LinkRemoved`],
+/// [`Event::AddrAdded`] and [`Event::AddrRemoved`].
+///
+/// - **Linux / Android**: a netlink socket joined to
+///   `RTMGRP_LINK | RTMGRP_IPV4_IFADDR | RTMGRP_IPV6_IFADDR`.
+/// - **Windows**: `NotifyIpInterfaceChange` and
+///   `NotifyUnicastIpAddressChange`, cancelled when the watcher is
+///   dropped. Interface notifications are per address family, so one
+///   adapter change usually arrives as two link events.
+///
+/// Link events carry only the interface index; look the interface up with
+/// [`interface_by_index`](crate::interface_by_index) if more is needed,
+/// bearing in mind it may already be gone.
+///
+/// Only changes made after this call are reported.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use getifs::{watch, Event};
+///
+/// for event in watch().unwrap() {
+///   match event.unwrap() {
+///     Event::LinkAdded(index) => println!("link {index} changed"),
+///     Event::LinkRemoved(index) => println!("link {index} removed"),
+///     Event::AddrAdded(addr) => println!("+ {addr}"),
+///     Event::AddrRemoved(addr) => println!("- {addr}"),
+///     _ => {}
+///   }
+/// }
+/// ```
+#[cfg(any(linux_like, windows))]
+#[cfg_attr(
+  docsrs,
+  doc(cfg(any(target_os = "linux", target_os = "android", windows)))
+)]
+pub fn watch() -> Result<Watcher> {
+  os::AddrWatcher::with_links()
+    .map(|inner| Watcher { inner })
+    .map_err(Into::into)
+}
@@ -3,23 +3,36 @@ use std::{
   marker::PhantomData,
   mem::MaybeUninit,
   net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  ops::ControlFlow,
+  time::{Duration, SystemTime},
 };
 
 use smallvec_wrapper::{SmallVec, TinyVec};
 use windows_sys::{
-  Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR},
+  Win32::Foundation::{
+    ERROR_BUFFER_OVERFLOW, ERROR_FILE_NOT_FOUND, ERROR_NOT_FOUND, ERROR_NO_DATA, NO_ERROR,
+  },
   Win32::NetworkManagement::{IpHelper::*, Ndis::*},
   Win32::Networking::WinSock::*,
+  Win32::System::SystemInformation::GetTickCount64,
 };
 
 use super::{
-  Address, IfAddr, IfNet, Ifv4Addr, Ifv4Net, Ifv6Addr, Ifv6Net, Interface, IpRoute, Ipv4Route,
-  Ipv6Route, MacAddr, Net, MAC_ADDRESS_SIZE,
+  Address, EnumerationError, IfAddr, IfNet, Ifv4Addr, Ifv4Net, Ifv6Addr, Ifv6Net, Interface,
+  InterfaceStats, IpRoute, Ipv4Route, Ipv6Route, MacAddr, Net, OperStatus, MAC_ADDRESS_SIZE,
 };
 
+#[cfg(feature = "configure")]
+pub(super) use configure::*;
 pub(super) use gateway::*;
 pub(super) use local_addr::*;
+pub(super) use neighbor::neighbor_table;
 pub(super) use route::*;
+pub(super) use watch::AddrWatcher;
+
+#[cfg(feature = "configure")]
+#[path = "windows/configure.rs"]
+mod configure;
 
 #[path = "windows/local_addr.rs"]
 mod local_addr;
@@ -27,9 +40,15 @@ mod local_addr;
 #[path = "windows/gateway.rs"]
 mod gateway;
 
+#[path = "windows/neighbor.rs"]
+mod neighbor;
+
 #[path = "windows/route.rs"]
 mod route;
 
+#[path = "windows/watch.rs"]
+mod watch;
+
 bitflags::bitflags! {
   /// Flags represents the interface flags.
   #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -91,6 +110,13 @@ fn slots_for(size_bytes: u32) -> usize {
 
 impl Information {
   fn fetch() -> Result<Self> {
+    Self::fetch_with(AF_UNSPEC as u32, GAA_FLAG_INCLUDE_PREFIX)
+  }
+
+  /// Fetches the adapters with `family` addresses, leaving out the
+  /// sections `flags` skips. Adapters without an address of `family`
+  /// may be left out entirely.
+  fn fetch_with(family: u32, flags: u32) -> Result<Self> {
     let mut size = 15000u32; // recommended initial size
 
     let mut buffer: Vec<MaybeUninit<IP_ADAPTER_ADDRESSES_LH>> = Vec::new();
@@ -101,8 +127,8 @@ impl Information {
     loop {
       let result = unsafe {
         GetAdaptersAddresses(
-          AF_UNSPEC as u32,
-          GAA_FLAG_INCLUDE_PREFIX,
+          family,
+          flags,
           std::ptr::null() as _,
           buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH,
           &mut size,
@@ -116,14 +142,22 @@ impl Information {
         break;
       }
 
+      // No adapters at all is an answer, not a failure.
+      if result == ERROR_NO_DATA {
+        return Ok(Self { buffer: Vec::new() });
+      }
+
+      // The return value *is* the Win32 error code; the thread's last
+      // error is not reliably set, so reading it could turn e.g.
+      // `ERROR_ACCESS_DENIED` into an unrelated error or success.
       if result != ERROR_BUFFER_OVERFLOW {
-        return Err(Error::last_os_error());
+        return Err(Error::from_raw_os_error(result as i32));
       }
 
       // `size` is in bytes; compare against the byte capacity of
       // the slot-backed buffer.
       if (size as usize) <= buffer.len() * SLOT_SIZE {
-        return Err(Error::last_os_error());
+        return Err(Error::from_raw_os_error(result as i32));
       }
       buffer.resize_with(slots_for(size), MaybeUninit::uninit);
     }
@@ -214,127 +248,336 @@ fn adapter_index(adapter: &IP_ADAPTER_ADDRESSES_LH) -> u32 {
 }
 
 pub(super) fn interface_table(idx: Option<u32>) -> io::Result<TinyVec<Interface>> {
+  Ok(
+    interface_iter(idx)?
+      .filter_map(std::result::Result::ok)
+      .collect(),
+  )
+}
+
+/// Fetches the adapter list and returns an iterator that builds one
+/// [`Interface`] per adapter as it walks the list. With `idx` set only
+/// that adapter is yielded.
+pub(super) fn interface_iter(idx: Option<u32>) -> io::Result<InterfaceIter> {
   let info = Information::fetch()?;
-  let mut interfaces = TinyVec::new();
+  let current = match info.iter().next() {
+    Some(head) => head as *const _,
+    None => std::ptr::null(),
+  };
+  Ok(InterfaceIter {
+    _info: info,
+    current,
+    idx,
+    carriers: media_carriers(),
+  })
+}
 
-  for adapter in info.iter() {
-    let index = adapter_index(adapter);
+/// Iterator over the adapters of a `GetAdaptersAddresses` snapshot. It
+/// owns the snapshot, so the linked list stays valid while iterating.
+/// `GetAdaptersAddresses` hands back fully decoded records, so every
+/// item is `Ok`.
+pub(crate) struct InterfaceIter {
+  /// Only held so the list `current` walks stays alive.
+  _info: Information,
+  /// The next adapter to visit: null, or a node in `_info.buffer`. The
+  /// buffer is heap-allocated, so moving the iterator keeps it valid.
+  current: *const IP_ADAPTER_ADDRESSES_LH,
+  idx: Option<u32>,
+  /// Carrier state by LUID, from one `GetIfTable2` taken alongside the
+  /// adapter list.
+  carriers: SmallVec<(u64, bool)>,
+}
 
-    if let Some(idx) = idx {
-      if idx == index {
-        let name = match crate::utils::friendly_name(adapter.FriendlyName) {
-          Some(name) => name,
-          None => interface_name_fallback(index),
-        };
+impl Iterator for InterfaceIter {
+  type Item = std::result::Result<Interface, EnumerationError>;
 
-        let mut flags = Flags::empty();
-        if adapter.OperStatus == IfOperStatusUp {
-          flags |= Flags::UP | Flags::RUNNING;
-        }
+  fn next(&mut self) -> Option<Self::Item> {
+    // SAFETY: `current` is null or points at a node of the list in the
+    // buffer `self._info` owns; each `Next` is null or the next node.
+    while let Some(adapter) = unsafe { self.current.as_ref() } {
+      self.current = adapter.Next;
+      let index = adapter_index(adapter);
+      match self.idx {
+        Some(idx) if idx != index => continue,
+        // Indices are unique, so nothing after the match can match.
+        Some(_) => self.current = std::ptr::null(),
+        None => {}
+      }
+      // SAFETY: every view of the `NET_LUID_LH` union is a `u64`.
+      let luid = unsafe { adapter.Luid.Value };
+      let carrier = self
+        .carriers
+        .iter()
+        .find(|(l, _)| *l == luid)
+        .map(|&(_, carrier)| carrier);
+      return Some(Ok(adapter_interface(adapter, index, carrier)));
+    }
+    None
+  }
+}
 
-        match adapter.IfType {
-          IF_TYPE_ETHERNET_CSMACD
-          | IF_TYPE_IEEE80211
-          | IF_TYPE_IEEE1394
-          | IF_TYPE_ISO88025_TOKENRING => {
-            flags |= Flags::BROADCAST | Flags::MULTICAST;
-          }
-          IF_TYPE_PPP | IF_TYPE_TUNNEL => {
-            flags |= Flags::POINTOPOINT | Flags::MULTICAST;
-          }
-          IF_TYPE_SOFTWARE_LOOPBACK => {
-            flags |= Flags::LOOPBACK | Flags::MULTICAST;
-          }
-          IF_TYPE_ATM => {
-            flags |= Flags::BROADCAST | Flags::POINTOPOINT | Flags::MULTICAST;
-          }
-          _ => {}
-        }
+/// Builds the [`Interface`] for one adapter record; `carrier` comes from
+/// [`media_carriers`].
+fn adapter_interface(
+  adapter: &IP_ADAPTER_ADDRESSES_LH,
+  index: u32,
+  carrier: Option<bool>,
+) -> Interface {
+  let name = match crate::utils::friendly_name(adapter.FriendlyName) {
+    Some(name) => name,
+    None => interface_name_fallback(index),
+  };
+
+  let mut flags = Flags::empty();
+  if adapter.OperStatus == IfOperStatusUp {
+    flags |= Flags::UP | Flags::RUNNING;
+  }
 
-        let mtu = if adapter.Mtu == 0xffffffff {
-          0
-        } else {
-          adapter.Mtu
-        };
-
-        let hardware_addr = if adapter.PhysicalAddressLength > 0 {
-          let mut buf = [0u8; MAC_ADDRESS_SIZE];
-          let max_addr_len = (adapter.PhysicalAddressLength as usize).min(MAC_ADDRESS_SIZE);
-          let addr = &adapter.PhysicalAddress[..max_addr_len];
-          buf[..max_addr_len].copy_from_slice(addr);
-          Some(MacAddr::from_raw(buf))
-        } else {
-          None
-        };
-
-        let interface = Interface {
-          index,
-          name,
-          flags,
-          mtu,
-          mac_addr: hardware_addr,
-        };
+  match adapter.IfType {
+    IF_TYPE_ETHERNET_CSMACD | IF_TYPE_IEEE80211 | IF_TYPE_IEEE1394 | IF_TYPE_ISO88025_TOKENRING => {
+      flags |= Flags::BROADCAST | Flags::MULTICAST;
+    }
+    IF_TYPE_PPP | IF_TYPE_TUNNEL => {
+      flags |= Flags::POINTOPOINT | Flags::MULTICAST;
+    }
+    IF_TYPE_SOFTWARE_LOOPBACK => {
+      flags |= Flags::LOOPBACK | Flags::MULTICAST;
+    }
+    IF_TYPE_ATM => {
+      flags |= Flags::BROADCAST | Flags::POINTOPOINT | Flags::MULTICAST;
+    }
+    _ => {}
+  }
 
-        interfaces.push(interface);
-        break;
-      }
-    } else {
-      let name = match crate::utils::friendly_name(adapter.FriendlyName) {
-        Some(name) => name,
-        None => interface_name_fallback(index),
-      };
+  let mtu = if adapter.Mtu == 0xffffffff {
+    0
+  } else {
+    adapter.Mtu
+  };
+
+  let (hardware_addr, physical_address) =
+    decode_physical_address(&adapter.PhysicalAddress, adapter.PhysicalAddressLength);
+
+  Interface {
+    index,
+    name,
+    flags,
+    mtu,
+    mac_addr: hardware_addr,
+    physical_address,
+    carrier,
+    oper_status: oper_status(adapter.OperStatus),
+    media_type: crate::utils::iana_media_type(adapter.IfType),
+    kind: crate::utils::iana_kind(adapter.IfType),
+    link_speed: link_speed(adapter),
+    dns_servers: adapter_dns_servers(adapter),
+  }
+}
 
-      let mut flags = Flags::empty();
-      if adapter.OperStatus == IfOperStatusUp {
-        flags |= Flags::UP | Flags::RUNNING;
-      }
+/// Maps the adapter's `IF_OPER_STATUS` to [`OperStatus`]. The values
+/// follow RFC 2863, so the mapping is one-to-one.
+fn oper_status(status: IF_OPER_STATUS) -> OperStatus {
+  match status {
+    s if s == IfOperStatusUp => OperStatus::Up,
+    s if s == IfOperStatusDown => OperStatus::Down,
+    s if s == IfOperStatusTesting => OperStatus::Testing,
+    s if s == IfOperStatusDormant => OperStatus::Dormant,
+    s if s == IfOperStatusNotPresent => OperStatus::NotPresent,
+    s if s == IfOperStatusLowerLayerDown => OperStatus::LowerLayerDown,
+    _ => OperStatus::Unknown,
+  }
+}
 
-      match adapter.IfType {
-        IF_TYPE_ETHERNET_CSMACD
-        | IF_TYPE_IEEE80211
-        | IF_TYPE_IEEE1394
-        | IF_TYPE_ISO88025_TOKENRING => {
-          flags |= Flags::BROADCAST | Flags::MULTICAST;
-        }
-        IF_TYPE_PPP | IF_TYPE_TUNNEL => {
-          flags |= Flags::POINTOPOINT | Flags::MULTICAST;
-        }
-        IF_TYPE_SOFTWARE_LOOPBACK => {
-          flags |= Flags::LOOPBACK | Flags::MULTICAST;
-        }
-        IF_TYPE_ATM => {
-          flags |= Flags::BROADCAST | Flags::POINTOPOINT | Flags::MULTICAST;
-        }
-        _ => {}
-      }
+/// Picks the adapter's link speed in bits per second. Both directions
+/// use `u64::MAX` for "unknown" and some virtual adapters report zero.
+fn link_speed(adapter: &IP_ADAPTER_ADDRESSES_LH) -> Option<u64> {
+  [adapter.TransmitLinkSpeed, adapter.ReceiveLinkSpeed]
+    .into_iter()
+    .find(|&speed| speed != 0 && speed != u64::MAX)
+}
 
-      let mtu = if adapter.Mtu == 0xffffffff {
-        0
-      } else {
-        adapter.Mtu
-      };
+/// Reads the `family` configuration of interface `index`, or `None` if
+/// the interface is not bound to that family.
+pub(super) fn ip_interface_entry(
+  index: u32,
+  family: ADDRESS_FAMILY,
+) -> io::Result<Option<MIB_IPINTERFACE_ROW>> {
+  // SAFETY: `MIB_IPINTERFACE_ROW` is a plain C struct for which
+  // all-zero is a valid value; `InitializeIpInterfaceEntry` then sets
+  // the fields to their defaults.
+  let mut row: MIB_IPINTERFACE_ROW = unsafe { core::mem::zeroed() };
+  unsafe { InitializeIpInterfaceEntry(&mut row) };
+  row.Family = family;
+  row.InterfaceIndex = index;
+  // SAFETY: `row` is initialised and keyed by family and index.
+  match unsafe { GetIpInterfaceEntry(&mut row) } {
+    NO_ERROR => Ok(Some(row)),
+    ERROR_NOT_FOUND => Ok(None),
+    err => Err(io::Error::from_raw_os_error(err as i32)),
+  }
+}
+
+/// Reads the IP-layer MTU `family` uses on interface `index`
+/// (`NlMtu`), or `None` if the interface is not bound to that family.
+pub(super) fn family_mtu(index: u32, family: super::Family) -> io::Result<Option<u32>> {
+  let family = match family {
+    super::Family::V4 => AF_INET,
+    super::Family::V6 => AF_INET6,
+  };
+  ip_interface_entry(index, family).map(|row| row.map(|row| row.NlMtu))
+}
+
+/// Reads the traffic counters of interface `idx` with `GetIfEntry2`,
+/// adding unicast and non-unicast packets together.
+pub(super) fn interface_stats(idx: u32) -> io::Result<InterfaceStats> {
+  // SAFETY: `MIB_IF_ROW2` is plain-old-data; all-zero is a valid value.
+  let mut row: MIB_IF_ROW2 = unsafe { core::mem::zeroed() };
+  // A zero LUID makes `GetIfEntry2` look the row up by index.
+  row.InterfaceIndex = idx;
+  // SAFETY: `row` is a valid, writable `MIB_IF_ROW2` keyed by index.
+  match unsafe { GetIfEntry2(&mut row) } {
+    NO_ERROR => Ok(InterfaceStats {
+      rx_bytes: row.InOctets,
+      tx_bytes: row.OutOctets,
+      rx_packets: row.InUcastPkts.saturating_add(row.InNUcastPkts),
+      tx_packets: row.OutUcastPkts.saturating_add(row.OutNUcastPkts),
+      rx_errors: row.InErrors,
+      tx_errors: row.OutErrors,
+    }),
+    // `GetIfEntry2` reports an index no adapter has this way.
+    ERROR_FILE_NOT_FOUND => Err(crate::Error::InterfaceNotFound.into()),
+    err => Err(io::Error::from_raw_os_error(err as i32)),
+  }
+}
+
+/// Reads `MediaConnectState` for every interface with one
+/// `GetIfTable2` call, keyed by LUID.
+///
+/// `IP_ADAPTER_ADDRESSES_LH` only carries `OperStatus`, which folds
+/// administrative state, dormancy and carrier into one value, so the
+/// raw media state needs the interface table. Interfaces whose state is
+/// unknown are left out, and a failed fetch yields an empty list, so
+/// every adapter then reports `None`.
+fn media_carriers() -> SmallVec<(u64, bool)> {
+  let mut table = std::ptr::null_mut();
+  // SAFETY: `table` is a writable out-pointer; on success it owns a
+  // table freed below with `FreeMibTable`.
+  if unsafe { GetIfTable2(&mut table) } != NO_ERROR {
+    return SmallVec::new();
+  }
+
+  struct TableGuard(*mut MIB_IF_TABLE2);
+
+  impl Drop for TableGuard {
+    fn drop(&mut self) {
+      // SAFETY: the pointer came from `GetIfTable2`.
+      unsafe { FreeMibTable(self.0.cast()) };
+    }
+  }
 
-      let hardware_addr = if adapter.PhysicalAddressLength > 0 {
-        let mut buf = [0u8; MAC_ADDRESS_SIZE];
-        let max_addr_len = (adapter.PhysicalAddressLength as usize).min(MAC_ADDRESS_SIZE);
-        let addr = &adapter.PhysicalAddress[..max_addr_len];
-        buf[..max_addr_len].copy_from_slice(addr);
-        Some(MacAddr::from_raw(buf))
+  let _guard = TableGuard(table);
+
+  // SAFETY: `GetIfTable2` succeeded, so `table` points at a table with
+  // `NumEntries` rows laid out from `Table`.
+  let rows = unsafe {
+    let table = &*table;
+    core::slice::from_raw_parts(
+      &table.Table as *const _ as *const MIB_IF_ROW2,
+      table.NumEntries as usize,
+    )
+  };
+  rows
+    .iter()
+    .filter_map(|row| {
+      let carrier = if row.MediaConnectState == MediaConnectStateConnected {
+        true
+      } else if row.MediaConnectState == MediaConnectStateDisconnected {
+        false
       } else {
-        None
+        return None;
       };
+      // SAFETY: every view of the `NET_LUID_LH` union is a `u64`.
+      Some((unsafe { row.InterfaceLuid.Value }, carrier))
+    })
+    .collect()
+}
+
+/// Reads `dwLastChange` for the interface with the given index and
+/// turns it into wall-clock time. `MIB_IF_ROW2` has no equivalent, so
+/// this uses the legacy `GetIfEntry`. Any failure reports `None`.
+pub(super) fn last_change(index: u32) -> Option<SystemTime> {
+  // SAFETY: `MIB_IFROW` is plain-old-data; all-zero is a valid value.
+  let mut row: MIB_IFROW = unsafe { core::mem::zeroed() };
+  row.dwIndex = index;
+  // SAFETY: `row` is a valid, writable `MIB_IFROW` whose index has been
+  // set, which is all `GetIfEntry` requires.
+  if unsafe { GetIfEntry(&mut row) } != NO_ERROR {
+    return None;
+  }
+  // SAFETY: no preconditions.
+  let uptime = Duration::from_millis(unsafe { GetTickCount64() });
+  last_change_from_uptime(row.dwLastChange, uptime, SystemTime::now())
+}
 
-      interfaces.push(Interface {
-        index,
-        name,
-        flags,
-        mtu,
-        mac_addr: hardware_addr,
-      });
+/// `dwLastChange` is `sysUpTime` at the change: hundredths of a second
+/// since boot in a 32-bit counter that wraps every ~497 days. On a host
+/// up longer than that, the most recent instant consistent with the
+/// counter is taken.
+fn last_change_from_uptime(
+  last_change: u32,
+  uptime: Duration,
+  now: SystemTime,
+) -> Option<SystemTime> {
+  const WRAP: Duration = Duration::from_millis((u32::MAX as u64 + 1) * 10);
+
+  let mut since_boot = Duration::from_millis(last_change as u64 * 10);
+  if since_boot > uptime {
+    return None;
+  }
+  let wraps = ((uptime - since_boot).as_millis() / WRAP.as_millis()) as u32;
+  since_boot += WRAP * wraps;
+  now.checked_sub(uptime)?.checked_add(since_boot)
+}
+
+/// Splits an adapter's `PhysicalAddress` into a MAC and the raw bytes.
+///
+/// Only a length of exactly six becomes a [`MacAddr`]; zero-padding a
+/// shorter address (as some virtual-switch adapters report) or
+/// truncating a longer one would fabricate a MAC the adapter doesn't
+/// have. The raw bytes are kept either way, clamped to the buffer.
+fn decode_physical_address(bytes: &[u8], len: u32) -> (Option<MacAddr>, SmallVec<u8>) {
+  let raw = &bytes[..(len as usize).min(bytes.len())];
+  let mac = <[u8; MAC_ADDRESS_SIZE]>::try_from(raw)
+    .ok()
+    .map(MacAddr::from_raw);
+  (mac, raw.iter().copied().collect())
+}
+
+/// Collects the adapter's `FirstDnsServerAddress` list.
+fn adapter_dns_servers(adapter: &IP_ADAPTER_ADDRESSES_LH) -> SmallVec<IpAddr> {
+  let mut servers = SmallVec::new();
+  // SAFETY: the list nodes live inside the `Information` buffer that
+  // `adapter` borrows from, and each `Next` is either null or points
+  // at the following node in that buffer.
+  unsafe {
+    let mut dns = adapter.FirstDnsServerAddress;
+    while let Some(server) = dns.as_ref() {
+      if let Some(ip) = sockaddr_to_ipaddr(AF_UNSPEC, server.Address.lpSockaddr) {
+        servers.push(ip);
+      }
+      dns = server.Next;
     }
   }
+  servers
+}
 
-  Ok(interfaces)
+/// `GetAdaptersAddresses` hands back fully decoded adapter records, so
+/// there is nothing to skip per interface; the table either loads or
+/// fails as a whole.
+pub(super) fn interface_table_lossy(
+  _errors: &mut Vec<EnumerationError>,
+) -> io::Result<TinyVec<Interface>> {
+  interface_table(None)
 }
 
 pub(super) fn interface_ipv4_addresses<F>(idx: Option<u32>, f: F) -> io::Result<SmallVec<Ifv4Net>>
@@ -351,6 +594,26 @@ where
   interface_addr_table(AF_INET6, idx, f)
 }
 
+/// Windows reports no broadcast address, so derive the directed
+/// broadcast from the prefix for IPv4 addresses on the adapter types
+/// `interface_table` marks `BROADCAST`. `/31` and `/32` have none.
+fn prefix_broadcast(if_type: u32, ip: IpAddr, prefix: u8) -> Option<Ipv4Addr> {
+  let broadcast_capable = matches!(
+    if_type,
+    IF_TYPE_ETHERNET_CSMACD
+      | IF_TYPE_IEEE80211
+      | IF_TYPE_IEEE1394
+      | IF_TYPE_ISO88025_TOKENRING
+      | IF_TYPE_ATM
+  );
+  match ip {
+    IpAddr::V4(ip) if broadcast_capable && prefix < 31 => ipnet::Ipv4Net::new(ip, prefix)
+      .ok()
+      .map(|net| net.broadcast()),
+    _ => None,
+  }
+}
+
 pub(super) fn interface_addresses<F>(idx: Option<u32>, f: F) -> io::Result<SmallVec<IfNet>>
 where
   F: FnMut(&IpAddr) -> bool,
@@ -361,69 +624,143 @@ where
 pub(super) fn interface_addr_table<T, F>(
   family: u16,
   ifi: Option<u32>,
-  mut f: F,
+  f: F,
+) -> io::Result<SmallVec<T>>
+where
+  T: Net,
+  F: FnMut(&IpAddr) -> bool,
+{
+  unicast_addr_table(family, ifi, false, f)
+}
+
+/// Like `interface_addr_table`, but with `bindable_only` skips
+/// adapters that are not operationally up and addresses whose DAD
+/// state is anything but preferred (tentative, duplicate, deprecated,
+/// invalid).
+pub(super) fn unicast_addr_table<T, F>(
+  family: u16,
+  ifi: Option<u32>,
+  bindable_only: bool,
+  f: F,
 ) -> io::Result<SmallVec<T>>
 where
   T: Net,
   F: FnMut(&IpAddr) -> bool,
 {
-  let info = Information::fetch()?;
   let mut addresses = SmallVec::new();
+  unicast_addr_walk(family, ifi, bindable_only, f, |net, _| {
+    addresses.push(net);
+    ControlFlow::Continue(())
+  })?;
+  Ok(addresses)
+}
+
+pub(super) fn for_each_interface_addr<F>(mut f: F) -> io::Result<()>
+where
+  F: FnMut(&IfNet) -> ControlFlow<()>,
+{
+  unicast_addr_walk(AF_UNSPEC, None, false, |_| true, |net: IfNet, _| f(&net))
+}
+
+/// Picks the address of `family` on adapter `ifi` (any, if `None`) to
+/// use as source, reading deprecation from the DAD state and a
+/// temporary address from a random suffix; see
+/// `crate::scope::pick_source`.
+pub(super) fn unicast_source_addr(family: u16, ifi: Option<u32>) -> io::Result<Option<IfNet>> {
+  let mut candidates = SmallVec::new();
+  unicast_addr_walk(
+    family,
+    ifi,
+    false,
+    |_| true,
+    |net: IfNet, flags| {
+      candidates.push((net, flags));
+      ControlFlow::Continue(())
+    },
+  )?;
+  Ok(crate::scope::pick_source(candidates))
+}
+
+/// Walks the unicast addresses of `GetAdaptersAddresses`, handing each
+/// one and its [`SourceFlags`](crate::scope::SourceFlags) to `emit`
+/// until it breaks.
+fn unicast_addr_walk<T, F, E>(
+  family: u16,
+  ifi: Option<u32>,
+  bindable_only: bool,
+  mut f: F,
+  mut emit: E,
+) -> io::Result<()>
+where
+  T: Net,
+  F: FnMut(&IpAddr) -> bool,
+  E: FnMut(T, crate::scope::SourceFlags) -> ControlFlow<()>,
+{
+  // `GetAdaptersAddresses` cannot be asked for one adapter, but it can
+  // be told to leave out the family and sections an address query
+  // never reads, which is most of its work on hosts with many
+  // adapters.
+  let info = Information::fetch_with(
+    family as u32,
+    GAA_FLAG_INCLUDE_PREFIX
+      | GAA_FLAG_SKIP_ANYCAST
+      | GAA_FLAG_SKIP_MULTICAST
+      | GAA_FLAG_SKIP_DNS_SERVER
+      | GAA_FLAG_SKIP_FRIENDLY_NAME,
+  )?;
 
   for adapter in info.iter() {
     let index = adapter_index(adapter);
+    if ifi.is_some_and(|ifi| ifi != index) {
+      continue;
+    }
+    if bindable_only && adapter.OperStatus != IfOperStatusUp {
+      continue;
+    }
 
-    if let Some(ifi) = ifi {
-      if ifi == index {
-        unsafe {
-          let mut unicast = adapter.FirstUnicastAddress;
-          while let Some(addr) = unicast.as_ref() {
-            if let Some(ip) = sockaddr_to_ipaddr(family, addr.Address.lpSockaddr) {
-              if let Some(ip) = T::try_from_with_filter(index, ip, addr.OnLinkPrefixLength, &mut f)
-              {
-                addresses.push(ip);
-              }
-            }
-            unicast = addr.Next;
-          }
-
-          // TODO(al8n): Should we include anycast addresses?
-          // let mut anycast = adapter.FirstAnycastAddress;
-          // while let Some(addr) = anycast.as_ref() {
-          //   if let Some(ip) = sockaddr_to_ipaddr(addr.Address.lpSockaddr) {
-          //     let ip = IfNet::new(index, ip);
-          //     addresses.push(ip);
-          //   }
-          //   anycast = addr.Next;
-          // }
+    unsafe {
+      let mut unicast = adapter.FirstUnicastAddress;
+      while let Some(addr) = unicast.as_ref() {
+        if bindable_only && addr.DadState != IpDadStatePreferred {
+          unicast = addr.Next;
+          continue;
         }
-      }
-    } else {
-      unsafe {
-        let mut unicast = adapter.FirstUnicastAddress;
-        while let Some(addr) = unicast.as_ref() {
-          if let Some(ip) = sockaddr_to_ipaddr(family, addr.Address.lpSockaddr) {
-            if let Some(ip) = T::try_from_with_filter(index, ip, addr.OnLinkPrefixLength, &mut f) {
-              addresses.push(ip);
+        if let Some(ip) = sockaddr_to_ipaddr(family, addr.Address.lpSockaddr) {
+          let prefix = addr.OnLinkPrefixLength;
+          if let Some(net) = T::try_from_with_filter(index, ip, prefix, &mut f) {
+            let net = net
+              .with_broadcast(prefix_broadcast(adapter.IfType, ip, prefix))
+              .with_scope(sockaddr_scope(addr.Address.lpSockaddr));
+            let flags = crate::scope::SourceFlags {
+              deprecated: addr.DadState == IpDadStateDeprecated,
+              temporary: addr.SuffixOrigin == IpSuffixOriginRandom,
+            };
+            if emit(net, flags).is_break() {
+              return Ok(());
             }
           }
-          unicast = addr.Next;
         }
-
-        // TODO(al8n): Should we include anycast addresses?
-        // let mut anycast = adapter.FirstAnycastAddress;
-        // while let Some(addr) = anycast.as_ref() {
-        //   if let Some(ip) = sockaddr_to_ipaddr(addr.Address.lpSockaddr) {
-        //     let ip = IfNet::new(index, ip);
-        //     addresses.push(ip);
-        //   }
-        //   anycast = addr.Next;
-        // }
+        unicast = addr.Next;
       }
+
+      // TODO(al8n): Should we include anycast addresses?
+      // let mut anycast = adapter.FirstAnycastAddress;
+      // while let Some(addr) = anycast.as_ref() {
+      //   if let Some(ip) = sockaddr_to_ipaddr(addr.Address.lpSockaddr) {
+      //     let ip = IfNet::new(index, ip);
+      //     addresses.push(ip);
+      //   }
+      //   anycast = addr.Next;
+      // }
+    }
+
+    // Indices are unique, so the requested adapter has been seen.
+    if ifi.is_some() {
+      break;
     }
   }
 
-  Ok(addresses)
+  Ok(())
 }
 
 pub(super) fn interface_multicast_ipv4_addresses<F>(
@@ -509,6 +846,34 @@ where
   Ok(addresses)
 }
 
+/// Builds the `SOCKADDR_INET` the IP Helper row APIs key addresses by.
+fn sockaddr_inet(addr: IpAddr) -> SOCKADDR_INET {
+  match addr {
+    IpAddr::V4(addr) => SOCKADDR_INET {
+      Ipv4: SOCKADDR_IN {
+        sin_family: AF_INET,
+        sin_addr: IN_ADDR {
+          S_un: IN_ADDR_0 {
+            S_addr: u32::from_ne_bytes(addr.octets()),
+          },
+        },
+        ..Default::default()
+      },
+    },
+    IpAddr::V6(addr) => SOCKADDR_INET {
+      Ipv6: SOCKADDR_IN6 {
+        sin6_family: AF_INET6,
+        sin6_addr: IN6_ADDR {
+          u: IN6_ADDR_0 {
+            Byte: addr.octets(),
+          },
+        },
+        ..Default::default()
+      },
+    },
+  }
+}
+
 fn sockaddr_to_ipaddr(family: u16, sockaddr: *const SOCKADDR) -> Option<IpAddr> {
   if sockaddr.is_null() {
     return None;
@@ -536,3 +901,118 @@ fn sockaddr_to_ipaddr(family: u16, sockaddr: *const SOCKADDR) -> Option<IpAddr>
     }
   }
 }
+
+/// Reads the `SCOPE_LEVEL` an IPv6 sockaddr carries in the top four
+/// bits of `sin6_scope_id` (its `SCOPE_ID.Level`). IPv4 addresses and a
+/// zero level report nothing, leaving the scope to inference.
+fn sockaddr_scope(sockaddr: *const SOCKADDR) -> Option<crate::AddrScope> {
+  use crate::AddrScope;
+
+  if sockaddr.is_null() {
+    return None;
+  }
+
+  let level = unsafe {
+    if (*sockaddr).sa_family != AF_INET6 {
+      return None;
+    }
+    ((*(sockaddr as *const SOCKADDR_IN6)).Anonymous.sin6_scope_id >> 28) as SCOPE_LEVEL
+  };
+  match level {
+    l if l == ScopeLevelInterface => Some(AddrScope::Host),
+    l if l == ScopeLevelLink => Some(AddrScope::Link),
+    l if l == ScopeLevelSubnet || l == ScopeLevelAdmin || l == ScopeLevelSite => {
+      Some(AddrScope::Site)
+    }
+    l if l == ScopeLevelOrganization || l == ScopeLevelGlobal => Some(AddrScope::Global),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Broadcast-capable adapters get the directed broadcast of prefixes
+  // up to /30; point-to-point prefixes, loopback and IPv6 get none.
+  #[test]
+  fn prefix_broadcast_cases() {
+    let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 5));
+    assert_eq!(
+      prefix_broadcast(IF_TYPE_ETHERNET_CSMACD, ip, 24),
+      Some(Ipv4Addr::new(192, 0, 2, 255))
+    );
+    assert_eq!(prefix_broadcast(IF_TYPE_IEEE80211, ip, 31), None);
+    assert_eq!(prefix_broadcast(IF_TYPE_SOFTWARE_LOOPBACK, ip, 8), None);
+    assert_eq!(
+      prefix_broadcast(IF_TYPE_ETHERNET_CSMACD, IpAddr::V6(Ipv6Addr::LOCALHOST), 64),
+      None
+    );
+  }
+
+  // Only a six-byte physical address may become a MAC. Loopback and
+  // tunnel adapters report none, and the odd-length addresses some
+  // virtual switches report must stay raw instead of being zero-padded.
+  #[test]
+  fn physical_address_lengths() {
+    let buf = [0x02, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
+
+    let (mac, raw) = decode_physical_address(&buf, 0);
+    assert_eq!(mac, None);
+    assert!(raw.is_empty());
+
+    let (mac, raw) = decode_physical_address(&buf, 4);
+    assert_eq!(mac, None);
+    assert_eq!(raw.as_slice(), &buf[..4]);
+
+    let (mac, raw) = decode_physical_address(&buf, 6);
+    assert_eq!(
+      mac,
+      Some(MacAddr::from_raw([0x02, 0x11, 0x22, 0x33, 0x44, 0x55]))
+    );
+    assert_eq!(raw.as_slice(), &buf[..6]);
+
+    let (mac, raw) = decode_physical_address(&buf, 8);
+    assert_eq!(mac, None);
+    assert_eq!(raw.as_slice(), &buf[..]);
+
+    // A length past the buffer is clamped rather than trusted.
+    let (_, raw) = decode_physical_address(&buf, 64);
+    assert_eq!(raw.len(), buf.len());
+  }
+
+  // `dwLastChange` is measured from boot, so it lands between boot and
+  // now; a counter ahead of the uptime is nonsense, and a wrapped
+  // counter on a long-running host resolves to its latest occurrence.
+  #[test]
+  fn last_change_from_uptime_cases() {
+    let now = SystemTime::now();
+    let uptime = Duration::from_secs(3600);
+    let boot = now - uptime;
+
+    assert_eq!(last_change_from_uptime(0, uptime, now), Some(boot));
+    assert_eq!(
+      last_change_from_uptime(6000, uptime, now),
+      Some(boot + Duration::from_secs(60))
+    );
+    assert_eq!(last_change_from_uptime(360_001 * 100, uptime, now), None);
+
+    let wrap = Duration::from_millis((u32::MAX as u64 + 1) * 10);
+    let long_uptime = wrap + Duration::from_secs(120);
+    assert_eq!(
+      last_change_from_uptime(6000, long_uptime, now),
+      Some(now - long_uptime + wrap + Duration::from_secs(60))
+    );
+  }
+
+  // Every live interface's last change must lie in the past.
+  #[test]
+  fn last_change_not_in_future() {
+    let now = SystemTime::now();
+    for ifi in crate::interfaces().unwrap() {
+      if let Some(at) = ifi.last_change() {
+        assert!(at <= now, "{ifi:?}: {at:?}");
+      }
+    }
+  }
+}
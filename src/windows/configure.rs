@@ -0,0 +1,153 @@
+use std::{
+  io,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use ipnet::IpNet;
+use windows_sys::Win32::{
+  Foundation::ERROR_NOT_FOUND,
+  NetworkManagement::IpHelper::{
+    CreateIpForwardEntry2, CreateUnicastIpAddressEntry, DeleteIpForwardEntry2,
+    DeleteUnicastIpAddressEntry, InitializeIpForwardEntry, InitializeUnicastIpAddressEntry,
+    SetIfEntry, SetIpInterfaceEntry, MIB_IFROW, MIB_IF_ADMIN_STATUS_DOWN, MIB_IF_ADMIN_STATUS_UP,
+    MIB_IPFORWARD_ROW2, MIB_IPINTERFACE_ROW, MIB_UNICASTIPADDRESS_ROW,
+  },
+  Networking::WinSock::{
+    IpDadStatePreferred, AF_INET, AF_INET6, AF_UNSPEC, MIB_IPPROTO_NETMGMT, SOCKADDR,
+  },
+};
+
+use super::{ip_interface_entry, route::ForwardTable, sockaddr_inet, sockaddr_to_ipaddr, NO_ERROR};
+
+/// Writes back a row read by [`ip_interface_entry`](super::ip_interface_entry).
+fn set_ip_interface_entry(mut row: MIB_IPINTERFACE_ROW) -> io::Result<()> {
+  // `SetIpInterfaceEntry` rejects an IPv4 row whose
+  // `SitePrefixLength` is not zero, yet `GetIpInterfaceEntry` may fill
+  // it in.
+  if row.Family == AF_INET {
+    row.SitePrefixLength = 0;
+  }
+  // SAFETY: `row` came from `GetIpInterfaceEntry`.
+  match unsafe { SetIpInterfaceEntry(&mut row) } {
+    NO_ERROR => Ok(()),
+    err => Err(io::Error::from_raw_os_error(err as i32)),
+  }
+}
+
+pub(crate) fn set_mtu(index: u32, mtu: u32) -> io::Result<()> {
+  let mut found = false;
+  for family in [AF_INET, AF_INET6] {
+    if let Some(mut row) = ip_interface_entry(index, family)? {
+      row.NlMtu = mtu;
+      set_ip_interface_entry(row)?;
+      found = true;
+    }
+  }
+  if !found {
+    return Err(io::Error::from_raw_os_error(ERROR_NOT_FOUND as i32));
+  }
+  Ok(())
+}
+
+pub(crate) fn set_interface_up(index: u32, up: bool) -> io::Result<()> {
+  // `SetIfEntry` only reads `dwIndex` and `dwAdminStatus`.
+  let row = MIB_IFROW {
+    dwIndex: index,
+    dwAdminStatus: if up {
+      MIB_IF_ADMIN_STATUS_UP
+    } else {
+      MIB_IF_ADMIN_STATUS_DOWN
+    },
+    ..Default::default()
+  };
+  // SAFETY: `row` is a valid `MIB_IFROW` that outlives the call.
+  match unsafe { SetIfEntry(&row) } {
+    NO_ERROR => Ok(()),
+    err => Err(io::Error::from_raw_os_error(err as i32)),
+  }
+}
+
+/// A unicast address row for `net` on interface `index`, with the
+/// defaults `InitializeUnicastIpAddressEntry` fills in (infinite
+/// lifetimes, manual origin).
+fn unicast_row(index: u32, net: IpNet) -> MIB_UNICASTIPADDRESS_ROW {
+  let mut row = MIB_UNICASTIPADDRESS_ROW::default();
+  // SAFETY: `row` is a writable `MIB_UNICASTIPADDRESS_ROW`.
+  unsafe { InitializeUnicastIpAddressEntry(&mut row) };
+  row.InterfaceIndex = index;
+  row.Address = sockaddr_inet(net.addr());
+  row.OnLinkPrefixLength = net.prefix_len();
+  row
+}
+
+pub(crate) fn add_addr(index: u32, net: IpNet) -> io::Result<()> {
+  let mut row = unicast_row(index, net);
+  // Skip duplicate address detection for a manually assigned address,
+  // as `netsh interface ip add address` does.
+  row.DadState = IpDadStatePreferred;
+  // SAFETY: `row` is a fully initialised row that outlives the call.
+  match unsafe { CreateUnicastIpAddressEntry(&row) } {
+    NO_ERROR => Ok(()),
+    err => Err(io::Error::from_raw_os_error(err as i32)),
+  }
+}
+
+pub(crate) fn del_addr(index: u32, net: IpNet) -> io::Result<()> {
+  let row = unicast_row(index, net);
+  // SAFETY: as above; only the interface and address are consulted.
+  match unsafe { DeleteUnicastIpAddressEntry(&row) } {
+    NO_ERROR => Ok(()),
+    err => Err(io::Error::from_raw_os_error(err as i32)),
+  }
+}
+
+pub(crate) fn add_route(
+  dest: IpNet,
+  gateway: Option<IpAddr>,
+  ifindex: u32,
+  metric: u32,
+) -> io::Result<()> {
+  let mut row = MIB_IPFORWARD_ROW2::default();
+  // SAFETY: `row` is a writable `MIB_IPFORWARD_ROW2`.
+  unsafe { InitializeIpForwardEntry(&mut row) };
+  row.InterfaceIndex = ifindex;
+  row.DestinationPrefix.Prefix = sockaddr_inet(dest.addr());
+  row.DestinationPrefix.PrefixLength = dest.prefix_len();
+  // An unspecified next hop of the destination's family makes the
+  // route on-link.
+  row.NextHop = sockaddr_inet(gateway.unwrap_or(match dest {
+    IpNet::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+    IpNet::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+  }));
+  row.Metric = metric;
+  row.Protocol = MIB_IPPROTO_NETMGMT;
+  // SAFETY: `row` is a fully initialised row that outlives the call.
+  match unsafe { CreateIpForwardEntry2(&row) } {
+    NO_ERROR => Ok(()),
+    err => Err(io::Error::from_raw_os_error(err as i32)),
+  }
+}
+
+pub(crate) fn del_route(dest: IpNet) -> io::Result<()> {
+  // `DeleteIpForwardEntry2` is keyed by interface and next hop as well
+  // as the prefix, so look up the first row for `dest` to fill them in.
+  let family = match dest {
+    IpNet::V4(_) => AF_INET,
+    IpNet::V6(_) => AF_INET6,
+  };
+  let table = ForwardTable::fetch(family)?;
+  let row = table.rows().iter().find(|row| {
+    let prefix = &row.DestinationPrefix;
+    prefix.PrefixLength == dest.prefix_len()
+      && sockaddr_to_ipaddr(AF_UNSPEC, &prefix.Prefix as *const _ as *const SOCKADDR)
+        == Some(dest.addr())
+  });
+  let Some(row) = row else {
+    return Err(io::Error::from_raw_os_error(ERROR_NOT_FOUND as i32));
+  };
+  // SAFETY: `row` points into `table`, which is alive for the call.
+  match unsafe { DeleteIpForwardEntry2(row) } {
+    NO_ERROR => Ok(()),
+    err => Err(io::Error::from_raw_os_error(err as i32)),
+  }
+}
@@ -6,9 +6,9 @@ use std::{
 use smallvec_wrapper::SmallVec;
 
 use super::{
-  super::{ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter, local_ip_filter},
-  interface_addresses, interface_ipv4_addresses, interface_ipv6_addresses, IfNet, Ifv4Net, Ifv6Net,
-  NO_ERROR,
+  super::{ipv4_filter_to_ip_filter, ipv6_filter_to_ip_filter, local_ip_filter, Family},
+  interface_addresses, interface_ipv4_addresses, interface_ipv6_addresses, unicast_addr_table,
+  unicast_source_addr, IfNet, Ifv4Net, Ifv6Net, NO_ERROR,
 };
 
 use windows_sys::Win32::NetworkManagement::IpHelper::*;
@@ -167,6 +167,43 @@ fn best_default_route_interface(family: u16) -> io::Result<SmallVec<u32>> {
   }
 }
 
+/// Asks `GetBestRoute2` which route Windows would use for `dest` and
+/// returns its interface together with the source address the stack
+/// selected, or `None` when `dest` is unreachable.
+pub(crate) fn route_source(dest: IpAddr) -> io::Result<Option<(u32, Option<IpAddr>)>> {
+  let dest = super::sockaddr_inet(dest);
+  let mut route = MIB_IPFORWARD_ROW2::default();
+  let mut source = SOCKADDR_INET::default();
+  // SAFETY: `dest` is initialised for its family, and `route` and
+  // `source` are writable locals that outlive the call.
+  let r = unsafe {
+    GetBestRoute2(
+      std::ptr::null(),
+      0,
+      std::ptr::null(),
+      &dest,
+      0,
+      &mut route,
+      &mut source,
+    )
+  };
+  if r != NO_ERROR {
+    return classify_table_error(r).map(|_| None);
+  }
+  let source = super::sockaddr_to_ipaddr(AF_UNSPEC, &source as *const _ as *const SOCKADDR);
+  Ok(Some((route.InterfaceIndex, source)))
+}
+
+/// The address of `family` on interface `index` to use as source when
+/// no route names one; see `crate::scope::pick_source`.
+pub(crate) fn interface_source_addr(index: u32, family: Family) -> io::Result<Option<IfNet>> {
+  let family = match family {
+    Family::V4 => AF_INET,
+    Family::V6 => AF_INET6,
+  };
+  unicast_source_addr(family, (index != 0).then_some(index))
+}
+
 /// Map a `MIB`-table fetch failure: known "no stack / no entries"
 /// codes collapse to `Ok(empty)`, anything else propagates as the
 /// concrete syscall error. Same whitelist `windows/route.rs` and
@@ -186,6 +223,17 @@ fn classify_table_error(code: u32) -> io::Result<SmallVec<u32>> {
   }
 }
 
+/// The first interface behind the best IPv4 default route, else the
+/// best IPv6 one.
+pub(crate) fn default_route_index() -> io::Result<Option<u32>> {
+  for family in [AF_INET, AF_INET6] {
+    if let Some(&index) = best_default_route_interface(family)?.first() {
+      return Ok(Some(index));
+    }
+  }
+  Ok(None)
+}
+
 pub(crate) fn best_local_ipv4_addrs() -> io::Result<SmallVec<Ifv4Net>> {
   let mut out: SmallVec<Ifv4Net> = SmallVec::new();
   for idx in best_default_route_interface(AF_INET)? {
@@ -208,6 +256,30 @@ pub(crate) fn best_local_ipv6_addrs() -> io::Result<SmallVec<Ifv6Net>> {
   Ok(out)
 }
 
+pub(crate) fn best_local_ipv4_addrs_opt() -> io::Result<Option<SmallVec<Ifv4Net>>> {
+  let indices = best_default_route_interface(AF_INET)?;
+  if indices.is_empty() {
+    return Ok(None);
+  }
+  let mut out: SmallVec<Ifv4Net> = SmallVec::new();
+  for idx in indices {
+    out.extend(interface_ipv4_addresses(Some(idx), local_ip_filter)?);
+  }
+  Ok(Some(out))
+}
+
+pub(crate) fn best_local_ipv6_addrs_opt() -> io::Result<Option<SmallVec<Ifv6Net>>> {
+  let indices = best_default_route_interface(AF_INET6)?;
+  if indices.is_empty() {
+    return Ok(None);
+  }
+  let mut out: SmallVec<Ifv6Net> = SmallVec::new();
+  for idx in indices {
+    out.extend(interface_ipv6_addresses(Some(idx), local_ip_filter)?);
+  }
+  Ok(Some(out))
+}
+
 pub(crate) fn best_local_addrs() -> io::Result<SmallVec<IfNet>> {
   // For the any-family variant, independently pick the best v4 and
   // best v6 default-route interfaces. This lets a dual-stack host
@@ -265,6 +337,16 @@ where
   interface_addresses(None, |addr| f(addr) && local_ip_filter(addr))
 }
 
+/// Every address of `family` on an adapter that is up, except those
+/// still in or failed by DAD, or deprecated.
+pub(crate) fn bindable_addrs(family: Family) -> io::Result<SmallVec<IfNet>> {
+  let family = match family {
+    Family::V4 => AF_INET,
+    Family::V6 => AF_INET6,
+  };
+  unicast_addr_table(family, None, true, |_| true)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
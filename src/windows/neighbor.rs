@@ -0,0 +1,96 @@
+use std::{io, net::IpAddr};
+
+use windows_sys::Win32::{
+  Foundation::ERROR_NOT_FOUND,
+  NetworkManagement::IpHelper::{FreeMibTable, GetIpNetTable2, MIB_IPNET_ROW2},
+  Networking::WinSock::{
+    NlnsDelay, NlnsIncomplete, NlnsPermanent, NlnsProbe, NlnsReachable, NlnsStale, NlnsUnreachable,
+    AF_INET, AF_INET6, AF_UNSPEC, NL_NEIGHBOR_STATE, SOCKADDR,
+  },
+};
+
+use smallvec_wrapper::SmallVec;
+
+use crate::{Family, Neighbor, NeighborState};
+
+use super::{sockaddr_to_ipaddr, MacAddr, MAC_ADDRESS_SIZE, NO_ERROR};
+
+pub(crate) fn neighbor_table<F>(family: Option<Family>, mut f: F) -> io::Result<SmallVec<Neighbor>>
+where
+  F: FnMut(&IpAddr) -> bool,
+{
+  let family = match family {
+    None => AF_UNSPEC,
+    Some(Family::V4) => AF_INET,
+    Some(Family::V6) => AF_INET6,
+  };
+  let mut out = SmallVec::new();
+  walk_neighbors(family, |index, ip, mac, state| {
+    if f(&ip) {
+      out.push(Neighbor::new(index, ip, mac, state));
+    }
+  })?;
+  Ok(out)
+}
+
+fn neighbor_state(state: NL_NEIGHBOR_STATE) -> NeighborState {
+  match state {
+    s if s == NlnsUnreachable => NeighborState::Failed,
+    s if s == NlnsIncomplete => NeighborState::Incomplete,
+    s if s == NlnsProbe => NeighborState::Probe,
+    s if s == NlnsDelay => NeighborState::Delay,
+    s if s == NlnsStale => NeighborState::Stale,
+    s if s == NlnsReachable => NeighborState::Reachable,
+    s if s == NlnsPermanent => NeighborState::Permanent,
+    _ => NeighborState::Unknown,
+  }
+}
+
+/// Walks the neighbour table (`GetIpNetTable2`) for `family`, calling
+/// `on_neigh(index, ip, lladdr, state)` for each row. `lladdr` is
+/// `None` when the row has no 6-byte physical address.
+fn walk_neighbors<F>(family: u16, mut on_neigh: F) -> io::Result<()>
+where
+  F: FnMut(u32, IpAddr, Option<MacAddr>, NeighborState),
+{
+  let mut table = std::ptr::null_mut();
+  // SAFETY: `table` is a writable out-pointer; on success it owns a
+  // table freed below with `FreeMibTable`.
+  let res = unsafe { GetIpNetTable2(family, &mut table) };
+  match res {
+    NO_ERROR => {}
+    // An empty neighbour table is reported as "not found".
+    ERROR_NOT_FOUND => return Ok(()),
+    err => return Err(io::Error::from_raw_os_error(err as i32)),
+  }
+
+  struct TableGuard(*mut core::ffi::c_void);
+
+  impl Drop for TableGuard {
+    fn drop(&mut self) {
+      // SAFETY: the pointer came from `GetIpNetTable2`.
+      unsafe { FreeMibTable(self.0) };
+    }
+  }
+
+  let _guard = TableGuard(table.cast());
+
+  // SAFETY: `GetIpNetTable2` succeeded, so `table` points at a table
+  // with `NumEntries` rows laid out from `Table`.
+  let rows = unsafe {
+    let table = &*table;
+    core::slice::from_raw_parts(
+      &table.Table as *const _ as *const MIB_IPNET_ROW2,
+      table.NumEntries as usize,
+    )
+  };
+  for row in rows {
+    let Some(ip) = sockaddr_to_ipaddr(family, &row.Address as *const _ as *const SOCKADDR) else {
+      continue;
+    };
+    let mac = (row.PhysicalAddressLength as usize == MAC_ADDRESS_SIZE)
+      .then(|| MacAddr::from_raw(row.PhysicalAddress[..MAC_ADDRESS_SIZE].try_into().unwrap()));
+    on_neigh(row.InterfaceIndex, ip, mac, neighbor_state(row.State));
+  }
+  Ok(())
+}
@@ -27,12 +27,12 @@ const ERROR_NOT_SUPPORTED: i32 = 50;
 /// Owned wrapper around `MIB_IPFORWARD_TABLE2` that frees the table on
 /// drop. `GetIpForwardTable2` allocates the buffer; the caller must
 /// release it with `FreeMibTable`.
-struct ForwardTable {
+pub(super) struct ForwardTable {
   ptr: *const MIB_IPFORWARD_TABLE2,
 }
 
 impl ForwardTable {
-  fn fetch(family: u16) -> io::Result<Self> {
+  pub(super) fn fetch(family: u16) -> io::Result<Self> {
     let mut ptr = std::ptr::null_mut();
     let result = unsafe { GetIpForwardTable2(family, &mut ptr) };
     if result != NO_ERROR {
@@ -46,7 +46,7 @@ impl ForwardTable {
     Ok(Self { ptr })
   }
 
-  fn rows(&self) -> &[MIB_IPFORWARD_ROW2] {
+  pub(super) fn rows(&self) -> &[MIB_IPFORWARD_ROW2] {
     if self.ptr.is_null() {
       return &[];
     }
@@ -207,7 +207,7 @@ fn build_routev4(
   }
   let net = Ipv4Net::new(dst_v4, row.DestinationPrefix.PrefixLength).ok()?;
 
-  Some(Ipv4Route::new(row.InterfaceIndex, net, gw))
+  Some(Ipv4Route::new(row.InterfaceIndex, net, gw).with_metric(row.Metric))
 }
 
 #[inline]
@@ -237,7 +237,7 @@ fn build_routev6(row: &MIB_IPFORWARD_ROW2) -> Option<Ipv6Route> {
     _ => None,
   };
 
-  Some(Ipv6Route::new(row.InterfaceIndex, net, gw))
+  Some(Ipv6Route::new(row.InterfaceIndex, net, gw).with_metric(row.Metric))
 }
 
 /// `Ok(Some(table))` for a populated family, `Ok(None)` for "no
@@ -247,7 +247,7 @@ fn build_routev6(row: &MIB_IPFORWARD_ROW2) -> Option<Ipv6Route> {
 /// failure (allocation, invalid parameter, etc.) — those propagate so
 /// the union API can't silently turn genuine syscall failures into
 /// empty results.
-fn fetch_family(family: u16) -> io::Result<Option<ForwardTable>> {
+pub(super) fn fetch_family(family: u16) -> io::Result<Option<ForwardTable>> {
   match ForwardTable::fetch(family) {
     Ok(table) => Ok(Some(table)),
     Err(e)
@@ -262,6 +262,12 @@ fn fetch_family(family: u16) -> io::Result<Option<ForwardTable>> {
   }
 }
 
+pub(crate) fn route_probe() -> io::Result<()> {
+  // `fetch_family` already folds the "family has no routes / no stack"
+  // codes into `Ok(None)`; only a genuine failure makes it here.
+  fetch_family(AF_INET).map(|_| ())
+}
+
 pub(crate) fn route_table_by_filter<F>(mut f: F) -> io::Result<SmallVec<IpRoute>>
 where
   F: FnMut(&IpRoute) -> bool,
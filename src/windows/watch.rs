@@ -0,0 +1,202 @@
+use std::{
+  cell::Cell,
+  ffi::c_void,
+  io,
+  sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError},
+  time::Instant,
+};
+
+use windows_sys::Win32::{
+  Foundation::{HANDLE, NO_ERROR, WIN32_ERROR},
+  NetworkManagement::IpHelper::{
+    CancelMibChangeNotify2, MibAddInstance, MibDeleteInstance, MibParameterNotification,
+    NotifyIpInterfaceChange, NotifyUnicastIpAddressChange, MIB_IPINTERFACE_ROW,
+    MIB_NOTIFICATION_TYPE, MIB_UNICASTIPADDRESS_ROW,
+  },
+  Networking::WinSock::{AF_UNSPEC, SOCKADDR},
+};
+
+use crate::Event;
+
+use super::{sockaddr_to_ipaddr, IfNet, Net};
+
+/// A `NotifyUnicastIpAddressChange` registration, plus a
+/// `NotifyIpInterfaceChange` one when built with
+/// [`AddrWatcher::with_links`]. Their callbacks, run on a system
+/// thread pool, forward notifications into a channel that
+/// [`AddrWatcher::recv`] drains.
+pub(crate) struct AddrWatcher {
+  handle: HANDLE,
+  /// The interface registration, null when only addresses are watched.
+  link_handle: HANDLE,
+  rx: Receiver<Event>,
+  /// The callback context, shared by both registrations. Owned here
+  /// and freed only after they are cancelled, so the callbacks never
+  /// see it dangle.
+  tx: *mut Sender<Event>,
+  nonblocking: Cell<bool>,
+}
+
+// SAFETY: the handles are only passed to `CancelMibChangeNotify2` on
+// drop, which may happen on any thread, and `tx` is only dereferenced
+// by the callbacks (a `Sender` is `Send + Sync`) and freed after
+// cancellation.
+unsafe impl Send for AddrWatcher {}
+
+impl AddrWatcher {
+  pub(crate) fn new() -> io::Result<Self> {
+    let (tx, rx) = mpsc::channel();
+    let tx = Box::into_raw(Box::new(tx));
+    let mut handle: HANDLE = std::ptr::null_mut();
+    // SAFETY: `tx` stays valid until `drop` cancels the registration;
+    // `handle` is a writable local.
+    let res = unsafe {
+      NotifyUnicastIpAddressChange(
+        AF_UNSPEC,
+        Some(on_unicast_change),
+        tx as *const c_void,
+        false,
+        &mut handle,
+      )
+    };
+    if res != NO_ERROR {
+      // SAFETY: registration failed, so nothing else holds `tx`.
+      drop(unsafe { Box::from_raw(tx) });
+      return Err(registration_error(res));
+    }
+    Ok(Self {
+      handle,
+      link_handle: std::ptr::null_mut(),
+      rx,
+      tx,
+      nonblocking: Cell::new(false),
+    })
+  }
+
+  /// Like [`AddrWatcher::new`], but also registered for interface
+  /// changes, which surface as link events.
+  pub(crate) fn with_links() -> io::Result<Self> {
+    let mut this = Self::new()?;
+    // SAFETY: `this.tx` stays valid until `drop` cancels both
+    // registrations; `link_handle` is a writable field. On failure
+    // `this` is dropped, cancelling the address registration only.
+    let res = unsafe {
+      NotifyIpInterfaceChange(
+        AF_UNSPEC,
+        Some(on_interface_change),
+        this.tx as *const c_void,
+        false,
+        &mut this.link_handle,
+      )
+    };
+    if res != NO_ERROR {
+      this.link_handle = std::ptr::null_mut();
+      return Err(registration_error(res));
+    }
+    Ok(this)
+  }
+
+  pub(crate) fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+    self.nonblocking.set(nonblocking);
+    Ok(())
+  }
+
+  pub(crate) fn recv(&self, deadline: Option<Instant>) -> io::Result<Event> {
+    // The sender lives as long as `self`, so the channel can't
+    // disconnect while we wait on it.
+    if let Some(deadline) = deadline {
+      let timeout = deadline.saturating_duration_since(Instant::now());
+      return self.rx.recv_timeout(timeout).map_err(|e| match e {
+        RecvTimeoutError::Timeout => io::ErrorKind::TimedOut.into(),
+        RecvTimeoutError::Disconnected => io::Error::new(io::ErrorKind::BrokenPipe, e),
+      });
+    }
+    if self.nonblocking.get() {
+      return self.rx.try_recv().map_err(|e| match e {
+        TryRecvError::Empty => io::ErrorKind::WouldBlock.into(),
+        TryRecvError::Disconnected => io::Error::new(io::ErrorKind::BrokenPipe, e),
+      });
+    }
+    self
+      .rx
+      .recv()
+      .map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e))
+  }
+}
+
+fn registration_error(res: WIN32_ERROR) -> io::Error {
+  io::Error::from_raw_os_error(res as i32)
+}
+
+impl Drop for AddrWatcher {
+  fn drop(&mut self) {
+    // SAFETY: both handles came from successful registrations. Each
+    // call blocks until in-flight callbacks return, after which none
+    // can touch `tx` again.
+    unsafe {
+      if !self.link_handle.is_null() {
+        CancelMibChangeNotify2(self.link_handle);
+      }
+      CancelMibChangeNotify2(self.handle);
+      drop(Box::from_raw(self.tx));
+    }
+  }
+}
+
+unsafe extern "system" fn on_unicast_change(
+  ctx: *const c_void,
+  row: *const MIB_UNICASTIPADDRESS_ROW,
+  ty: MIB_NOTIFICATION_TYPE,
+) {
+  // SAFETY: `ctx` is the `Sender` registered in `AddrWatcher::new`,
+  // alive until the registration is cancelled; `row` is either null or
+  // valid for the duration of the callback.
+  let (tx, row) = unsafe {
+    match row.as_ref() {
+      Some(row) => (&*(ctx as *const Sender<Event>), row),
+      None => return,
+    }
+  };
+  let Some(ip) = sockaddr_to_ipaddr(AF_UNSPEC, &row.Address as *const _ as *const SOCKADDR) else {
+    return;
+  };
+  let Some(net) = <IfNet as Net>::try_from(row.InterfaceIndex, ip, row.OnLinkPrefixLength) else {
+    return;
+  };
+  let event = if ty == MibAddInstance {
+    Event::AddrAdded(net)
+  } else if ty == MibDeleteInstance {
+    Event::AddrRemoved(net)
+  } else {
+    return;
+  };
+  // The receiver only goes away together with the registration.
+  let _ = tx.send(event);
+}
+
+unsafe extern "system" fn on_interface_change(
+  ctx: *const c_void,
+  row: *const MIB_IPINTERFACE_ROW,
+  ty: MIB_NOTIFICATION_TYPE,
+) {
+  // SAFETY: `ctx` is the `Sender` registered in `AddrWatcher::new`,
+  // alive until the registrations are cancelled; `row` is either null
+  // or valid for the duration of the callback.
+  let (tx, row) = unsafe {
+    match row.as_ref() {
+      Some(row) => (&*(ctx as *const Sender<Event>), row),
+      None => return,
+    }
+  };
+  // A parameter change (connect state, MTU, metric) is reported like a
+  // new link, matching the kernel's `RTM_NEWLINK` on Linux.
+  let event = if ty == MibAddInstance || ty == MibParameterNotification {
+    Event::LinkAdded(row.InterfaceIndex)
+  } else if ty == MibDeleteInstance {
+    Event::LinkRemoved(row.InterfaceIndex)
+  } else {
+    return;
+  };
+  // The receiver only goes away together with the registration.
+  let _ = tx.send(event);
+}
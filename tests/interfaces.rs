@@ -1,8 +1,18 @@
 use std::net::IpAddr;
 
 use getifs::{
-  gateway_addrs, interface_addrs, interface_by_index, interface_by_name, interfaces, local_addrs,
-  Flags, IfNet, Interface,
+  addrs_for_mac_prefix, addrs_within, best_local_addrs, best_local_ipv4_addrs_opt,
+  best_local_ipv6_addrs_opt, bindable_addrs, default_interface, for_each_interface_addr,
+  gateway_addrs, gateway_ipv4_addrs, gateway_ipv6_addrs, gateway_mac, ifname_to_index,
+  interface_addrs, interface_addrs_by_net_filter, interface_addrs_in_any,
+  interface_addrs_scope_sorted, interface_broadcast_addrs, interface_by_index,
+  interface_by_link_local, interface_by_name, interfaces, interfaces_iter,
+  interfaces_require_nonempty, interfaces_with_wildcard, ipv6_addrs_in_zone, is_local_addr,
+  is_local_addr_on, local_addrs, local_addrs_by_net_filter, local_addrs_with_metric,
+  loopback_interface, neighbors, neighbors_by_filter, neighbors_ipv4, neighbors_ipv6,
+  primary_addrs_per_family, routable_interfaces, route_table, runtime_hints, source_addr_for,
+  try_interfaces, wait_for_addr, AddrScope, CommonFlags, Error, Family, Flags, IfNet, Interface,
+  InterfaceKind, OperStatus,
 };
 
 // `IfAddr` is only used by the multicast helper below, which is
@@ -18,6 +28,11 @@ use getifs::{
 ))]
 use getifs::IfAddr;
 
+// Only a cfg-gated block of `missing_interface_is_interface_not_found`
+// uses it.
+#[cfg(any(target_os = "linux", target_vendor = "apple", target_os = "freebsd"))]
+use getifs::StatsSampler;
+
 use iprobe::{ipv4, ipv6};
 
 #[derive(Debug)]
@@ -259,6 +274,52 @@ fn if_addrs() {
   check_unicast_stats(&stats, &uni_stats).unwrap();
 }
 
+// Bind candidates are a subset of the configured addresses and, since
+// loopback can always be bound, include it for every family it has.
+#[test]
+fn bindable_addrs_subset_with_loopback() {
+  let all = interface_addrs().unwrap();
+  for family in [Family::V4, Family::V6] {
+    let bindable = bindable_addrs(family).unwrap();
+    for net in &bindable {
+      assert_eq!(Family::of(&net.addr()), family, "{net}");
+      assert!(all.contains(net), "{net} is not configured");
+    }
+    let loopback = all
+      .iter()
+      .find(|net| Family::of(&net.addr()) == family && net.addr().is_loopback());
+    if let Some(loopback) = loopback {
+      assert!(bindable.contains(loopback), "{loopback} missing");
+    }
+  }
+}
+
+// A per-interface query must agree with the host-wide walk; Windows
+// answers the two from differently scoped adapter fetches. Same NetBSD
+// / DragonFly skip rationale as `if_addrs` above.
+#[cfg(not(any(target_os = "netbsd", target_os = "dragonfly")))]
+#[test]
+fn per_interface_addrs_match_full_walk() {
+  let all = interface_addrs().unwrap();
+  for ifi in interfaces().unwrap() {
+    let mut want: Vec<IfNet> = all
+      .iter()
+      .filter(|net| net.index() == ifi.index())
+      .copied()
+      .collect();
+    let mut got: Vec<IfNet> = interface_by_index(ifi.index())
+      .unwrap()
+      .unwrap()
+      .addrs()
+      .unwrap()
+      .into_iter()
+      .collect();
+    want.sort();
+    got.sort();
+    assert_eq!(got, want, "{}", ifi.name());
+  }
+}
+
 // Same NetBSD / DragonFly skip rationale as `if_addrs` above.
 #[cfg(not(any(target_os = "netbsd", target_os = "dragonfly")))]
 #[test]
@@ -279,6 +340,49 @@ fn if_unicast_addrs() {
   check_unicast_stats(&if_stats, &uni_stats).unwrap();
 }
 
+// Same NetBSD skip rationale as `if_addrs` above: the join goes
+// through the same address walker. Loopback must never qualify, and
+// CI runners always have at least one interface with a routable
+// address.
+#[cfg(not(target_os = "netbsd"))]
+#[test]
+fn routable_ifis() {
+  let ift = routable_interfaces().unwrap();
+  for ifi in &ift {
+    println!("routable: {} index={}", ifi.name(), ifi.index());
+    assert!(!ifi.flags().contains(Flags::LOOPBACK), "{ifi:?}");
+    assert!(ifi.flags().contains(Flags::UP), "{ifi:?}");
+  }
+  assert!(!ift.is_empty());
+}
+
+// On a healthy host the lossy enumeration must agree with the strict
+// one and report nothing skipped. Skipped on DragonFly for the same
+// interface-churn reason as `ifis`.
+#[cfg(not(target_os = "dragonfly"))]
+#[test]
+fn try_ifis() {
+  let (ift, errors) = try_interfaces();
+  assert!(errors.is_empty(), "{errors:?}");
+  let strict = interfaces().unwrap();
+  assert_eq!(ift.len(), strict.len());
+}
+
+// The loopback MTU on Linux defaults to 65536, above `u16::MAX`; it
+// must come back exactly as sysfs reports it, not truncated.
+#[cfg(target_os = "linux")]
+#[test]
+fn loopback_mtu_is_preserved() {
+  let lo = interfaces()
+    .unwrap()
+    .into_iter()
+    .find(|ifi| ifi.flags().contains(Flags::LOOPBACK))
+    .unwrap();
+  let sysfs = std::fs::read_to_string(format!("/sys/class/net/{}/mtu", lo.name())).unwrap();
+  assert_eq!(lo.mtu(), sysfs.trim().parse::<u32>().unwrap());
+  assert_eq!(lo.supports_jumbo(), lo.mtu() > 1500);
+}
+
 #[test]
 fn gw_addrs() {
   let addrs = gateway_addrs().unwrap();
@@ -350,3 +454,837 @@ fn if_multicast_addrs() {
   #[cfg(target_os = "dragonfly")]
   let _ = (&if_stats, &uni_stats, &multi_stats);
 }
+
+// Every broadcast address listed belongs to an IPv4 address on the
+// same interface, and the per-interface lists add up to the
+// system-wide one.
+#[test]
+fn broadcast_addrs_match_ipv4_addrs() {
+  let all = interface_broadcast_addrs().unwrap();
+  let mut per_interface = Vec::new();
+  for ifi in interfaces().unwrap() {
+    let nets = ifi.ipv4_addrs().unwrap();
+    for addr in ifi.ipv4_broadcast_addrs().unwrap() {
+      assert_eq!(addr.index(), ifi.index(), "{addr} on {}", ifi.name());
+      assert!(
+        nets.iter().any(|net| net.broadcast() == Some(addr.addr())),
+        "{addr} has no address on {}",
+        ifi.name()
+      );
+      per_interface.push(addr);
+    }
+  }
+  for addr in &all {
+    assert!(per_interface.contains(addr), "{addr} missing per interface");
+  }
+}
+
+// `common_flags` agrees with the platform flags bit for bit on the
+// shared subset.
+#[test]
+fn common_flags_match_platform_flags() {
+  for ifi in interfaces().unwrap() {
+    let common = ifi.common_flags();
+    for (c, f) in [
+      (CommonFlags::UP, Flags::UP),
+      (CommonFlags::BROADCAST, Flags::BROADCAST),
+      (CommonFlags::LOOPBACK, Flags::LOOPBACK),
+      (CommonFlags::POINTOPOINT, Flags::POINTOPOINT),
+      (CommonFlags::MULTICAST, Flags::MULTICAST),
+      (CommonFlags::RUNNING, Flags::RUNNING),
+    ] {
+      assert_eq!(
+        common.contains(c),
+        ifi.flags().contains(f),
+        "{c:?} on {}",
+        ifi.name()
+      );
+    }
+  }
+}
+
+// The net filters see every address the plain dumps return, and
+// selecting by index and prefix keeps exactly the matching ones.
+#[test]
+fn net_filters_see_index_and_prefix() {
+  let lo = loopback_interface().unwrap().unwrap();
+  let expected: Vec<IfNet> = interface_addrs()
+    .unwrap()
+    .into_iter()
+    .filter(|net| net.index() == lo.index() && net.prefix_len() >= 8)
+    .collect();
+  let got = interface_addrs_by_net_filter(|net| net.index() == lo.index() && net.prefix_len() >= 8)
+    .unwrap();
+  assert_eq!(got.as_slice(), expected.as_slice());
+
+  let local = local_addrs().unwrap();
+  let all = local_addrs_by_net_filter(|_| true).unwrap();
+  assert_eq!(all.as_slice(), local.as_slice());
+  assert!(local_addrs_by_net_filter(|_| false).unwrap().is_empty());
+}
+
+// The streaming walk visits what `interface_addrs` returns, in order,
+// and stops as soon as the callback breaks.
+#[test]
+fn for_each_interface_addr_streams_and_stops() {
+  let mut seen = Vec::new();
+  for_each_interface_addr(|net| {
+    seen.push(*net);
+    std::ops::ControlFlow::Continue(())
+  })
+  .unwrap();
+  assert_eq!(seen, interface_addrs().unwrap().to_vec());
+
+  let mut calls = 0;
+  for_each_interface_addr(|_| {
+    calls += 1;
+    std::ops::ControlFlow::Break(())
+  })
+  .unwrap();
+  assert_eq!(calls, seen.len().min(1));
+}
+
+// Linux reads memberships from /proc in kernel order; the system-wide
+// list must still come back sorted by interface and then address, with
+// each membership listed once.
+#[cfg(target_os = "linux")]
+#[test]
+fn multicast_addrs_sorted_and_unique() {
+  let addrs = getifs::interface_multicast_addrs().unwrap();
+  for pair in addrs.windows(2) {
+    assert!(
+      (pair[0].index(), pair[0].addr()) < (pair[1].index(), pair[1].addr()),
+      "{pair:?}"
+    );
+  }
+}
+
+// Every adapter's DNS list, including the loopback pseudo-interface's
+// (usually empty, or the `fec0:0:0:ffff::*` site-local defaults), must
+// parse into concrete server addresses.
+#[cfg(windows)]
+#[test]
+fn dns_servers() {
+  let ift = interfaces().unwrap();
+  assert!(ift.iter().any(|ifi| ifi.flags().contains(Flags::LOOPBACK)));
+  for ifi in ift {
+    for server in ifi.dns_servers() {
+      assert!(!server.is_unspecified(), "{ifi:?}");
+    }
+  }
+}
+
+// The fast-path lookup must land on an interface that really is the
+// loopback, and agree with what a full dump reports.
+#[test]
+fn loopback_fast_path() {
+  let lo = loopback_interface()
+    .unwrap()
+    .expect("host has no loopback interface");
+  assert!(lo.flags().contains(Flags::LOOPBACK), "{lo:?}");
+  assert!(interfaces()
+    .unwrap()
+    .iter()
+    .any(|ifi| ifi.index() == lo.index() && ifi.flags().contains(Flags::LOOPBACK)));
+}
+
+// Every interface has counters to read, and they never go backwards
+// between two reads.
+#[test]
+fn statistics_for_every_interface() {
+  for ifi in interfaces().unwrap() {
+    let before = ifi.statistics().unwrap();
+    let after = ifi.statistics().unwrap();
+    assert!(after.rx_bytes() >= before.rx_bytes(), "{}", ifi.name());
+    assert!(after.tx_packets() >= before.tx_packets(), "{}", ifi.name());
+  }
+}
+
+// Every platform reports the loopback's hardware type as such.
+#[test]
+fn loopback_kind() {
+  let lo = loopback_interface().unwrap().unwrap();
+  assert_eq!(lo.kind(), InterfaceKind::Loopback, "{lo:?}");
+}
+
+// Filtering by an interface's own OUI must return that interface's
+// addresses, and nothing from interfaces whose MAC has another prefix.
+#[test]
+fn mac_prefix_filter() {
+  let ift = interfaces().unwrap();
+  let Some((ifi, mac)) = ift.iter().find_map(|ifi| {
+    let mac = ifi.mac_addr()?;
+    let has_addrs = !ifi.addrs().ok()?.is_empty();
+    (mac.as_bytes().iter().any(|&b| b != 0) && has_addrs).then_some((ifi, mac))
+  }) else {
+    return;
+  };
+
+  let oui: [u8; 3] = mac.as_bytes()[..3].try_into().unwrap();
+  let addrs = addrs_for_mac_prefix(oui).unwrap();
+  for addr in ifi.addrs().unwrap() {
+    assert!(addrs.contains(&addr), "{addr} missing from {addrs:?}");
+  }
+  for addr in &addrs {
+    let owner = interface_by_index(addr.index()).unwrap().unwrap();
+    assert!(owner
+      .mac_addr()
+      .is_some_and(|m| m.as_bytes().starts_with(&oui)));
+  }
+}
+
+// Addresses inside any listed subnet are returned and the rest are
+// not; a subnet no address falls into contributes nothing.
+#[test]
+fn addrs_in_any_subnet() {
+  let all = interface_addrs().unwrap();
+  let Some(first) = all.first() else {
+    return;
+  };
+
+  let unused: ipnet::IpNet = "198.51.100.0/24".parse().unwrap();
+  let subnets = [first.net().trunc(), unused];
+  let found = interface_addrs_in_any(&subnets).unwrap();
+  assert!(found.contains(first), "{first} missing from {found:?}");
+  for addr in &all {
+    let inside = subnets.iter().any(|net| net.contains(&addr.addr()));
+    assert_eq!(found.contains(addr), inside, "{addr}");
+  }
+
+  assert!(interface_addrs_in_any(&[]).unwrap().is_empty());
+}
+
+// The route-guided lookup must agree with filtering the full dump: it
+// never returns anything outside `net`, and it finds every matching
+// address on interfaces that are up and running (down ones may have no
+// route to lead to them).
+#[test]
+fn addrs_within_matches_full_scan() {
+  let running: Vec<u32> = interfaces()
+    .unwrap()
+    .into_iter()
+    .filter(|ifi| ifi.flags().contains(Flags::UP | Flags::RUNNING))
+    .map(|ifi| ifi.index())
+    .collect();
+
+  for addr in interface_addrs().unwrap() {
+    let net = addr.net().trunc();
+    let found = addrs_within(net).unwrap();
+    let all = interface_addrs().unwrap();
+    for a in &found {
+      assert!(net.contains(&a.addr()), "{a} outside {net}");
+      assert!(all.contains(a), "{a} not in the full dump");
+    }
+    for a in all.iter().filter(|a| net.contains(&a.addr())) {
+      if running.contains(&a.index()) {
+        assert!(
+          found.contains(a),
+          "{a} missing from addrs_within({net}): {found:?}"
+        );
+      }
+    }
+  }
+}
+
+// Scope order must be non-decreasing, and on a host with both a global
+// and a link-local IPv6 address the global one must come first.
+#[test]
+fn scope_sorted_addrs() {
+  let addrs = interface_addrs_scope_sorted().unwrap();
+  assert!(
+    addrs.windows(2).all(|w| w[0].scope() <= w[1].scope()),
+    "{addrs:?}"
+  );
+
+  let v6 = |scope| {
+    addrs
+      .iter()
+      .position(|a| a.addr().is_ipv6() && a.scope() == scope)
+  };
+  if let (Some(global), Some(link)) = (v6(AddrScope::Global), v6(AddrScope::Link)) {
+    assert!(global < link, "{addrs:?}");
+  }
+}
+
+// The loopback interface's source address is its loopback address in
+// each family it carries one of.
+#[test]
+fn loopback_source_addr() {
+  let lo = loopback_interface().unwrap().unwrap();
+  let addrs = lo.addrs().unwrap();
+
+  if addrs
+    .iter()
+    .any(|a| a.addr() == IpAddr::from([127, 0, 0, 1]))
+  {
+    assert_eq!(
+      lo.source_addr(Family::V4).unwrap(),
+      Some(IpAddr::from([127, 0, 0, 1]))
+    );
+  }
+  if addrs
+    .iter()
+    .any(|a| a.addr() == std::net::Ipv6Addr::LOCALHOST)
+  {
+    assert_eq!(
+      lo.source_addr(Family::V6).unwrap(),
+      Some(IpAddr::from(std::net::Ipv6Addr::LOCALHOST))
+    );
+  }
+}
+
+// A loopback that carries `::1` has IPv6 enabled, whichever way the
+// platform determines it.
+#[test]
+fn loopback_ipv6_enabled() {
+  let lo = loopback_interface().unwrap().unwrap();
+  let has_v6 = lo
+    .ipv6_addrs()
+    .unwrap()
+    .iter()
+    .any(|a| a.addr() == std::net::Ipv6Addr::LOCALHOST);
+  if has_v6 {
+    assert!(lo.ipv6_enabled().unwrap(), "{lo:?}");
+  }
+}
+
+// The parallel walk must produce exactly what a sequential
+// per-interface walk does, in the same order.
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_matches_sequential() {
+  let parallel = getifs::interfaces_with_addrs_parallel().unwrap();
+  let sequential: Vec<_> = interfaces()
+    .unwrap()
+    .into_iter()
+    .map(|ifi| {
+      let addrs = ifi.addrs().unwrap();
+      (ifi, addrs)
+    })
+    .collect();
+
+  assert_eq!(parallel.len(), sequential.len());
+  for ((pi, pa), (si, sa)) in parallel.iter().zip(&sequential) {
+    assert_eq!(pi.index(), si.index());
+    assert_eq!(pi.name(), si.name());
+    assert_eq!(pa, sa, "{}", si.name());
+  }
+}
+
+// `::1` must be reported once for the loopback, both by the
+// per-interface query and by the host-wide IPv6 dump.
+#[test]
+fn loopback_ipv6_not_duplicated() {
+  let lo = loopback_interface().unwrap().unwrap();
+  let is_lo_v6 = |addr: &IpAddr, index: u32| {
+    index == lo.index() && *addr == IpAddr::from(std::net::Ipv6Addr::LOCALHOST)
+  };
+
+  let per_iface = lo
+    .ipv6_addrs()
+    .unwrap()
+    .iter()
+    .filter(|a| is_lo_v6(&a.addr().into(), a.index()))
+    .count();
+  if per_iface == 0 {
+    return;
+  }
+  assert_eq!(per_iface, 1);
+
+  let host_wide = getifs::interface_ipv6_addrs()
+    .unwrap()
+    .iter()
+    .filter(|a| is_lo_v6(&a.addr().into(), a.index()))
+    .count();
+  assert_eq!(host_wide, 1);
+}
+
+// The wildcard entry is opt-in: absent by default, and when requested
+// it comes first with index 0 and every real interface follows in order.
+#[test]
+fn wildcard_only_when_requested() {
+  let plain = interfaces_with_wildcard(false).unwrap();
+  assert!(plain.iter().all(|ifi| ifi.name() != "*"));
+  assert_eq!(plain.len(), interfaces().unwrap().len());
+
+  let with = interfaces_with_wildcard(true).unwrap();
+  assert_eq!(with.len(), plain.len() + 1);
+  assert_eq!(with[0].index(), 0);
+  assert_eq!(with[0].name(), "*");
+  assert!(with[0].mac_addr().is_none());
+  assert_eq!(&with[1..], &plain[..]);
+}
+
+// Index 0 means "every interface" to the OS lookups; the wildcard must
+// not answer its address queries with the whole host's addresses.
+#[test]
+fn wildcard_has_no_addrs() {
+  let with = interfaces_with_wildcard(true).unwrap();
+  let wildcard = &with[0];
+  assert!(wildcard.addrs().unwrap().is_empty());
+  assert!(wildcard.ipv4_addrs().unwrap().is_empty());
+  assert!(wildcard.ipv6_addrs().unwrap().is_empty());
+  assert_eq!(wildcard.source_addr(Family::V4).unwrap(), None);
+  #[cfg(any(
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "linux",
+    windows,
+  ))]
+  assert!(wildcard.multicast_addrs().unwrap().is_empty());
+}
+
+// Whatever carries the default route must be an up, non-loopback link.
+#[test]
+fn default_interface_is_up_and_not_loopback() {
+  let Some(ifi) = default_interface().unwrap() else {
+    return;
+  };
+  assert!(ifi.flags().contains(Flags::UP), "{ifi:?}");
+  assert!(!ifi.flags().contains(Flags::LOOPBACK), "{ifi:?}");
+}
+
+// An operationally-up interface is necessarily administratively up,
+// and loopback is never reported as down.
+#[test]
+fn oper_status_consistent_with_flags() {
+  for ifi in interfaces().unwrap() {
+    if ifi.oper_status() == OperStatus::Up {
+      assert!(ifi.flags().contains(Flags::UP), "{ifi:?}");
+    }
+    if ifi.flags().contains(Flags::UP | Flags::LOOPBACK) {
+      assert!(
+        matches!(ifi.oper_status(), OperStatus::Up | OperStatus::Unknown),
+        "{ifi:?}"
+      );
+    }
+  }
+}
+
+// Loopback is the one hardware type every platform both flags and
+// names, so the two must agree in both directions.
+#[test]
+fn loopback_media_type() {
+  for ifi in interfaces().unwrap() {
+    assert_eq!(
+      ifi.flags().contains(Flags::LOOPBACK),
+      ifi.media_type().as_deref() == Some("loopback"),
+      "{ifi:?}"
+    );
+  }
+}
+
+// A non-blocking watcher must hand back whatever is queued, or
+// `WouldBlock`, instead of waiting for the next change.
+#[cfg(any(target_os = "linux", target_os = "android", windows))]
+#[test]
+fn nonblocking_watch_never_waits() {
+  let watcher = match getifs::watch() {
+    Ok(watcher) => watcher,
+    Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return,
+    Err(e) => panic!("{e}"),
+  };
+  watcher.set_nonblocking(true).unwrap();
+  loop {
+    match watcher.recv() {
+      Ok(_) => continue,
+      Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+      Err(e) => panic!("{e}"),
+    }
+  }
+}
+
+// Loopback traffic is sourced from the loopback address, and each
+// gateway is reached from an address on the gateway's own interface.
+#[test]
+fn source_addr_for_loopback_and_gateways() {
+  let src = source_addr_for("127.0.0.1".parse().unwrap())
+    .unwrap()
+    .expect("no route to 127.0.0.1");
+  assert_eq!(src.addr(), "127.0.0.1".parse::<IpAddr>().unwrap());
+
+  for gw in gateway_ipv4_addrs().unwrap() {
+    let src = source_addr_for(IpAddr::V4(gw.addr()))
+      .unwrap()
+      .unwrap_or_else(|| panic!("no route to gateway {gw}"));
+    assert_eq!(src.index(), gw.index(), "{gw} via {src}");
+    assert!(src.addr().is_ipv4(), "{src}");
+  }
+}
+
+// An address that is already configured is returned without waiting
+// for any event.
+#[test]
+fn wait_for_addr_returns_present_address() {
+  let Some(lo) = interface_addrs()
+    .unwrap()
+    .into_iter()
+    .find(|net| net.addr().is_loopback())
+  else {
+    return;
+  };
+  let net = wait_for_addr(lo.addr(), std::time::Duration::ZERO).unwrap();
+  assert_eq!(net.addr(), lo.addr());
+}
+
+// A routing table nothing was ever installed in reads as empty rather
+// than failing.
+#[cfg(target_os = "linux")]
+#[test]
+fn route_table_in_unused_table_is_empty() {
+  assert!(getifs::route_table_in(4_242_424).unwrap().is_empty());
+  assert!(getifs::gateway_addrs_in(4_242_424).unwrap().is_empty());
+  assert!(getifs::best_local_addrs_in(4_242_424).unwrap().is_empty());
+}
+
+// Table 0 (`RT_TABLE_UNSPEC`) names no table, so every per-table query
+// refuses it instead of answering for all tables or none.
+#[cfg(target_os = "linux")]
+#[test]
+fn table_zero_is_invalid_input() {
+  use std::io::ErrorKind::InvalidInput;
+
+  assert_eq!(getifs::route_table_in(0).unwrap_err().kind(), InvalidInput);
+  assert_eq!(
+    getifs::gateway_addrs_in(0).unwrap_err().kind(),
+    InvalidInput
+  );
+  assert_eq!(
+    getifs::best_local_addrs_in(0).unwrap_err().kind(),
+    InvalidInput
+  );
+}
+
+// The three dumps over one socket see what the separate calls see;
+// the host's tables are static for the duration of the test.
+#[cfg(target_os = "linux")]
+#[test]
+fn capture_matches_separate_calls() {
+  let (ifs, addrs, routes) = getifs::capture_linux().unwrap();
+
+  let indices = |ifs: &[Interface]| {
+    let mut v: Vec<u32> = ifs.iter().map(Interface::index).collect();
+    v.sort_unstable();
+    v
+  };
+  assert_eq!(indices(&ifs), indices(&interfaces().unwrap()));
+
+  let mut got: Vec<IfNet> = addrs.to_vec();
+  let mut want: Vec<IfNet> = interface_addrs().unwrap().to_vec();
+  got.sort_by_key(|net| (net.index(), net.addr()));
+  want.sort_by_key(|net| (net.index(), net.addr()));
+  assert_eq!(got, want);
+
+  assert_eq!(routes.len(), route_table().unwrap().len());
+  for route in route_table().unwrap() {
+    assert!(routes.contains(&route), "{route} missing");
+  }
+}
+
+// Each interface's zone holds exactly its own link-local addresses,
+// and zone 0 holds none.
+#[test]
+fn ipv6_addrs_in_each_zone() {
+  assert!(ipv6_addrs_in_zone(0).unwrap().is_empty());
+  for ifi in interfaces().unwrap() {
+    let zoned = ipv6_addrs_in_zone(ifi.index()).unwrap();
+    let mut expected = ifi
+      .ipv6_addrs()
+      .unwrap()
+      .into_iter()
+      .filter(|a| a.addr().segments()[0] & 0xffc0 == 0xfe80)
+      .map(|a| a.addr())
+      .collect::<Vec<_>>();
+    let mut got = zoned
+      .iter()
+      .inspect(|a| assert_eq!(a.index(), ifi.index(), "{a:?}"))
+      .map(|a| a.addr())
+      .collect::<Vec<_>>();
+    expected.sort();
+    got.sort();
+    assert_eq!(got, expected, "{}", ifi.name());
+  }
+}
+
+// The empty-vs-error contract: asking about something that does not
+// exist is an empty answer, and system-wide listings succeed (possibly
+// empty) on a healthy host rather than erroring for lack of entries.
+#[test]
+fn absent_is_empty_not_error() {
+  const UNUSED_INDEX: u32 = 0x7fff_fff0;
+  let unused_v4: IpAddr = "203.0.113.77".parse().unwrap();
+
+  assert!(interface_by_index(UNUSED_INDEX).unwrap().is_none());
+  assert!(interface_by_name("getifs-absent0").unwrap().is_none());
+  assert!(interface_by_link_local("fe80::1".parse().unwrap(), 0)
+    .unwrap()
+    .is_none());
+  assert!(ipv6_addrs_in_zone(UNUSED_INDEX).unwrap().is_empty());
+  assert!(gateway_mac(unused_v4).unwrap().is_none());
+  assert!(!is_local_addr(unused_v4).unwrap());
+  assert!(!is_local_addr_on(unused_v4, UNUSED_INDEX).unwrap());
+  assert!(neighbors_by_filter(|_| false).unwrap().is_empty());
+  assert!(addrs_within("203.0.113.0/24".parse().unwrap())
+    .unwrap()
+    .is_empty());
+
+  gateway_addrs().unwrap();
+  gateway_ipv4_addrs().unwrap();
+  gateway_ipv6_addrs().unwrap();
+  best_local_addrs().unwrap();
+  best_local_ipv4_addrs_opt().unwrap();
+  best_local_ipv6_addrs_opt().unwrap();
+  default_interface().unwrap();
+  route_table().unwrap();
+  neighbors().unwrap();
+}
+
+// Link-local next hops and link-scoped groups come back with their
+// zone, which is the interface they were seen on; global ones without.
+#[test]
+fn ipv6_scope_ids_round_trip() {
+  let link_scoped = |addr: std::net::Ipv6Addr| {
+    let s = addr.segments()[0];
+    s & 0xffc0 == 0xfe80 || (s & 0xff00 == 0xff00 && matches!(s & 0x000f, 1 | 2))
+  };
+  let check = |addr: &getifs::Ifv6Addr| {
+    let expected = link_scoped(addr.addr()).then_some(addr.index());
+    assert_eq!(addr.scope_id(), expected, "{addr:?}");
+  };
+  gateway_ipv6_addrs().unwrap().iter().for_each(check);
+  #[cfg(any(
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "linux",
+    windows,
+  ))]
+  getifs::interface_multicast_ipv6_addrs()
+    .unwrap()
+    .iter()
+    .for_each(check);
+}
+
+// `TryFrom` resolves every listed interface both ways and reports
+// unknown ones as `NotFound`.
+#[test]
+fn interface_try_from() {
+  for ifi in interfaces().unwrap() {
+    assert_eq!(Interface::try_from(ifi.index()).unwrap().name(), ifi.name());
+    assert_eq!(
+      Interface::try_from(ifi.name().as_str()).unwrap().index(),
+      ifi.index()
+    );
+  }
+  assert_eq!(
+    Interface::try_from(u32::MAX).unwrap_err().kind(),
+    std::io::ErrorKind::NotFound
+  );
+  assert_eq!(
+    Interface::try_from("no-such-if0").unwrap_err().kind(),
+    std::io::ErrorKind::NotFound
+  );
+}
+
+// Every local address comes back exactly once, and a reported metric
+// always belongs to a connected route on that address's interface.
+#[test]
+fn local_addrs_metrics() {
+  let plain = local_addrs().unwrap();
+  let with_metric = local_addrs_with_metric().unwrap();
+  let addrs: Vec<IfNet> = with_metric.iter().map(|(addr, _)| *addr).collect();
+  assert_eq!(addrs, plain.to_vec());
+
+  let routes = getifs::route_table().unwrap();
+  for (addr, metric) in with_metric {
+    let Some(metric) = metric else {
+      continue;
+    };
+    assert!(
+      routes.iter().any(|r| r.index() == addr.index()
+        && r.gateway().is_none()
+        && r.destination() == addr.net().trunc()
+        && r.metric() == metric),
+      "{addr}: {metric}"
+    );
+  }
+}
+
+// Loopback addresses are the host's own; a public address that no test
+// host is configured with is not, and neither is anything on index 0.
+#[test]
+fn local_addr_checks() {
+  use std::net::{Ipv4Addr, Ipv6Addr};
+
+  let lo = loopback_interface().unwrap().unwrap();
+  let v4 = IpAddr::V4(Ipv4Addr::LOCALHOST);
+  assert!(is_local_addr(v4).unwrap());
+  assert!(is_local_addr_on(v4, lo.index()).unwrap());
+  assert!(!is_local_addr_on(v4, 0).unwrap());
+
+  let v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+  if lo
+    .ipv6_addrs()
+    .unwrap()
+    .iter()
+    .any(|a| a.addr() == Ipv6Addr::LOCALHOST)
+  {
+    assert!(is_local_addr(v6).unwrap());
+    assert!(is_local_addr_on(v6, lo.index()).unwrap());
+  }
+
+  let public = IpAddr::V4(Ipv4Addr::new(8, 8, 4, 4));
+  assert!(!is_local_addr(public).unwrap());
+  assert!(!is_local_addr_on(public, lo.index()).unwrap());
+}
+
+// Every interface contributes at most one address per family, and each
+// one is an address the full dump also reports.
+#[test]
+fn primary_addrs_one_per_family() {
+  let all = interface_addrs().unwrap();
+  let primary = primary_addrs_per_family().unwrap();
+  for addr in &primary {
+    assert!(all.contains(addr), "{addr:?} not in {all:?}");
+    let same_family = primary
+      .iter()
+      .filter(|a| a.index() == addr.index() && a.addr().is_ipv4() == addr.addr().is_ipv4())
+      .count();
+    assert_eq!(same_family, 1, "{primary:?}");
+  }
+}
+
+// Every gateway can be looked up in the neighbour table; an idle one may
+// be unresolved, but a resolved one never reports an all-zero MAC.
+#[test]
+fn gateway_macs() {
+  for gw in gateway_addrs().unwrap() {
+    if let Some(mac) = gateway_mac(gw.addr()).unwrap() {
+      assert!(mac.as_bytes().iter().any(|&b| b != 0), "{gw}: {mac}");
+      println!("{gw}: {mac}");
+    }
+  }
+}
+
+// Every neighbour entry names a live interface, the per-family variants
+// only return their own family, and the filter variant only keeps what
+// the filter accepts. (Linux keeps a `0.0.0.0` NOARP entry on loopback,
+// so the addresses themselves are not checked.)
+#[test]
+fn neighbor_table_well_formed() {
+  let ifindices: Vec<u32> = interfaces().unwrap().iter().map(Interface::index).collect();
+  let all = neighbors().unwrap();
+  for n in &all {
+    assert_ne!(n.index(), 0, "{n}");
+    assert!(ifindices.contains(&n.index()), "{n}");
+    println!("{n}");
+  }
+
+  let v4 = neighbors_ipv4().unwrap();
+  let v6 = neighbors_ipv6().unwrap();
+  assert!(v4.iter().all(|n| n.ip().is_ipv4()));
+  assert!(v6.iter().all(|n| n.ip().is_ipv6()));
+
+  let filtered = neighbors_by_filter(|ip| ip.is_ipv4()).unwrap();
+  assert!(filtered.iter().all(|n| n.ip().is_ipv4()));
+}
+
+// Each interface's own link-local addresses resolve back to it through
+// their zone; a global address is rejected.
+#[test]
+fn link_local_lookup() {
+  use std::net::Ipv6Addr;
+
+  for ifi in interfaces().unwrap() {
+    for addr in ifi.ipv6_addrs().unwrap() {
+      if addr.addr().segments()[0] & 0xffc0 != 0xfe80 {
+        continue;
+      }
+      let found = interface_by_link_local(addr.addr(), addr.index())
+        .unwrap()
+        .unwrap_or_else(|| panic!("{addr} on {} not resolved", ifi.name()));
+      assert_eq!(found.index(), ifi.index());
+    }
+  }
+
+  let ll: Ipv6Addr = "fe80::1".parse().unwrap();
+  assert!(interface_by_link_local(ll, 0).unwrap().is_none());
+  assert_eq!(
+    interface_by_link_local(Ipv6Addr::LOCALHOST, 1)
+      .unwrap_err()
+      .kind(),
+    std::io::ErrorKind::InvalidInput
+  );
+}
+
+// Probing never fails. Where this suite can enumerate interfaces on
+// Linux, one of the two kernel channels must be usable; elsewhere
+// every hint is the conservative default.
+#[test]
+fn runtime_hints_plausible() {
+  let hints = runtime_hints();
+  if cfg!(any(target_os = "linux", target_os = "android")) {
+    if interfaces().is_ok() {
+      assert!(hints.netlink_available(), "{hints:?}");
+    }
+    if std::path::Path::new("/.dockerenv").exists() {
+      assert!(hints.in_container(), "{hints:?}");
+    }
+  } else {
+    assert_eq!(hints, Default::default());
+  }
+}
+
+// The non-empty variant always succeeds on a real host, and what it
+// returns includes the loopback interface.
+#[test]
+fn interfaces_require_nonempty_has_loopback() {
+  let ifs = interfaces_require_nonempty().unwrap();
+  assert!(
+    ifs.iter().any(|ifi| ifi.flags().contains(Flags::LOOPBACK)),
+    "{ifs:?}"
+  );
+}
+
+// The lazy iterator walks the same dump as `interfaces()`, in the same
+// order, and lookups built on it find every interface it yields.
+#[test]
+fn interfaces_iter_matches_interfaces() {
+  let eager: Vec<_> = interfaces()
+    .unwrap()
+    .into_iter()
+    .map(|ifi| (ifi.index(), ifi.name().to_string()))
+    .collect();
+  let lazy: Vec<_> = interfaces_iter()
+    .unwrap()
+    .map(|ifi| (ifi.index(), ifi.name().to_string()))
+    .collect();
+  assert_eq!(eager, lazy);
+
+  for (index, name) in lazy {
+    assert_eq!(
+      interface_by_index(index).unwrap().unwrap().name(),
+      name.as_str()
+    );
+    assert_eq!(interface_by_name(&name).unwrap().unwrap().index(), index);
+  }
+}
+
+// Lookups that name a missing interface report it as its own variant,
+// which still converts to `NotFound` for callers matching on the kind.
+#[test]
+fn missing_interface_is_interface_not_found() {
+  let err = Interface::try_from(u32::MAX).unwrap_err();
+  assert!(matches!(err, Error::InterfaceNotFound), "{err:?}");
+  assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+  let err = ifname_to_index("no-such-if0").unwrap_err();
+  assert!(matches!(err, Error::InterfaceNotFound), "{err:?}");
+
+  #[cfg(any(target_os = "linux", target_vendor = "apple", target_os = "freebsd"))]
+  {
+    let err = StatsSampler::new(999_999).unwrap_err();
+    assert!(matches!(err, Error::InterfaceNotFound), "{err:?}");
+  }
+}